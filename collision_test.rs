@@ -30,12 +30,10 @@ fn main() {
         Err(e) => println!("   ✅ Cross-factory registration failed: {}", e),
     }
 
-    // Test 3: TryFrom<u32> prevents silent narrowing
-    println!("\n3. Testing TryFrom<u32> conversion:");
-    match PrefabId::try_from(42u32) {
-        Ok(id) => println!("   ✅ u32 conversion succeeded: {:?}", id),
-        Err(e) => println!("   ❌ u32 conversion failed: {}", e),
-    }
+    // Test 3: From<u32> widens without loss
+    println!("\n3. Testing From<u32> conversion:");
+    let id = PrefabId::from(42u32);
+    println!("   ✅ u32 conversion succeeded: {:?}", id);
 
     // Test 4: Global registry functions
     println!("\n4. Testing global registry functions:");
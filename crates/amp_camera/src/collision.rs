@@ -0,0 +1,120 @@
+//! Camera collision queries
+
+use bevy_ecs::system::Resource;
+use bevy_math::Vec3;
+
+/// World geometry the camera must stay out of
+pub trait CameraCollision: Send + Sync {
+    /// Cast a sphere of `radius` from `origin` along the unit vector
+    /// `direction`
+    ///
+    /// Returns the distance travelled before the sphere first touches an
+    /// obstacle, if that happens within `max_distance`.
+    fn cast_sphere(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+        radius: f32,
+    ) -> Option<f32>;
+}
+
+/// Obstacles queried by [`update_orbit_camera`](crate::update_orbit_camera)
+#[derive(Resource)]
+pub struct CameraObstacles(pub Box<dyn CameraCollision>);
+
+/// Axis-aligned boxes, such as building footprints, as camera obstacles
+#[derive(Debug, Clone, Default)]
+pub struct BoxObstacles {
+    boxes: Vec<(Vec3, Vec3)>,
+}
+
+impl BoxObstacles {
+    /// Create an empty set of obstacles
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a box by its minimum and maximum corners
+    pub fn add(&mut self, min: Vec3, max: Vec3) {
+        self.boxes.push((min.min(max), min.max(max)));
+    }
+
+    /// Get the number of boxes
+    pub fn len(&self) -> usize {
+        self.boxes.len()
+    }
+
+    /// Check if there are no boxes
+    pub fn is_empty(&self) -> bool {
+        self.boxes.is_empty()
+    }
+}
+
+impl CameraCollision for BoxObstacles {
+    fn cast_sphere(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+        radius: f32,
+    ) -> Option<f32> {
+        // Casting a sphere against a box is approximated by casting a ray
+        // against the box grown by the radius, which only errs near edges
+        self.boxes
+            .iter()
+            .filter_map(|&(min, max)| {
+                ray_box(
+                    origin,
+                    direction,
+                    min - Vec3::splat(radius),
+                    max + Vec3::splat(radius),
+                )
+            })
+            .filter(|&distance| distance <= max_distance)
+            .min_by(f32::total_cmp)
+    }
+}
+
+/// Distance along a ray to where it enters a box, or 0 if it starts inside
+fn ray_box(origin: Vec3, direction: Vec3, min: Vec3, max: Vec3) -> Option<f32> {
+    let inverse = direction.recip();
+    let near = (min - origin) * inverse;
+    let far = (max - origin) * inverse;
+    let enter = near.min(far).max_element();
+    let exit = near.max(far).min_element();
+    (exit >= enter.max(0.0)).then_some(enter.max(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cast_hits_nearest_box() {
+        let mut obstacles = BoxObstacles::new();
+        obstacles.add(Vec3::new(-1.0, 0.0, 4.0), Vec3::new(1.0, 3.0, 6.0));
+        obstacles.add(Vec3::new(-1.0, 0.0, 8.0), Vec3::new(1.0, 3.0, 9.0));
+
+        let hit = obstacles.cast_sphere(Vec3::new(0.0, 1.0, 0.0), Vec3::Z, 10.0, 0.5);
+        assert_eq!(hit, Some(3.5));
+        assert_eq!(
+            obstacles.cast_sphere(Vec3::new(0.0, 1.0, 0.0), Vec3::Z, 3.0, 0.5),
+            None
+        );
+        assert_eq!(
+            obstacles.cast_sphere(Vec3::new(0.0, 1.0, 0.0), Vec3::NEG_Z, 10.0, 0.5),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cast_from_inside_hits_immediately() {
+        let mut obstacles = BoxObstacles::new();
+        obstacles.add(Vec3::splat(-1.0), Vec3::splat(1.0));
+        assert_eq!(
+            obstacles.cast_sphere(Vec3::ZERO, Vec3::X, 5.0, 0.1),
+            Some(0.0)
+        );
+    }
+}
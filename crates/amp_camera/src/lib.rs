@@ -0,0 +1,43 @@
+//! Player camera rigs
+//!
+//! [`OrbitCamera`] is the third-person camera: the player orbits it around
+//! the followed entity with the mouse or right stick through
+//! [`CameraInput`](amp_input::CameraInput), it swings back behind vehicles
+//! marked [`AutoRecenter`] when the player stops looking around, and it pulls
+//! in towards the target instead of clipping through the obstacles in
+//! [`CameraObstacles`]. [`PhotoMode`] detaches the camera for free flight
+//! and takes supersampled photos.
+//!
+//! # Features
+//!
+//! - `unstable_advanced_input`: photo mode pushes the photo input context
+//!   while it is active, see `amp_input::contexts`
+
+#![deny(missing_docs)]
+
+pub mod collision;
+pub mod orbit;
+pub mod photo;
+
+pub use collision::{BoxObstacles, CameraCollision, CameraObstacles};
+pub use orbit::{update_orbit_camera, AutoRecenter, OrbitCamera, OrbitCameraSettings};
+pub use photo::{
+    downsample, photo_path, save_photo, toggle_photo_mode, update_photo_camera, PhotoFilter,
+    PhotoMode, PhotoModeSettings, ScreenshotRequest, DEFAULT_FOV,
+};
+
+use bevy_ecs::schedule::{IntoSystemConfigs, SystemConfigs, SystemSet};
+
+/// System set of the per-frame camera systems added by [`systems`]
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CameraSystems;
+
+/// Get the per-frame camera systems, ordered and in [`CameraSystems`]
+///
+/// Photo mode runs before the orbit camera, which leaves the camera alone
+/// while photo mode flies it.
+pub fn systems() -> SystemConfigs {
+    (toggle_photo_mode, update_photo_camera, update_orbit_camera)
+        .chain()
+        .in_set(CameraSystems)
+}
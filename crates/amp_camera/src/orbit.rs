@@ -0,0 +1,252 @@
+//! Third-person orbit camera
+
+use crate::collision::CameraObstacles;
+use crate::photo::PhotoMode;
+use amp_input::CameraInput;
+use bevy_ecs::prelude::*;
+use bevy_math::{EulerRot, Quat, Vec3};
+use bevy_time::Time;
+use bevy_transform::components::Transform;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::{PI, TAU};
+
+/// Tuning shared by every [`OrbitCamera`]
+///
+/// Look sensitivity and inversion are input settings, see
+/// [`InputSettings`](amp_input::InputSettings).
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OrbitCameraSettings {
+    /// Lowest pitch, looking down from above, in radians
+    pub min_pitch: f32,
+    /// Highest pitch, looking up from below, in radians
+    pub max_pitch: f32,
+    /// Height above the target's origin the camera orbits around
+    pub focus_height: f32,
+    /// Closest the camera gets to the focus when pulled in by obstacles
+    pub min_distance: f32,
+    /// Radius kept clear between the camera and obstacles
+    pub collision_radius: f32,
+    /// Speed at which the camera moves back out once an obstacle clears,
+    /// in meters per second; pulling in is immediate
+    pub return_speed: f32,
+    /// Seconds without look input before the camera recenters
+    pub recenter_delay: f32,
+    /// Turn rate while recentering, in radians per second
+    pub recenter_speed: f32,
+}
+
+impl Default for OrbitCameraSettings {
+    fn default() -> Self {
+        Self {
+            min_pitch: -1.2,
+            max_pitch: 0.6,
+            focus_height: 1.5,
+            min_distance: 0.5,
+            collision_radius: 0.25,
+            return_speed: 4.0,
+            recenter_delay: 1.5,
+            recenter_speed: 2.5,
+        }
+    }
+}
+
+/// Camera orbiting a target entity at a distance
+///
+/// A yaw of zero places the camera on the target's +Z side looking towards
+/// -Z; negative pitch raises the camera and tilts it down.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct OrbitCamera {
+    /// Entity the camera follows
+    pub target: Entity,
+    /// Rotation about the vertical axis, in radians
+    pub yaw: f32,
+    /// Tilt, in radians, limited by the settings
+    pub pitch: f32,
+    /// Preferred distance from the focus
+    pub distance: f32,
+    current_distance: f32,
+    idle_seconds: f32,
+}
+
+impl OrbitCamera {
+    /// Create a camera behind `target` at `distance`, tilted slightly down
+    pub fn new(target: Entity, distance: f32) -> Self {
+        Self {
+            target,
+            yaw: 0.0,
+            pitch: -0.3,
+            distance,
+            current_distance: distance,
+            idle_seconds: 0.0,
+        }
+    }
+
+    /// Get the distance after pulling in for obstacles
+    pub fn current_distance(&self) -> f32 {
+        self.current_distance
+    }
+
+    fn rotation(&self) -> Quat {
+        Quat::from_euler(EulerRot::YXZ, self.yaw, self.pitch, 0.0)
+    }
+}
+
+/// Marks targets the camera swings back behind when the player stops
+/// looking around, such as driven vehicles
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct AutoRecenter;
+
+/// Apply look input to every [`OrbitCamera`] and place it around its target
+///
+/// Cameras whose target no longer exists are left where they are, as are
+/// all cameras while [`PhotoMode`] is active.
+#[allow(clippy::type_complexity)]
+pub fn update_orbit_camera(
+    time: Res<Time>,
+    input: Res<CameraInput>,
+    settings: Res<OrbitCameraSettings>,
+    obstacles: Option<Res<CameraObstacles>>,
+    photo: Option<Res<PhotoMode>>,
+    targets: Query<(&Transform, Has<AutoRecenter>), Without<OrbitCamera>>,
+    mut cameras: Query<(&mut OrbitCamera, &mut Transform)>,
+) {
+    if photo.is_some_and(|photo| photo.is_active()) {
+        return;
+    }
+    let delta = time.delta_seconds();
+    let look = input.look(delta);
+
+    for (mut camera, mut transform) in &mut cameras {
+        let Ok((target, recenter)) = targets.get(camera.target) else {
+            continue;
+        };
+
+        if look != bevy_math::Vec2::ZERO {
+            camera.yaw = wrap_angle(camera.yaw - look.x);
+            camera.pitch += look.y;
+            camera.idle_seconds = 0.0;
+        } else {
+            camera.idle_seconds += delta;
+        }
+        camera.pitch = camera.pitch.clamp(settings.min_pitch, settings.max_pitch);
+
+        if recenter && camera.idle_seconds >= settings.recenter_delay {
+            let forward = target.forward();
+            let behind = (-forward.x).atan2(-forward.z);
+            let offset = wrap_angle(behind - camera.yaw);
+            let step = settings.recenter_speed * delta;
+            camera.yaw = wrap_angle(camera.yaw + offset.clamp(-step, step));
+        }
+
+        let focus = target.translation + Vec3::Y * settings.focus_height;
+        let rotation = camera.rotation();
+        let back = rotation * Vec3::Z;
+
+        let clear = obstacles
+            .as_ref()
+            .and_then(|obstacles| {
+                obstacles
+                    .0
+                    .cast_sphere(focus, back, camera.distance, settings.collision_radius)
+            })
+            .unwrap_or(camera.distance)
+            .max(settings.min_distance);
+        camera.current_distance = if clear < camera.current_distance {
+            clear
+        } else {
+            (camera.current_distance + settings.return_speed * delta).min(clear)
+        };
+
+        transform.translation = focus + back * camera.current_distance;
+        transform.rotation = rotation;
+    }
+}
+
+/// Wrap an angle into -PI..=PI
+fn wrap_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BoxObstacles, CameraObstacles};
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_math::Vec2;
+    use std::time::Duration;
+
+    fn world(look: Vec2) -> (World, Entity, Entity) {
+        let mut world = World::new();
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_millis(100));
+        world.insert_resource(time);
+        world.insert_resource(CameraInput {
+            mouse_delta: look,
+            stick_rate: Vec2::ZERO,
+        });
+        world.init_resource::<OrbitCameraSettings>();
+        let target = world.spawn(Transform::default()).id();
+        let camera = world
+            .spawn((OrbitCamera::new(target, 6.0), Transform::default()))
+            .id();
+        (world, target, camera)
+    }
+
+    #[test]
+    fn test_look_orbits_and_clamps_pitch() {
+        let (mut world, _, camera) = world(Vec2::new(0.5, -10.0));
+        world.run_system_once(update_orbit_camera);
+
+        let orbit = world.get::<OrbitCamera>(camera).unwrap();
+        assert_eq!(orbit.yaw, -0.5);
+        assert_eq!(orbit.pitch, OrbitCameraSettings::default().min_pitch);
+
+        let transform = world.get::<Transform>(camera).unwrap();
+        let focus = Vec3::Y * OrbitCameraSettings::default().focus_height;
+        assert!((transform.translation.distance(focus) - 6.0).abs() < 1e-4);
+        assert!(transform.translation.y > focus.y);
+        // The camera looks at the focus
+        let to_focus = (focus - transform.translation).normalize();
+        assert!(transform.forward().dot(to_focus) > 0.9999);
+    }
+
+    #[test]
+    fn test_obstacle_pulls_camera_in_then_eases_out() {
+        let (mut world, _, camera) = world(Vec2::ZERO);
+        world.get_mut::<OrbitCamera>(camera).unwrap().pitch = 0.0;
+        let mut wall = BoxObstacles::new();
+        wall.add(Vec3::new(-5.0, 0.0, 3.0), Vec3::new(5.0, 10.0, 4.0));
+        world.insert_resource(CameraObstacles(Box::new(wall)));
+
+        world.run_system_once(update_orbit_camera);
+        let pulled = world.get::<OrbitCamera>(camera).unwrap().current_distance();
+        assert!((pulled - 2.75).abs() < 1e-4);
+
+        world.remove_resource::<CameraObstacles>();
+        world.run_system_once(update_orbit_camera);
+        let eased = world.get::<OrbitCamera>(camera).unwrap().current_distance();
+        assert!((eased - 3.15).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_recenters_behind_vehicle_when_idle() {
+        let (mut world, target, camera) = world(Vec2::ZERO);
+        world.entity_mut(target).insert(AutoRecenter);
+        world.get_mut::<OrbitCamera>(camera).unwrap().yaw = 0.2;
+
+        world.run_system_once(update_orbit_camera);
+        assert_eq!(world.get::<OrbitCamera>(camera).unwrap().yaw, 0.2);
+
+        for _ in 0..20 {
+            world.run_system_once(update_orbit_camera);
+        }
+        assert!(world.get::<OrbitCamera>(camera).unwrap().yaw.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_wrap_angle() {
+        assert!((wrap_angle(3.0 * PI / 2.0) + PI / 2.0).abs() < 1e-5);
+        assert!((wrap_angle(-0.25) + 0.25).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,552 @@
+//! Photo mode
+//!
+//! Entering photo mode pauses virtual time, so everything driven by it
+//! freezes, and detaches the camera from its [`OrbitCamera`] rig: the player
+//! flies it freely within [`PhotoModeSettings::max_distance`] of where photo
+//! mode was entered, rolls it, zooms and picks a [`PhotoFilter`]. The HUD
+//! hides while [`PhotoMode::hud_visible`] is false. Taking a photo sends a
+//! [`ScreenshotRequest`] for the renderer to render the view supersampled
+//! to a texture; it hands the pixels back to [`save_photo`], which
+//! downsamples, filters and writes the PNG.
+
+use crate::orbit::OrbitCamera;
+use amp_core::{Error, Result};
+use amp_input::{Action, ActionInput, CameraInput};
+#[cfg(feature = "unstable_advanced_input")]
+use amp_input::{InputContext, InputContextRequest};
+use bevy_ecs::prelude::*;
+use bevy_math::{EulerRot, Quat, Vec3};
+use bevy_time::{Real, Time, Virtual};
+use bevy_transform::components::Transform;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Color treatment applied to photos
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum PhotoFilter {
+    /// Unchanged colors
+    #[default]
+    None,
+    /// Black and white
+    Monochrome,
+    /// Warm brown tones
+    Sepia,
+    /// High-contrast black and white
+    Noir,
+    /// Boosted saturation
+    Vivid,
+}
+
+impl PhotoFilter {
+    /// Every filter, in cycling order
+    pub const ALL: [PhotoFilter; 5] = [
+        PhotoFilter::None,
+        PhotoFilter::Monochrome,
+        PhotoFilter::Sepia,
+        PhotoFilter::Noir,
+        PhotoFilter::Vivid,
+    ];
+
+    /// Get the filter after this one, wrapping around
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Apply the filter to one RGB color with channels in 0..=1
+    pub fn apply(self, [r, g, b]: [f32; 3]) -> [f32; 3] {
+        let luma = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let color = match self {
+            PhotoFilter::None => [r, g, b],
+            PhotoFilter::Monochrome => [luma; 3],
+            PhotoFilter::Sepia => [
+                0.393 * r + 0.769 * g + 0.189 * b,
+                0.349 * r + 0.686 * g + 0.168 * b,
+                0.272 * r + 0.534 * g + 0.131 * b,
+            ],
+            PhotoFilter::Noir => [((luma - 0.5) * 1.6 + 0.5); 3],
+            PhotoFilter::Vivid => [r, g, b].map(|channel| luma + (channel - luma) * 1.5),
+        };
+        color.map(|channel| channel.clamp(0.0, 1.0))
+    }
+}
+
+/// Photo mode tuning
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhotoModeSettings {
+    /// Flying speed, in meters per second
+    pub move_speed: f32,
+    /// Speed multiplier while sprinting
+    pub fast_multiplier: f32,
+    /// Farthest the camera may fly from where photo mode was entered
+    pub max_distance: f32,
+    /// Narrowest vertical field of view, in radians
+    pub min_fov: f32,
+    /// Widest vertical field of view, in radians
+    pub max_fov: f32,
+    /// Zoom rate, in radians of field of view per second
+    pub zoom_speed: f32,
+    /// Roll rate, in radians per second
+    pub roll_speed: f32,
+    /// Render scale of photos before downsampling
+    pub supersample: u32,
+    /// Directory photos are saved to
+    pub directory: PathBuf,
+}
+
+impl Default for PhotoModeSettings {
+    fn default() -> Self {
+        Self {
+            move_speed: 5.0,
+            fast_multiplier: 4.0,
+            max_distance: 40.0,
+            min_fov: 10f32.to_radians(),
+            max_fov: 100f32.to_radians(),
+            zoom_speed: 0.5,
+            roll_speed: 1.0,
+            supersample: 2,
+            directory: PathBuf::from("screenshots"),
+        }
+    }
+}
+
+/// Default vertical field of view, in radians
+pub const DEFAULT_FOV: f32 = std::f32::consts::FRAC_PI_4;
+
+/// State of photo mode and its free camera
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct PhotoMode {
+    active: bool,
+    was_paused: bool,
+    origin: Vec3,
+    /// Camera position
+    pub position: Vec3,
+    /// Rotation about the vertical axis, in radians
+    pub yaw: f32,
+    /// Tilt, in radians
+    pub pitch: f32,
+    /// Rotation about the view direction, in radians
+    pub roll: f32,
+    /// Vertical field of view, in radians
+    pub fov: f32,
+    /// Color treatment of the view and photos
+    pub filter: PhotoFilter,
+}
+
+impl Default for PhotoMode {
+    fn default() -> Self {
+        Self {
+            active: false,
+            was_paused: false,
+            origin: Vec3::ZERO,
+            position: Vec3::ZERO,
+            yaw: 0.0,
+            pitch: 0.0,
+            roll: 0.0,
+            fov: DEFAULT_FOV,
+            filter: PhotoFilter::None,
+        }
+    }
+}
+
+impl PhotoMode {
+    /// Check if photo mode is active
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Check if the HUD should be drawn
+    pub fn hud_visible(&self) -> bool {
+        !self.active
+    }
+
+    /// Get the free camera's transform
+    pub fn transform(&self) -> Transform {
+        Transform::from_translation(self.position).with_rotation(Quat::from_euler(
+            EulerRot::YXZ,
+            self.yaw,
+            self.pitch,
+            self.roll,
+        ))
+    }
+
+    /// Enter photo mode at a camera transform, pausing virtual time
+    pub fn enter(&mut self, camera: &Transform, time: &mut Time<Virtual>) {
+        let (yaw, pitch, _) = camera.rotation.to_euler(EulerRot::YXZ);
+        *self = Self {
+            active: true,
+            was_paused: time.is_paused(),
+            origin: camera.translation,
+            position: camera.translation,
+            yaw,
+            pitch,
+            ..Self::default()
+        };
+        time.pause();
+    }
+
+    /// Leave photo mode, resuming virtual time unless it was already paused
+    pub fn exit(&mut self, time: &mut Time<Virtual>) {
+        if self.active && !self.was_paused {
+            time.unpause();
+        }
+        self.active = false;
+    }
+}
+
+/// Request to render and save a photo
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct ScreenshotRequest {
+    /// File the photo is saved to
+    pub path: PathBuf,
+    /// Render scale of the photo before downsampling
+    pub supersample: u32,
+    /// Camera to render from
+    pub transform: Transform,
+    /// Vertical field of view, in radians
+    pub fov: f32,
+    /// Color treatment
+    pub filter: PhotoFilter,
+}
+
+/// Enter and leave photo mode with [`Action::PhotoMode`]
+///
+/// The free camera starts at the [`OrbitCamera`]'s transform. With the
+/// `unstable_advanced_input` feature, entering pushes `InputContext::Photo`
+/// and leaving pops it again, if the app has registered
+/// `InputContextRequest` events.
+pub fn toggle_photo_mode(
+    actions: ActionInput,
+    mut photo: ResMut<PhotoMode>,
+    mut time: ResMut<Time<Virtual>>,
+    cameras: Query<&Transform, With<OrbitCamera>>,
+    #[cfg(feature = "unstable_advanced_input")] mut contexts: Option<
+        ResMut<Events<InputContextRequest>>,
+    >,
+) {
+    if !actions.just_pressed(Action::PhotoMode) {
+        return;
+    }
+    #[cfg(feature = "unstable_advanced_input")]
+    let mut request = |request| {
+        if let Some(contexts) = contexts.as_mut() {
+            contexts.send(request);
+        }
+    };
+
+    if photo.is_active() {
+        photo.exit(&mut time);
+        #[cfg(feature = "unstable_advanced_input")]
+        request(InputContextRequest::Pop(InputContext::Photo));
+    } else if let Some(camera) = cameras.iter().next() {
+        photo.enter(camera, &mut time);
+        #[cfg(feature = "unstable_advanced_input")]
+        request(InputContextRequest::Push(InputContext::Photo));
+    }
+}
+
+/// Fly, roll and zoom the photo camera, cycle filters and take photos
+///
+/// Runs on real time, since virtual time is paused. The camera's transform
+/// is written to every [`OrbitCamera`], which stops following its target
+/// while photo mode is active.
+#[allow(clippy::too_many_arguments)]
+pub fn update_photo_camera(
+    actions: ActionInput,
+    input: Res<CameraInput>,
+    time: Res<Time<Real>>,
+    settings: Res<PhotoModeSettings>,
+    mut photo: ResMut<PhotoMode>,
+    mut screenshots: EventWriter<ScreenshotRequest>,
+    mut cameras: Query<&mut Transform, With<OrbitCamera>>,
+) {
+    if !photo.is_active() {
+        return;
+    }
+    let delta = time.delta_seconds();
+
+    let look = input.look(delta);
+    photo.yaw -= look.x;
+    photo.pitch = (photo.pitch + look.y).clamp(-1.55, 1.55);
+    photo.roll +=
+        actions.axis(Action::PhotoRollRight, Action::PhotoRollLeft) * settings.roll_speed * delta;
+    photo.fov = (photo.fov
+        + actions.axis(Action::PhotoZoomIn, Action::PhotoZoomOut) * settings.zoom_speed * delta)
+        .clamp(settings.min_fov, settings.max_fov);
+    if actions.just_pressed(Action::PhotoFilter) {
+        photo.filter = photo.filter.next();
+    }
+
+    let heading = Quat::from_rotation_y(photo.yaw);
+    let movement = heading * Vec3::NEG_Z * actions.axis(Action::MoveBack, Action::MoveForward)
+        + heading * Vec3::X * actions.axis(Action::MoveLeft, Action::MoveRight)
+        + Vec3::Y * actions.axis(Action::Crouch, Action::Jump);
+    let speed = if actions.pressed(Action::Sprint) {
+        settings.move_speed * settings.fast_multiplier
+    } else {
+        settings.move_speed
+    };
+    let origin = photo.origin;
+    let offset = photo.position + movement.clamp_length_max(1.0) * speed * delta - origin;
+    photo.position = origin + offset.clamp_length_max(settings.max_distance);
+
+    let transform = photo.transform();
+    for mut camera in &mut cameras {
+        *camera = transform;
+    }
+
+    if actions.just_pressed(Action::PhotoCapture) {
+        screenshots.send(ScreenshotRequest {
+            path: photo_path(&settings.directory),
+            supersample: settings.supersample.max(1),
+            transform,
+            fov: photo.fov,
+            filter: photo.filter,
+        });
+    }
+}
+
+/// Get a new photo file name in `directory`, from the current time
+pub fn photo_path(directory: &Path) -> PathBuf {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    directory.join(format!(
+        "photo_{}_{:03}.png",
+        now.as_secs(),
+        now.subsec_millis()
+    ))
+}
+
+/// Finish a photo rendered for `request` and write it as a PNG
+///
+/// `pixels` are RGBA8 rows of the supersampled render, `width` by `height`;
+/// both must be multiples of the request's supersample factor. Missing
+/// directories are created.
+pub fn save_photo(
+    request: &ScreenshotRequest,
+    pixels: &[u8],
+    width: u32,
+    height: u32,
+) -> Result<()> {
+    let (mut image, width, height) = downsample(pixels, width, height, request.supersample)?;
+    if request.filter != PhotoFilter::None {
+        for pixel in image.chunks_exact_mut(4) {
+            let color = [pixel[0], pixel[1], pixel[2]].map(|c| f32::from(c) / 255.0);
+            let filtered = request.filter.apply(color);
+            for (channel, value) in pixel.iter_mut().zip(filtered) {
+                *channel = (value * 255.0).round() as u8;
+            }
+        }
+    }
+
+    if let Some(parent) = request.path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = std::io::BufWriter::new(std::fs::File::create(&request.path)?);
+    let mut encoder = png::Encoder::new(file, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&image))
+        .map_err(|e| Error::resource_load(request.path.display().to_string(), e.to_string()))
+}
+
+/// Average each `factor` by `factor` block of RGBA8 pixels into one
+///
+/// Returns the pixels and their width and height.
+pub fn downsample(
+    pixels: &[u8],
+    width: u32,
+    height: u32,
+    factor: u32,
+) -> Result<(Vec<u8>, u32, u32)> {
+    let factor = factor.max(1);
+    if pixels.len() != width as usize * height as usize * 4 {
+        return Err(Error::validation(format!(
+            "expected {width}x{height} RGBA pixels, got {} bytes",
+            pixels.len()
+        )));
+    }
+    if width % factor != 0 || height % factor != 0 {
+        return Err(Error::validation(format!(
+            "{width}x{height} is not a multiple of the supersample factor {factor}"
+        )));
+    }
+
+    let (out_width, out_height) = (width / factor, height / factor);
+    let samples = factor * factor;
+    let mut out = Vec::with_capacity(out_width as usize * out_height as usize * 4);
+    for y in 0..out_height {
+        for x in 0..out_width {
+            let mut sum = [0u32; 4];
+            for sy in 0..factor {
+                for sx in 0..factor {
+                    let index = (((y * factor + sy) * width + x * factor + sx) * 4) as usize;
+                    for (total, channel) in sum.iter_mut().zip(&pixels[index..index + 4]) {
+                        *total += u32::from(*channel);
+                    }
+                }
+            }
+            out.extend(sum.map(|total| ((total + samples / 2) / samples) as u8));
+        }
+    }
+    Ok((out, out_width, out_height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amp_input::{ActiveGamepad, InputBindings, InputSettings};
+    use bevy_ecs::schedule::Schedule;
+    use bevy_input::gamepad::{GamepadAxis, GamepadButton};
+    use bevy_input::keyboard::KeyCode;
+    use bevy_input::mouse::MouseButton;
+    use bevy_input::{Axis, ButtonInput};
+    use bevy_math::Vec2;
+    use std::time::Duration;
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<ButtonInput<MouseButton>>();
+        world.init_resource::<ButtonInput<GamepadButton>>();
+        world.init_resource::<Axis<GamepadButton>>();
+        world.init_resource::<Axis<GamepadAxis>>();
+        world.init_resource::<ActiveGamepad>();
+        config_core::init_config(&mut world, InputBindings::default());
+        config_core::init_config(&mut world, InputSettings::default());
+        world.insert_resource(CameraInput {
+            mouse_delta: Vec2::ZERO,
+            stick_rate: Vec2::ZERO,
+        });
+        let mut real = Time::<Real>::default();
+        real.update_with_duration(Duration::ZERO);
+        real.update_with_duration(Duration::from_secs(1));
+        world.insert_resource(real);
+        world.init_resource::<Time<Virtual>>();
+        world.init_resource::<PhotoModeSettings>();
+        world.init_resource::<PhotoMode>();
+        world.init_resource::<Events<ScreenshotRequest>>();
+        let target = world.spawn(Transform::default()).id();
+        world.spawn((
+            OrbitCamera::new(target, 6.0),
+            Transform::from_xyz(0.0, 2.0, 6.0),
+        ));
+        world
+    }
+
+    fn press(world: &mut World, schedule: &mut Schedule, keys: &[KeyCode]) {
+        let mut input = world.resource_mut::<ButtonInput<KeyCode>>();
+        input.release_all();
+        input.clear();
+        for key in keys {
+            input.press(*key);
+        }
+        schedule.run(world);
+    }
+
+    #[test]
+    fn test_photo_mode_pauses_and_flies_free_camera() {
+        let mut world = world();
+        let mut schedule = Schedule::default();
+        schedule.add_systems((toggle_photo_mode, update_photo_camera).chain());
+
+        press(&mut world, &mut schedule, &[KeyCode::KeyP]);
+        assert!(world.resource::<PhotoMode>().is_active());
+        assert!(!world.resource::<PhotoMode>().hud_visible());
+        assert!(world.resource::<Time<Virtual>>().is_paused());
+
+        press(&mut world, &mut schedule, &[KeyCode::KeyW, KeyCode::KeyQ]);
+        let photo = world.resource::<PhotoMode>().clone();
+        assert!((photo.position - Vec3::new(0.0, 2.0, 1.0)).length() < 1e-4);
+        assert!(photo.roll > 0.0);
+        let mut cameras = world.query_filtered::<&Transform, With<OrbitCamera>>();
+        assert_eq!(*cameras.single(&world), photo.transform());
+
+        press(&mut world, &mut schedule, &[KeyCode::KeyT, KeyCode::Enter]);
+        let requests = world.resource::<Events<ScreenshotRequest>>();
+        let request = requests.iter_current_update_events().next().unwrap();
+        assert_eq!(request.filter, PhotoFilter::Monochrome);
+        assert_eq!(request.supersample, 2);
+        assert!(request.path.starts_with("screenshots"));
+
+        press(&mut world, &mut schedule, &[KeyCode::KeyP]);
+        assert!(!world.resource::<PhotoMode>().is_active());
+        assert!(!world.resource::<Time<Virtual>>().is_paused());
+    }
+
+    #[cfg(feature = "unstable_advanced_input")]
+    #[test]
+    fn test_photo_mode_switches_input_context() {
+        use amp_input::{apply_input_context_requests, ActiveInputContexts};
+
+        let mut world = world();
+        world.init_resource::<ActiveInputContexts>();
+        world.init_resource::<Events<InputContextRequest>>();
+        let mut schedule = Schedule::default();
+        schedule.add_systems((toggle_photo_mode, apply_input_context_requests).chain());
+
+        press(&mut world, &mut schedule, &[KeyCode::KeyP]);
+        assert!(world.resource::<PhotoMode>().is_active());
+        let contexts = world.resource::<ActiveInputContexts>();
+        assert_eq!(contexts.current(), InputContext::Photo);
+        assert_eq!(contexts.base(), InputContext::OnFoot);
+
+        press(&mut world, &mut schedule, &[KeyCode::KeyP]);
+        assert!(!world.resource::<PhotoMode>().is_active());
+        let contexts = world.resource::<ActiveInputContexts>();
+        assert_eq!(contexts.current(), InputContext::OnFoot);
+    }
+
+    #[test]
+    fn test_camera_stays_near_entry_point() {
+        let mut world = world();
+        world.resource_mut::<PhotoModeSettings>().max_distance = 3.0;
+        let mut schedule = Schedule::default();
+        schedule.add_systems((toggle_photo_mode, update_photo_camera).chain());
+
+        press(&mut world, &mut schedule, &[KeyCode::KeyP]);
+        for _ in 0..3 {
+            press(&mut world, &mut schedule, &[KeyCode::KeyW]);
+        }
+        let photo = world.resource::<PhotoMode>();
+        assert!((photo.position - Vec3::new(0.0, 2.0, 3.0)).length() < 1e-4);
+    }
+
+    #[test]
+    fn test_save_photo_downsamples_and_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        // 2x2 render of one red and three black pixels, supersampled 2x
+        let pixels = [
+            [255, 0, 0, 255],
+            [0, 0, 0, 255],
+            [0, 0, 0, 255],
+            [0, 0, 0, 255],
+        ]
+        .concat();
+        let (image, width, height) = downsample(&pixels, 2, 2, 2).unwrap();
+        assert_eq!(
+            (image.as_slice(), width, height),
+            (&[64, 0, 0, 255][..], 1, 1)
+        );
+        assert!(downsample(&pixels, 2, 2, 3).is_err());
+
+        let request = ScreenshotRequest {
+            path: dir.path().join("shots/photo.png"),
+            supersample: 2,
+            transform: Transform::default(),
+            fov: DEFAULT_FOV,
+            filter: PhotoFilter::Monochrome,
+        };
+        save_photo(&request, &pixels, 2, 2).unwrap();
+        let decoder = png::Decoder::new(std::fs::File::open(&request.path).unwrap());
+        let mut reader = decoder.read_info().unwrap();
+        let mut image = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut image).unwrap();
+        assert_eq!(image[..4], [14, 14, 14, 255]);
+    }
+}
@@ -0,0 +1,399 @@
+//! Crash and panic report capture.
+//!
+//! [`install_panic_hook`] replaces the panic hook with one that writes a
+//! timestamped report file containing the panic message, a backtrace, the
+//! most recent log lines and any context values (perf stats, active sector
+//! and entity counts) that running systems have published to the global
+//! [`CrashContext`].
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use amp_core::crash::{self, CrashReportConfig};
+//!
+//! crash::install_panic_hook(CrashReportConfig::new("crash-reports"));
+//!
+//! // Systems keep the context up to date while the game runs.
+//! let context = crash::global_context();
+//! context.record_log("streaming: loaded sector (3, -2)");
+//! context.set_value("active_sectors", 42);
+//! context.set_value("entities", 18_250);
+//! ```
+
+use std::backtrace::Backtrace;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write as _;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write as _};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, TryLockError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Default number of log lines kept for crash reports.
+pub const DEFAULT_LOG_CAPACITY: usize = 200;
+
+/// Configuration for the crash-reporting panic hook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrashReportConfig {
+    /// Directory report files are written to
+    pub directory: PathBuf,
+    /// Whether the previously installed hook still runs after the report is written
+    pub chain_previous_hook: bool,
+}
+
+impl CrashReportConfig {
+    /// Create a configuration writing reports to `directory`.
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            chain_previous_hook: true,
+        }
+    }
+}
+
+struct ContextState {
+    logs: VecDeque<String>,
+    log_capacity: usize,
+    values: BTreeMap<String, String>,
+}
+
+/// Recent log lines and named values captured into crash reports.
+///
+/// All methods take `&self` so the context can be shared freely between
+/// threads.
+pub struct CrashContext {
+    state: Mutex<ContextState>,
+}
+
+impl CrashContext {
+    /// Create an empty context keeping [`DEFAULT_LOG_CAPACITY`] log lines.
+    pub const fn new() -> Self {
+        Self::with_log_capacity(DEFAULT_LOG_CAPACITY)
+    }
+
+    /// Create an empty context keeping `log_capacity` log lines.
+    pub const fn with_log_capacity(log_capacity: usize) -> Self {
+        Self {
+            state: Mutex::new(ContextState {
+                logs: VecDeque::new(),
+                log_capacity,
+                values: BTreeMap::new(),
+            }),
+        }
+    }
+
+    /// Append a log line, discarding the oldest line when full.
+    pub fn record_log(&self, line: impl Into<String>) {
+        let mut state = self.lock();
+        if state.log_capacity == 0 {
+            return;
+        }
+        while state.logs.len() >= state.log_capacity {
+            state.logs.pop_front();
+        }
+        state.logs.push_back(line.into());
+    }
+
+    /// Set a named value, replacing any previous value.
+    pub fn set_value(&self, key: impl Into<String>, value: impl ToString) {
+        self.lock().values.insert(key.into(), value.to_string());
+    }
+
+    /// Record an error as a log line tagged with its code and category.
+    pub fn record_error(&self, error: &crate::Error) {
+        self.record_log(format!(
+            "[{}] {}: {}",
+            error.code(),
+            error.category(),
+            error
+        ));
+    }
+
+    /// Remove a named value.
+    pub fn remove_value(&self, key: &str) {
+        self.lock().values.remove(key);
+    }
+
+    /// Recent log lines, oldest first.
+    pub fn recent_logs(&self) -> Vec<String> {
+        self.lock().logs.iter().cloned().collect()
+    }
+
+    /// Named values, sorted by key.
+    pub fn values(&self) -> Vec<(String, String)> {
+        self.lock()
+            .values
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ContextState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Lock without blocking, so a panic raised while the lock is held
+    /// cannot deadlock the panic hook.
+    fn try_lock(&self) -> Option<MutexGuard<'_, ContextState>> {
+        match self.state.try_lock() {
+            Ok(guard) => Some(guard),
+            Err(TryLockError::Poisoned(poisoned)) => Some(poisoned.into_inner()),
+            Err(TryLockError::WouldBlock) => None,
+        }
+    }
+}
+
+impl Default for CrashContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static GLOBAL_CONTEXT: CrashContext = CrashContext::new();
+
+/// The context captured by the panic hook.
+pub fn global_context() -> &'static CrashContext {
+    &GLOBAL_CONTEXT
+}
+
+/// Everything captured about a single panic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrashReport {
+    /// Seconds since the Unix epoch when the panic was captured
+    pub timestamp_secs: u64,
+    /// Panic message
+    pub message: String,
+    /// Source location of the panic, if known
+    pub location: Option<String>,
+    /// Name of the panicking thread, if any
+    pub thread: Option<String>,
+    /// Rendered backtrace
+    pub backtrace: String,
+    /// Most recent log lines, oldest first
+    pub recent_logs: Vec<String>,
+    /// Named context values, sorted by key
+    pub context: Vec<(String, String)>,
+}
+
+impl CrashReport {
+    /// Capture a report for the current thread using `context`.
+    ///
+    /// If the context is locked by the panicking thread its contents are
+    /// left out rather than blocking.
+    pub fn capture(message: String, location: Option<String>, context: &CrashContext) -> Self {
+        let (recent_logs, values) = match context.try_lock() {
+            Some(state) => (
+                state.logs.iter().cloned().collect(),
+                state
+                    .values
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect(),
+            ),
+            None => (Vec::new(), Vec::new()),
+        };
+
+        Self {
+            timestamp_secs: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or_default(),
+            message,
+            location,
+            thread: std::thread::current().name().map(str::to_owned),
+            backtrace: Backtrace::force_capture().to_string(),
+            recent_logs,
+            context: values,
+        }
+    }
+
+    /// File name the report is written under.
+    ///
+    /// If a report with this name already exists, [`CrashReport::write_to_dir`]
+    /// adds a numeric suffix instead, as in `crash-1700000000-1.txt`.
+    pub fn file_name(&self) -> String {
+        format!("crash-{}.txt", self.timestamp_secs)
+    }
+
+    /// Render the report as plain text.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "AMP crash report");
+        let _ = writeln!(out, "timestamp: {}", self.timestamp_secs);
+        let _ = writeln!(
+            out,
+            "thread: {}",
+            self.thread.as_deref().unwrap_or("<unnamed>")
+        );
+        let _ = writeln!(out, "message: {}", self.message);
+        let _ = writeln!(
+            out,
+            "location: {}",
+            self.location.as_deref().unwrap_or("<unknown>")
+        );
+
+        let _ = writeln!(out, "\n[context]");
+        for (key, value) in &self.context {
+            let _ = writeln!(out, "{key}: {value}");
+        }
+
+        let _ = writeln!(out, "\n[recent log]");
+        for line in &self.recent_logs {
+            let _ = writeln!(out, "{line}");
+        }
+
+        let _ = writeln!(out, "\n[backtrace]");
+        let _ = writeln!(out, "{}", self.backtrace);
+        out
+    }
+
+    /// Write the report into `directory`, creating it if needed.
+    ///
+    /// Existing reports are never overwritten, so panics in the same second
+    /// each get their own file. Returns the path of the written file.
+    pub fn write_to_dir(&self, directory: &Path) -> crate::Result<PathBuf> {
+        fs::create_dir_all(directory)?;
+        let rendered = self.render();
+        for attempt in 0u32.. {
+            let path = if attempt == 0 {
+                directory.join(self.file_name())
+            } else {
+                directory.join(format!("crash-{}-{attempt}.txt", self.timestamp_secs))
+            };
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    file.write_all(rendered.as_bytes())?;
+                    return Ok(path);
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        unreachable!("ran out of crash report file names")
+    }
+}
+
+/// Install a panic hook that writes a [`CrashReport`] for every panic.
+///
+/// The report captures the [`global_context`]. Failures to write the report
+/// are printed to stderr and otherwise ignored.
+pub fn install_panic_hook(config: CrashReportConfig) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| (*message).to_owned())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "<non-string panic payload>".to_owned());
+        let location = info.location().map(ToString::to_string);
+
+        let report = CrashReport::capture(message, location, global_context());
+        match report.write_to_dir(&config.directory) {
+            Ok(path) => eprintln!("crash report written to {}", path.display()),
+            Err(e) => eprintln!("failed to write crash report: {e}"),
+        }
+
+        if config.chain_previous_hook {
+            previous(info);
+        }
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_ring_keeps_most_recent_lines() {
+        let context = CrashContext::with_log_capacity(3);
+        for i in 0..5 {
+            context.record_log(format!("line {i}"));
+        }
+        assert_eq!(context.recent_logs(), vec!["line 2", "line 3", "line 4"]);
+
+        let disabled = CrashContext::with_log_capacity(0);
+        disabled.record_log("ignored");
+        assert!(disabled.recent_logs().is_empty());
+    }
+
+    #[test]
+    fn test_error_recorded_with_code() {
+        let context = CrashContext::new();
+        let error = crate::Error::streaming("sector stalled").with_context("sector", "(1, 2)");
+        context.record_error(&error);
+        assert_eq!(
+            context.recent_logs(),
+            ["[AMP-0800] streaming: Streaming error: sector stalled [sector=(1, 2)]"]
+        );
+    }
+
+    #[test]
+    fn test_context_values_are_sorted_and_replaced() {
+        let context = CrashContext::new();
+        context.set_value("entities", 10);
+        context.set_value("active_sectors", 4);
+        context.set_value("entities", 12);
+        assert_eq!(
+            context.values(),
+            vec![
+                ("active_sectors".to_owned(), "4".to_owned()),
+                ("entities".to_owned(), "12".to_owned()),
+            ]
+        );
+
+        context.remove_value("entities");
+        assert_eq!(context.values().len(), 1);
+    }
+
+    #[test]
+    fn test_report_captures_context() {
+        let context = CrashContext::new();
+        context.record_log("loaded sector (1, 2)");
+        context.set_value("frame_time_ms", 16.6);
+
+        let report = CrashReport::capture(
+            "boom".to_owned(),
+            Some("src/main.rs:1:1".to_owned()),
+            &context,
+        );
+        let rendered = report.render();
+
+        assert_eq!(
+            report.file_name(),
+            format!("crash-{}.txt", report.timestamp_secs)
+        );
+        assert!(rendered.contains("message: boom"));
+        assert!(rendered.contains("location: src/main.rs:1:1"));
+        assert!(rendered.contains("frame_time_ms: 16.6"));
+        assert!(rendered.contains("loaded sector (1, 2)"));
+        assert!(rendered.contains("[backtrace]"));
+    }
+
+    #[test]
+    fn test_report_written_to_directory() {
+        let directory =
+            std::env::temp_dir().join(format!("amp_core_crash_test_{}", std::process::id()));
+        let report = CrashReport::capture("boom".to_owned(), None, &CrashContext::new());
+
+        let path = report.write_to_dir(&directory).unwrap();
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.contains("location: <unknown>"));
+
+        // A second panic in the same second gets its own file
+        let second = CrashReport {
+            message: "bang".to_owned(),
+            ..report.clone()
+        };
+        let second_path = second.write_to_dir(&directory).unwrap();
+        assert_ne!(second_path, path);
+        assert!(fs::read_to_string(&path).unwrap().contains("message: boom"));
+        assert!(fs::read_to_string(&second_path)
+            .unwrap()
+            .contains("message: bang"));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
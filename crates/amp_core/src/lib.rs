@@ -2,6 +2,16 @@
 //!
 //! This crate provides core error handling and utilities for the AMP Game Engine.
 //! It defines the primary error types and result aliases used throughout the engine.
+//! Every [`Error`] has an [`ErrorCategory`] and a stable code, and can carry
+//! context such as the asset or sector being processed.
+
+pub mod crash;
+pub mod memory;
+#[cfg(feature = "serde")]
+pub mod recording;
+pub mod rng;
+pub mod telemetry;
+pub mod weighted;
 
 /// A specialized `Result` type for operations that may fail within the AMP engine.
 ///
@@ -69,12 +79,108 @@ pub enum Error {
     #[error("Config error: {0}")]
     Config(#[from] ConfigError),
 
+    /// Content errors in a specific asset (bad data, missing references)
+    #[error("Asset error in '{asset}': {message}")]
+    Asset {
+        /// Asset path or id
+        asset: String,
+        /// Error message
+        message: String,
+    },
+
+    /// World streaming errors
+    #[error("Streaming error: {message}")]
+    Streaming {
+        /// Error message
+        message: String,
+    },
+
+    /// Physics simulation errors
+    #[error("Physics error: {message}")]
+    Physics {
+        /// Error message
+        message: String,
+    },
+
+    /// Script errors
+    #[error("Script error in '{script}': {message}")]
+    Script {
+        /// Script name or path
+        script: String,
+        /// Error message
+        message: String,
+    },
+
     /// Generic error for cases not covered by specific variants
     #[error("Internal error: {message}")]
     Internal {
         /// Error message
         message: String,
     },
+
+    /// Another error with context attached, see [`Error::with_context`]
+    #[error("{error} [{}]", format_context(.context))]
+    WithContext {
+        /// The original error
+        error: Box<Error>,
+        /// Key/value pairs in the order they were attached
+        context: Vec<(String, String)>,
+    },
+}
+
+/// Broad area an [`Error`] comes from, for grouping in the console and
+/// crash reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// File and network I/O
+    Io,
+    /// Serialization and parsing
+    Serialization,
+    /// Configuration files and values
+    Config,
+    /// Asset and resource content
+    Asset,
+    /// Validation of inputs and data
+    Validation,
+    /// Engine state
+    State,
+    /// GPU and rendering
+    Gpu,
+    /// World streaming
+    Streaming,
+    /// Physics simulation
+    Physics,
+    /// Scripting
+    Script,
+    /// Everything else
+    Internal,
+}
+
+impl std::fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Io => "io",
+            Self::Serialization => "serialization",
+            Self::Config => "config",
+            Self::Asset => "asset",
+            Self::Validation => "validation",
+            Self::State => "state",
+            Self::Gpu => "gpu",
+            Self::Streaming => "streaming",
+            Self::Physics => "physics",
+            Self::Script => "script",
+            Self::Internal => "internal",
+        };
+        f.write_str(name)
+    }
+}
+
+fn format_context(context: &[(String, String)]) -> String {
+    context
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 /// Configuration-specific error types for the config_core crate
@@ -156,6 +262,140 @@ impl Error {
             message: message.into(),
         }
     }
+
+    /// Create a new asset error
+    pub fn asset<A: Into<String>, S: Into<String>>(asset: A, message: S) -> Self {
+        Self::Asset {
+            asset: asset.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Create a new streaming error
+    pub fn streaming<S: Into<String>>(message: S) -> Self {
+        Self::Streaming {
+            message: message.into(),
+        }
+    }
+
+    /// Create a new physics error
+    pub fn physics<S: Into<String>>(message: S) -> Self {
+        Self::Physics {
+            message: message.into(),
+        }
+    }
+
+    /// Create a new script error
+    pub fn script<N: Into<String>, S: Into<String>>(script: N, message: S) -> Self {
+        Self::Script {
+            script: script.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Attach a context value, such as the sector or entity being processed
+    ///
+    /// Context accumulates on a single [`Error::WithContext`] rather than
+    /// nesting, and is shown after the message.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_core::Error;
+    ///
+    /// let err = Error::asset("cars/taxi.ron", "missing wheel mesh")
+    ///     .with_context("sector", "(3, -2)")
+    ///     .with_context("prefab", "taxi");
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "Asset error in 'cars/taxi.ron': missing wheel mesh [sector=(3, -2), prefab=taxi]"
+    /// );
+    /// assert_eq!(err.code(), "AMP-0400");
+    /// ```
+    pub fn with_context<K: Into<String>, V: ToString>(self, key: K, value: V) -> Self {
+        let entry = (key.into(), value.to_string());
+        match self {
+            Self::WithContext { error, mut context } => {
+                context.push(entry);
+                Self::WithContext { error, context }
+            }
+            error => Self::WithContext {
+                error: Box::new(error),
+                context: vec![entry],
+            },
+        }
+    }
+
+    /// Get the attached context values in the order they were attached
+    pub fn context(&self) -> &[(String, String)] {
+        match self {
+            Self::WithContext { context, .. } => context,
+            _ => &[],
+        }
+    }
+
+    /// Get the error without any attached context
+    pub fn root(&self) -> &Error {
+        match self {
+            Self::WithContext { error, .. } => error.root(),
+            error => error,
+        }
+    }
+
+    /// Get the category the error belongs to
+    pub fn category(&self) -> ErrorCategory {
+        match self.root() {
+            Self::Io(_) => ErrorCategory::Io,
+            Self::Serialization { .. } => ErrorCategory::Serialization,
+            Self::Configuration { .. } | Self::Config(_) => ErrorCategory::Config,
+            Self::ResourceLoad { .. } | Self::Asset { .. } => ErrorCategory::Asset,
+            Self::Validation { .. } => ErrorCategory::Validation,
+            Self::InvalidState { .. } => ErrorCategory::State,
+            Self::Gpu { .. } => ErrorCategory::Gpu,
+            Self::Streaming { .. } => ErrorCategory::Streaming,
+            Self::Physics { .. } => ErrorCategory::Physics,
+            Self::Script { .. } => ErrorCategory::Script,
+            Self::Internal { .. } | Self::WithContext { .. } => ErrorCategory::Internal,
+        }
+    }
+
+    /// Get a stable code identifying the kind of error, such as `AMP-0401`
+    ///
+    /// The hundreds identify the category; codes never change meaning, so
+    /// they can be searched for in logs and documentation.
+    pub fn code(&self) -> &'static str {
+        match self.root() {
+            Self::Io(_) => "AMP-0100",
+            Self::Serialization { .. } => "AMP-0200",
+            Self::Configuration { .. } => "AMP-0300",
+            Self::Config(ConfigError::FileNotFound { .. }) => "AMP-0301",
+            Self::Config(ConfigError::ParseError { .. }) => "AMP-0302",
+            Self::Config(ConfigError::InvalidFormat { .. }) => "AMP-0303",
+            Self::Config(ConfigError::IoError(_)) => "AMP-0304",
+            Self::Asset { .. } => "AMP-0400",
+            Self::ResourceLoad { .. } => "AMP-0401",
+            Self::Validation { .. } => "AMP-0500",
+            Self::InvalidState { .. } => "AMP-0600",
+            Self::Gpu { .. } => "AMP-0700",
+            Self::Streaming { .. } => "AMP-0800",
+            Self::Physics { .. } => "AMP-0900",
+            Self::Script { .. } => "AMP-1000",
+            Self::Internal { .. } | Self::WithContext { .. } => "AMP-9000",
+        }
+    }
+}
+
+/// Attach context to the error of a [`Result`]
+pub trait ResultExt<T> {
+    /// Attach a context value to the error, if any; see
+    /// [`Error::with_context`]
+    fn with_context<K: Into<String>, V: ToString>(self, key: K, value: V) -> Result<T>;
+}
+
+impl<T, E: Into<Error>> ResultExt<T> for std::result::Result<T, E> {
+    fn with_context<K: Into<String>, V: ToString>(self, key: K, value: V) -> Result<T> {
+        self.map_err(|error| error.into().with_context(key, value))
+    }
 }
 
 impl ConfigError {
@@ -278,6 +518,85 @@ mod tests {
         assert!(error_chain.contains("Access denied"));
     }
 
+    #[test]
+    fn test_categorized_errors() {
+        let cases = [
+            (
+                Error::asset("a.ron", "bad"),
+                ErrorCategory::Asset,
+                "AMP-0400",
+            ),
+            (
+                Error::resource_load("b.png", "missing"),
+                ErrorCategory::Asset,
+                "AMP-0401",
+            ),
+            (
+                Error::streaming("sector timed out"),
+                ErrorCategory::Streaming,
+                "AMP-0800",
+            ),
+            (
+                Error::physics("solver diverged"),
+                ErrorCategory::Physics,
+                "AMP-0900",
+            ),
+            (
+                Error::script("mission.lua", "nil call"),
+                ErrorCategory::Script,
+                "AMP-1000",
+            ),
+            (
+                Error::from(ConfigError::parse_error("x")),
+                ErrorCategory::Config,
+                "AMP-0302",
+            ),
+        ];
+        for (err, category, code) in cases {
+            assert_eq!(err.category(), category);
+            assert_eq!(err.code(), code);
+        }
+        assert_eq!(
+            Error::script("mission.lua", "nil call").to_string(),
+            "Script error in 'mission.lua': nil call"
+        );
+        assert_eq!(ErrorCategory::Streaming.to_string(), "streaming");
+    }
+
+    #[test]
+    fn test_with_context() {
+        let err = Error::streaming("failed to load sector").with_context("sector", "(3, -2)");
+        assert_eq!(
+            err.to_string(),
+            "Streaming error: failed to load sector [sector=(3, -2)]"
+        );
+        assert!(matches!(err.root(), Error::Streaming { .. }));
+        assert_eq!(err.category(), ErrorCategory::Streaming);
+        assert_eq!(err.code(), "AMP-0800");
+
+        let err = err.with_context("attempt", 2);
+        assert_eq!(
+            err.context(),
+            [
+                ("sector".to_owned(), "(3, -2)".to_owned()),
+                ("attempt".to_owned(), "2".to_owned())
+            ]
+        );
+        assert!(Error::internal("x").context().is_empty());
+    }
+
+    #[test]
+    fn test_result_with_context() {
+        let io: std::result::Result<(), std::io::Error> =
+            Err(std::io::Error::new(std::io::ErrorKind::NotFound, "missing"));
+        let err = io.with_context("asset", "roads.bin").unwrap_err();
+        assert_eq!(err.category(), ErrorCategory::Io);
+        assert!(err.to_string().ends_with("[asset=roads.bin]"));
+
+        let ok: Result<u32> = Ok(3);
+        assert_eq!(ok.with_context("asset", "x").unwrap(), 3);
+    }
+
     // ConfigError tests
     #[test]
     fn test_config_error_file_not_found() {
@@ -0,0 +1,690 @@
+//! Memory tracking with per-subsystem accounting.
+//!
+//! [`TrackingAllocator`] wraps another [`GlobalAlloc`] and attributes every
+//! allocation to the [`MemoryCategory`] active on the allocating thread.
+//! Categories are selected with [`MemoryScope`] guards, and the allocator
+//! keeps current and peak byte counts for each of them. [`MemoryMonitor`]
+//! samples the allocator every frame and warns when a [`MemoryBudget`] is
+//! exceeded.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use amp_core::memory::{MemoryBudget, MemoryCategory, MemoryScope, TrackingAllocator};
+//!
+//! // In a binary this static would carry `#[global_allocator]`.
+//! static ALLOCATOR: TrackingAllocator = TrackingAllocator::new();
+//!
+//! {
+//!     let _scope = MemoryScope::enter(MemoryCategory::Streaming);
+//!     // Allocations made here are charged to streaming.
+//! }
+//!
+//! let snapshot = ALLOCATOR.snapshot();
+//! let budget = MemoryBudget::new().with_limit(MemoryCategory::Streaming, 512 * 1024 * 1024);
+//! for overrun in snapshot.overruns(&budget) {
+//!     eprintln!("warning: {overrun}");
+//! }
+//! ```
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Subsystem an allocation is charged to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum MemoryCategory {
+    /// Allocations made outside any explicit scope
+    General = 0,
+    /// World streaming and region loading
+    Streaming = 1,
+    /// GPU resources and render preparation
+    Rendering = 2,
+    /// Physics simulation
+    Physics = 3,
+    /// Audio playback and mixing
+    Audio = 4,
+    /// Gameplay logic and entity spawning
+    Gameplay = 5,
+}
+
+impl MemoryCategory {
+    /// Number of memory categories.
+    pub const COUNT: usize = 6;
+
+    /// All categories, ordered by their index.
+    pub const ALL: [MemoryCategory; Self::COUNT] = [
+        MemoryCategory::General,
+        MemoryCategory::Streaming,
+        MemoryCategory::Rendering,
+        MemoryCategory::Physics,
+        MemoryCategory::Audio,
+        MemoryCategory::Gameplay,
+    ];
+
+    /// Human-readable name of the category.
+    pub fn name(self) -> &'static str {
+        match self {
+            MemoryCategory::General => "general",
+            MemoryCategory::Streaming => "streaming",
+            MemoryCategory::Rendering => "rendering",
+            MemoryCategory::Physics => "physics",
+            MemoryCategory::Audio => "audio",
+            MemoryCategory::Gameplay => "gameplay",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn from_tag(tag: u8) -> Self {
+        Self::ALL
+            .get(tag as usize)
+            .copied()
+            .unwrap_or(MemoryCategory::General)
+    }
+}
+
+impl fmt::Display for MemoryCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+thread_local! {
+    static CURRENT_CATEGORY: Cell<MemoryCategory> = const { Cell::new(MemoryCategory::General) };
+}
+
+/// Category that allocations on the current thread are charged to.
+///
+/// Falls back to [`MemoryCategory::General`] while thread-local storage is
+/// being torn down.
+pub fn current_category() -> MemoryCategory {
+    CURRENT_CATEGORY
+        .try_with(Cell::get)
+        .unwrap_or(MemoryCategory::General)
+}
+
+/// Guard that charges allocations on the current thread to a category.
+///
+/// Scopes nest; dropping a guard restores the category that was active when
+/// it was entered.
+#[derive(Debug)]
+#[must_use = "allocations are only charged to the category while the scope is alive"]
+pub struct MemoryScope {
+    previous: MemoryCategory,
+    // Scopes manipulate thread-local state and must stay on their thread
+    _not_send: PhantomData<*const ()>,
+}
+
+impl MemoryScope {
+    /// Enter a scope charging allocations to `category`.
+    pub fn enter(category: MemoryCategory) -> Self {
+        let previous = CURRENT_CATEGORY
+            .try_with(|current| current.replace(category))
+            .unwrap_or(MemoryCategory::General);
+        Self {
+            previous,
+            _not_send: PhantomData,
+        }
+    }
+}
+
+impl Drop for MemoryScope {
+    fn drop(&mut self) {
+        let _ = CURRENT_CATEGORY.try_with(|current| current.set(self.previous));
+    }
+}
+
+const fn zeroed_counters() -> [AtomicUsize; MemoryCategory::COUNT] {
+    [
+        AtomicUsize::new(0),
+        AtomicUsize::new(0),
+        AtomicUsize::new(0),
+        AtomicUsize::new(0),
+        AtomicUsize::new(0),
+        AtomicUsize::new(0),
+    ]
+}
+
+/// Global allocator wrapper that accounts allocations per [`MemoryCategory`].
+///
+/// Each allocation is prefixed with a small header recording its category so
+/// that deallocations are charged back to the category that allocated,
+/// regardless of the scope active when memory is freed.
+#[derive(Debug)]
+pub struct TrackingAllocator<A = System> {
+    inner: A,
+    current: [AtomicUsize; MemoryCategory::COUNT],
+    peak: [AtomicUsize; MemoryCategory::COUNT],
+    live_allocations: [AtomicUsize; MemoryCategory::COUNT],
+}
+
+impl TrackingAllocator<System> {
+    /// Create a tracking allocator backed by the system allocator.
+    pub const fn new() -> Self {
+        Self::with_allocator(System)
+    }
+}
+
+impl Default for TrackingAllocator<System> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A> TrackingAllocator<A> {
+    /// Create a tracking allocator wrapping `inner`.
+    pub const fn with_allocator(inner: A) -> Self {
+        Self {
+            inner,
+            current: zeroed_counters(),
+            peak: zeroed_counters(),
+            live_allocations: zeroed_counters(),
+        }
+    }
+
+    /// Capture current and peak usage for every category.
+    pub fn snapshot(&self) -> MemorySnapshot {
+        MemorySnapshot {
+            usage: MemoryCategory::ALL.map(|category| {
+                let index = category.index();
+                CategoryUsage {
+                    category,
+                    current_bytes: self.current[index].load(Ordering::Relaxed),
+                    peak_bytes: self.peak[index].load(Ordering::Relaxed),
+                    live_allocations: self.live_allocations[index].load(Ordering::Relaxed),
+                }
+            }),
+        }
+    }
+
+    /// Reset peak usage to the current usage for every category.
+    pub fn reset_peaks(&self) {
+        for index in 0..MemoryCategory::COUNT {
+            let current = self.current[index].load(Ordering::Relaxed);
+            self.peak[index].store(current, Ordering::Relaxed);
+        }
+    }
+
+    fn record_alloc(&self, category: MemoryCategory, size: usize) {
+        let index = category.index();
+        let now = self.current[index].fetch_add(size, Ordering::Relaxed) + size;
+        self.peak[index].fetch_max(now, Ordering::Relaxed);
+        self.live_allocations[index].fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_dealloc(&self, category: MemoryCategory, size: usize) {
+        let index = category.index();
+        self.current[index].fetch_sub(size, Ordering::Relaxed);
+        self.live_allocations[index].fetch_sub(1, Ordering::Relaxed);
+    }
+
+    fn record_realloc(&self, category: MemoryCategory, old_size: usize, new_size: usize) {
+        let index = category.index();
+        if new_size >= old_size {
+            let grown = new_size - old_size;
+            let now = self.current[index].fetch_add(grown, Ordering::Relaxed) + grown;
+            self.peak[index].fetch_max(now, Ordering::Relaxed);
+        } else {
+            self.current[index].fetch_sub(old_size - new_size, Ordering::Relaxed);
+        }
+    }
+
+    /// Layout of the underlying block: the user layout preceded by a header
+    /// of `align` bytes, the last of which stores the category tag.
+    fn outer_layout(layout: Layout) -> Option<Layout> {
+        let size = layout.size().checked_add(layout.align())?;
+        Layout::from_size_align(size, layout.align()).ok()
+    }
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for TrackingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let Some(outer) = Self::outer_layout(layout) else {
+            return std::ptr::null_mut();
+        };
+        let base = self.inner.alloc(outer);
+        if base.is_null() {
+            return base;
+        }
+        let category = current_category();
+        let ptr = base.add(layout.align());
+        ptr.sub(1).write(category as u8);
+        self.record_alloc(category, layout.size());
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let Some(outer) = Self::outer_layout(layout) else {
+            return std::ptr::null_mut();
+        };
+        let base = self.inner.alloc_zeroed(outer);
+        if base.is_null() {
+            return base;
+        }
+        let category = current_category();
+        let ptr = base.add(layout.align());
+        ptr.sub(1).write(category as u8);
+        self.record_alloc(category, layout.size());
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let category = MemoryCategory::from_tag(ptr.sub(1).read());
+        let base = ptr.sub(layout.align());
+        // The layout was valid when the block was allocated
+        let outer = Self::outer_layout(layout).unwrap_unchecked();
+        self.inner.dealloc(base, outer);
+        self.record_dealloc(category, layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let Some(new_outer_size) = new_size.checked_add(layout.align()) else {
+            return std::ptr::null_mut();
+        };
+        let category = MemoryCategory::from_tag(ptr.sub(1).read());
+        let base = ptr.sub(layout.align());
+        let outer = Self::outer_layout(layout).unwrap_unchecked();
+        // The header is part of the block, so the tag survives the move
+        let new_base = self.inner.realloc(base, outer, new_outer_size);
+        if new_base.is_null() {
+            return new_base;
+        }
+        self.record_realloc(category, layout.size(), new_size);
+        new_base.add(layout.align())
+    }
+}
+
+/// Usage figures for a single category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CategoryUsage {
+    /// Category the figures belong to
+    pub category: MemoryCategory,
+    /// Bytes currently allocated
+    pub current_bytes: usize,
+    /// Highest number of bytes allocated at once since the last peak reset
+    pub peak_bytes: usize,
+    /// Number of allocations not yet freed
+    pub live_allocations: usize,
+}
+
+/// Point-in-time view of memory usage across all categories.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemorySnapshot {
+    usage: [CategoryUsage; MemoryCategory::COUNT],
+}
+
+impl MemorySnapshot {
+    /// Usage for a single category.
+    pub fn usage(&self, category: MemoryCategory) -> CategoryUsage {
+        self.usage[category.index()]
+    }
+
+    /// Usage for every category, ordered by category index.
+    pub fn iter(&self) -> impl Iterator<Item = &CategoryUsage> {
+        self.usage.iter()
+    }
+
+    /// Bytes currently allocated across all categories.
+    pub fn total_bytes(&self) -> usize {
+        self.usage.iter().map(|usage| usage.current_bytes).sum()
+    }
+
+    /// Categories whose current usage exceeds their budget.
+    pub fn overruns(&self, budget: &MemoryBudget) -> Vec<BudgetOverrun> {
+        self.usage
+            .iter()
+            .filter_map(|usage| {
+                let limit_bytes = budget.limit(usage.category)?;
+                (usage.current_bytes > limit_bytes).then_some(BudgetOverrun {
+                    category: usage.category,
+                    current_bytes: usage.current_bytes,
+                    limit_bytes,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Per-category byte limits.
+///
+/// Categories without a limit are never reported as over budget.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemoryBudget {
+    limits: [Option<usize>; MemoryCategory::COUNT],
+}
+
+impl MemoryBudget {
+    /// Create a budget without any limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the limit for a category.
+    pub fn with_limit(mut self, category: MemoryCategory, bytes: usize) -> Self {
+        self.set_limit(category, Some(bytes));
+        self
+    }
+
+    /// Set or clear the limit for a category.
+    pub fn set_limit(&mut self, category: MemoryCategory, bytes: Option<usize>) {
+        self.limits[category.index()] = bytes;
+    }
+
+    /// Limit for a category, if any.
+    pub fn limit(&self, category: MemoryCategory) -> Option<usize> {
+        self.limits[category.index()]
+    }
+}
+
+/// A category whose usage exceeds its budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BudgetOverrun {
+    /// Category over budget
+    pub category: MemoryCategory,
+    /// Bytes currently allocated
+    pub current_bytes: usize,
+    /// Configured limit in bytes
+    pub limit_bytes: usize,
+}
+
+impl fmt::Display for BudgetOverrun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} memory over budget: {} bytes allocated, limit {} bytes",
+            self.category, self.current_bytes, self.limit_bytes
+        )
+    }
+}
+
+/// Memory usage sampled once per frame, checked against a budget.
+///
+/// With the `bevy_ecs` feature this is a resource kept current by
+/// [`update_memory_monitor`]. A warning is logged when a category goes over
+/// its limit, and again only after it has dropped back under the limit and
+/// crossed it once more.
+#[derive(Debug)]
+#[cfg_attr(feature = "bevy_ecs", derive(bevy_ecs::system::Resource))]
+pub struct MemoryMonitor {
+    allocator: &'static TrackingAllocator,
+    budget: MemoryBudget,
+    snapshot: MemorySnapshot,
+    over_budget: [bool; MemoryCategory::COUNT],
+}
+
+impl MemoryMonitor {
+    /// Monitor `allocator`, usually the `#[global_allocator]` static.
+    pub fn new(allocator: &'static TrackingAllocator, budget: MemoryBudget) -> Self {
+        Self {
+            allocator,
+            budget,
+            snapshot: allocator.snapshot(),
+            over_budget: [false; MemoryCategory::COUNT],
+        }
+    }
+
+    /// Usage as of the last update.
+    pub fn snapshot(&self) -> &MemorySnapshot {
+        &self.snapshot
+    }
+
+    /// Budget checked on every update.
+    pub fn budget(&self) -> &MemoryBudget {
+        &self.budget
+    }
+
+    /// Replace the budget; categories are re-checked on the next update.
+    pub fn set_budget(&mut self, budget: MemoryBudget) {
+        self.budget = budget;
+    }
+
+    /// Whether a category was over budget at the last update.
+    pub fn is_over_budget(&self, category: MemoryCategory) -> bool {
+        self.over_budget[category.index()]
+    }
+
+    /// Take a new snapshot and return the categories that went over budget
+    /// since the previous update.
+    pub fn update(&mut self) -> Vec<BudgetOverrun> {
+        self.snapshot = self.allocator.snapshot();
+        let overruns = self.snapshot.overruns(&self.budget);
+
+        let mut over_budget = [false; MemoryCategory::COUNT];
+        for overrun in &overruns {
+            over_budget[overrun.category.index()] = true;
+        }
+        let crossed = overruns
+            .into_iter()
+            .filter(|overrun| !self.over_budget[overrun.category.index()])
+            .collect();
+        self.over_budget = over_budget;
+        crossed
+    }
+}
+
+/// Refresh the [`MemoryMonitor`] and warn about new budget overruns.
+#[cfg(feature = "bevy_ecs")]
+pub fn update_memory_monitor(mut monitor: bevy_ecs::system::ResMut<MemoryMonitor>) {
+    for overrun in monitor.update() {
+        log::warn!("{overrun}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alloc_in(
+        allocator: &TrackingAllocator,
+        category: MemoryCategory,
+        layout: Layout,
+    ) -> *mut u8 {
+        let _scope = MemoryScope::enter(category);
+        let ptr = unsafe { allocator.alloc(layout) };
+        assert!(!ptr.is_null());
+        ptr
+    }
+
+    #[test]
+    fn test_allocation_charged_to_scope() {
+        let allocator = TrackingAllocator::new();
+        let layout = Layout::from_size_align(128, 8).unwrap();
+
+        let ptr = alloc_in(&allocator, MemoryCategory::Physics, layout);
+        let usage = allocator.snapshot().usage(MemoryCategory::Physics);
+        assert_eq!(usage.current_bytes, 128);
+        assert_eq!(usage.live_allocations, 1);
+        assert_eq!(
+            allocator
+                .snapshot()
+                .usage(MemoryCategory::General)
+                .current_bytes,
+            0
+        );
+
+        unsafe { allocator.dealloc(ptr, layout) };
+        let usage = allocator.snapshot().usage(MemoryCategory::Physics);
+        assert_eq!(usage.current_bytes, 0);
+        assert_eq!(usage.peak_bytes, 128);
+        assert_eq!(usage.live_allocations, 0);
+    }
+
+    #[test]
+    fn test_dealloc_charged_to_allocating_category() {
+        let allocator = TrackingAllocator::new();
+        let layout = Layout::from_size_align(64, 8).unwrap();
+
+        let ptr = alloc_in(&allocator, MemoryCategory::Audio, layout);
+        {
+            let _scope = MemoryScope::enter(MemoryCategory::Rendering);
+            unsafe { allocator.dealloc(ptr, layout) };
+        }
+
+        let snapshot = allocator.snapshot();
+        assert_eq!(snapshot.usage(MemoryCategory::Audio).current_bytes, 0);
+        assert_eq!(snapshot.usage(MemoryCategory::Rendering).current_bytes, 0);
+    }
+
+    #[test]
+    fn test_realloc_preserves_category_and_alignment() {
+        let allocator = TrackingAllocator::new();
+        let layout = Layout::from_size_align(32, 64).unwrap();
+
+        let ptr = alloc_in(&allocator, MemoryCategory::Streaming, layout);
+        assert_eq!(ptr as usize % 64, 0);
+        unsafe { ptr.write_bytes(0xAB, 32) };
+
+        let grown = unsafe { allocator.realloc(ptr, layout, 256) };
+        assert!(!grown.is_null());
+        assert_eq!(grown as usize % 64, 0);
+        assert_eq!(unsafe { grown.read() }, 0xAB);
+
+        let usage = allocator.snapshot().usage(MemoryCategory::Streaming);
+        assert_eq!(usage.current_bytes, 256);
+        assert_eq!(usage.peak_bytes, 256);
+
+        let grown_layout = Layout::from_size_align(256, 64).unwrap();
+        unsafe { allocator.dealloc(grown, grown_layout) };
+        assert_eq!(
+            allocator
+                .snapshot()
+                .usage(MemoryCategory::Streaming)
+                .current_bytes,
+            0
+        );
+    }
+
+    #[test]
+    fn test_scopes_nest_and_restore() {
+        assert_eq!(current_category(), MemoryCategory::General);
+        {
+            let _outer = MemoryScope::enter(MemoryCategory::Gameplay);
+            {
+                let _inner = MemoryScope::enter(MemoryCategory::Physics);
+                assert_eq!(current_category(), MemoryCategory::Physics);
+            }
+            assert_eq!(current_category(), MemoryCategory::Gameplay);
+        }
+        assert_eq!(current_category(), MemoryCategory::General);
+    }
+
+    #[test]
+    fn test_reset_peaks() {
+        let allocator = TrackingAllocator::new();
+        let layout = Layout::from_size_align(512, 8).unwrap();
+
+        let ptr = alloc_in(&allocator, MemoryCategory::Rendering, layout);
+        unsafe { allocator.dealloc(ptr, layout) };
+        assert_eq!(
+            allocator
+                .snapshot()
+                .usage(MemoryCategory::Rendering)
+                .peak_bytes,
+            512
+        );
+
+        allocator.reset_peaks();
+        assert_eq!(
+            allocator
+                .snapshot()
+                .usage(MemoryCategory::Rendering)
+                .peak_bytes,
+            0
+        );
+    }
+
+    #[test]
+    fn test_budget_overruns() {
+        let allocator = TrackingAllocator::new();
+        let layout = Layout::from_size_align(1024, 8).unwrap();
+        let ptr = alloc_in(&allocator, MemoryCategory::Streaming, layout);
+
+        let snapshot = allocator.snapshot();
+        let budget = MemoryBudget::new()
+            .with_limit(MemoryCategory::Streaming, 512)
+            .with_limit(MemoryCategory::Rendering, 512);
+        let overruns = snapshot.overruns(&budget);
+
+        assert_eq!(overruns.len(), 1);
+        assert_eq!(overruns[0].category, MemoryCategory::Streaming);
+        assert_eq!(overruns[0].current_bytes, 1024);
+        assert_eq!(overruns[0].limit_bytes, 512);
+        assert_eq!(
+            overruns[0].to_string(),
+            "streaming memory over budget: 1024 bytes allocated, limit 512 bytes"
+        );
+        assert!(snapshot.overruns(&MemoryBudget::new()).is_empty());
+
+        unsafe { allocator.dealloc(ptr, layout) };
+    }
+
+    #[test]
+    fn test_monitor_reports_each_crossing_once() {
+        let allocator: &'static TrackingAllocator = Box::leak(Box::new(TrackingAllocator::new()));
+        let budget = MemoryBudget::new().with_limit(MemoryCategory::Audio, 100);
+        let mut monitor = MemoryMonitor::new(allocator, budget);
+        let layout = Layout::from_size_align(256, 8).unwrap();
+
+        assert!(monitor.update().is_empty());
+
+        let ptr = alloc_in(allocator, MemoryCategory::Audio, layout);
+        let crossed = monitor.update();
+        assert_eq!(crossed.len(), 1);
+        assert_eq!(crossed[0].category, MemoryCategory::Audio);
+        assert!(monitor.is_over_budget(MemoryCategory::Audio));
+        assert_eq!(
+            monitor
+                .snapshot()
+                .usage(MemoryCategory::Audio)
+                .current_bytes,
+            256
+        );
+
+        // Still over budget, so no new warning
+        assert!(monitor.update().is_empty());
+
+        unsafe { allocator.dealloc(ptr, layout) };
+        assert!(monitor.update().is_empty());
+        assert!(!monitor.is_over_budget(MemoryCategory::Audio));
+
+        let ptr = alloc_in(allocator, MemoryCategory::Audio, layout);
+        assert_eq!(monitor.update().len(), 1);
+        unsafe { allocator.dealloc(ptr, layout) };
+    }
+
+    #[cfg(feature = "bevy_ecs")]
+    #[test]
+    fn test_monitor_system_updates_snapshot() {
+        use bevy_ecs::prelude::*;
+        use bevy_ecs::system::RunSystemOnce;
+
+        let allocator: &'static TrackingAllocator = Box::leak(Box::new(TrackingAllocator::new()));
+        let mut world = World::new();
+        world.insert_resource(MemoryMonitor::new(allocator, MemoryBudget::new()));
+
+        let layout = Layout::from_size_align(64, 8).unwrap();
+        let ptr = alloc_in(allocator, MemoryCategory::Physics, layout);
+        world.run_system_once(update_memory_monitor);
+
+        let monitor = world.resource::<MemoryMonitor>();
+        assert_eq!(
+            monitor
+                .snapshot()
+                .usage(MemoryCategory::Physics)
+                .current_bytes,
+            64
+        );
+        unsafe { allocator.dealloc(ptr, layout) };
+    }
+}
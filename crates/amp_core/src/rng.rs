@@ -0,0 +1,167 @@
+//! Seeded random number streams.
+//!
+//! Every random decision in the game draws from a named stream of an
+//! [`RngService`], so a session is reproducible from its world seed. Streams
+//! are independent: drawing more traffic numbers never shifts the sequence
+//! world generation sees. Each stream is a ChaCha8 generator keyed by the
+//! world seed, with the stream name selecting the ChaCha stream.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use amp_core::rng::{streams, RngService};
+//! use rand_chacha::rand_core::RngCore;
+//!
+//! let mut a = RngService::new(42);
+//! let mut b = RngService::new(42);
+//!
+//! // Using one stream does not disturb another
+//! a.stream(streams::TRAFFIC).next_u64();
+//! assert_eq!(
+//!     a.stream(streams::WORLDGEN).next_u64(),
+//!     b.stream(streams::WORLDGEN).next_u64()
+//! );
+//! ```
+
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::collections::HashMap;
+
+/// Names of the standard streams.
+pub mod streams {
+    /// World and city generation
+    pub const WORLDGEN: &str = "worldgen";
+    /// Traffic and pedestrian spawning and behavior
+    pub const TRAFFIC: &str = "traffic";
+    /// Sound variation such as pitch and sample choice
+    pub const AUDIO_VARIATION: &str = "audio-variation";
+    /// Gameplay outcomes such as loot and mission variation
+    pub const GAMEPLAY: &str = "gameplay";
+}
+
+/// Named, independently seeded random streams derived from a world seed.
+#[cfg_attr(feature = "bevy_ecs", derive(bevy_ecs::system::Resource))]
+#[derive(Debug, Clone)]
+pub struct RngService {
+    seed: u64,
+    streams: HashMap<String, ChaCha8Rng>,
+}
+
+impl RngService {
+    /// Create a service for a world seed.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            streams: HashMap::new(),
+        }
+    }
+
+    /// The world seed all streams derive from.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Restart every stream from a new world seed.
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.streams.clear();
+    }
+
+    /// The generator of a named stream, created on first use.
+    pub fn stream(&mut self, name: &str) -> &mut ChaCha8Rng {
+        let seed = self.seed;
+        self.streams
+            .entry(name.to_owned())
+            .or_insert_with(|| stream_rng(seed, name))
+    }
+
+    /// The [`streams::WORLDGEN`] stream.
+    pub fn worldgen(&mut self) -> &mut ChaCha8Rng {
+        self.stream(streams::WORLDGEN)
+    }
+
+    /// The [`streams::TRAFFIC`] stream.
+    pub fn traffic(&mut self) -> &mut ChaCha8Rng {
+        self.stream(streams::TRAFFIC)
+    }
+
+    /// The [`streams::AUDIO_VARIATION`] stream.
+    pub fn audio_variation(&mut self) -> &mut ChaCha8Rng {
+        self.stream(streams::AUDIO_VARIATION)
+    }
+
+    /// The [`streams::GAMEPLAY`] stream.
+    pub fn gameplay(&mut self) -> &mut ChaCha8Rng {
+        self.stream(streams::GAMEPLAY)
+    }
+
+    /// A fresh generator for one item of a stream, such as a sector.
+    ///
+    /// The result depends only on the world seed, the stream name and `key`,
+    /// not on how much any stream has been used, so content generated per
+    /// item is the same in whatever order items are visited.
+    pub fn fork(&self, name: &str, key: u64) -> ChaCha8Rng {
+        stream_rng(splitmix64(self.seed ^ splitmix64(key)), name)
+    }
+}
+
+fn stream_rng(seed: u64, name: &str) -> ChaCha8Rng {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    rng.set_stream(fnv1a(name));
+    rng
+}
+
+/// 64-bit FNV-1a hash, stable across builds and platforms.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::rand_core::RngCore;
+
+    fn draw(rng: &mut ChaCha8Rng) -> Vec<u64> {
+        (0..4).map(|_| rng.next_u64()).collect()
+    }
+
+    #[test]
+    fn test_streams_are_reproducible_and_independent() {
+        let mut a = RngService::new(7);
+        let mut b = RngService::new(7);
+
+        draw(a.traffic());
+        assert_eq!(draw(a.worldgen()), draw(b.worldgen()));
+        assert_ne!(draw(a.gameplay()), draw(a.audio_variation()));
+
+        let mut other_seed = RngService::new(8);
+        assert_ne!(draw(b.gameplay()), draw(other_seed.gameplay()));
+    }
+
+    #[test]
+    fn test_reseed_restarts_streams() {
+        let mut service = RngService::new(1);
+        let first = draw(service.worldgen());
+        service.reseed(1);
+        assert_eq!(draw(service.worldgen()), first);
+    }
+
+    #[test]
+    fn test_fork_depends_only_on_seed_name_and_key() {
+        let mut service = RngService::new(3);
+        let before = draw(&mut service.fork(streams::WORLDGEN, 12));
+        draw(service.worldgen());
+        assert_eq!(draw(&mut service.fork(streams::WORLDGEN, 12)), before);
+        assert_ne!(draw(&mut service.fork(streams::WORLDGEN, 13)), before);
+        assert_ne!(draw(&mut service.fork(streams::TRAFFIC, 12)), before);
+    }
+}
@@ -0,0 +1,231 @@
+//! Structured telemetry export.
+//!
+//! Systems record named metrics into a [`MetricsFrame`] each frame, and a
+//! [`JsonLinesExporter`] periodically appends frames as JSON lines so long
+//! soak sessions can be graphed with external tools.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use amp_core::telemetry::{JsonLinesExporter, MetricsFrame};
+//! use std::time::Duration;
+//!
+//! let mut exporter = JsonLinesExporter::new(Vec::new(), Duration::from_secs(1));
+//!
+//! let mut frame = MetricsFrame::new(120);
+//! frame.record("frame_ms", 16.4);
+//! frame.record("physics_ms", 2.1);
+//! frame.record("streaming.sectors_loaded", 37.0);
+//!
+//! assert!(exporter.export(&frame).unwrap());
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Metrics captured for a single frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricsFrame {
+    /// Frame number the metrics belong to
+    pub frame: u64,
+    /// Milliseconds since the Unix epoch when the frame was created
+    pub timestamp_ms: u64,
+    metrics: BTreeMap<String, f64>,
+}
+
+impl MetricsFrame {
+    /// Create an empty frame stamped with the current time.
+    pub fn new(frame: u64) -> Self {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or_default();
+        Self::with_timestamp(frame, timestamp_ms)
+    }
+
+    /// Create an empty frame with an explicit timestamp.
+    pub fn with_timestamp(frame: u64, timestamp_ms: u64) -> Self {
+        Self {
+            frame,
+            timestamp_ms,
+            metrics: BTreeMap::new(),
+        }
+    }
+
+    /// Record a metric, replacing any previous value with the same name.
+    pub fn record(&mut self, name: impl Into<String>, value: f64) {
+        self.metrics.insert(name.into(), value);
+    }
+
+    /// Get a recorded metric.
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.metrics.get(name).copied()
+    }
+
+    /// Iterate over recorded metrics, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f64)> {
+        self.metrics
+            .iter()
+            .map(|(name, value)| (name.as_str(), *value))
+    }
+
+    /// Render the frame as a single JSON object without a trailing newline.
+    ///
+    /// Non-finite values are written as `null`.
+    pub fn to_json_line(&self) -> String {
+        let mut line = format!(
+            "{{\"frame\":{},\"timestamp_ms\":{},\"metrics\":{{",
+            self.frame, self.timestamp_ms
+        );
+        for (index, (name, value)) in self.metrics.iter().enumerate() {
+            if index > 0 {
+                line.push(',');
+            }
+            write_json_string(&mut line, name);
+            line.push(':');
+            if value.is_finite() {
+                let _ = write!(line, "{value}");
+            } else {
+                line.push_str("null");
+            }
+        }
+        line.push_str("}}");
+        line
+    }
+}
+
+fn write_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if u32::from(ch) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", u32::from(ch));
+            }
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+}
+
+/// Writes [`MetricsFrame`]s as JSON lines at a fixed interval.
+#[derive(Debug)]
+pub struct JsonLinesExporter<W: Write> {
+    writer: W,
+    interval: Duration,
+    last_export: Option<Instant>,
+}
+
+impl JsonLinesExporter<BufWriter<File>> {
+    /// Create an exporter appending to the file at `path`.
+    pub fn append_to_file(path: impl AsRef<Path>, interval: Duration) -> crate::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::new(BufWriter::new(file), interval))
+    }
+}
+
+impl<W: Write> JsonLinesExporter<W> {
+    /// Create an exporter writing to `writer` at most once per `interval`.
+    pub fn new(writer: W, interval: Duration) -> Self {
+        Self {
+            writer,
+            interval,
+            last_export: None,
+        }
+    }
+
+    /// Export `frame` if the interval has elapsed since the last export.
+    ///
+    /// Returns `true` if the frame was written.
+    pub fn export(&mut self, frame: &MetricsFrame) -> crate::Result<bool> {
+        let due = self
+            .last_export
+            .map_or(true, |last| last.elapsed() >= self.interval);
+        if !due {
+            return Ok(false);
+        }
+        self.export_now(frame)?;
+        Ok(true)
+    }
+
+    /// Export `frame` immediately, regardless of the interval.
+    pub fn export_now(&mut self, frame: &MetricsFrame) -> crate::Result<()> {
+        writeln!(self.writer, "{}", frame.to_json_line())?;
+        self.last_export = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Flush buffered output.
+    pub fn flush(&mut self) -> crate::Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Consume the exporter and return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_line_format() {
+        let mut frame = MetricsFrame::with_timestamp(7, 1_000);
+        frame.record("render_ms", 4.5);
+        frame.record("frame_ms", 16.0);
+        frame.record("broken", f64::NAN);
+
+        assert_eq!(
+            frame.to_json_line(),
+            r#"{"frame":7,"timestamp_ms":1000,"metrics":{"broken":null,"frame_ms":16,"render_ms":4.5}}"#
+        );
+    }
+
+    #[test]
+    fn test_metric_names_are_escaped() {
+        let mut frame = MetricsFrame::with_timestamp(0, 0);
+        frame.record("a\"b\\c\n", 1.0);
+
+        assert_eq!(
+            frame.to_json_line(),
+            r#"{"frame":0,"timestamp_ms":0,"metrics":{"a\"b\\c\n":1}}"#
+        );
+    }
+
+    #[test]
+    fn test_export_respects_interval() {
+        let mut exporter = JsonLinesExporter::new(Vec::new(), Duration::from_secs(3600));
+        let frame = MetricsFrame::with_timestamp(1, 0);
+
+        assert!(exporter.export(&frame).unwrap());
+        assert!(!exporter.export(&frame).unwrap());
+        exporter.export_now(&frame).unwrap();
+
+        let output = String::from_utf8(exporter.into_inner()).unwrap();
+        assert_eq!(output.lines().count(), 2);
+    }
+
+    #[test]
+    fn test_zero_interval_exports_every_frame() {
+        let mut exporter = JsonLinesExporter::new(Vec::new(), Duration::ZERO);
+        for frame in 0..3 {
+            assert!(exporter
+                .export(&MetricsFrame::with_timestamp(frame, 0))
+                .unwrap());
+        }
+
+        let output = String::from_utf8(exporter.into_inner()).unwrap();
+        assert_eq!(output.lines().count(), 3);
+    }
+}
@@ -0,0 +1,351 @@
+//! Weighted random selection.
+//!
+//! Spawning picks building types, props and traffic vehicles with different
+//! frequencies. A [`WeightedTable`] pairs items with relative weights and
+//! samples them in constant time through an [`AliasTable`]. Tables are
+//! usually defined in RON as a list of `(item: ..., weight: ...)` entries and
+//! sampled from a stream of an [`RngService`](crate::rng::RngService), so
+//! picks are reproducible from the world seed.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use amp_core::rng::RngService;
+//! use amp_core::weighted::{Weighted, WeightedTable};
+//!
+//! let table = WeightedTable::new(vec![
+//!     Weighted::new("apartment", 3.0),
+//!     Weighted::new("shop", 1.0),
+//! ])
+//! .unwrap();
+//!
+//! let mut rng = RngService::new(42);
+//! let building = table.choose(rng.worldgen());
+//! assert!(["apartment", "shop"].contains(building));
+//! ```
+
+use crate::{Error, Result};
+use rand_chacha::rand_core::RngCore;
+
+/// An item with a relative weight.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Weighted<T> {
+    /// The item
+    pub item: T,
+    /// Relative weight; zero means never chosen
+    pub weight: f32,
+}
+
+impl<T> Weighted<T> {
+    /// Pair an item with a weight.
+    pub fn new(item: T, weight: f32) -> Self {
+        Self { item, weight }
+    }
+}
+
+/// Samples indices in proportion to their weights in constant time.
+///
+/// Built with Vose's alias method: each index owns a slot holding the
+/// probability of keeping it and an alias to use otherwise, so a sample is
+/// one uniform slot choice and one comparison.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AliasTable {
+    probabilities: Vec<f64>,
+    aliases: Vec<usize>,
+    weights: Vec<f64>,
+}
+
+impl AliasTable {
+    /// Build a table from relative weights.
+    ///
+    /// Fails if there are no weights, any weight is negative or not finite,
+    /// or all weights are zero.
+    pub fn new(weights: &[f32]) -> Result<Self> {
+        let total = validate_weights(weights.iter().copied())?;
+        let count = weights.len();
+        let weights: Vec<f64> = weights.iter().map(|&w| f64::from(w) / total).collect();
+
+        let mut scaled: Vec<f64> = weights.iter().map(|w| w * count as f64).collect();
+        let mut probabilities = vec![1.0; count];
+        let mut aliases: Vec<usize> = (0..count).collect();
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..count).partition(|&i| scaled[i] < 1.0);
+
+        while let (Some(&less), Some(&more)) = (small.last(), large.last()) {
+            small.pop();
+            probabilities[less] = scaled[less];
+            aliases[less] = more;
+            scaled[more] -= 1.0 - scaled[less];
+            if scaled[more] < 1.0 {
+                large.pop();
+                small.push(more);
+            }
+        }
+        // Whatever is left is 1 up to rounding error.
+        for i in small.into_iter().chain(large) {
+            probabilities[i] = 1.0;
+        }
+
+        Ok(Self {
+            probabilities,
+            aliases,
+            weights,
+        })
+    }
+
+    /// Number of indices in the table.
+    pub fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    /// Whether the table is empty; never true for a successfully built table.
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    /// Probability of sampling `index`.
+    pub fn probability(&self, index: usize) -> f64 {
+        self.weights.get(index).copied().unwrap_or(0.0)
+    }
+
+    /// Sample an index.
+    pub fn sample<R: RngCore + ?Sized>(&self, rng: &mut R) -> usize {
+        let slot = uniform_index(rng, self.len());
+        if unit_f64(rng) < self.probabilities[slot] {
+            slot
+        } else {
+            self.aliases[slot]
+        }
+    }
+}
+
+/// Items with relative weights, sampled in constant time.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "Vec<Weighted<T>>",
+        into = "Vec<Weighted<T>>",
+        bound(
+            serialize = "T: Clone + serde::Serialize",
+            deserialize = "T: serde::Deserialize<'de>"
+        )
+    )
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightedTable<T> {
+    entries: Vec<Weighted<T>>,
+    alias: AliasTable,
+}
+
+impl<T> WeightedTable<T> {
+    /// Build a table; fails under the same conditions as [`AliasTable::new`].
+    pub fn new(entries: Vec<Weighted<T>>) -> Result<Self> {
+        let weights: Vec<f32> = entries.iter().map(|entry| entry.weight).collect();
+        let alias = AliasTable::new(&weights)?;
+        Ok(Self { entries, alias })
+    }
+
+    /// The entries in the order they were defined.
+    pub fn entries(&self) -> &[Weighted<T>] {
+        &self.entries
+    }
+
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the table is empty; never true for a successfully built table.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Probability of choosing the entry at `index`.
+    pub fn probability(&self, index: usize) -> f64 {
+        self.alias.probability(index)
+    }
+
+    /// Choose an item.
+    pub fn choose<R: RngCore + ?Sized>(&self, rng: &mut R) -> &T {
+        &self.entries[self.alias.sample(rng)].item
+    }
+
+    /// Iterate over an endless sequence of choices.
+    pub fn sample_iter<'a, R: RngCore + ?Sized>(
+        &'a self,
+        rng: &'a mut R,
+    ) -> impl Iterator<Item = &'a T> + 'a {
+        std::iter::repeat_with(move || self.choose(rng))
+    }
+}
+
+impl<T> TryFrom<Vec<Weighted<T>>> for WeightedTable<T> {
+    type Error = Error;
+
+    fn try_from(entries: Vec<Weighted<T>>) -> Result<Self> {
+        Self::new(entries)
+    }
+}
+
+impl<T> From<WeightedTable<T>> for Vec<Weighted<T>> {
+    fn from(table: WeightedTable<T>) -> Self {
+        table.entries
+    }
+}
+
+/// Choose one item with a weight function, without building a table.
+///
+/// Takes linear time; prefer a [`WeightedTable`] for repeated choices from the
+/// same items. Returns `None` if there are no items, or no item has a positive
+/// finite weight. Items with negative or non-finite weights are skipped.
+pub fn choose_weighted<'a, T, R: RngCore + ?Sized>(
+    items: &'a [T],
+    weight: impl Fn(&T) -> f32,
+    rng: &mut R,
+) -> Option<&'a T> {
+    let usable = |item: &T| {
+        let w = weight(item);
+        if w.is_finite() && w > 0.0 {
+            f64::from(w)
+        } else {
+            0.0
+        }
+    };
+    let total: f64 = items.iter().map(usable).sum();
+    if total <= 0.0 {
+        return None;
+    }
+
+    let mut target = unit_f64(rng) * total;
+    let mut last = None;
+    for item in items {
+        let w = usable(item);
+        if w > 0.0 {
+            if target < w {
+                return Some(item);
+            }
+            target -= w;
+            last = Some(item);
+        }
+    }
+    // Rounding can leave a sliver past the last positive weight.
+    last
+}
+
+fn validate_weights(weights: impl Iterator<Item = f32>) -> Result<f64> {
+    let mut total = 0.0;
+    let mut count = 0;
+    for weight in weights {
+        if !weight.is_finite() || weight < 0.0 {
+            return Err(Error::validation(format!(
+                "weight {weight} must be finite and non-negative"
+            )));
+        }
+        total += f64::from(weight);
+        count += 1;
+    }
+    if count == 0 {
+        return Err(Error::validation("weight table is empty"));
+    }
+    if total <= 0.0 {
+        return Err(Error::validation("weight table has no positive weights"));
+    }
+    Ok(total)
+}
+
+/// Uniform index below `len` using Lemire's multiply-shift.
+fn uniform_index<R: RngCore + ?Sized>(rng: &mut R, len: usize) -> usize {
+    ((u128::from(rng.next_u64()) * len as u128) >> 64) as usize
+}
+
+/// Uniform float in `[0, 1)` from the top 53 bits.
+fn unit_f64<R: RngCore + ?Sized>(rng: &mut R) -> f64 {
+    (rng.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::RngService;
+
+    fn frequencies(table: &AliasTable, draws: usize) -> Vec<f64> {
+        let mut rng = RngService::new(9);
+        let mut counts = vec![0usize; table.len()];
+        for _ in 0..draws {
+            counts[table.sample(rng.worldgen())] += 1;
+        }
+        counts.iter().map(|&c| c as f64 / draws as f64).collect()
+    }
+
+    #[test]
+    fn test_alias_table_matches_weights() {
+        let table = AliasTable::new(&[1.0, 0.0, 3.0, 4.0]).unwrap();
+        assert_eq!(table.probability(2), 0.375);
+
+        let observed = frequencies(&table, 80_000);
+        for (i, expected) in [0.125, 0.0, 0.375, 0.5].into_iter().enumerate() {
+            assert!(
+                (observed[i] - expected).abs() < 0.01,
+                "index {i}: {} vs {expected}",
+                observed[i]
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_weights_are_rejected() {
+        assert!(AliasTable::new(&[]).is_err());
+        assert!(AliasTable::new(&[0.0, 0.0]).is_err());
+        assert!(AliasTable::new(&[1.0, -1.0]).is_err());
+        assert!(AliasTable::new(&[1.0, f32::NAN]).is_err());
+        assert!(AliasTable::new(&[2.0]).is_ok());
+    }
+
+    #[test]
+    fn test_table_choices_are_reproducible() {
+        let table = WeightedTable::new(vec![
+            Weighted::new("sedan", 5.0),
+            Weighted::new("truck", 1.0),
+            Weighted::new("bus", 0.5),
+        ])
+        .unwrap();
+
+        let mut a = RngService::new(1);
+        let mut b = RngService::new(1);
+        let first: Vec<_> = table.sample_iter(a.traffic()).take(16).collect();
+        let second: Vec<_> = table.sample_iter(b.traffic()).take(16).collect();
+        assert_eq!(first, second);
+        assert!(first.contains(&&"sedan"));
+    }
+
+    #[test]
+    fn test_choose_weighted_skips_unusable_weights() {
+        let items = [("never", 0.0), ("bad", f32::NAN), ("always", 2.0)];
+        let mut rng = RngService::new(5);
+        for _ in 0..32 {
+            let item = choose_weighted(&items, |item| item.1, rng.gameplay());
+            assert_eq!(item.map(|item| item.0), Some("always"));
+        }
+        assert!(choose_weighted(&items[..2], |item| item.1, rng.gameplay()).is_none());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_table_from_ron() {
+        let table: WeightedTable<String> =
+            ron::from_str(r#"[(item: "house", weight: 3.0), (item: "tower", weight: 1.0)]"#)
+                .unwrap();
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.probability(1), 0.25);
+
+        let text = ron::to_string(&table).unwrap();
+        assert_eq!(
+            ron::from_str::<WeightedTable<String>>(&text).unwrap(),
+            table
+        );
+
+        assert!(ron::from_str::<WeightedTable<String>>("[]").is_err());
+    }
+}
@@ -0,0 +1,304 @@
+//! Debug overlay toggles
+//!
+//! Debug visualizations register a named toggle in [`DebugOverlays`] and
+//! draw only while it is enabled. Toggles can be bound to a function key,
+//! flipped by console commands through [`DebugOverlays::run_command`], and
+//! the enabled ones are listed in an on-screen legend.
+
+use bevy_ecs::prelude::*;
+use bevy_input::keyboard::KeyCode;
+use bevy_input::ButtonInput;
+use std::fmt;
+
+/// Physics collider shapes
+pub const COLLIDERS: &str = "colliders";
+/// World streaming sector bounds
+pub const SECTOR_BOUNDS: &str = "sectors";
+/// Navigation mesh
+pub const NAVMESH: &str = "navmesh";
+/// Culling statistics
+pub const CULLING_STATS: &str = "culling";
+/// Road graph nodes and edges
+pub const ROAD_GRAPH: &str = "roads";
+
+/// A debug overlay that can be toggled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugOverlay {
+    /// Name used by console commands and checks
+    pub name: String,
+    /// Short description shown in the legend and command help
+    pub description: String,
+    /// Key that toggles the overlay
+    pub key: Option<KeyCode>,
+    /// Whether the overlay is drawn
+    pub enabled: bool,
+}
+
+/// Error from a debug overlay console command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OverlayCommandError {
+    /// The command isn't an overlay command
+    UnknownCommand(String),
+    /// No overlay has the name
+    UnknownOverlay(String),
+    /// The arguments don't match `overlay <name> [on|off|toggle]`
+    Usage,
+}
+
+impl fmt::Display for OverlayCommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownCommand(command) => write!(f, "unknown command '{command}'"),
+            Self::UnknownOverlay(name) => write!(f, "unknown overlay '{name}'"),
+            Self::Usage => write!(f, "usage: overlay list | overlay <name> [on|off|toggle]"),
+        }
+    }
+}
+
+impl std::error::Error for OverlayCommandError {}
+
+/// Registered debug overlays and whether each is drawn
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct DebugOverlays {
+    overlays: Vec<DebugOverlay>,
+    /// Whether the legend of enabled overlays is shown
+    pub legend_visible: bool,
+}
+
+impl Default for DebugOverlays {
+    /// Create the engine's overlays, bound to F1 through F5 and disabled
+    fn default() -> Self {
+        let mut overlays = Self::empty();
+        overlays.register(COLLIDERS, "Physics colliders", Some(KeyCode::F1));
+        overlays.register(SECTOR_BOUNDS, "Sector bounds", Some(KeyCode::F2));
+        overlays.register(NAVMESH, "Navigation mesh", Some(KeyCode::F3));
+        overlays.register(CULLING_STATS, "Culling statistics", Some(KeyCode::F4));
+        overlays.register(ROAD_GRAPH, "Road graph", Some(KeyCode::F5));
+        overlays
+    }
+}
+
+impl DebugOverlays {
+    /// Create without any overlays
+    pub fn empty() -> Self {
+        Self {
+            overlays: Vec::new(),
+            legend_visible: true,
+        }
+    }
+
+    /// Register a disabled overlay, or update the description and key of an
+    /// existing one
+    ///
+    /// A key already bound to another overlay is moved to this one.
+    pub fn register(&mut self, name: &str, description: &str, key: Option<KeyCode>) {
+        if key.is_some() {
+            for overlay in &mut self.overlays {
+                if overlay.key == key {
+                    overlay.key = None;
+                }
+            }
+        }
+        match self
+            .overlays
+            .iter_mut()
+            .find(|overlay| overlay.name == name)
+        {
+            Some(overlay) => {
+                overlay.description = description.to_owned();
+                overlay.key = key;
+            }
+            None => self.overlays.push(DebugOverlay {
+                name: name.to_owned(),
+                description: description.to_owned(),
+                key,
+                enabled: false,
+            }),
+        }
+    }
+
+    /// Check whether an overlay is enabled; unknown overlays are disabled
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.get(name).is_some_and(|overlay| overlay.enabled)
+    }
+
+    /// Enable or disable an overlay, returning whether it exists
+    pub fn set(&mut self, name: &str, enabled: bool) -> bool {
+        match self
+            .overlays
+            .iter_mut()
+            .find(|overlay| overlay.name == name)
+        {
+            Some(overlay) => {
+                overlay.enabled = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Flip an overlay, returning its new state if it exists
+    pub fn toggle(&mut self, name: &str) -> Option<bool> {
+        let enabled = !self.get(name)?.enabled;
+        self.set(name, enabled);
+        Some(enabled)
+    }
+
+    /// Get an overlay by name
+    pub fn get(&self, name: &str) -> Option<&DebugOverlay> {
+        self.overlays.iter().find(|overlay| overlay.name == name)
+    }
+
+    /// Iterate over every overlay in registration order
+    pub fn iter(&self) -> impl Iterator<Item = &DebugOverlay> {
+        self.overlays.iter()
+    }
+
+    /// Get the legend lines for the enabled overlays, such as
+    /// `F1 Physics colliders`, or nothing while the legend is hidden
+    pub fn legend(&self) -> Vec<String> {
+        if !self.legend_visible {
+            return Vec::new();
+        }
+        self.overlays
+            .iter()
+            .filter(|overlay| overlay.enabled)
+            .map(|overlay| match overlay.key {
+                Some(key) => format!("{key:?} {}", overlay.description),
+                None => overlay.description.clone(),
+            })
+            .collect()
+    }
+
+    /// Run a console command and return the text to print
+    ///
+    /// Accepts `overlay list`, `overlay legend [on|off]` and
+    /// `overlay <name> [on|off|toggle]`, where a missing state toggles.
+    pub fn run_command(&mut self, command: &str) -> Result<String, OverlayCommandError> {
+        let mut words = command.split_whitespace();
+        match words.next() {
+            Some("overlay") => {}
+            Some(other) => return Err(OverlayCommandError::UnknownCommand(other.to_owned())),
+            None => return Err(OverlayCommandError::Usage),
+        }
+        let target = words.next().ok_or(OverlayCommandError::Usage)?;
+        let state = words.next();
+        if words.next().is_some() {
+            return Err(OverlayCommandError::Usage);
+        }
+
+        if target == "list" && state.is_none() {
+            return Ok(self
+                .overlays
+                .iter()
+                .map(|overlay| {
+                    let state = if overlay.enabled { "on" } else { "off" };
+                    format!("{} ({state}): {}", overlay.name, overlay.description)
+                })
+                .collect::<Vec<_>>()
+                .join("\n"));
+        }
+        let current = if target == "legend" {
+            self.legend_visible
+        } else {
+            self.get(target)
+                .ok_or_else(|| OverlayCommandError::UnknownOverlay(target.to_owned()))?
+                .enabled
+        };
+        let enabled = match state {
+            None | Some("toggle") => !current,
+            Some("on") => true,
+            Some("off") => false,
+            Some(_) => return Err(OverlayCommandError::Usage),
+        };
+        if target == "legend" {
+            self.legend_visible = enabled;
+        } else {
+            self.set(target, enabled);
+        }
+        Ok(format!("{target} {}", if enabled { "on" } else { "off" }))
+    }
+}
+
+/// Toggle overlays whose key was just pressed
+pub fn toggle_debug_overlays(keys: Res<ButtonInput<KeyCode>>, mut overlays: ResMut<DebugOverlays>) {
+    for overlay in &mut overlays.overlays {
+        if overlay.key.is_some_and(|key| keys.just_pressed(key)) {
+            overlay.enabled = !overlay.enabled;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::system::RunSystemOnce;
+
+    #[test]
+    fn test_keys_toggle_and_legend() {
+        let mut world = World::new();
+        world.init_resource::<DebugOverlays>();
+        let mut keys = ButtonInput::<KeyCode>::default();
+        keys.press(KeyCode::F2);
+        world.insert_resource(keys);
+
+        world.run_system_once(toggle_debug_overlays);
+        let overlays = world.resource::<DebugOverlays>();
+        assert!(overlays.is_enabled(SECTOR_BOUNDS));
+        assert!(!overlays.is_enabled(COLLIDERS));
+        assert_eq!(overlays.legend(), ["F2 Sector bounds"]);
+
+        // Held keys don't toggle again
+        world.resource_mut::<ButtonInput<KeyCode>>().clear();
+        world.run_system_once(toggle_debug_overlays);
+        assert!(world.resource::<DebugOverlays>().is_enabled(SECTOR_BOUNDS));
+    }
+
+    #[test]
+    fn test_register_moves_key() {
+        let mut overlays = DebugOverlays::default();
+        overlays.register("lights", "Light volumes", Some(KeyCode::F1));
+        assert_eq!(overlays.get(COLLIDERS).unwrap().key, None);
+        assert_eq!(overlays.get("lights").unwrap().key, Some(KeyCode::F1));
+        assert_eq!(overlays.iter().count(), 6);
+    }
+
+    #[test]
+    fn test_console_commands() {
+        let mut overlays = DebugOverlays::default();
+        assert_eq!(
+            overlays.run_command("overlay navmesh").unwrap(),
+            "navmesh on"
+        );
+        assert_eq!(
+            overlays.run_command("overlay navmesh off").unwrap(),
+            "navmesh off"
+        );
+        assert_eq!(
+            overlays.run_command("overlay roads on").unwrap(),
+            "roads on"
+        );
+        assert!(overlays
+            .run_command("overlay list")
+            .unwrap()
+            .contains("roads (on): Road graph"));
+        assert_eq!(
+            overlays.run_command("overlay legend").unwrap(),
+            "legend off"
+        );
+        assert!(overlays.legend().is_empty());
+
+        assert_eq!(
+            overlays.run_command("overlay bogus"),
+            Err(OverlayCommandError::UnknownOverlay("bogus".into()))
+        );
+        assert_eq!(
+            overlays.run_command("overlay roads maybe"),
+            Err(OverlayCommandError::Usage)
+        );
+        assert_eq!(
+            overlays.run_command("spawn car"),
+            Err(OverlayCommandError::UnknownCommand("spawn".into()))
+        );
+    }
+}
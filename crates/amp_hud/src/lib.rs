@@ -0,0 +1,47 @@
+//! HUD widget state
+//!
+//! Each widget is a resource holding what the HUD shows, already converted
+//! to display units and labels, and updated by a system from gameplay
+//! state. Drawing reads these resources, so the widgets' behavior does not
+//! depend on the UI framework. [`VehicleHud`] is the speedometer cluster
+//! shown while driving; [`StatusHud`] shows health, armor and the wanted
+//! level; [`Notifications`] holds toasts and the current objective, sent by
+//! any crate as [`HudNotification`] events. [`DebugOverlays`] tracks which
+//! debug visualizations are drawn.
+
+#![deny(missing_docs)]
+
+pub mod debug;
+pub mod notifications;
+pub mod status;
+pub mod vehicle;
+
+pub use debug::{toggle_debug_overlays, DebugOverlay, DebugOverlays, OverlayCommandError};
+pub use notifications::{
+    update_notifications, ActiveNotification, HudNotification, NotificationIcon, NotificationKind,
+    NotificationPriority, Notifications,
+};
+pub use status::{
+    update_status_hud, DamageIndicator, HealthChanged, LowHealthFeedback, PlayerDamaged, StatusHud,
+    WantedLevelChanged, LOW_HEALTH, MAX_WANTED_LEVEL,
+};
+pub use vehicle::{update_vehicle_hud, Gear, OccupiedVehicle, VehicleHud, VehicleTelemetry};
+
+use bevy_ecs::schedule::{IntoSystemConfigs, SystemConfigs, SystemSet};
+
+/// System set of the per-frame HUD systems added by [`systems`]
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HudSystems;
+
+/// Get the per-frame HUD systems in [`HudSystems`]
+///
+/// Each widget has its own resource, so they run in any order.
+pub fn systems() -> SystemConfigs {
+    (
+        toggle_debug_overlays,
+        update_notifications,
+        update_status_hud,
+        update_vehicle_hud,
+    )
+        .in_set(HudSystems)
+}
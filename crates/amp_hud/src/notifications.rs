@@ -0,0 +1,314 @@
+//! Toast notifications and the current objective
+//!
+//! Any crate reports something worth showing by sending a
+//! [`HudNotification`]. Toasts wait in a queue ordered by priority, then by
+//! arrival, and up to [`MAX_VISIBLE_TOASTS`] are shown at a time, each for
+//! its own duration. An objective replaces the previous one and stays until
+//! it expires or is cleared.
+
+use bevy_ecs::prelude::*;
+use bevy_time::Time;
+use std::collections::VecDeque;
+
+/// Most toasts shown at once
+pub const MAX_VISIBLE_TOASTS: usize = 3;
+
+/// Seconds a toast is shown unless given a duration
+pub const DEFAULT_TOAST_SECONDS: f32 = 4.0;
+
+/// Seconds a notification takes to fade out at the end of its duration
+const FADE_SECONDS: f32 = 0.3;
+
+/// How urgently a notification should be shown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NotificationPriority {
+    /// Shown when nothing else is waiting
+    Low,
+    /// Most notifications
+    #[default]
+    Normal,
+    /// Shown ahead of normal notifications
+    High,
+    /// Shown as soon as a slot is free, ahead of everything else
+    Critical,
+}
+
+/// Icon drawn next to a notification
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum NotificationIcon {
+    /// No icon
+    #[default]
+    None,
+    /// General information
+    Info,
+    /// Mission progress
+    Mission,
+    /// Item or weapon picked up
+    Pickup,
+    /// Money earned or spent
+    Money,
+    /// Game saved
+    Save,
+    /// Something needs attention
+    Warning,
+}
+
+/// Where a notification is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotificationKind {
+    /// A short message in the toast stack
+    Toast,
+    /// The current objective line
+    Objective,
+}
+
+/// Show a notification on the HUD
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct HudNotification {
+    /// Where the notification is shown
+    pub kind: NotificationKind,
+    /// Text to show, already localized
+    pub text: String,
+    /// Icon drawn next to the text
+    pub icon: NotificationIcon,
+    /// Position in the toast queue
+    pub priority: NotificationPriority,
+    /// Seconds to show the notification for, or `None` for the default:
+    /// [`DEFAULT_TOAST_SECONDS`] for toasts, until replaced for objectives
+    pub duration: Option<f32>,
+}
+
+impl HudNotification {
+    /// Create a toast with normal priority and the default duration
+    pub fn toast(text: impl Into<String>) -> Self {
+        Self {
+            kind: NotificationKind::Toast,
+            text: text.into(),
+            icon: NotificationIcon::None,
+            priority: NotificationPriority::Normal,
+            duration: None,
+        }
+    }
+
+    /// Create an objective that stays until replaced
+    pub fn objective(text: impl Into<String>) -> Self {
+        Self {
+            kind: NotificationKind::Objective,
+            ..Self::toast(text)
+        }
+    }
+
+    /// Create a notification that clears the current objective
+    pub fn clear_objective() -> Self {
+        Self::objective(String::new())
+    }
+
+    /// Set the icon
+    pub fn with_icon(mut self, icon: NotificationIcon) -> Self {
+        self.icon = icon;
+        self
+    }
+
+    /// Set the priority
+    pub fn with_priority(mut self, priority: NotificationPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Set the duration in seconds
+    pub fn with_duration(mut self, seconds: f32) -> Self {
+        self.duration = Some(seconds);
+        self
+    }
+}
+
+/// A notification being shown
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveNotification {
+    /// Text to show
+    pub text: String,
+    /// Icon drawn next to the text
+    pub icon: NotificationIcon,
+    /// Priority it was sent with
+    pub priority: NotificationPriority,
+    /// Opacity, fading out at the end of the duration
+    pub alpha: f32,
+    remaining: Option<f32>,
+}
+
+impl ActiveNotification {
+    fn new(notification: HudNotification, default_seconds: Option<f32>) -> Self {
+        Self {
+            text: notification.text,
+            icon: notification.icon,
+            priority: notification.priority,
+            alpha: 1.0,
+            remaining: notification.duration.or(default_seconds),
+        }
+    }
+
+    /// Advance the timer, returning whether the notification is still shown
+    fn tick(&mut self, delta: f32) -> bool {
+        let Some(remaining) = self.remaining.as_mut() else {
+            return true;
+        };
+        *remaining -= delta;
+        self.alpha = (*remaining / FADE_SECONDS).clamp(0.0, 1.0);
+        *remaining > 0.0
+    }
+}
+
+/// What the toast stack and objective line show
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct Notifications {
+    toasts: Vec<ActiveNotification>,
+    queue: VecDeque<HudNotification>,
+    objective: Option<ActiveNotification>,
+}
+
+impl Notifications {
+    /// Get the toasts being shown, oldest first
+    pub fn toasts(&self) -> &[ActiveNotification] {
+        &self.toasts
+    }
+
+    /// Get the number of toasts waiting for a free slot
+    pub fn queued(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Get the current objective
+    pub fn objective(&self) -> Option<&ActiveNotification> {
+        self.objective.as_ref()
+    }
+
+    fn push(&mut self, notification: HudNotification) {
+        match notification.kind {
+            NotificationKind::Objective if notification.text.is_empty() => {
+                self.objective = None;
+            }
+            NotificationKind::Objective => {
+                self.objective = Some(ActiveNotification::new(notification, None));
+            }
+            NotificationKind::Toast => {
+                // Behind everything of the same or higher priority
+                let index = self
+                    .queue
+                    .iter()
+                    .position(|queued| queued.priority < notification.priority)
+                    .unwrap_or(self.queue.len());
+                self.queue.insert(index, notification);
+            }
+        }
+    }
+}
+
+/// Queue [`HudNotification`] events and advance the shown notifications
+pub fn update_notifications(
+    time: Res<Time>,
+    mut events: EventReader<HudNotification>,
+    mut notifications: ResMut<Notifications>,
+) {
+    let delta = time.delta_seconds();
+    let notifications = &mut *notifications;
+
+    notifications.toasts.retain_mut(|toast| toast.tick(delta));
+    if let Some(objective) = notifications.objective.as_mut() {
+        if !objective.tick(delta) {
+            notifications.objective = None;
+        }
+    }
+
+    for notification in events.read() {
+        notifications.push(notification.clone());
+    }
+    while notifications.toasts.len() < MAX_VISIBLE_TOASTS {
+        let Some(next) = notifications.queue.pop_front() else {
+            break;
+        };
+        notifications
+            .toasts
+            .push(ActiveNotification::new(next, Some(DEFAULT_TOAST_SECONDS)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn world() -> (World, impl System<In = (), Out = ()>) {
+        let mut world = World::new();
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_secs(1));
+        world.insert_resource(time);
+        world.init_resource::<Events<HudNotification>>();
+        world.init_resource::<Notifications>();
+        let mut system = IntoSystem::into_system(update_notifications);
+        system.initialize(&mut world);
+        (world, system)
+    }
+
+    fn texts(world: &World) -> Vec<&str> {
+        let notifications = world.resource::<Notifications>();
+        notifications
+            .toasts()
+            .iter()
+            .map(|toast| toast.text.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_toasts_queue_by_priority() {
+        let (mut world, mut system) = world();
+        for text in ["a", "b", "c", "d"] {
+            world.send_event(HudNotification::toast(text).with_duration(1.5));
+        }
+        world.send_event(HudNotification::toast("low").with_priority(NotificationPriority::Low));
+        world.send_event(
+            HudNotification::toast("saved")
+                .with_icon(NotificationIcon::Save)
+                .with_priority(NotificationPriority::Critical),
+        );
+        system.run((), &mut world);
+        assert_eq!(texts(&world), ["saved", "a", "b"]);
+        assert_eq!(world.resource::<Notifications>().queued(), 3);
+        assert_eq!(
+            world.resource::<Notifications>().toasts()[0].icon,
+            NotificationIcon::Save
+        );
+
+        // "a" and "b" fade out and expire, "saved" has the default duration
+        system.run((), &mut world);
+        assert_eq!(texts(&world), ["saved", "a", "b"]);
+        assert_eq!(world.resource::<Notifications>().toasts()[1].alpha, 1.0);
+        system.run((), &mut world);
+        assert_eq!(texts(&world), ["saved", "c", "d"]);
+    }
+
+    #[test]
+    fn test_objective_replaced_and_cleared() {
+        let (mut world, mut system) = world();
+        world.send_event(HudNotification::objective("Get to the car"));
+        system.run((), &mut world);
+        world.send_event(
+            HudNotification::objective("Lose the cops").with_icon(NotificationIcon::Mission),
+        );
+        for _ in 0..10 {
+            system.run((), &mut world);
+        }
+        let objective = world.resource::<Notifications>().objective().unwrap();
+        assert_eq!(objective.text, "Lose the cops");
+        assert_eq!(objective.alpha, 1.0);
+
+        world.send_event(HudNotification::clear_objective());
+        system.run((), &mut world);
+        assert!(world.resource::<Notifications>().objective().is_none());
+
+        world.send_event(HudNotification::objective("Timed").with_duration(1.5));
+        system.run((), &mut world);
+        system.run((), &mut world);
+        system.run((), &mut world);
+        assert!(world.resource::<Notifications>().objective().is_none());
+    }
+}
@@ -0,0 +1,282 @@
+//! Health, armor and wanted level
+//!
+//! The health and police systems report changes through events; the HUD
+//! keeps what it last heard instead of querying the player every frame.
+//! Damage with a known source adds a directional indicator that fades out,
+//! and dropping below [`LOW_HEALTH`] starts the low health pulse and sends
+//! a [`LowHealthFeedback`] event for the heartbeat sound.
+
+use bevy_ecs::prelude::*;
+use bevy_math::Vec2;
+use bevy_time::Time;
+
+/// Health fraction below which low health feedback plays
+pub const LOW_HEALTH: f32 = 0.25;
+
+/// Highest wanted level
+pub const MAX_WANTED_LEVEL: u8 = 5;
+
+/// Seconds a damage indicator stays visible
+pub const DAMAGE_INDICATOR_SECONDS: f32 = 1.5;
+
+/// Seconds the wanted stars flash after the level changes
+pub const WANTED_FLASH_SECONDS: f32 = 3.0;
+
+/// Low health pulses per second
+const PULSE_RATE: f32 = 1.2;
+
+/// The player's health or armor changed
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct HealthChanged {
+    /// Current health
+    pub health: f32,
+    /// Full health
+    pub max_health: f32,
+    /// Current armor
+    pub armor: f32,
+    /// Full armor
+    pub max_armor: f32,
+}
+
+/// The player took damage
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct PlayerDamaged {
+    /// Health and armor lost
+    pub amount: f32,
+    /// Horizontal world direction from the player towards the source, as
+    /// X and Z, if the source is known
+    pub from: Option<Vec2>,
+}
+
+/// The player's wanted level changed
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WantedLevelChanged {
+    /// New level, from 0 to [`MAX_WANTED_LEVEL`]
+    pub level: u8,
+}
+
+/// Low health feedback started or stopped, for audio
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LowHealthFeedback {
+    /// Whether low health feedback is playing
+    pub active: bool,
+}
+
+/// A fading marker pointing towards a damage source
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DamageIndicator {
+    /// Normalized horizontal world direction towards the source
+    pub direction: Vec2,
+    /// Opacity, fading from 1 to 0
+    pub alpha: f32,
+    remaining: f32,
+}
+
+impl DamageIndicator {
+    /// Get the angle to draw the indicator at around the screen center, in
+    /// radians clockwise from straight ahead, for a camera with `yaw`
+    ///
+    /// The camera yaw is a rotation about the vertical axis with zero
+    /// looking towards -Z, like the orbit camera's.
+    pub fn screen_angle(&self, camera_yaw: f32) -> f32 {
+        let source_yaw = (-self.direction.x).atan2(-self.direction.y);
+        let angle = camera_yaw - source_yaw;
+        (angle + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI
+    }
+}
+
+/// What the health, armor and wanted level widgets show
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct StatusHud {
+    /// Health bar fill, from 0 to 1
+    pub health: f32,
+    /// Armor bar fill, from 0 to 1
+    pub armor: f32,
+    /// Whether low health feedback is playing
+    pub low_health: bool,
+    /// Low health vignette strength, pulsing between 0 and 1
+    pub low_health_pulse: f32,
+    /// Number of wanted stars lit
+    pub wanted_level: u8,
+    /// Whether the wanted stars are flashing after a change
+    pub wanted_flashing: bool,
+    /// Active damage indicators
+    pub damage_indicators: Vec<DamageIndicator>,
+    pulse_phase: f32,
+    wanted_flash_remaining: f32,
+}
+
+impl Default for StatusHud {
+    fn default() -> Self {
+        Self {
+            health: 1.0,
+            armor: 0.0,
+            low_health: false,
+            low_health_pulse: 0.0,
+            wanted_level: 0,
+            wanted_flashing: false,
+            damage_indicators: Vec::new(),
+            pulse_phase: 0.0,
+            wanted_flash_remaining: 0.0,
+        }
+    }
+}
+
+/// Apply health, damage and wanted level events to the [`StatusHud`] and
+/// advance its animations
+pub fn update_status_hud(
+    time: Res<Time>,
+    mut health: EventReader<HealthChanged>,
+    mut damage: EventReader<PlayerDamaged>,
+    mut wanted: EventReader<WantedLevelChanged>,
+    mut feedback: EventWriter<LowHealthFeedback>,
+    mut hud: ResMut<StatusHud>,
+) {
+    let delta = time.delta_seconds();
+    let hud = &mut *hud;
+
+    if let Some(changed) = health.read().last() {
+        hud.health = fraction(changed.health, changed.max_health);
+        hud.armor = fraction(changed.armor, changed.max_armor);
+        let low_health = hud.health > 0.0 && hud.health < LOW_HEALTH;
+        if low_health != hud.low_health {
+            hud.low_health = low_health;
+            hud.pulse_phase = 0.0;
+            feedback.send(LowHealthFeedback { active: low_health });
+        }
+    }
+
+    for damaged in damage.read() {
+        let Some(direction) = damaged.from.and_then(Vec2::try_normalize) else {
+            continue;
+        };
+        hud.damage_indicators.push(DamageIndicator {
+            direction,
+            alpha: 1.0,
+            remaining: DAMAGE_INDICATOR_SECONDS,
+        });
+    }
+
+    if let Some(changed) = wanted.read().last() {
+        let level = changed.level.min(MAX_WANTED_LEVEL);
+        if level != hud.wanted_level {
+            hud.wanted_level = level;
+            hud.wanted_flash_remaining = WANTED_FLASH_SECONDS;
+        }
+    }
+
+    hud.damage_indicators.retain_mut(|indicator| {
+        indicator.remaining -= delta;
+        indicator.alpha = (indicator.remaining / DAMAGE_INDICATOR_SECONDS).clamp(0.0, 1.0);
+        indicator.remaining > 0.0
+    });
+    hud.wanted_flash_remaining = (hud.wanted_flash_remaining - delta).max(0.0);
+    hud.wanted_flashing = hud.wanted_flash_remaining > 0.0 && hud.wanted_level > 0;
+    if hud.low_health {
+        hud.pulse_phase = (hud.pulse_phase + delta * PULSE_RATE).fract();
+        hud.low_health_pulse = 0.5 - 0.5 * (hud.pulse_phase * std::f32::consts::TAU).cos();
+    } else {
+        hud.low_health_pulse = 0.0;
+    }
+}
+
+fn fraction(value: f32, max: f32) -> f32 {
+    if max > 0.0 {
+        (value / max).clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn world() -> (World, impl System<In = (), Out = ()>) {
+        let mut world = World::new();
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_millis(500));
+        world.insert_resource(time);
+        world.init_resource::<Events<HealthChanged>>();
+        world.init_resource::<Events<PlayerDamaged>>();
+        world.init_resource::<Events<WantedLevelChanged>>();
+        world.init_resource::<Events<LowHealthFeedback>>();
+        world.init_resource::<StatusHud>();
+        let mut system = IntoSystem::into_system(update_status_hud);
+        system.initialize(&mut world);
+        (world, system)
+    }
+
+    fn health(health: f32, armor: f32) -> HealthChanged {
+        HealthChanged {
+            health,
+            max_health: 100.0,
+            armor,
+            max_armor: 50.0,
+        }
+    }
+
+    #[test]
+    fn test_health_and_low_health_feedback() {
+        let (mut world, mut system) = world();
+        world.send_event(health(60.0, 25.0));
+        system.run((), &mut world);
+        let hud = world.resource::<StatusHud>();
+        assert_eq!((hud.health, hud.armor, hud.low_health), (0.6, 0.5, false));
+
+        world.send_event(health(20.0, 0.0));
+        system.run((), &mut world);
+        let hud = world.resource::<StatusHud>();
+        assert!(hud.low_health && hud.low_health_pulse > 0.0);
+        let feedback = world.resource::<Events<LowHealthFeedback>>();
+        assert_eq!(
+            feedback.iter_current_update_events().collect::<Vec<_>>(),
+            [&LowHealthFeedback { active: true }]
+        );
+
+        // No event, no change
+        system.run((), &mut world);
+        assert!(world.resource::<StatusHud>().low_health);
+    }
+
+    #[test]
+    fn test_damage_indicators_fade() {
+        let (mut world, mut system) = world();
+        world.send_event(PlayerDamaged {
+            amount: 10.0,
+            from: Some(Vec2::new(3.0, 0.0)),
+        });
+        world.send_event(PlayerDamaged {
+            amount: 5.0,
+            from: None,
+        });
+        system.run((), &mut world);
+
+        let indicators = &world.resource::<StatusHud>().damage_indicators;
+        assert_eq!(indicators.len(), 1);
+        // A source on +X is to the right of a camera looking towards -Z
+        let angle = indicators[0].screen_angle(0.0);
+        assert!((angle - std::f32::consts::FRAC_PI_2).abs() < 1e-5);
+        assert!((indicators[0].alpha - 2.0 / 3.0).abs() < 1e-5);
+
+        system.run((), &mut world);
+        system.run((), &mut world);
+        assert!(world.resource::<StatusHud>().damage_indicators.is_empty());
+    }
+
+    #[test]
+    fn test_wanted_level_flashes_on_change() {
+        let (mut world, mut system) = world();
+        world.send_event(WantedLevelChanged { level: 9 });
+        system.run((), &mut world);
+        let hud = world.resource::<StatusHud>();
+        assert_eq!(hud.wanted_level, MAX_WANTED_LEVEL);
+        assert!(hud.wanted_flashing);
+
+        for _ in 0..6 {
+            system.run((), &mut world);
+        }
+        assert!(!world.resource::<StatusHud>().wanted_flashing);
+    }
+}
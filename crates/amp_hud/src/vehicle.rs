@@ -0,0 +1,188 @@
+//! Vehicle HUD cluster: speedometer, rev bar, gear, handbrake and fuel
+
+use amp_settings::GameSettings;
+use bevy_ecs::prelude::*;
+use config_core::ConfigHandle;
+
+/// Meters per second to kilometers per hour
+const KPH_PER_MPS: f32 = 3.6;
+
+/// Meters per second to miles per hour
+const MPH_PER_MPS: f32 = 2.236_936;
+
+/// Fuel fraction below which the low fuel light comes on
+pub const LOW_FUEL: f32 = 0.15;
+
+/// Selected gear
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Gear {
+    /// Reverse
+    Reverse,
+    /// Neutral
+    #[default]
+    Neutral,
+    /// A forward gear, starting at 1
+    Forward(u8),
+}
+
+impl Gear {
+    /// Get the label shown on the gear indicator
+    pub fn label(self) -> String {
+        match self {
+            Gear::Reverse => "R".to_string(),
+            Gear::Neutral => "N".to_string(),
+            Gear::Forward(gear) => gear.to_string(),
+        }
+    }
+}
+
+/// Live state of a vehicle, written by the vehicle simulation
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct VehicleTelemetry {
+    /// Forward speed, in meters per second; negative when reversing
+    pub speed: f32,
+    /// Engine speed, in revolutions per minute
+    pub rpm: f32,
+    /// Engine speed at which the rev bar turns red
+    pub redline_rpm: f32,
+    /// Highest engine speed, filling the rev bar
+    pub max_rpm: f32,
+    /// Selected gear
+    pub gear: Gear,
+    /// Whether the handbrake is applied
+    pub handbrake: bool,
+    /// Fuel left, from 0 to 1
+    pub fuel: f32,
+}
+
+impl Default for VehicleTelemetry {
+    fn default() -> Self {
+        Self {
+            speed: 0.0,
+            rpm: 800.0,
+            redline_rpm: 6500.0,
+            max_rpm: 7500.0,
+            gear: Gear::Neutral,
+            handbrake: false,
+            fuel: 1.0,
+        }
+    }
+}
+
+/// Vehicle the player is driving, set when entering and leaving vehicles
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OccupiedVehicle(pub Option<Entity>);
+
+/// What the vehicle cluster shows
+#[derive(Resource, Debug, Clone, PartialEq, Default)]
+pub struct VehicleHud {
+    /// Whether the cluster is shown
+    pub visible: bool,
+    /// Speed in display units, rounded down
+    pub speed: u32,
+    /// Speed unit label
+    pub unit: &'static str,
+    /// Rev bar fill, from 0 to 1
+    pub rpm_fraction: f32,
+    /// Whether the engine is past the redline
+    pub redline: bool,
+    /// Gear indicator label
+    pub gear: String,
+    /// Whether the handbrake light is on
+    pub handbrake: bool,
+    /// Fuel gauge fill, from 0 to 1
+    pub fuel: f32,
+    /// Whether the low fuel light is on
+    pub low_fuel: bool,
+}
+
+/// Update the [`VehicleHud`] from the [`OccupiedVehicle`]'s telemetry
+///
+/// The cluster is hidden when the player is on foot or the vehicle has no
+/// telemetry. Speed is shown in km/h or mph following the gameplay
+/// settings' `metric_units`.
+pub fn update_vehicle_hud(
+    occupied: Res<OccupiedVehicle>,
+    settings: Option<Res<ConfigHandle<GameSettings>>>,
+    vehicles: Query<&VehicleTelemetry>,
+    mut hud: ResMut<VehicleHud>,
+) {
+    let Some(telemetry) = occupied.0.and_then(|vehicle| vehicles.get(vehicle).ok()) else {
+        hud.visible = false;
+        return;
+    };
+
+    let metric = settings.map_or(true, |settings| settings.get().gameplay.metric_units);
+    let (scale, unit) = if metric {
+        (KPH_PER_MPS, "km/h")
+    } else {
+        (MPH_PER_MPS, "mph")
+    };
+    let max_rpm = telemetry.max_rpm.max(1.0);
+
+    *hud = VehicleHud {
+        visible: true,
+        speed: (telemetry.speed.abs() * scale) as u32,
+        unit,
+        rpm_fraction: (telemetry.rpm / max_rpm).clamp(0.0, 1.0),
+        redline: telemetry.rpm >= telemetry.redline_rpm,
+        gear: telemetry.gear.label(),
+        handbrake: telemetry.handbrake,
+        fuel: telemetry.fuel.clamp(0.0, 1.0),
+        low_fuel: telemetry.fuel < LOW_FUEL,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::system::RunSystemOnce;
+
+    fn world(metric: bool) -> (World, Entity) {
+        let mut world = World::new();
+        world.init_resource::<VehicleHud>();
+        let mut settings = GameSettings::default();
+        settings.gameplay.metric_units = metric;
+        config_core::init_config(&mut world, settings);
+        let vehicle = world
+            .spawn(VehicleTelemetry {
+                speed: 25.0,
+                rpm: 7000.0,
+                gear: Gear::Forward(3),
+                handbrake: true,
+                fuel: 0.1,
+                ..VehicleTelemetry::default()
+            })
+            .id();
+        world.insert_resource(OccupiedVehicle(Some(vehicle)));
+        (world, vehicle)
+    }
+
+    #[test]
+    fn test_cluster_shows_telemetry_in_vehicle() {
+        let (mut world, _) = world(true);
+        world.run_system_once(update_vehicle_hud);
+
+        let hud = world.resource::<VehicleHud>();
+        assert!(hud.visible);
+        assert_eq!((hud.speed, hud.unit), (90, "km/h"));
+        assert!(hud.redline && hud.handbrake && hud.low_fuel);
+        assert_eq!(hud.gear, "3");
+        assert!((hud.rpm_fraction - 7000.0 / 7500.0).abs() < 1e-6);
+
+        world.insert_resource(OccupiedVehicle(None));
+        world.run_system_once(update_vehicle_hud);
+        assert!(!world.resource::<VehicleHud>().visible);
+    }
+
+    #[test]
+    fn test_imperial_units() {
+        let (mut world, vehicle) = world(false);
+        world.get_mut::<VehicleTelemetry>(vehicle).unwrap().gear = Gear::Reverse;
+        world.run_system_once(update_vehicle_hud);
+
+        let hud = world.resource::<VehicleHud>();
+        assert_eq!((hud.speed, hud.unit), (55, "mph"));
+        assert_eq!(hud.gear, "R");
+    }
+}
@@ -0,0 +1,152 @@
+//! Action state from bound inputs
+
+use crate::bindings::{Action, Binding, InputBindings};
+use crate::gamepad::GamepadInput;
+use crate::recording::{ActionFrame, ActionPlayback};
+use crate::settings::InputSettings;
+use bevy_ecs::prelude::*;
+use bevy_ecs::system::SystemParam;
+use bevy_input::keyboard::KeyCode;
+use bevy_input::mouse::MouseButton;
+use bevy_input::ButtonInput;
+use config_core::ConfigHandle;
+
+/// Read access to actions through the live [`InputBindings`]
+///
+/// An action bound to several inputs reads as the strongest of them. With
+/// the `unstable_advanced_input` feature, actions outside the current
+/// [`InputContext`](crate::InputContext) read as released; without the
+/// [`ActiveInputContexts`](crate::ActiveInputContexts) resource every action
+/// is evaluated.
+///
+/// While an [`ActionPlayback`] is playing, actions read from the recorded
+/// frame instead of the devices.
+#[derive(SystemParam)]
+pub struct ActionInput<'w> {
+    bindings: Res<'w, ConfigHandle<InputBindings>>,
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    gamepad: GamepadInput<'w>,
+    settings: Res<'w, ConfigHandle<InputSettings>>,
+    playback: Option<Res<'w, ActionPlayback>>,
+    #[cfg(feature = "unstable_advanced_input")]
+    contexts: Option<Res<'w, crate::contexts::ActiveInputContexts>>,
+}
+
+impl ActionInput<'_> {
+    /// Get the input settings
+    pub fn settings(&self) -> &InputSettings {
+        self.settings.get()
+    }
+
+    /// Get how far an action is pushed, from 0 to 1
+    ///
+    /// Buttons read 0 or 1 and triggers are analog with the trigger dead
+    /// zone applied. Stick axes are raw, so callers can apply a radial or
+    /// axial dead zone to the control they build from them.
+    pub fn value(&self, action: Action) -> f32 {
+        if let Some(frame) = self.replayed() {
+            return frame.value(action);
+        }
+        self.bound(action)
+            .map(|binding| self.binding_value(binding))
+            .fold(0.0, f32::max)
+    }
+
+    /// Get an axis from an opposing pair of actions, from -1 to 1
+    pub fn axis(&self, negative: Action, positive: Action) -> f32 {
+        self.value(positive) - self.value(negative)
+    }
+
+    /// Check if an action is held
+    ///
+    /// Stick axes count as held once they leave the stick dead zone.
+    pub fn pressed(&self, action: Action) -> bool {
+        if let Some(frame) = self.replayed() {
+            return frame.pressed.contains(&action);
+        }
+        self.bound(action).any(|binding| match binding {
+            Binding::Key(key) => self.keys.pressed(key),
+            Binding::Mouse(button) => self.mouse.pressed(button),
+            Binding::GamepadButton(button) => self.gamepad.pressed(button),
+            Binding::GamepadAxis(..) => {
+                self.binding_value(binding) > self.settings().stick_dead_zone
+            }
+        })
+    }
+
+    /// Check if a button bound to an action was pressed this frame
+    ///
+    /// Stick axis bindings never count as just pressed.
+    pub fn just_pressed(&self, action: Action) -> bool {
+        if let Some(frame) = self.replayed() {
+            return frame.just_pressed.contains(&action);
+        }
+        self.bound(action).any(|binding| match binding {
+            Binding::Key(key) => self.keys.just_pressed(key),
+            Binding::Mouse(button) => self.mouse.just_pressed(button),
+            Binding::GamepadButton(button) => self.gamepad.just_pressed(button),
+            Binding::GamepadAxis(..) => false,
+        })
+    }
+
+    /// Get the frame being played back, if an [`ActionPlayback`] is playing
+    pub fn replayed(&self) -> Option<&ActionFrame> {
+        self.playback
+            .as_ref()
+            .and_then(|playback| playback.current())
+    }
+
+    /// Capture the state of every action, without mouse motion
+    pub fn frame(&self) -> ActionFrame {
+        let mut frame = ActionFrame::default();
+        for action in Action::ALL {
+            let value = self.value(action);
+            if value > 0.0 {
+                frame.values.insert(action, value);
+            }
+            if self.pressed(action) {
+                frame.pressed.insert(action);
+            }
+            if self.just_pressed(action) {
+                frame.just_pressed.insert(action);
+            }
+        }
+        frame
+    }
+
+    fn bound(&self, action: Action) -> impl Iterator<Item = Binding> + '_ {
+        let bindings = if self.evaluates(action) {
+            self.bindings.get().bindings(action)
+        } else {
+            &[]
+        };
+        bindings.iter().copied()
+    }
+
+    #[cfg(feature = "unstable_advanced_input")]
+    fn evaluates(&self, action: Action) -> bool {
+        self.contexts
+            .as_ref()
+            .map_or(true, |contexts| contexts.allows(action))
+    }
+
+    #[cfg(not(feature = "unstable_advanced_input"))]
+    fn evaluates(&self, _action: Action) -> bool {
+        true
+    }
+
+    fn binding_value(&self, binding: Binding) -> f32 {
+        let digital = |pressed: bool| f32::from(u8::from(pressed));
+        match binding {
+            Binding::Key(key) => digital(self.keys.pressed(key)),
+            Binding::Mouse(button) => digital(self.mouse.pressed(button)),
+            Binding::GamepadButton(button) => self
+                .gamepad
+                .trigger(button, self.settings().trigger_dead_zone),
+            Binding::GamepadAxis(axis, direction) => {
+                (self.gamepad.axis(axis) * direction.sign()).max(0.0)
+            }
+        }
+    }
+}
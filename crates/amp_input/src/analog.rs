@@ -0,0 +1,54 @@
+//! Analog input shaping
+
+use bevy_math::Vec2;
+
+/// Apply a dead zone to a single axis or trigger
+///
+/// Values within `dead_zone` of zero read as zero; the remaining range is
+/// rescaled so output still reaches ±1 and starts smoothly at the edge of
+/// the dead zone.
+pub fn apply_dead_zone(value: f32, dead_zone: f32) -> f32 {
+    let magnitude = value.abs();
+    if magnitude <= dead_zone {
+        return 0.0;
+    }
+    let scaled = (magnitude - dead_zone) / (1.0 - dead_zone).max(f32::EPSILON);
+    value.signum() * scaled.min(1.0)
+}
+
+/// Apply a radial dead zone to a stick
+///
+/// Unlike applying [`apply_dead_zone`] per axis, this keeps the stick's
+/// direction, so diagonal input does not snap to the axes.
+pub fn apply_radial_dead_zone(stick: Vec2, dead_zone: f32) -> Vec2 {
+    let length = stick.length();
+    if length <= dead_zone {
+        return Vec2::ZERO;
+    }
+    let scaled = (length - dead_zone) / (1.0 - dead_zone).max(f32::EPSILON);
+    stick / length * scaled.min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0.1, 0.0)]
+    #[case(-0.2, 0.0)]
+    #[case(0.6, 0.5)]
+    #[case(-1.0, -1.0)]
+    #[case(1.5, 1.0)]
+    fn test_dead_zone_rescales(#[case] value: f32, #[case] expected: f32) {
+        assert!((apply_dead_zone(value, 0.2) - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_radial_dead_zone_keeps_direction() {
+        assert_eq!(apply_radial_dead_zone(Vec2::new(0.1, 0.1), 0.2), Vec2::ZERO);
+        let diagonal = apply_radial_dead_zone(Vec2::new(0.5, 0.5), 0.2);
+        assert!((diagonal.x - diagonal.y).abs() < 1e-6);
+        assert!(apply_radial_dead_zone(Vec2::new(3.0, 0.0), 0.2).x <= 1.0);
+    }
+}
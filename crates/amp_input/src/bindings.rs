@@ -0,0 +1,736 @@
+//! Rebindable input bindings
+//!
+//! Each gameplay [`Action`] is bound to any number of physical inputs. The
+//! bindings are a config_core configuration, `input.ron`, so shipped
+//! defaults, platform files and the player's own file are layered like any
+//! other config. Bindings are stored as strings such as `"key:KeyW"`,
+//! `"gamepad:RightTrigger2"` or `"axis:LeftStickX-"`, so a layer only needs
+//! to list the actions it changes.
+//!
+//! At runtime the live bindings are the [`ConfigHandle<InputBindings>`]
+//! resource; [`rebind`] edits them, publishes the change and saves it.
+
+use amp_core::{Error, Result};
+use bevy_ecs::world::World;
+use bevy_input::gamepad::{GamepadAxisType, GamepadButtonType};
+use bevy_input::keyboard::KeyCode;
+use bevy_input::mouse::MouseButton;
+use config_core::{publish_config, save_config, Config, ConfigHandle, Validate};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// A gameplay action that physical inputs can be bound to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum Action {
+    /// Walk forward
+    MoveForward,
+    /// Walk backward
+    MoveBack,
+    /// Strafe left
+    MoveLeft,
+    /// Strafe right
+    MoveRight,
+    /// Run
+    Sprint,
+    /// Crouch
+    Crouch,
+    /// Aim a weapon
+    Aim,
+    /// Jump
+    Jump,
+    /// Interact, such as entering a vehicle
+    Interact,
+    /// Turn the camera left
+    LookLeft,
+    /// Turn the camera right
+    LookRight,
+    /// Tilt the camera up
+    LookUp,
+    /// Tilt the camera down
+    LookDown,
+    /// Accelerate
+    Throttle,
+    /// Brake, and reverse when stopped
+    Brake,
+    /// Steer left
+    SteerLeft,
+    /// Steer right
+    SteerRight,
+    /// Handbrake
+    Handbrake,
+    /// Next radio station
+    RadioNext,
+    /// Previous radio station
+    RadioPrevious,
+    /// Open the pause menu
+    Pause,
+    /// Move the menu selection up
+    MenuUp,
+    /// Move the menu selection down
+    MenuDown,
+    /// Move the menu selection left, or decrease a value
+    MenuLeft,
+    /// Move the menu selection right, or increase a value
+    MenuRight,
+    /// Activate the selected menu item
+    MenuConfirm,
+    /// Leave the current menu
+    MenuBack,
+    /// Enter or leave photo mode
+    PhotoMode,
+    /// Take a photo
+    PhotoCapture,
+    /// Cycle the photo filter
+    PhotoFilter,
+    /// Roll the photo camera left
+    PhotoRollLeft,
+    /// Roll the photo camera right
+    PhotoRollRight,
+    /// Narrow the photo camera's field of view
+    PhotoZoomIn,
+    /// Widen the photo camera's field of view
+    PhotoZoomOut,
+}
+
+impl Action {
+    /// Every action, in declaration order
+    pub const ALL: [Action; 34] = [
+        Action::MoveForward,
+        Action::MoveBack,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Sprint,
+        Action::Crouch,
+        Action::Aim,
+        Action::Jump,
+        Action::Interact,
+        Action::LookLeft,
+        Action::LookRight,
+        Action::LookUp,
+        Action::LookDown,
+        Action::Throttle,
+        Action::Brake,
+        Action::SteerLeft,
+        Action::SteerRight,
+        Action::Handbrake,
+        Action::RadioNext,
+        Action::RadioPrevious,
+        Action::Pause,
+        Action::MenuUp,
+        Action::MenuDown,
+        Action::MenuLeft,
+        Action::MenuRight,
+        Action::MenuConfirm,
+        Action::MenuBack,
+        Action::PhotoMode,
+        Action::PhotoCapture,
+        Action::PhotoFilter,
+        Action::PhotoRollLeft,
+        Action::PhotoRollRight,
+        Action::PhotoZoomIn,
+        Action::PhotoZoomOut,
+    ];
+
+    /// Get the name used in config files
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::MoveForward => "move_forward",
+            Action::MoveBack => "move_back",
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::Sprint => "sprint",
+            Action::Crouch => "crouch",
+            Action::Aim => "aim",
+            Action::Jump => "jump",
+            Action::Interact => "interact",
+            Action::LookLeft => "look_left",
+            Action::LookRight => "look_right",
+            Action::LookUp => "look_up",
+            Action::LookDown => "look_down",
+            Action::Throttle => "throttle",
+            Action::Brake => "brake",
+            Action::SteerLeft => "steer_left",
+            Action::SteerRight => "steer_right",
+            Action::Handbrake => "handbrake",
+            Action::RadioNext => "radio_next",
+            Action::RadioPrevious => "radio_previous",
+            Action::Pause => "pause",
+            Action::MenuUp => "menu_up",
+            Action::MenuDown => "menu_down",
+            Action::MenuLeft => "menu_left",
+            Action::MenuRight => "menu_right",
+            Action::MenuConfirm => "menu_confirm",
+            Action::MenuBack => "menu_back",
+            Action::PhotoMode => "photo_mode",
+            Action::PhotoCapture => "photo_capture",
+            Action::PhotoFilter => "photo_filter",
+            Action::PhotoRollLeft => "photo_roll_left",
+            Action::PhotoRollRight => "photo_roll_right",
+            Action::PhotoZoomIn => "photo_zoom_in",
+            Action::PhotoZoomOut => "photo_zoom_out",
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Action {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        Action::ALL
+            .into_iter()
+            .find(|action| action.name() == name)
+            .ok_or_else(|| Error::validation(format!("unknown input action `{name}`")))
+    }
+}
+
+impl From<Action> for String {
+    fn from(action: Action) -> Self {
+        action.name().to_string()
+    }
+}
+
+impl TryFrom<String> for Action {
+    type Error = Error;
+
+    fn try_from(name: String) -> Result<Self> {
+        name.parse()
+    }
+}
+
+/// Which half of a gamepad axis a binding reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AxisDirection {
+    /// Right or up
+    Positive,
+    /// Left or down
+    Negative,
+}
+
+impl AxisDirection {
+    /// Get the sign of axis values in this direction
+    pub fn sign(self) -> f32 {
+        match self {
+            AxisDirection::Positive => 1.0,
+            AxisDirection::Negative => -1.0,
+        }
+    }
+}
+
+/// A physical input bound to an action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum Binding {
+    /// A keyboard key, written `key:<KeyCode>`
+    Key(KeyCode),
+    /// A mouse button, written `mouse:<MouseButton>`
+    Mouse(MouseButton),
+    /// A button of the active gamepad, written `gamepad:<GamepadButtonType>`
+    ///
+    /// Analog buttons such as triggers report partial values.
+    GamepadButton(GamepadButtonType),
+    /// One half of a stick axis of the active gamepad, written
+    /// `axis:<GamepadAxisType>+` or `axis:<GamepadAxisType>-`
+    GamepadAxis(GamepadAxisType, AxisDirection),
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "key:{}", input_name(key)?),
+            Binding::Mouse(button) => write!(f, "mouse:{}", input_name(button)?),
+            Binding::GamepadButton(button) => write!(f, "gamepad:{}", input_name(button)?),
+            Binding::GamepadAxis(axis, direction) => {
+                let sign = match direction {
+                    AxisDirection::Positive => '+',
+                    AxisDirection::Negative => '-',
+                };
+                write!(f, "axis:{}{sign}", input_name(axis)?)
+            }
+        }
+    }
+}
+
+impl FromStr for Binding {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        let invalid = || Error::validation(format!("invalid input binding `{text}`"));
+        let (device, name) = text.split_once(':').ok_or_else(invalid)?;
+        match device {
+            "key" => parse_input(name).map(Binding::Key),
+            "mouse" => parse_input(name).map(Binding::Mouse),
+            "gamepad" => parse_input(name).map(Binding::GamepadButton),
+            "axis" => {
+                let (axis, direction) = if let Some(axis) = name.strip_suffix('+') {
+                    (axis, AxisDirection::Positive)
+                } else if let Some(axis) = name.strip_suffix('-') {
+                    (axis, AxisDirection::Negative)
+                } else {
+                    return Err(invalid());
+                };
+                parse_input(axis).map(|axis| Binding::GamepadAxis(axis, direction))
+            }
+            _ => None,
+        }
+        .ok_or_else(invalid)
+    }
+}
+
+impl From<Binding> for String {
+    fn from(binding: Binding) -> Self {
+        binding.to_string()
+    }
+}
+
+impl TryFrom<String> for Binding {
+    type Error = Error;
+
+    fn try_from(text: String) -> Result<Self> {
+        text.parse()
+    }
+}
+
+/// Name of a `bevy_input` enum variant, such as `KeyW`
+fn input_name<T: Serialize>(input: &T) -> std::result::Result<String, fmt::Error> {
+    ron::to_string(input).map_err(|_| fmt::Error)
+}
+
+fn parse_input<T: DeserializeOwned>(name: &str) -> Option<T> {
+    // Reject anything but a bare variant name, such as nested RON values
+    let valid = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "()".contains(c));
+    valid.then(|| ron::from_str(name).ok()).flatten()
+}
+
+/// Physical inputs bound to each action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputBindings {
+    /// Bindings by action; an action may have several bindings and one
+    /// input may drive several actions
+    pub actions: BTreeMap<Action, Vec<Binding>>,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        Self::preset(BindingPreset::Standard)
+    }
+}
+
+/// Starting layouts for [`InputBindings`]
+///
+/// Presets only differ in keyboard and mouse bindings; every preset keeps
+/// the gamepad layout. A preset can be rebound further like any bindings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BindingPreset {
+    /// Keyboard and mouse, WASD movement
+    #[default]
+    Standard,
+    /// Keyboard only, everything reachable by the left hand
+    LeftHand,
+    /// Arrow keys, numpad and mouse, everything reachable by the right hand
+    RightHand,
+}
+
+type KeyTable = &'static [(Action, &'static [KeyCode])];
+
+const STANDARD_KEYS: KeyTable = {
+    use KeyCode::*;
+    &[
+        (Action::MoveForward, &[KeyW]),
+        (Action::MoveBack, &[KeyS]),
+        (Action::MoveLeft, &[KeyA]),
+        (Action::MoveRight, &[KeyD]),
+        (Action::Sprint, &[ShiftLeft, ShiftRight]),
+        (Action::Crouch, &[KeyC]),
+        (Action::Jump, &[Space]),
+        (Action::Interact, &[KeyF]),
+        (Action::Throttle, &[KeyW]),
+        (Action::Brake, &[KeyS]),
+        (Action::SteerLeft, &[KeyA]),
+        (Action::SteerRight, &[KeyD]),
+        (Action::Handbrake, &[Space]),
+        (Action::RadioNext, &[Period]),
+        (Action::RadioPrevious, &[Comma]),
+        (Action::Pause, &[Escape]),
+        (Action::MenuUp, &[ArrowUp]),
+        (Action::MenuDown, &[ArrowDown]),
+        (Action::MenuLeft, &[ArrowLeft]),
+        (Action::MenuRight, &[ArrowRight]),
+        (Action::MenuConfirm, &[Enter]),
+        (Action::MenuBack, &[Escape]),
+        (Action::PhotoMode, &[KeyP]),
+        (Action::PhotoCapture, &[Enter]),
+        (Action::PhotoFilter, &[KeyT]),
+        (Action::PhotoRollLeft, &[KeyQ]),
+        (Action::PhotoRollRight, &[KeyE]),
+        (Action::PhotoZoomIn, &[Equal]),
+        (Action::PhotoZoomOut, &[Minus]),
+    ]
+};
+
+const LEFT_HAND_KEYS: KeyTable = {
+    use KeyCode::*;
+    &[
+        (Action::MoveForward, &[KeyW]),
+        (Action::MoveBack, &[KeyS]),
+        (Action::MoveLeft, &[KeyA]),
+        (Action::MoveRight, &[KeyD]),
+        (Action::Sprint, &[ShiftLeft]),
+        (Action::Crouch, &[KeyC]),
+        (Action::Aim, &[KeyR]),
+        (Action::Jump, &[Space]),
+        (Action::Interact, &[KeyF]),
+        (Action::LookLeft, &[KeyQ]),
+        (Action::LookRight, &[KeyE]),
+        (Action::LookUp, &[KeyZ]),
+        (Action::LookDown, &[KeyX]),
+        (Action::Throttle, &[KeyW]),
+        (Action::Brake, &[KeyS]),
+        (Action::SteerLeft, &[KeyA]),
+        (Action::SteerRight, &[KeyD]),
+        (Action::Handbrake, &[Space]),
+        (Action::RadioNext, &[Digit2]),
+        (Action::RadioPrevious, &[Digit1]),
+        (Action::Pause, &[Escape]),
+        (Action::MenuUp, &[KeyW]),
+        (Action::MenuDown, &[KeyS]),
+        (Action::MenuLeft, &[KeyA]),
+        (Action::MenuRight, &[KeyD]),
+        (Action::MenuConfirm, &[Space]),
+        (Action::MenuBack, &[Escape]),
+        (Action::PhotoMode, &[KeyP]),
+        (Action::PhotoCapture, &[KeyF]),
+        (Action::PhotoFilter, &[KeyT]),
+        (Action::PhotoRollLeft, &[Digit3]),
+        (Action::PhotoRollRight, &[Digit4]),
+        (Action::PhotoZoomIn, &[KeyG]),
+        (Action::PhotoZoomOut, &[KeyB]),
+    ]
+};
+
+const RIGHT_HAND_KEYS: KeyTable = {
+    use KeyCode::*;
+    &[
+        (Action::MoveForward, &[ArrowUp]),
+        (Action::MoveBack, &[ArrowDown]),
+        (Action::MoveLeft, &[ArrowLeft]),
+        (Action::MoveRight, &[ArrowRight]),
+        (Action::Sprint, &[ShiftRight]),
+        (Action::Crouch, &[ControlRight]),
+        (Action::Jump, &[Numpad0]),
+        (Action::Interact, &[Enter]),
+        (Action::Throttle, &[ArrowUp]),
+        (Action::Brake, &[ArrowDown]),
+        (Action::SteerLeft, &[ArrowLeft]),
+        (Action::SteerRight, &[ArrowRight]),
+        (Action::Handbrake, &[Numpad0]),
+        (Action::RadioNext, &[Numpad6]),
+        (Action::RadioPrevious, &[Numpad4]),
+        (Action::Pause, &[Backspace]),
+        (Action::MenuUp, &[ArrowUp]),
+        (Action::MenuDown, &[ArrowDown]),
+        (Action::MenuLeft, &[ArrowLeft]),
+        (Action::MenuRight, &[ArrowRight]),
+        (Action::MenuConfirm, &[Enter]),
+        (Action::MenuBack, &[Backspace]),
+        (Action::PhotoMode, &[Home]),
+        (Action::PhotoCapture, &[Enter]),
+        (Action::PhotoFilter, &[End]),
+        (Action::PhotoRollLeft, &[Numpad7]),
+        (Action::PhotoRollRight, &[Numpad9]),
+        (Action::PhotoZoomIn, &[NumpadAdd]),
+        (Action::PhotoZoomOut, &[NumpadSubtract]),
+    ]
+};
+
+const GAMEPAD_BINDINGS: &[(Action, &[Binding])] = {
+    use AxisDirection::{Negative, Positive};
+    use Binding::{GamepadAxis as Axis, GamepadButton as Pad};
+    use GamepadAxisType::{LeftStickX, LeftStickY, RightStickX, RightStickY};
+    use GamepadButtonType::*;
+    &[
+        (Action::MoveForward, &[Axis(LeftStickY, Positive)]),
+        (Action::MoveBack, &[Axis(LeftStickY, Negative)]),
+        (Action::MoveLeft, &[Axis(LeftStickX, Negative)]),
+        (Action::MoveRight, &[Axis(LeftStickX, Positive)]),
+        (Action::Sprint, &[Pad(LeftThumb)]),
+        (Action::Crouch, &[Pad(RightThumb)]),
+        (Action::Aim, &[Pad(LeftTrigger2)]),
+        (Action::Jump, &[Pad(South)]),
+        (Action::Interact, &[Pad(North)]),
+        (Action::LookLeft, &[Axis(RightStickX, Negative)]),
+        (Action::LookRight, &[Axis(RightStickX, Positive)]),
+        (Action::LookUp, &[Axis(RightStickY, Positive)]),
+        (Action::LookDown, &[Axis(RightStickY, Negative)]),
+        (Action::Throttle, &[Pad(RightTrigger2)]),
+        (Action::Brake, &[Pad(LeftTrigger2)]),
+        (Action::SteerLeft, &[Axis(LeftStickX, Negative)]),
+        (Action::SteerRight, &[Axis(LeftStickX, Positive)]),
+        (Action::Handbrake, &[Pad(RightTrigger)]),
+        (Action::RadioNext, &[Pad(DPadRight)]),
+        (Action::RadioPrevious, &[Pad(DPadLeft)]),
+        (Action::Pause, &[Pad(Start)]),
+        (Action::MenuUp, &[Pad(DPadUp)]),
+        (Action::MenuDown, &[Pad(DPadDown)]),
+        (Action::MenuLeft, &[Pad(DPadLeft)]),
+        (Action::MenuRight, &[Pad(DPadRight)]),
+        (Action::MenuConfirm, &[Pad(South)]),
+        (Action::MenuBack, &[Pad(East)]),
+        (Action::PhotoMode, &[Pad(Select)]),
+        (Action::PhotoCapture, &[Pad(South)]),
+        (Action::PhotoFilter, &[Pad(West)]),
+        (Action::PhotoRollLeft, &[Pad(LeftTrigger)]),
+        (Action::PhotoRollRight, &[Pad(RightTrigger)]),
+        (Action::PhotoZoomIn, &[Pad(DPadUp)]),
+        (Action::PhotoZoomOut, &[Pad(DPadDown)]),
+    ]
+};
+
+impl InputBindings {
+    /// Create the bindings of a preset layout
+    pub fn preset(preset: BindingPreset) -> Self {
+        let (keys, aim_with_mouse) = match preset {
+            BindingPreset::Standard => (STANDARD_KEYS, true),
+            BindingPreset::LeftHand => (LEFT_HAND_KEYS, false),
+            BindingPreset::RightHand => (RIGHT_HAND_KEYS, true),
+        };
+
+        let mut bindings = Self {
+            actions: Action::ALL
+                .into_iter()
+                .map(|action| (action, Vec::new()))
+                .collect(),
+        };
+        for &(action, keys) in keys {
+            for &key in keys {
+                bindings.bind(action, Binding::Key(key));
+            }
+        }
+        if aim_with_mouse {
+            bindings.bind(Action::Aim, Binding::Mouse(MouseButton::Right));
+        }
+        for &(action, pad) in GAMEPAD_BINDINGS {
+            for &binding in pad {
+                bindings.bind(action, binding);
+            }
+        }
+        bindings
+    }
+
+    /// Get the inputs bound to an action
+    pub fn bindings(&self, action: Action) -> &[Binding] {
+        self.actions.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Add a binding to an action, if not already bound
+    pub fn bind(&mut self, action: Action, binding: Binding) {
+        let bindings = self.actions.entry(action).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Remove a binding from an action, returning whether it was bound
+    pub fn unbind(&mut self, action: Action, binding: Binding) -> bool {
+        let Some(bindings) = self.actions.get_mut(&action) else {
+            return false;
+        };
+        let len = bindings.len();
+        bindings.retain(|bound| *bound != binding);
+        bindings.len() != len
+    }
+
+    /// Replace `old` with `new` on an action, keeping its position
+    ///
+    /// If `old` is not bound, `new` is added instead.
+    pub fn rebind(&mut self, action: Action, old: Binding, new: Binding) {
+        let bindings = self.actions.entry(action).or_default();
+        bindings.retain(|bound| *bound != new);
+        match bindings.iter_mut().find(|bound| **bound == old) {
+            Some(bound) => *bound = new,
+            None => bindings.push(new),
+        }
+    }
+
+    /// Restore the default bindings of an action
+    pub fn reset(&mut self, action: Action) {
+        let defaults = Self::default().bindings(action).to_vec();
+        self.actions.insert(action, defaults);
+    }
+
+    /// Get every action an input is bound to, for showing conflicts
+    pub fn actions_bound_to(&self, binding: Binding) -> impl Iterator<Item = Action> + '_ {
+        self.actions
+            .iter()
+            .filter(move |(_, bindings)| bindings.contains(&binding))
+            .map(|(action, _)| *action)
+    }
+}
+
+impl Config for InputBindings {
+    const FILE_NAME: &'static str = "input.ron";
+}
+
+impl Validate for InputBindings {}
+
+/// Edit the live [`InputBindings`], publish the change and save it to `path`
+///
+/// Nothing is published or written if `edit` leaves the bindings unchanged,
+/// or if they fail validation, in which case the error is returned.
+/// `path` is usually the user layer, [`ConfigLoader::user_path`].
+///
+/// [`ConfigLoader::user_path`]: config_core::ConfigLoader::user_path
+///
+/// # Panics
+///
+/// Panics if the [`ConfigHandle<InputBindings>`] resource has not been
+/// initialized with [`config_core::init_config`].
+pub fn rebind(world: &mut World, path: &Path, edit: impl FnOnce(&mut InputBindings)) -> Result<()> {
+    let mut bindings = world
+        .resource::<ConfigHandle<InputBindings>>()
+        .get()
+        .clone();
+    edit(&mut bindings);
+    if publish_config(world, bindings.clone())?.is_empty() {
+        return Ok(());
+    }
+    save_config(&bindings, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::event::Events;
+    use config_core::{init_config, ConfigChanged, ConfigLayer, ConfigLoader};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("key:KeyW", Binding::Key(KeyCode::KeyW))]
+    #[case("mouse:Left", Binding::Mouse(MouseButton::Left))]
+    #[case(
+        "gamepad:RightTrigger2",
+        Binding::GamepadButton(GamepadButtonType::RightTrigger2)
+    )]
+    #[case(
+        "axis:LeftStickX-",
+        Binding::GamepadAxis(GamepadAxisType::LeftStickX, AxisDirection::Negative)
+    )]
+    fn test_binding_round_trips(#[case] text: &str, #[case] binding: Binding) {
+        assert_eq!(text.parse::<Binding>().unwrap(), binding);
+        assert_eq!(binding.to_string(), text);
+    }
+
+    #[rstest]
+    #[case("KeyW")]
+    #[case("key:NotAKey")]
+    #[case("axis:LeftStickX")]
+    #[case("joystick:South")]
+    fn test_invalid_bindings_rejected(#[case] text: &str) {
+        assert!(text.parse::<Binding>().is_err());
+    }
+
+    #[test]
+    fn test_user_layer_overrides_listed_actions() {
+        let dir = tempfile::tempdir().unwrap();
+        let user = dir.path().join("input.ron");
+        std::fs::write(&user, r#"(actions: {"throttle": ["key:ArrowUp"]})"#).unwrap();
+
+        let (bindings, _) = ConfigLoader::new()
+            .load_layers::<InputBindings>(&[ConfigLayer::new("user", &user)])
+            .unwrap();
+        assert_eq!(
+            bindings.bindings(Action::Throttle),
+            [Binding::Key(KeyCode::ArrowUp)]
+        );
+        assert_eq!(
+            bindings.bindings(Action::Brake),
+            InputBindings::default().bindings(Action::Brake)
+        );
+    }
+
+    #[test]
+    fn test_rebind_publishes_and_saves() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("amp/input.ron");
+        let mut world = World::new();
+        init_config(&mut world, InputBindings::default());
+
+        rebind(&mut world, &path, |bindings| {
+            bindings.rebind(
+                Action::Jump,
+                Binding::Key(KeyCode::Space),
+                Binding::Key(KeyCode::KeyJ),
+            );
+        })
+        .unwrap();
+
+        let live = world.resource::<ConfigHandle<InputBindings>>().get();
+        assert_eq!(
+            live.bindings(Action::Jump),
+            [
+                Binding::Key(KeyCode::KeyJ),
+                Binding::GamepadButton(GamepadButtonType::South)
+            ]
+        );
+        assert!(live
+            .actions_bound_to(Binding::Key(KeyCode::Space))
+            .eq([Action::Handbrake]));
+        assert_eq!(
+            world
+                .resource::<Events<ConfigChanged<InputBindings>>>()
+                .len(),
+            1
+        );
+
+        let (saved, _) = ConfigLoader::new()
+            .load_layers::<InputBindings>(&[ConfigLayer::new("user", &path)])
+            .unwrap();
+        assert_eq!(
+            &saved,
+            world.resource::<ConfigHandle<InputBindings>>().get()
+        );
+    }
+
+    #[test]
+    fn test_one_handed_presets() {
+        let left = InputBindings::preset(BindingPreset::LeftHand);
+        assert!(left
+            .actions
+            .values()
+            .flatten()
+            .all(|binding| !matches!(binding, Binding::Mouse(_))));
+        assert!(left
+            .bindings(Action::LookLeft)
+            .contains(&Binding::Key(KeyCode::KeyQ)));
+
+        let right = InputBindings::preset(BindingPreset::RightHand);
+        assert_eq!(
+            right.bindings(Action::Throttle),
+            [
+                Binding::Key(KeyCode::ArrowUp),
+                Binding::GamepadButton(GamepadButtonType::RightTrigger2)
+            ]
+        );
+        assert_eq!(
+            InputBindings::default(),
+            InputBindings::preset(BindingPreset::Standard)
+        );
+        for preset in [left, right] {
+            assert!(Action::ALL
+                .into_iter()
+                .all(|action| !preset.bindings(action).is_empty()));
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! On-foot movement and camera controls
+
+use crate::actions::ActionInput;
+use crate::analog::apply_radial_dead_zone;
+use crate::bindings::Action;
+use bevy_ecs::prelude::*;
+use bevy_input::mouse::MouseMotion;
+use bevy_math::Vec2;
+
+/// On-foot input for the current frame
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq)]
+pub struct CharacterInput {
+    /// Movement direction relative to the camera, `y` forward, length at most 1
+    pub movement: Vec2,
+    /// Sprinting, held or toggled depending on the accessibility settings
+    pub sprint: bool,
+    /// Crouching, held or toggled
+    pub crouch: bool,
+    /// Aiming, held or toggled
+    pub aim: bool,
+    /// Jump pressed this frame
+    pub jump: bool,
+    /// Interact, such as entering a vehicle, pressed this frame
+    pub interact: bool,
+}
+
+/// Camera look input for the current frame
+///
+/// `x` turns right and `y` looks up. Mouse movement is already a per-frame
+/// angle, while the gamepad stick gives a turn rate that must be scaled by
+/// the frame time; [`CameraInput::look`] combines both.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq)]
+pub struct CameraInput {
+    /// Mouse look this frame, in radians
+    pub mouse_delta: Vec2,
+    /// Gamepad look rate, in radians per second
+    pub stick_rate: Vec2,
+}
+
+impl CameraInput {
+    /// Get the total look angle for a frame lasting `delta_seconds`
+    pub fn look(&self, delta_seconds: f32) -> Vec2 {
+        self.mouse_delta + self.stick_rate * delta_seconds
+    }
+}
+
+/// Fill [`CharacterInput`] from the bound on-foot actions
+///
+/// By default WASD or the left stick move, Shift or a left stick click
+/// sprints, C or a right stick click crouches, the right mouse button or
+/// left trigger aims, Space or the south button jumps and F or the north
+/// button interacts. Sprint, crouch and aim can be switched to toggles in
+/// the [`AccessibilitySettings`](crate::AccessibilitySettings).
+pub fn read_character_input(actions: ActionInput, mut input: ResMut<CharacterInput>) {
+    let settings = actions.settings();
+    let accessibility = &settings.accessibility;
+    let movement = apply_radial_dead_zone(
+        Vec2::new(
+            actions.axis(Action::MoveLeft, Action::MoveRight),
+            actions.axis(Action::MoveBack, Action::MoveForward),
+        ),
+        settings.stick_dead_zone,
+    );
+
+    let held_or_toggled = |action, toggle, was_on: bool| {
+        if toggle {
+            was_on != actions.just_pressed(action)
+        } else {
+            actions.pressed(action)
+        }
+    };
+    let sprint = held_or_toggled(Action::Sprint, accessibility.toggle_sprint, input.sprint)
+        // A toggled sprint ends when the character stops
+        && (movement != Vec2::ZERO || !accessibility.toggle_sprint);
+
+    *input = CharacterInput {
+        movement,
+        sprint,
+        crouch: held_or_toggled(Action::Crouch, accessibility.toggle_crouch, input.crouch),
+        aim: held_or_toggled(Action::Aim, accessibility.toggle_aim, input.aim),
+        jump: actions.just_pressed(Action::Jump),
+        interact: actions.just_pressed(Action::Interact),
+    };
+}
+
+/// Fill [`CameraInput`] from mouse motion and the bound look actions
+///
+/// By default the look actions are bound to the right stick.
+pub fn read_camera_input(
+    mut motion: EventReader<MouseMotion>,
+    actions: ActionInput,
+    mut input: ResMut<CameraInput>,
+) {
+    let settings = actions.settings();
+    let live: Vec2 = motion.read().map(|event| event.delta).sum();
+    let mouse = actions
+        .replayed()
+        .map_or(live, |frame| Vec2::from(frame.mouse_motion));
+    let stick = apply_radial_dead_zone(
+        Vec2::new(
+            actions.axis(Action::LookLeft, Action::LookRight),
+            actions.axis(Action::LookDown, Action::LookUp),
+        ),
+        settings.stick_dead_zone,
+    );
+
+    // Screen-space mouse motion grows downwards
+    let invert = if settings.invert_camera_y { -1.0 } else { 1.0 };
+    *input = CameraInput {
+        mouse_delta: Vec2::new(mouse.x, -mouse.y * invert) * settings.mouse_sensitivity,
+        stick_rate: Vec2::new(stick.x, stick.y * invert) * settings.camera_sensitivity,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ActiveGamepad, InputBindings, InputSettings};
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_input::gamepad::{Gamepad, GamepadAxis, GamepadAxisType, GamepadButton};
+    use bevy_input::keyboard::KeyCode;
+    use bevy_input::mouse::MouseButton;
+    use bevy_input::{Axis, ButtonInput};
+
+    const PAD: Gamepad = Gamepad { id: 0 };
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<ButtonInput<MouseButton>>();
+        world.init_resource::<ButtonInput<GamepadButton>>();
+        world.init_resource::<Axis<GamepadButton>>();
+        world.init_resource::<Axis<GamepadAxis>>();
+        world.init_resource::<Events<MouseMotion>>();
+        config_core::init_config(&mut world, InputSettings::default());
+        world.init_resource::<CharacterInput>();
+        world.init_resource::<CameraInput>();
+        world.insert_resource(ActiveGamepad(Some(PAD)));
+        config_core::init_config(&mut world, InputBindings::default());
+        world
+    }
+
+    #[test]
+    fn test_movement_combines_keyboard_and_stick() {
+        let mut world = world();
+        world
+            .resource_mut::<Axis<GamepadAxis>>()
+            .set(GamepadAxis::new(PAD, GamepadAxisType::LeftStickX), 0.08);
+        let mut keys = world.resource_mut::<ButtonInput<KeyCode>>();
+        keys.press(KeyCode::KeyW);
+        keys.press(KeyCode::KeyD);
+        keys.press(KeyCode::Space);
+
+        world.run_system_once(read_character_input);
+        let input = *world.resource::<CharacterInput>();
+        assert!((input.movement.length() - 1.0).abs() < 1e-5);
+        assert!((input.movement.x - input.movement.y).abs() < 1e-5);
+        assert!(input.jump);
+        assert!(!input.sprint);
+    }
+
+    #[test]
+    fn test_camera_uses_mouse_and_right_stick() {
+        let mut world = world();
+        config_core::init_config(
+            &mut world,
+            InputSettings {
+                invert_camera_y: true,
+                ..InputSettings::default()
+            },
+        );
+        world.send_event(MouseMotion {
+            delta: Vec2::new(10.0, 0.0),
+        });
+        world
+            .resource_mut::<Axis<GamepadAxis>>()
+            .set(GamepadAxis::new(PAD, GamepadAxisType::RightStickY), 1.0);
+
+        world.run_system_once(read_camera_input);
+        let input = *world.resource::<CameraInput>();
+        let settings = InputSettings::default();
+        assert_eq!(input.mouse_delta.x, 10.0 * settings.mouse_sensitivity);
+        assert_eq!(input.stick_rate.y, -settings.camera_sensitivity);
+        assert_eq!(input.look(0.5).y, -settings.camera_sensitivity * 0.5);
+    }
+
+    #[test]
+    fn test_toggle_sprint_and_crouch() {
+        let mut world = world();
+        let mut settings = InputSettings::default();
+        settings.accessibility.toggle_sprint = true;
+        settings.accessibility.toggle_crouch = true;
+        config_core::init_config(&mut world, settings);
+
+        let mut keys = world.resource_mut::<ButtonInput<KeyCode>>();
+        keys.press(KeyCode::KeyW);
+        keys.press(KeyCode::ShiftLeft);
+        keys.press(KeyCode::KeyC);
+        world.run_system_once(read_character_input);
+        let input = *world.resource::<CharacterInput>();
+        assert!(input.sprint && input.crouch);
+
+        // Releasing keeps the toggles on; pressing again turns crouch off
+        let mut keys = world.resource_mut::<ButtonInput<KeyCode>>();
+        keys.clear();
+        keys.release(KeyCode::ShiftLeft);
+        keys.release(KeyCode::KeyC);
+        world.run_system_once(read_character_input);
+        assert!(world.resource::<CharacterInput>().sprint);
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::KeyC);
+        world.run_system_once(read_character_input);
+        let input = *world.resource::<CharacterInput>();
+        assert!(input.sprint && !input.crouch);
+
+        // Stopping ends a toggled sprint
+        let mut keys = world.resource_mut::<ButtonInput<KeyCode>>();
+        keys.clear();
+        keys.release(KeyCode::KeyW);
+        world.run_system_once(read_character_input);
+        assert!(!world.resource::<CharacterInput>().sprint);
+    }
+}
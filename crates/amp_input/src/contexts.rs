@@ -0,0 +1,198 @@
+//! Input contexts
+//!
+//! The same physical input means different things depending on what the
+//! player is doing: W walks on foot and accelerates in a car, Escape opens
+//! the pause menu in game and backs out of a menu inside it. Each [`Action`]
+//! belongs to one or more [`InputContext`]s, and [`ActionInput`] only
+//! evaluates actions of the current context in [`ActiveInputContexts`].
+//!
+//! Contexts form a stack. The bottom entry is the gameplay context, which
+//! the interaction systems switch between [`InputContext::OnFoot`] and
+//! [`InputContext::InVehicle`]; menus and other overlays push on top of it
+//! and pop when closed. Systems request changes with [`InputContextRequest`]
+//! events, applied by [`apply_input_context_requests`].
+//!
+//! [`ActionInput`]: crate::ActionInput
+
+use crate::bindings::Action;
+use bevy_ecs::prelude::*;
+
+/// What the player's input currently controls
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputContext {
+    /// Walking around as the character
+    OnFoot,
+    /// Driving a vehicle
+    InVehicle,
+    /// Navigating a menu
+    Menu,
+    /// Flying the photo mode camera
+    Photo,
+}
+
+impl Action {
+    /// Get the contexts in which this action is evaluated
+    pub fn contexts(self) -> &'static [InputContext] {
+        use InputContext::{InVehicle, Menu, OnFoot, Photo};
+        match self {
+            Action::MoveForward
+            | Action::MoveBack
+            | Action::MoveLeft
+            | Action::MoveRight
+            | Action::Sprint
+            | Action::Crouch
+            | Action::Jump => &[OnFoot, Photo],
+            Action::Aim => &[OnFoot],
+            Action::Throttle
+            | Action::Brake
+            | Action::SteerLeft
+            | Action::SteerRight
+            | Action::Handbrake
+            | Action::RadioNext
+            | Action::RadioPrevious => &[InVehicle],
+            Action::Interact | Action::Pause => &[OnFoot, InVehicle],
+            Action::LookLeft | Action::LookRight | Action::LookUp | Action::LookDown => {
+                &[OnFoot, InVehicle, Photo]
+            }
+            Action::MenuUp
+            | Action::MenuDown
+            | Action::MenuLeft
+            | Action::MenuRight
+            | Action::MenuConfirm
+            | Action::MenuBack => &[Menu],
+            Action::PhotoMode => &[OnFoot, InVehicle, Photo],
+            Action::PhotoCapture
+            | Action::PhotoFilter
+            | Action::PhotoRollLeft
+            | Action::PhotoRollRight
+            | Action::PhotoZoomIn
+            | Action::PhotoZoomOut => &[Photo],
+        }
+    }
+}
+
+/// Stack of input contexts; only the top one is evaluated
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct ActiveInputContexts {
+    stack: Vec<InputContext>,
+}
+
+impl Default for ActiveInputContexts {
+    fn default() -> Self {
+        Self::new(InputContext::OnFoot)
+    }
+}
+
+impl ActiveInputContexts {
+    /// Create a stack holding only a gameplay context
+    pub fn new(base: InputContext) -> Self {
+        Self { stack: vec![base] }
+    }
+
+    /// Get the context actions are evaluated in
+    pub fn current(&self) -> InputContext {
+        *self.stack.last().expect("context stack is never empty")
+    }
+
+    /// Get the gameplay context at the bottom of the stack
+    pub fn base(&self) -> InputContext {
+        self.stack[0]
+    }
+
+    /// Replace the gameplay context, keeping overlays pushed on top of it
+    pub fn set_base(&mut self, context: InputContext) {
+        self.stack[0] = context;
+    }
+
+    /// Push an overlay context such as a menu
+    pub fn push(&mut self, context: InputContext) {
+        self.stack.push(context);
+    }
+
+    /// Pop the topmost overlay and return it
+    ///
+    /// The gameplay context is never popped.
+    pub fn pop(&mut self) -> Option<InputContext> {
+        if self.stack.len() > 1 {
+            self.stack.pop()
+        } else {
+            None
+        }
+    }
+
+    /// Check if an action is evaluated in the current context
+    pub fn allows(&self, action: Action) -> bool {
+        action.contexts().contains(&self.current())
+    }
+}
+
+/// Request to change the [`ActiveInputContexts`]
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputContextRequest {
+    /// Switch the gameplay context, e.g. when entering or leaving a vehicle
+    SetBase(InputContext),
+    /// Open an overlay such as a menu
+    Push(InputContext),
+    /// Close the topmost overlay if it is the given context
+    Pop(InputContext),
+}
+
+/// Apply [`InputContextRequest`]s in the order they were sent
+pub fn apply_input_context_requests(
+    mut requests: EventReader<InputContextRequest>,
+    mut contexts: ResMut<ActiveInputContexts>,
+) {
+    for request in requests.read() {
+        match *request {
+            InputContextRequest::SetBase(context) => contexts.set_base(context),
+            InputContextRequest::Push(context) => contexts.push(context),
+            InputContextRequest::Pop(context) => {
+                if contexts.current() == context {
+                    contexts.pop();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::system::System;
+
+    #[test]
+    fn test_menu_overlay_hides_gameplay_actions() {
+        let mut world = World::new();
+        world.init_resource::<Events<InputContextRequest>>();
+        world.init_resource::<ActiveInputContexts>();
+        let mut apply = IntoSystem::into_system(apply_input_context_requests);
+        apply.initialize(&mut world);
+
+        world.send_event(InputContextRequest::SetBase(InputContext::InVehicle));
+        world.send_event(InputContextRequest::Push(InputContext::Menu));
+        apply.run((), &mut world);
+
+        let contexts = world.resource::<ActiveInputContexts>();
+        assert_eq!(contexts.current(), InputContext::Menu);
+        assert_eq!(contexts.base(), InputContext::InVehicle);
+        assert!(contexts.allows(Action::MenuBack));
+        assert!(!contexts.allows(Action::Throttle));
+        assert!(!contexts.allows(Action::Interact));
+
+        world.send_event(InputContextRequest::Pop(InputContext::Menu));
+        world.send_event(InputContextRequest::Pop(InputContext::InVehicle));
+        apply.run((), &mut world);
+
+        let contexts = world.resource::<ActiveInputContexts>();
+        assert_eq!(contexts.current(), InputContext::InVehicle);
+        assert!(contexts.allows(Action::Throttle));
+        assert!(!contexts.allows(Action::MoveForward));
+    }
+
+    #[test]
+    fn test_every_action_has_a_context() {
+        for action in Action::ALL {
+            assert!(!action.contexts().is_empty(), "{action}");
+        }
+    }
+}
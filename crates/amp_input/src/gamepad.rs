@@ -0,0 +1,86 @@
+//! Gamepad selection and state access
+
+use crate::analog::{apply_dead_zone, apply_radial_dead_zone};
+use bevy_ecs::prelude::*;
+use bevy_ecs::system::SystemParam;
+use bevy_input::gamepad::{
+    Gamepad, GamepadAxis, GamepadAxisType, GamepadButton, GamepadButtonType, Gamepads,
+};
+use bevy_input::{Axis, ButtonInput};
+use bevy_math::Vec2;
+
+/// The gamepad controlling the player, if any
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ActiveGamepad(pub Option<Gamepad>);
+
+/// Keep [`ActiveGamepad`] pointing at a connected gamepad
+///
+/// The current gamepad is kept while it stays connected; otherwise the
+/// connected gamepad with the lowest id is used.
+pub fn select_active_gamepad(gamepads: Res<Gamepads>, mut active: ResMut<ActiveGamepad>) {
+    if active.0.is_some_and(|gamepad| gamepads.contains(gamepad)) {
+        return;
+    }
+    let next = gamepads.iter().min_by_key(|gamepad| gamepad.id);
+    if active.0 != next {
+        active.0 = next;
+    }
+}
+
+/// Read access to the [`ActiveGamepad`]
+///
+/// Every value reads as released or centered while no gamepad is active.
+#[derive(SystemParam)]
+pub struct GamepadInput<'w> {
+    active: Res<'w, ActiveGamepad>,
+    buttons: Res<'w, ButtonInput<GamepadButton>>,
+    button_axes: Res<'w, Axis<GamepadButton>>,
+    axes: Res<'w, Axis<GamepadAxis>>,
+}
+
+impl GamepadInput<'_> {
+    /// Get the active gamepad
+    pub fn gamepad(&self) -> Option<Gamepad> {
+        self.active.0
+    }
+
+    /// Check if a button is held
+    pub fn pressed(&self, button: GamepadButtonType) -> bool {
+        self.gamepad()
+            .is_some_and(|gamepad| self.buttons.pressed(GamepadButton::new(gamepad, button)))
+    }
+
+    /// Check if a button was pressed this frame
+    pub fn just_pressed(&self, button: GamepadButtonType) -> bool {
+        self.gamepad().is_some_and(|gamepad| {
+            self.buttons
+                .just_pressed(GamepadButton::new(gamepad, button))
+        })
+    }
+
+    /// Get an analog button such as a trigger, from 0 to 1, with a dead zone
+    ///
+    /// Gamepads without analog triggers report 1 while the button is held.
+    pub fn trigger(&self, button: GamepadButtonType, dead_zone: f32) -> f32 {
+        let Some(gamepad) = self.gamepad() else {
+            return 0.0;
+        };
+        let value = self
+            .button_axes
+            .get(GamepadButton::new(gamepad, button))
+            .unwrap_or_else(|| f32::from(u8::from(self.pressed(button))));
+        apply_dead_zone(value, dead_zone).max(0.0)
+    }
+
+    /// Get the raw value of a stick axis, from -1 to 1
+    pub fn axis(&self, axis: GamepadAxisType) -> f32 {
+        self.gamepad()
+            .and_then(|gamepad| self.axes.get(GamepadAxis::new(gamepad, axis)))
+            .unwrap_or_default()
+    }
+
+    /// Get a stick with a radial dead zone; `y` is positive when pushed up
+    pub fn stick(&self, x: GamepadAxisType, y: GamepadAxisType, dead_zone: f32) -> Vec2 {
+        apply_radial_dead_zone(Vec2::new(self.axis(x), self.axis(y)), dead_zone)
+    }
+}
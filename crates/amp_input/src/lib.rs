@@ -0,0 +1,69 @@
+//! Player input mapping
+//!
+//! This crate turns raw keyboard, mouse and gamepad state from `bevy_input`
+//! into the per-frame input resources gameplay systems read:
+//! [`CharacterInput`] and [`CameraInput`] on foot, and [`VehicleInput`] when
+//! driving. Keyboard and mouse work alongside the [`ActiveGamepad`]; inputs
+//! map to actions through the rebindable [`InputBindings`] config.
+//!
+//! # Features
+//!
+//! - `unstable_advanced_input`: input contexts (on foot, in vehicle, menu)
+//!   that gate which actions are evaluated, see `contexts`
+
+#![deny(missing_docs)]
+
+pub mod actions;
+pub mod analog;
+pub mod bindings;
+pub mod character;
+#[cfg(feature = "unstable_advanced_input")]
+pub mod contexts;
+pub mod gamepad;
+pub mod recording;
+pub mod settings;
+pub mod vehicle;
+
+pub use actions::ActionInput;
+pub use bindings::{rebind, Action, AxisDirection, Binding, BindingPreset, InputBindings};
+pub use character::{read_camera_input, read_character_input, CameraInput, CharacterInput};
+#[cfg(feature = "unstable_advanced_input")]
+pub use contexts::{
+    apply_input_context_requests, ActiveInputContexts, InputContext, InputContextRequest,
+};
+pub use gamepad::{select_active_gamepad, ActiveGamepad, GamepadInput};
+pub use recording::{
+    play_back_actions, record_actions, ActionFrame, ActionPlayback, ActionRecorder,
+};
+pub use settings::{AccessibilitySettings, InputSettings};
+pub use vehicle::{read_vehicle_input, VehicleInput};
+
+use bevy_ecs::schedule::{IntoSystemConfigs, SystemConfigs, SystemSet};
+
+/// System set of the per-frame input systems added by [`systems`]
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InputSystems;
+
+/// Get the per-frame input systems, ordered and in [`InputSystems`]
+///
+/// Playback, gamepad selection and context changes run first, then the
+/// input readers, then [`record_actions`] once the frame's input is final.
+pub fn systems() -> SystemConfigs {
+    #[cfg(not(feature = "unstable_advanced_input"))]
+    let prepare = (play_back_actions, select_active_gamepad).chain();
+    #[cfg(feature = "unstable_advanced_input")]
+    let prepare = (
+        play_back_actions,
+        select_active_gamepad,
+        apply_input_context_requests,
+    )
+        .chain();
+
+    (
+        prepare,
+        (read_character_input, read_camera_input, read_vehicle_input),
+        record_actions,
+    )
+        .chain()
+        .in_set(InputSystems)
+}
@@ -0,0 +1,229 @@
+//! Recording and playback of action state
+//!
+//! [`record_actions`] writes the state of every action each frame through an
+//! [`ActionRecorder`], and [`play_back_actions`] feeds a recording back
+//! through an [`ActionPlayback`], which [`ActionInput`] then reads instead of
+//! the devices. Together with the recorded world seed this replays a session
+//! headlessly, so gameplay flows can be covered by deterministic tests.
+//!
+//! Run [`play_back_actions`] before the systems reading input and
+//! [`record_actions`] after the input state for the frame is final.
+
+use crate::actions::ActionInput;
+use crate::bindings::Action;
+use amp_core::recording::{InputPlayback, InputRecorder};
+use amp_core::{Error, Result};
+use bevy_ecs::prelude::*;
+use bevy_input::mouse::MouseMotion;
+use bevy_math::Vec2;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// State of every action during one frame
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActionFrame {
+    /// How far each action was pushed, from 0 to 1; released actions are left out
+    pub values: BTreeMap<Action, f32>,
+    /// Actions held
+    pub pressed: BTreeSet<Action>,
+    /// Actions pressed this frame
+    pub just_pressed: BTreeSet<Action>,
+    /// Mouse motion this frame, in pixels
+    pub mouse_motion: [f32; 2],
+}
+
+impl ActionFrame {
+    /// Get how far an action was pushed, from 0 to 1
+    pub fn value(&self, action: Action) -> f32 {
+        self.values.get(&action).copied().unwrap_or_default()
+    }
+}
+
+type RecordingWriter = Box<dyn Write + Send + Sync>;
+
+/// Destination of [`record_actions`]
+///
+/// A write error stops the recording and is returned by
+/// [`ActionRecorder::finish`].
+#[derive(Resource)]
+pub struct ActionRecorder {
+    recorder: InputRecorder<ActionFrame, RecordingWriter>,
+    error: Option<Error>,
+}
+
+impl ActionRecorder {
+    /// Start recording to `writer` for a session using world seed `seed`
+    pub fn new(writer: impl Write + Send + Sync + 'static, seed: u64) -> Result<Self> {
+        Ok(Self {
+            recorder: InputRecorder::new(Box::new(writer) as RecordingWriter, seed)?,
+            error: None,
+        })
+    }
+
+    /// Start recording to a new file at `path`
+    pub fn create(path: impl AsRef<Path>, seed: u64) -> Result<Self> {
+        Self::new(BufWriter::new(std::fs::File::create(path)?), seed)
+    }
+
+    /// Get the number of frames recorded so far
+    pub fn frames(&self) -> usize {
+        self.recorder.frames()
+    }
+
+    /// Append a frame, unless an earlier write failed
+    pub fn record(&mut self, frame: &ActionFrame) {
+        if self.error.is_none() {
+            self.error = self.recorder.record(frame).err();
+        }
+    }
+
+    /// Flush the recording, returning the first write error
+    pub fn finish(self) -> Result<()> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        self.recorder.finish().map(drop)
+    }
+}
+
+/// Record this frame's action state, if an [`ActionRecorder`] exists
+pub fn record_actions(
+    actions: ActionInput,
+    mut motion: EventReader<MouseMotion>,
+    recorder: Option<ResMut<ActionRecorder>>,
+) {
+    let live: Vec2 = motion.read().map(|event| event.delta).sum();
+    let Some(mut recorder) = recorder else {
+        return;
+    };
+    let mut frame = actions.frame();
+    frame.mouse_motion = actions
+        .replayed()
+        .map_or(live.to_array(), |replayed| replayed.mouse_motion);
+    recorder.record(&frame);
+}
+
+/// Recorded action state replacing device input
+#[derive(Resource, Debug)]
+pub struct ActionPlayback {
+    playback: InputPlayback<ActionFrame>,
+    current: Option<ActionFrame>,
+}
+
+impl ActionPlayback {
+    /// Play back a loaded recording
+    pub fn new(playback: InputPlayback<ActionFrame>) -> Self {
+        Self {
+            playback,
+            current: None,
+        }
+    }
+
+    /// Load the recording at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        InputPlayback::open(path).map(Self::new)
+    }
+
+    /// Get the world seed the recording was made with
+    pub fn seed(&self) -> u64 {
+        self.playback.seed()
+    }
+
+    /// Get the frame being played, or `None` before the first frame and
+    /// after the last
+    pub fn current(&self) -> Option<&ActionFrame> {
+        self.current.as_ref()
+    }
+
+    /// Check if every frame has been played
+    pub fn is_finished(&self) -> bool {
+        self.playback.is_finished()
+    }
+
+    /// Move to the next recorded frame
+    pub fn advance(&mut self) {
+        self.current = self.playback.next_frame().cloned();
+    }
+}
+
+/// Advance the [`ActionPlayback`] by one frame, if one exists
+///
+/// Once the recording ends, input comes from the devices again.
+pub fn play_back_actions(playback: Option<ResMut<ActionPlayback>>) {
+    if let Some(mut playback) = playback {
+        playback.advance();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_vehicle_input, ActiveGamepad, InputBindings, InputSettings, VehicleInput};
+    use bevy_ecs::schedule::Schedule;
+    use bevy_input::gamepad::{GamepadAxis, GamepadButton};
+    use bevy_input::keyboard::KeyCode;
+    use bevy_input::mouse::MouseButton;
+    use bevy_input::{Axis, ButtonInput};
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<ButtonInput<MouseButton>>();
+        world.init_resource::<ButtonInput<GamepadButton>>();
+        world.init_resource::<Axis<GamepadButton>>();
+        world.init_resource::<Axis<GamepadAxis>>();
+        world.init_resource::<Events<MouseMotion>>();
+        config_core::init_config(&mut world, InputSettings::default());
+        world.init_resource::<VehicleInput>();
+        world.init_resource::<ActiveGamepad>();
+        config_core::init_config(&mut world, InputBindings::default());
+        world
+    }
+
+    fn schedule() -> Schedule {
+        let mut schedule = Schedule::default();
+        schedule.add_systems((play_back_actions, read_vehicle_input, record_actions).chain());
+        schedule
+    }
+
+    #[test]
+    fn test_playback_reproduces_recorded_driving() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("drive.ron");
+
+        let mut world = world();
+        world.insert_resource(ActionRecorder::create(&path, 9).unwrap());
+        let mut schedule = schedule();
+        let mut live = Vec::new();
+        for keys in [&[KeyCode::KeyW][..], &[KeyCode::KeyW, KeyCode::KeyA], &[]] {
+            let mut input = world.resource_mut::<ButtonInput<KeyCode>>();
+            input.clear();
+            input.release_all();
+            for key in keys {
+                input.press(*key);
+            }
+            schedule.run(&mut world);
+            live.push(*world.resource::<VehicleInput>());
+        }
+        let recorder = world.remove_resource::<ActionRecorder>().unwrap();
+        assert_eq!(recorder.frames(), 3);
+        recorder.finish().unwrap();
+
+        let mut world = self::world();
+        let playback = ActionPlayback::open(&path).unwrap();
+        assert_eq!(playback.seed(), 9);
+        world.insert_resource(playback);
+        let mut schedule = self::schedule();
+        let replayed: Vec<VehicleInput> = (0..3)
+            .map(|_| {
+                schedule.run(&mut world);
+                *world.resource::<VehicleInput>()
+            })
+            .collect();
+        assert_eq!(replayed, live);
+        assert!(world.resource::<ActionPlayback>().is_finished());
+    }
+}
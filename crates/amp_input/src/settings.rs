@@ -0,0 +1,118 @@
+//! Input sensitivity, dead zone and accessibility settings
+//!
+//! [`InputSettings`] is a config_core configuration, `input_settings.ron`,
+//! read at runtime from the [`ConfigHandle<InputSettings>`] resource, so a
+//! settings menu changes it with [`config_core::publish_config`].
+//!
+//! [`ConfigHandle<InputSettings>`]: config_core::ConfigHandle
+
+use config_core::{Config, Validate, ValidationErrors};
+use serde::{Deserialize, Serialize};
+
+/// Player-adjustable input settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputSettings {
+    /// Radial dead zone of both gamepad sticks, from 0 to 1
+    pub stick_dead_zone: f32,
+    /// Dead zone of the analog triggers, from 0 to 1
+    pub trigger_dead_zone: f32,
+    /// Multiplier for gamepad steering; values above 1 reach full lock sooner
+    pub steer_sensitivity: f32,
+    /// Camera turn rate at full right-stick deflection, in radians per second
+    pub camera_sensitivity: f32,
+    /// Camera turn per pixel of mouse movement, in radians
+    pub mouse_sensitivity: f32,
+    /// Invert vertical camera look for both mouse and gamepad
+    pub invert_camera_y: bool,
+    /// Options for players who find the default controls hard to use
+    pub accessibility: AccessibilitySettings,
+}
+
+impl Default for InputSettings {
+    fn default() -> Self {
+        Self {
+            stick_dead_zone: 0.15,
+            trigger_dead_zone: 0.05,
+            steer_sensitivity: 1.0,
+            camera_sensitivity: 3.0,
+            mouse_sensitivity: 0.003,
+            invert_camera_y: false,
+            accessibility: AccessibilitySettings::default(),
+        }
+    }
+}
+
+impl Config for InputSettings {
+    const FILE_NAME: &'static str = "input_settings.ron";
+}
+
+impl Validate for InputSettings {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.check_range("stick_dead_zone", self.stick_dead_zone, 0.0..=0.9);
+        errors.check_range("trigger_dead_zone", self.trigger_dead_zone, 0.0..=0.9);
+        errors.check_range("steer_sensitivity", self.steer_sensitivity, 0.1..=5.0);
+        errors.check_range("camera_sensitivity", self.camera_sensitivity, 0.0..=20.0);
+        errors.check_range("mouse_sensitivity", self.mouse_sensitivity, 0.0..=0.1);
+        errors.nested("accessibility", |errors| {
+            self.accessibility.validate(errors)
+        });
+    }
+}
+
+/// Accessibility options of the input layer
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessibilitySettings {
+    /// Press sprint once to start running instead of holding it; running
+    /// stops when the character stops moving
+    pub toggle_sprint: bool,
+    /// Press crouch to toggle crouching instead of holding it
+    pub toggle_crouch: bool,
+    /// Press aim to toggle aiming instead of holding it
+    pub toggle_aim: bool,
+    /// Steering assist, from 0 (off) to 1 (strongest)
+    ///
+    /// Stronger assist softens small steering inputs, so holding a steady
+    /// line needs less precision while full lock stays reachable.
+    pub steering_assist: f32,
+    /// Drive at full throttle unless braking
+    pub auto_accelerate: bool,
+}
+
+impl Validate for AccessibilitySettings {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.check_range("steering_assist", self.steering_assist, 0.0..=1.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use config_core::{ConfigLayer, ConfigLoader};
+
+    #[test]
+    fn test_loads_accessibility_overrides_and_validates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("input_settings.ron");
+        std::fs::write(
+            &path,
+            "(accessibility: (toggle_sprint: true, steering_assist: 0.5))",
+        )
+        .unwrap();
+        let layers = [ConfigLayer::new("user", &path)];
+
+        let (settings, _) = ConfigLoader::new()
+            .load_layers::<InputSettings>(&layers)
+            .unwrap();
+        assert!(settings.accessibility.toggle_sprint);
+        assert_eq!(settings.accessibility.steering_assist, 0.5);
+        assert_eq!(settings.stick_dead_zone, 0.15);
+
+        std::fs::write(&path, "(accessibility: (steering_assist: 2.0))").unwrap();
+        let err = ConfigLoader::new()
+            .load_layers::<InputSettings>(&layers)
+            .unwrap_err();
+        assert!(err.to_string().contains("accessibility.steering_assist"));
+    }
+}
@@ -0,0 +1,178 @@
+//! Driving controls
+
+use crate::actions::ActionInput;
+use crate::analog::apply_dead_zone;
+use crate::bindings::Action;
+use bevy_ecs::prelude::*;
+
+/// Driver input for the current frame
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq)]
+pub struct VehicleInput {
+    /// Throttle, from 0 to 1
+    pub throttle: f32,
+    /// Brake, and reverse when stopped, from 0 to 1
+    pub brake: f32,
+    /// Steering, from -1 (full left) to 1 (full right)
+    pub steer: f32,
+    /// Handbrake held
+    pub handbrake: bool,
+    /// Radio stations to skip this frame; negative steps back
+    pub radio_step: i32,
+}
+
+/// Fill [`VehicleInput`] from the bound driving actions
+///
+/// By default W/S or the right/left triggers drive and brake, A/D or the
+/// left stick steer, Space or the right bumper pull the handbrake and
+/// Comma/Period or the d-pad change the radio station. Steering assist and
+/// auto-accelerate come from the
+/// [`AccessibilitySettings`](crate::AccessibilitySettings).
+pub fn read_vehicle_input(actions: ActionInput, mut input: ResMut<VehicleInput>) {
+    let settings = actions.settings();
+    let accessibility = &settings.accessibility;
+    let steer = apply_dead_zone(
+        actions.axis(Action::SteerLeft, Action::SteerRight),
+        settings.stick_dead_zone,
+    );
+    let steer = (steer * settings.steer_sensitivity).clamp(-1.0, 1.0);
+    // Raising to a power above 1 softens small inputs but keeps full lock
+    let steer = steer.signum() * steer.abs().powf(1.0 + 2.0 * accessibility.steering_assist);
+
+    let brake = actions.value(Action::Brake);
+    let throttle = if accessibility.auto_accelerate && brake == 0.0 {
+        1.0
+    } else {
+        actions.value(Action::Throttle)
+    };
+
+    *input = VehicleInput {
+        throttle,
+        brake,
+        steer,
+        handbrake: actions.pressed(Action::Handbrake),
+        radio_step: i32::from(actions.just_pressed(Action::RadioNext))
+            - i32::from(actions.just_pressed(Action::RadioPrevious)),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ActiveGamepad, InputBindings, InputSettings};
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_input::gamepad::{
+        Gamepad, GamepadAxis, GamepadAxisType, GamepadButton, GamepadButtonType,
+    };
+    use bevy_input::keyboard::KeyCode;
+    use bevy_input::mouse::MouseButton;
+    use bevy_input::{Axis, ButtonInput};
+
+    const PAD: Gamepad = Gamepad { id: 0 };
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<ButtonInput<MouseButton>>();
+        world.init_resource::<ButtonInput<GamepadButton>>();
+        world.init_resource::<Axis<GamepadButton>>();
+        world.init_resource::<Axis<GamepadAxis>>();
+        config_core::init_config(&mut world, InputSettings::default());
+        world.init_resource::<VehicleInput>();
+        world.insert_resource(ActiveGamepad(Some(PAD)));
+        config_core::init_config(&mut world, InputBindings::default());
+        world
+    }
+
+    #[test]
+    fn test_analog_gamepad_driving() {
+        let mut world = world();
+        let mut buttons = world.resource_mut::<Axis<GamepadButton>>();
+        buttons.set(
+            GamepadButton::new(PAD, GamepadButtonType::RightTrigger2),
+            0.5,
+        );
+        buttons.set(
+            GamepadButton::new(PAD, GamepadButtonType::LeftTrigger2),
+            0.02,
+        );
+        world
+            .resource_mut::<Axis<GamepadAxis>>()
+            .set(GamepadAxis::new(PAD, GamepadAxisType::LeftStickX), -1.0);
+        world
+            .resource_mut::<ButtonInput<GamepadButton>>()
+            .press(GamepadButton::new(PAD, GamepadButtonType::DPadRight));
+
+        world.run_system_once(read_vehicle_input);
+        let input = *world.resource::<VehicleInput>();
+        assert!(input.throttle > 0.4 && input.throttle < 0.5);
+        assert_eq!(input.brake, 0.0);
+        assert_eq!(input.steer, -1.0);
+        assert_eq!(input.radio_step, 1);
+    }
+
+    #[test]
+    fn test_keyboard_works_alongside_gamepad() {
+        let mut world = world();
+        world
+            .resource_mut::<Axis<GamepadAxis>>()
+            .set(GamepadAxis::new(PAD, GamepadAxisType::LeftStickX), -0.4);
+        let mut keys = world.resource_mut::<ButtonInput<KeyCode>>();
+        keys.press(KeyCode::KeyW);
+        keys.press(KeyCode::KeyA);
+        keys.press(KeyCode::Comma);
+
+        world.run_system_once(read_vehicle_input);
+        let input = *world.resource::<VehicleInput>();
+        assert_eq!(input.throttle, 1.0);
+        assert_eq!(input.steer, -1.0);
+        assert_eq!(input.radio_step, -1);
+    }
+
+    #[test]
+    fn test_steering_assist_and_auto_accelerate() {
+        let mut world = world();
+        let mut settings = InputSettings {
+            stick_dead_zone: 0.0,
+            ..InputSettings::default()
+        };
+        settings.accessibility.steering_assist = 0.5;
+        settings.accessibility.auto_accelerate = true;
+        config_core::init_config(&mut world, settings);
+
+        let mut axes = world.resource_mut::<Axis<GamepadAxis>>();
+        axes.set(GamepadAxis::new(PAD, GamepadAxisType::LeftStickX), 0.5);
+        world.run_system_once(read_vehicle_input);
+        let input = *world.resource::<VehicleInput>();
+        assert_eq!(input.throttle, 1.0);
+        assert!((input.steer - 0.25).abs() < 1e-6);
+
+        world
+            .resource_mut::<Axis<GamepadAxis>>()
+            .set(GamepadAxis::new(PAD, GamepadAxisType::LeftStickX), -1.0);
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::KeyS);
+        world.run_system_once(read_vehicle_input);
+        let input = *world.resource::<VehicleInput>();
+        assert_eq!((input.throttle, input.brake, input.steer), (0.0, 1.0, -1.0));
+    }
+
+    #[cfg(feature = "unstable_advanced_input")]
+    #[test]
+    fn test_driving_actions_only_in_vehicle_context() {
+        use crate::{ActiveInputContexts, InputContext};
+
+        let mut world = world();
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::KeyW);
+
+        world.insert_resource(ActiveInputContexts::new(InputContext::OnFoot));
+        world.run_system_once(read_vehicle_input);
+        assert_eq!(world.resource::<VehicleInput>().throttle, 0.0);
+
+        world.insert_resource(ActiveInputContexts::new(InputContext::InVehicle));
+        world.run_system_once(read_vehicle_input);
+        assert_eq!(world.resource::<VehicleInput>().throttle, 1.0);
+    }
+}
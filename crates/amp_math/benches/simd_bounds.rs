@@ -0,0 +1,92 @@
+//! Batched versus scalar bounding volume benchmarks
+
+use amp_math::bounds::Aabb;
+use amp_math::simd::{AabbBatch, Frustum};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use glam::{Mat4, Quat, Vec3};
+
+fn boxes() -> Vec<Aabb> {
+    (0..4096)
+        .map(|i| {
+            let i = i as f32;
+            let center = Vec3::new((i * 7.3) % 200.0 - 100.0, (i * 0.37) % 20.0, -(i % 150.0));
+            Aabb::from_center_half_extents(center, Vec3::splat(1.0 + (i % 4.0)))
+        })
+        .collect()
+}
+
+fn frustum() -> Frustum {
+    let view = Mat4::look_at_rh(
+        Vec3::new(0.0, 5.0, 10.0),
+        Vec3::new(0.0, 0.0, -50.0),
+        Vec3::Y,
+    );
+    let projection = Mat4::perspective_rh(1.0, 16.0 / 9.0, 0.1, 120.0);
+    Frustum::from_view_projection(&(projection * view))
+}
+
+fn bench_frustum(c: &mut Criterion) {
+    let boxes = boxes();
+    let batches: Vec<AabbBatch> = AabbBatch::chunks(&boxes).collect();
+    let frustum = frustum();
+    let mut group = c.benchmark_group("frustum_cull_4096");
+
+    group.bench_function("scalar", |b| {
+        b.iter(|| {
+            black_box(&boxes)
+                .iter()
+                .filter(|aabb| frustum.intersects_aabb(aabb))
+                .count()
+        })
+    });
+    group.bench_function("batched", |b| {
+        b.iter(|| {
+            black_box(&batches)
+                .iter()
+                .map(|batch| batch.intersects_frustum(&frustum).count_ones())
+                .sum::<u32>()
+        })
+    });
+    group.finish();
+}
+
+fn bench_transform(c: &mut Criterion) {
+    let boxes = boxes();
+    let batches: Vec<AabbBatch> = AabbBatch::chunks(&boxes).collect();
+    let matrix =
+        Mat4::from_rotation_translation(Quat::from_rotation_y(0.8), Vec3::new(3.0, 0.0, 1.0));
+    let mut group = c.benchmark_group("transform_4096");
+
+    group.bench_function("scalar_corners", |b| {
+        b.iter(|| {
+            black_box(&boxes)
+                .iter()
+                .map(|aabb| {
+                    let mut out = Aabb::empty();
+                    for x in [aabb.min.x, aabb.max.x] {
+                        for y in [aabb.min.y, aabb.max.y] {
+                            for z in [aabb.min.z, aabb.max.z] {
+                                out.expand_to_include_point(
+                                    matrix.transform_point3(Vec3::new(x, y, z)),
+                                );
+                            }
+                        }
+                    }
+                    out
+                })
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("batched", |b| {
+        b.iter(|| {
+            black_box(&batches)
+                .iter()
+                .map(|batch| batch.transform(&matrix))
+                .collect::<Vec<_>>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_frustum, bench_transform);
+criterion_main!(benches);
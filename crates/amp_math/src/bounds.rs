@@ -1,7 +1,8 @@
 //! Bounding volume implementations for spatial calculations.
 //!
-//! Provides axis-aligned bounding boxes (AABB) and spheres with efficient
-//! intersection tests and spatial operations.
+//! Provides axis-aligned bounding boxes (AABB), spheres, oriented bounding
+//! boxes (OBB), and capsules with efficient intersection tests and spatial
+//! operations.
 //!
 //! # Examples
 //!
@@ -15,7 +16,8 @@
 //! assert!(aabb.intersects_sphere(&sphere));
 //! ```
 
-use glam::Vec3;
+use crate::transforms::Transform;
+use glam::{Quat, Vec3};
 use serde::{Deserialize, Serialize};
 
 /// Axis-aligned bounding box in 3D space.
@@ -435,6 +437,504 @@ impl Default for Sphere {
     }
 }
 
+/// Oriented bounding box in 3D space.
+///
+/// Fits rotated buildings and props far tighter than an [`Aabb`], which has
+/// to grow to enclose the rotated corners.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Obb {
+    /// Center point of the box
+    pub center: Vec3,
+    /// Half-extents along the box's local axes
+    pub half_extents: Vec3,
+    /// Rotation from local to world space
+    pub rotation: Quat,
+}
+
+impl Obb {
+    /// Create a new OBB from center, half-extents, and rotation.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::bounds::Obb;
+    /// use glam::{Quat, Vec3};
+    ///
+    /// let obb = Obb::new(Vec3::ZERO, Vec3::new(2.0, 1.0, 1.0), Quat::from_rotation_y(0.5));
+    /// ```
+    pub fn new(center: Vec3, half_extents: Vec3, rotation: Quat) -> Self {
+        Self {
+            center,
+            half_extents: half_extents.abs(),
+            rotation: rotation.normalize(),
+        }
+    }
+
+    /// Create an unrotated OBB matching an AABB.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::bounds::{Aabb, Obb};
+    /// use glam::Vec3;
+    ///
+    /// let aabb = Aabb::new(Vec3::ZERO, Vec3::splat(2.0));
+    /// let obb = Obb::from_aabb(&aabb);
+    /// assert_eq!(obb.center, Vec3::ONE);
+    /// assert_eq!(obb.bounding_box(), aabb);
+    /// ```
+    pub fn from_aabb(aabb: &Aabb) -> Self {
+        Self::new(aabb.center(), aabb.half_extents(), Quat::IDENTITY)
+    }
+
+    /// Get the box's local X, Y, and Z axes in world space.
+    pub fn axes(&self) -> [Vec3; 3] {
+        [
+            self.rotation * Vec3::X,
+            self.rotation * Vec3::Y,
+            self.rotation * Vec3::Z,
+        ]
+    }
+
+    /// Get the eight corners of the box.
+    pub fn corners(&self) -> [Vec3; 8] {
+        let [x, y, z] = self.axes();
+        let (x, y, z) = (
+            x * self.half_extents.x,
+            y * self.half_extents.y,
+            z * self.half_extents.z,
+        );
+        let c = self.center;
+        [
+            c - x - y - z,
+            c + x - y - z,
+            c - x + y - z,
+            c + x + y - z,
+            c - x - y + z,
+            c + x - y + z,
+            c - x + y + z,
+            c + x + y + z,
+        ]
+    }
+
+    /// Get the smallest AABB enclosing the box.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::bounds::Obb;
+    /// use glam::{Quat, Vec3};
+    ///
+    /// let obb = Obb::new(Vec3::ZERO, Vec3::ONE, Quat::from_rotation_y(std::f32::consts::FRAC_PI_4));
+    /// let aabb = obb.bounding_box();
+    /// assert!((aabb.max.x - std::f32::consts::SQRT_2).abs() < 1e-5);
+    /// ```
+    pub fn bounding_box(&self) -> Aabb {
+        let [x, y, z] = self.axes();
+        let extent = x.abs() * self.half_extents.x
+            + y.abs() * self.half_extents.y
+            + z.abs() * self.half_extents.z;
+        Aabb::from_center_half_extents(self.center, extent)
+    }
+
+    /// Apply a transform to the box.
+    ///
+    /// Non-uniform scale can't be represented once the box is rotated
+    /// relative to it, so the box is scaled by the transform's largest
+    /// scale component, which keeps it enclosing the scaled shape.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::bounds::Obb;
+    /// use amp_math::transforms::Transform;
+    /// use glam::{Quat, Vec3};
+    ///
+    /// let obb = Obb::new(Vec3::X, Vec3::ONE, Quat::IDENTITY);
+    /// let moved = obb.transformed(&Transform::from_translation(Vec3::Y));
+    /// assert_eq!(moved.center, Vec3::new(1.0, 1.0, 0.0));
+    /// ```
+    pub fn transformed(&self, transform: &Transform) -> Self {
+        let scale = transform.scale.abs();
+        let half_extents = if self.rotation.is_near_identity() {
+            self.half_extents * scale
+        } else {
+            self.half_extents * scale.max_element()
+        };
+        Self::new(
+            transform.transform_point(self.center),
+            half_extents,
+            transform.rotation * self.rotation,
+        )
+    }
+
+    /// Get the point in or on the box closest to a point.
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        let local = self.rotation.inverse() * (point - self.center);
+        self.center + self.rotation * local.clamp(-self.half_extents, self.half_extents)
+    }
+
+    /// Check if a point is inside the OBB.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::bounds::Obb;
+    /// use glam::{Quat, Vec3};
+    ///
+    /// let obb = Obb::new(Vec3::ZERO, Vec3::new(2.0, 0.5, 0.5), Quat::from_rotation_y(std::f32::consts::FRAC_PI_2));
+    /// assert!(obb.contains_point(Vec3::new(0.0, 0.0, 1.5)));
+    /// assert!(!obb.contains_point(Vec3::new(1.5, 0.0, 0.0)));
+    /// ```
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        let local = self.rotation.inverse() * (point - self.center);
+        local
+            .abs()
+            .cmple(self.half_extents + Vec3::splat(BOUNDS_EPSILON))
+            .all()
+    }
+
+    /// Check if this OBB fully contains an AABB.
+    pub fn contains_aabb(&self, aabb: &Aabb) -> bool {
+        !aabb.is_empty()
+            && Obb::from_aabb(aabb)
+                .corners()
+                .iter()
+                .all(|&corner| self.contains_point(corner))
+    }
+
+    /// Check if this OBB intersects with a sphere.
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        (self.closest_point(sphere.center) - sphere.center).length_squared()
+            <= sphere.radius * sphere.radius
+    }
+
+    /// Check if this OBB intersects with an AABB.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        !aabb.is_empty() && self.intersects_obb(&Obb::from_aabb(aabb))
+    }
+
+    /// Check if this OBB intersects with another OBB.
+    ///
+    /// Uses the separating axis test over both boxes' face axes and their
+    /// nine edge cross products.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::bounds::Obb;
+    /// use glam::{Quat, Vec3};
+    ///
+    /// let a = Obb::new(Vec3::ZERO, Vec3::ONE, Quat::IDENTITY);
+    /// let b = Obb::new(Vec3::new(2.3, 0.0, 0.0), Vec3::ONE, Quat::from_rotation_z(std::f32::consts::FRAC_PI_4));
+    /// assert!(a.intersects_obb(&b));
+    /// ```
+    pub fn intersects_obb(&self, other: &Obb) -> bool {
+        let a = self.axes();
+        let b = other.axes();
+        let ea = self.half_extents.to_array();
+        let eb = other.half_extents.to_array();
+
+        // Rotation of `other` in this box's frame, padded against parallel
+        // edges whose cross products are near zero
+        let mut r = [[0.0; 3]; 3];
+        let mut abs_r = [[0.0; 3]; 3];
+        for i in 0..3 {
+            for j in 0..3 {
+                r[i][j] = a[i].dot(b[j]);
+                abs_r[i][j] = r[i][j].abs() + BOUNDS_EPSILON;
+            }
+        }
+        let offset = other.center - self.center;
+        let t = [offset.dot(a[0]), offset.dot(a[1]), offset.dot(a[2])];
+
+        for i in 0..3 {
+            let rb = eb[0] * abs_r[i][0] + eb[1] * abs_r[i][1] + eb[2] * abs_r[i][2];
+            if t[i].abs() > ea[i] + rb {
+                return false;
+            }
+        }
+        for j in 0..3 {
+            let ra = ea[0] * abs_r[0][j] + ea[1] * abs_r[1][j] + ea[2] * abs_r[2][j];
+            let distance = t[0] * r[0][j] + t[1] * r[1][j] + t[2] * r[2][j];
+            if distance.abs() > ra + eb[j] {
+                return false;
+            }
+        }
+        for i in 0..3 {
+            let (i1, i2) = ((i + 1) % 3, (i + 2) % 3);
+            for j in 0..3 {
+                let (j1, j2) = ((j + 1) % 3, (j + 2) % 3);
+                let ra = ea[i1] * abs_r[i2][j] + ea[i2] * abs_r[i1][j];
+                let rb = eb[j1] * abs_r[i][j2] + eb[j2] * abs_r[i][j1];
+                let distance = t[i2] * r[i1][j] - t[i1] * r[i2][j];
+                if distance.abs() > ra + rb {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+impl Default for Obb {
+    fn default() -> Self {
+        Self::new(Vec3::ZERO, Vec3::ZERO, Quat::IDENTITY)
+    }
+}
+
+impl From<Aabb> for Obb {
+    fn from(aabb: Aabb) -> Self {
+        Self::from_aabb(&aabb)
+    }
+}
+
+impl From<Obb> for Aabb {
+    fn from(obb: Obb) -> Self {
+        obb.bounding_box()
+    }
+}
+
+/// Capsule in 3D space: every point within a radius of a line segment.
+///
+/// Fits characters and poles, which a box or sphere bounds loosely.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Capsule {
+    /// Center of one end cap
+    pub start: Vec3,
+    /// Center of the other end cap
+    pub end: Vec3,
+    /// Radius around the segment
+    pub radius: f32,
+}
+
+impl Capsule {
+    /// Create a new capsule from its segment and radius.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::bounds::Capsule;
+    /// use glam::Vec3;
+    ///
+    /// let capsule = Capsule::new(Vec3::ZERO, Vec3::Y, 0.5);
+    /// ```
+    pub fn new(start: Vec3, end: Vec3, radius: f32) -> Self {
+        Self {
+            start,
+            end,
+            radius: radius.max(0.0),
+        }
+    }
+
+    /// Create an upright capsule standing on `base` with a total `height`,
+    /// the usual shape for a character.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::bounds::Capsule;
+    /// use glam::Vec3;
+    ///
+    /// let capsule = Capsule::upright(Vec3::ZERO, 1.8, 0.3);
+    /// assert_eq!(capsule.bounding_box().max.y, 1.8);
+    /// ```
+    pub fn upright(base: Vec3, height: f32, radius: f32) -> Self {
+        let radius = radius.max(0.0);
+        let top = (height - radius).max(radius);
+        Self::new(base + Vec3::Y * radius, base + Vec3::Y * top, radius)
+    }
+
+    /// Get the length of the capsule's segment.
+    pub fn segment_length(&self) -> f32 {
+        self.start.distance(self.end)
+    }
+
+    /// Get the point on the segment closest to a point.
+    pub fn closest_segment_point(&self, point: Vec3) -> Vec3 {
+        let segment = self.end - self.start;
+        let length_squared = segment.length_squared();
+        if length_squared <= f32::EPSILON {
+            return self.start;
+        }
+        let t = ((point - self.start).dot(segment) / length_squared).clamp(0.0, 1.0);
+        self.start + segment * t
+    }
+
+    /// Get the smallest AABB enclosing the capsule.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::bounds::Capsule;
+    /// use glam::Vec3;
+    ///
+    /// let capsule = Capsule::new(Vec3::ZERO, Vec3::X, 0.5);
+    /// let aabb = capsule.bounding_box();
+    /// assert_eq!(aabb.min, Vec3::new(-0.5, -0.5, -0.5));
+    /// assert_eq!(aabb.max, Vec3::new(1.5, 0.5, 0.5));
+    /// ```
+    pub fn bounding_box(&self) -> Aabb {
+        let radius = Vec3::splat(self.radius);
+        Aabb::new(
+            self.start.min(self.end) - radius,
+            self.start.max(self.end) + radius,
+        )
+    }
+
+    /// Get the smallest sphere enclosing the capsule.
+    pub fn bounding_sphere(&self) -> Sphere {
+        Sphere::new(
+            (self.start + self.end) * 0.5,
+            self.segment_length() * 0.5 + self.radius,
+        )
+    }
+
+    /// Apply a transform to the capsule.
+    ///
+    /// The radius is scaled by the transform's largest scale component, so
+    /// the capsule keeps enclosing the scaled shape.
+    pub fn transformed(&self, transform: &Transform) -> Self {
+        Self::new(
+            transform.transform_point(self.start),
+            transform.transform_point(self.end),
+            self.radius * transform.scale.abs().max_element(),
+        )
+    }
+
+    /// Check if a point is inside the capsule.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::bounds::Capsule;
+    /// use glam::Vec3;
+    ///
+    /// let capsule = Capsule::new(Vec3::ZERO, Vec3::new(0.0, 2.0, 0.0), 0.5);
+    /// assert!(capsule.contains_point(Vec3::new(0.4, 1.0, 0.0)));
+    /// assert!(!capsule.contains_point(Vec3::new(0.0, 2.6, 0.0)));
+    /// ```
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        (point - self.closest_segment_point(point)).length_squared() <= self.radius * self.radius
+    }
+
+    /// Check if this capsule intersects with a sphere.
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        let radius = self.radius + sphere.radius;
+        (sphere.center - self.closest_segment_point(sphere.center)).length_squared()
+            <= radius * radius
+    }
+
+    /// Check if this capsule intersects with another capsule.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::bounds::Capsule;
+    /// use glam::Vec3;
+    ///
+    /// let a = Capsule::new(Vec3::new(-1.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), 0.5);
+    /// let b = Capsule::new(Vec3::new(0.0, -1.0, 0.8), Vec3::new(0.0, 1.0, 0.8), 0.5);
+    /// assert!(a.intersects_capsule(&b));
+    /// ```
+    pub fn intersects_capsule(&self, other: &Capsule) -> bool {
+        let radius = self.radius + other.radius;
+        segment_distance_squared(self.start, self.end, other.start, other.end) <= radius * radius
+    }
+
+    /// Check if this capsule intersects with an OBB.
+    pub fn intersects_obb(&self, obb: &Obb) -> bool {
+        // The squared distance from a point on the segment to the box is
+        // convex along the segment, so a golden-section search converges
+        // on its minimum
+        let distance = |t: f32| {
+            let point = self.start.lerp(self.end, t);
+            (obb.closest_point(point) - point).length_squared()
+        };
+        let radius_squared = self.radius * self.radius;
+        let (mut low, mut high) = (0.0_f32, 1.0_f32);
+        let ratio = 0.618_034_f32;
+        for _ in 0..SEGMENT_SEARCH_STEPS {
+            let a = high - (high - low) * ratio;
+            let b = low + (high - low) * ratio;
+            if distance(a) <= radius_squared || distance(b) <= radius_squared {
+                return true;
+            }
+            if distance(a) < distance(b) {
+                high = b;
+            } else {
+                low = a;
+            }
+        }
+        distance(0.0)
+            .min(distance(1.0))
+            .min(distance((low + high) * 0.5))
+            <= radius_squared
+    }
+
+    /// Check if this capsule intersects with an AABB.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        !aabb.is_empty() && self.intersects_obb(&Obb::from_aabb(aabb))
+    }
+}
+
+impl Default for Capsule {
+    fn default() -> Self {
+        Self::new(Vec3::ZERO, Vec3::ZERO, 0.0)
+    }
+}
+
+impl From<Capsule> for Aabb {
+    fn from(capsule: Capsule) -> Self {
+        capsule.bounding_box()
+    }
+}
+
+/// Tolerance for containment and separating axis tests.
+const BOUNDS_EPSILON: f32 = 1e-5;
+
+/// Iterations of the capsule-box segment search.
+const SEGMENT_SEARCH_STEPS: usize = 32;
+
+/// Get the squared distance between two line segments.
+fn segment_distance_squared(p1: Vec3, q1: Vec3, p2: Vec3, q2: Vec3) -> f32 {
+    let d1 = q1 - p1;
+    let d2 = q2 - p2;
+    let r = p1 - p2;
+    let a = d1.length_squared();
+    let e = d2.length_squared();
+    let f = d2.dot(r);
+
+    let (s, t) = if a <= f32::EPSILON && e <= f32::EPSILON {
+        (0.0, 0.0)
+    } else if a <= f32::EPSILON {
+        (0.0, (f / e).clamp(0.0, 1.0))
+    } else {
+        let c = d1.dot(r);
+        if e <= f32::EPSILON {
+            ((-c / a).clamp(0.0, 1.0), 0.0)
+        } else {
+            let b = d1.dot(d2);
+            let denominator = a * e - b * b;
+            let mut s = if denominator > f32::EPSILON {
+                ((b * f - c * e) / denominator).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let mut t = (b * s + f) / e;
+            if t < 0.0 {
+                t = 0.0;
+                s = (-c / a).clamp(0.0, 1.0);
+            } else if t > 1.0 {
+                t = 1.0;
+                s = ((b - c) / a).clamp(0.0, 1.0);
+            }
+            (s, t)
+        }
+    };
+    (p1 + d1 * s - (p2 + d2 * t)).length_squared()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -598,4 +1098,136 @@ mod tests {
         sphere.expand_to_include_sphere(&sphere2);
         assert_eq!(sphere.radius, 6.0);
     }
+
+    #[test]
+    fn test_obb_aabb_conversion() {
+        let aabb = Aabb::new(Vec3::new(-1.0, 0.0, 2.0), Vec3::new(3.0, 2.0, 4.0));
+        let obb = Obb::from(aabb);
+        assert_eq!(obb.center, Vec3::new(1.0, 1.0, 3.0));
+        assert_eq!(obb.half_extents, Vec3::new(2.0, 1.0, 1.0));
+        assert_eq!(Aabb::from(obb), aabb);
+    }
+
+    #[test]
+    fn test_obb_point_containment() {
+        // A long thin box rotated 45 degrees covers the diagonal only
+        let obb = Obb::new(
+            Vec3::ZERO,
+            Vec3::new(4.0, 1.0, 0.5),
+            Quat::from_rotation_y(std::f32::consts::FRAC_PI_4),
+        );
+        assert!(obb.contains_point(Vec3::new(2.0, 0.0, -2.0)));
+        assert!(!obb.contains_point(Vec3::new(2.0, 0.0, 2.0)));
+        // Which its bounding box doesn't capture
+        assert!(obb.bounding_box().contains_point(Vec3::new(2.0, 0.0, 2.0)));
+    }
+
+    #[test]
+    fn test_obb_intersections() {
+        let rotated = Obb::new(
+            Vec3::ZERO,
+            Vec3::new(4.0, 1.0, 0.5),
+            Quat::from_rotation_y(std::f32::consts::FRAC_PI_4),
+        );
+        let corner = Aabb::new(Vec3::new(2.0, -1.0, 2.0), Vec3::new(3.0, 1.0, 3.0));
+        assert!(rotated.bounding_box().intersects_aabb(&corner));
+        assert!(!rotated.intersects_aabb(&corner));
+        let diagonal = Aabb::new(Vec3::new(2.0, -1.0, -3.0), Vec3::new(3.0, 1.0, -2.0));
+        assert!(rotated.intersects_aabb(&diagonal));
+
+        assert!(rotated.intersects_sphere(&Sphere::new(Vec3::new(2.5, 0.0, -2.5), 0.1)));
+        assert!(!rotated.intersects_sphere(&Sphere::new(Vec3::new(2.5, 0.0, 2.5), 1.0)));
+
+        let far = Obb::new(
+            Vec3::new(10.0, 0.0, 0.0),
+            Vec3::ONE,
+            Quat::from_rotation_x(0.3),
+        );
+        assert!(!rotated.intersects_obb(&far));
+        assert!(rotated.intersects_obb(&rotated));
+    }
+
+    #[test]
+    fn test_obb_contains_aabb() {
+        let obb = Obb::new(Vec3::ZERO, Vec3::splat(2.0), Quat::from_rotation_z(0.2));
+        assert!(obb.contains_aabb(&Aabb::new(-Vec3::ONE, Vec3::ONE)));
+        assert!(!obb.contains_aabb(&Aabb::new(-Vec3::ONE, Vec3::splat(2.0))));
+        assert!(!obb.contains_aabb(&Aabb::empty()));
+    }
+
+    #[test]
+    fn test_obb_transformed() {
+        let obb = Obb::new(Vec3::X, Vec3::new(1.0, 2.0, 3.0), Quat::IDENTITY);
+        let transform = Transform::from_trs(
+            Vec3::Y,
+            Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
+            Vec3::new(2.0, 1.0, 1.0),
+        );
+        let moved = obb.transformed(&transform);
+        assert!(moved.center.distance(Vec3::new(0.0, 1.0, -2.0)) < 1e-5);
+        assert_eq!(moved.half_extents, Vec3::new(2.0, 2.0, 3.0));
+
+        // Rotated boxes scale conservatively
+        let rotated = Obb::new(Vec3::ZERO, Vec3::ONE, Quat::from_rotation_y(0.5));
+        let scaled = rotated.transformed(&Transform::from_scale(Vec3::new(3.0, 1.0, 1.0)));
+        assert_eq!(scaled.half_extents, Vec3::splat(3.0));
+    }
+
+    #[test]
+    fn test_capsule_upright_and_bounds() {
+        let capsule = Capsule::upright(Vec3::new(1.0, 0.0, 1.0), 1.8, 0.3);
+        assert_eq!(capsule.start, Vec3::new(1.0, 0.3, 1.0));
+        assert!((capsule.end.y - 1.5).abs() < 1e-6);
+        let aabb = Aabb::from(capsule);
+        assert!((aabb.min - Vec3::new(0.7, 0.0, 0.7)).abs().max_element() < 1e-6);
+        assert!((aabb.max - Vec3::new(1.3, 1.8, 1.3)).abs().max_element() < 1e-6);
+        assert!((capsule.bounding_sphere().radius - 0.9).abs() < 1e-6);
+
+        // Too short for a segment collapses to a sphere
+        let short = Capsule::upright(Vec3::ZERO, 0.4, 0.3);
+        assert_eq!(short.segment_length(), 0.0);
+    }
+
+    #[test]
+    fn test_capsule_intersections() {
+        let capsule = Capsule::new(Vec3::ZERO, Vec3::new(0.0, 2.0, 0.0), 0.5);
+
+        assert!(capsule.intersects_sphere(&Sphere::new(Vec3::new(0.9, 1.0, 0.0), 0.5)));
+        assert!(!capsule.intersects_sphere(&Sphere::new(Vec3::new(1.1, 1.0, 0.0), 0.5)));
+
+        let crossing = Capsule::new(Vec3::new(-1.0, 1.0, 0.9), Vec3::new(1.0, 1.0, 0.9), 0.5);
+        let parallel = Capsule::new(Vec3::new(1.2, 0.0, 0.0), Vec3::new(1.2, 2.0, 0.0), 0.5);
+        assert!(capsule.intersects_capsule(&crossing));
+        assert!(!capsule.intersects_capsule(&parallel));
+
+        assert!(capsule.intersects_aabb(&Aabb::new(
+            Vec3::new(0.4, 1.0, -1.0),
+            Vec3::new(2.0, 1.5, 1.0)
+        )));
+        assert!(!capsule.intersects_aabb(&Aabb::new(
+            Vec3::new(0.4, 2.4, 0.4),
+            Vec3::new(1.0, 3.0, 1.0)
+        )));
+        assert!(!capsule.intersects_aabb(&Aabb::empty()));
+
+        let obb = Obb::new(
+            Vec3::new(1.0, 1.0, 0.0),
+            Vec3::new(0.6, 2.0, 0.1),
+            Quat::from_rotation_z(0.3),
+        );
+        assert!(capsule.intersects_obb(&obb));
+    }
+
+    #[test]
+    fn test_capsule_transformed() {
+        let capsule = Capsule::new(Vec3::ZERO, Vec3::Y, 0.5);
+        let moved = capsule.transformed(&Transform::from_trs(
+            Vec3::X,
+            Quat::IDENTITY,
+            Vec3::new(1.0, 2.0, 1.0),
+        ));
+        assert_eq!(moved.start, Vec3::X);
+        assert_eq!(moved.end, Vec3::new(1.0, 2.0, 0.0));
+        assert_eq!(moved.radius, 1.0);
+    }
 }
@@ -0,0 +1,621 @@
+//! Bounding volume hierarchy over caller-provided AABBs.
+//!
+//! Culling, broad-phase collision and picking all need to find the few
+//! boxes out of many that touch a region or a ray. A [`Bvh`] is built once
+//! from a slice of AABBs with the surface area heuristic (SAH), refers to
+//! them by index, and can be refit in place when they move. Queries report
+//! primitive indices through callbacks, so callers keep their own storage.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use amp_math::bounds::Aabb;
+//! use amp_math::bvh::Bvh;
+//! use glam::Vec3;
+//!
+//! let boxes: Vec<Aabb> = (0..100)
+//!     .map(|i| Aabb::from_center_half_extents(Vec3::new(i as f32 * 3.0, 0.0, 0.0), Vec3::ONE))
+//!     .collect();
+//! let bvh = Bvh::build(&boxes);
+//!
+//! let mut hits = Vec::new();
+//! bvh.query_aabb(&Aabb::new(Vec3::new(8.0, -1.0, -1.0), Vec3::new(10.0, 1.0, 1.0)), |i| {
+//!     hits.push(i)
+//! });
+//! hits.sort();
+//! assert_eq!(hits, [3]);
+//! ```
+
+use crate::bounds::{Aabb, Sphere};
+use crate::simd::Frustum;
+use glam::Vec3;
+
+/// Bins per axis when evaluating split candidates.
+const SAH_BINS: usize = 12;
+
+/// Most primitives a leaf holds when a split would still pay off.
+pub const MAX_LEAF_SIZE: usize = 4;
+
+/// Cost of visiting a node relative to testing one primitive.
+const TRAVERSAL_COST: f32 = 1.0;
+
+/// Node of a [`Bvh`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BvhNode {
+    /// Bounds of everything below the node
+    pub bounds: Aabb,
+    /// For leaves, the first entry of the leaf in [`Bvh::primitives`]; for
+    /// internal nodes, the index of the left child, with the right child
+    /// directly after it
+    first: u32,
+    /// Number of primitives in a leaf, zero for internal nodes
+    count: u32,
+}
+
+impl BvhNode {
+    /// Check whether the node is a leaf.
+    pub fn is_leaf(&self) -> bool {
+        self.count > 0
+    }
+
+    /// Get the child node indices of an internal node.
+    pub fn children(&self) -> Option<(usize, usize)> {
+        (!self.is_leaf()).then(|| (self.first as usize, self.first as usize + 1))
+    }
+
+    /// Get the range of [`Bvh::primitives`] held by a leaf.
+    pub fn primitive_range(&self) -> Option<std::ops::Range<usize>> {
+        self.is_leaf()
+            .then(|| self.first as usize..(self.first + self.count) as usize)
+    }
+}
+
+/// Bounding volume hierarchy built with the surface area heuristic.
+///
+/// Primitives are identified by their index in the slice the hierarchy was
+/// built from. The root is node 0, and children always come after their
+/// parent.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    primitives: Vec<u32>,
+}
+
+impl Bvh {
+    /// Build a hierarchy over `aabbs`.
+    ///
+    /// Splits are chosen per node from binned candidates on all three axes,
+    /// minimizing the expected cost of a query.
+    pub fn build(aabbs: &[Aabb]) -> Self {
+        let mut bvh = Self {
+            nodes: Vec::with_capacity(aabbs.len().max(1) * 2),
+            primitives: (0..aabbs.len() as u32).collect(),
+        };
+        if aabbs.is_empty() {
+            return bvh;
+        }
+
+        let centroids: Vec<Vec3> = aabbs.iter().map(Aabb::center).collect();
+        bvh.nodes.push(BvhNode {
+            bounds: Aabb::empty(),
+            first: 0,
+            count: aabbs.len() as u32,
+        });
+
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = bvh.nodes[index];
+            let range = node.first as usize..(node.first + node.count) as usize;
+            let bounds = bounds_of(aabbs, &bvh.primitives[range.clone()]);
+            bvh.nodes[index].bounds = bounds;
+
+            let Some(split) = best_split(aabbs, &centroids, &bvh.primitives[range.clone()]) else {
+                continue;
+            };
+            let leaf_cost = node.count as f32;
+            let split_cost = TRAVERSAL_COST + split.cost / half_area(&bounds);
+            if split_cost >= leaf_cost && range.len() <= MAX_LEAF_SIZE {
+                continue;
+            }
+
+            let left_count = partition(&mut bvh.primitives[range.clone()], |&primitive| {
+                split.bin_of(centroids[primitive as usize]) < split.bin
+            });
+            if left_count == 0 || left_count == range.len() {
+                continue;
+            }
+
+            let left = bvh.nodes.len();
+            for (first, count) in [
+                (range.start, left_count),
+                (range.start + left_count, range.len() - left_count),
+            ] {
+                bvh.nodes.push(BvhNode {
+                    bounds: Aabb::empty(),
+                    first: first as u32,
+                    count: count as u32,
+                });
+            }
+            bvh.nodes[index].first = left as u32;
+            bvh.nodes[index].count = 0;
+            stack.push(left);
+            stack.push(left + 1);
+        }
+        bvh
+    }
+
+    /// Recompute node bounds after primitives moved, keeping the tree shape.
+    ///
+    /// `aabbs` must have the same length as the slice the hierarchy was
+    /// built from. Refitting is much cheaper than rebuilding, but query
+    /// performance degrades as primitives move far from where they started.
+    pub fn refit(&mut self, aabbs: &[Aabb]) {
+        debug_assert_eq!(aabbs.len(), self.primitives.len());
+        // Children come after their parent, so a reverse pass is bottom-up
+        for index in (0..self.nodes.len()).rev() {
+            let node = self.nodes[index];
+            self.nodes[index].bounds = match node.children() {
+                Some((left, right)) => {
+                    let mut bounds = self.nodes[left].bounds;
+                    bounds.expand_to_include_aabb(&self.nodes[right].bounds);
+                    bounds
+                }
+                None => bounds_of(
+                    aabbs,
+                    &self.primitives[node.first as usize..(node.first + node.count) as usize],
+                ),
+            };
+        }
+    }
+
+    /// Get the number of primitives.
+    pub fn len(&self) -> usize {
+        self.primitives.len()
+    }
+
+    /// Check whether the hierarchy has no primitives.
+    pub fn is_empty(&self) -> bool {
+        self.primitives.is_empty()
+    }
+
+    /// Get the bounds of all primitives.
+    pub fn bounds(&self) -> Aabb {
+        self.nodes.first().map_or(Aabb::empty(), |root| root.bounds)
+    }
+
+    /// Get the nodes, root first.
+    pub fn nodes(&self) -> &[BvhNode] {
+        &self.nodes
+    }
+
+    /// Get the primitive indices in leaf order.
+    pub fn primitives(&self) -> &[u32] {
+        &self.primitives
+    }
+
+    /// Walk the hierarchy, descending into nodes whose bounds pass
+    /// `visit_node` and reporting the primitives of every leaf reached.
+    ///
+    /// This is the building block for custom queries; the leaf's own bounds
+    /// are tested too, but primitives are not tested individually.
+    pub fn traverse(
+        &self,
+        mut visit_node: impl FnMut(&Aabb) -> bool,
+        mut visit_primitive: impl FnMut(usize),
+    ) {
+        if self.nodes.is_empty() {
+            return;
+        }
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if node.bounds.is_empty() || !visit_node(&node.bounds) {
+                continue;
+            }
+            match node.children() {
+                Some((left, right)) => {
+                    stack.push(right);
+                    stack.push(left);
+                }
+                None => {
+                    for &primitive in &self.primitives[node.first as usize..][..node.count as usize]
+                    {
+                        visit_primitive(primitive as usize);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Report every primitive whose leaf overlaps `aabb`.
+    ///
+    /// Leaves group several primitives, so callers that need exact results
+    /// test each reported primitive against its own box.
+    pub fn query_aabb(&self, aabb: &Aabb, visit: impl FnMut(usize)) {
+        self.traverse(|bounds| bounds.intersects_aabb(aabb), visit);
+    }
+
+    /// Report every primitive whose leaf overlaps `sphere`.
+    pub fn query_sphere(&self, sphere: &Sphere, visit: impl FnMut(usize)) {
+        self.traverse(|bounds| bounds.intersects_sphere(sphere), visit);
+    }
+
+    /// Report every primitive whose leaf is at least partly inside `frustum`.
+    pub fn query_frustum(&self, frustum: &Frustum, visit: impl FnMut(usize)) {
+        self.traverse(|bounds| frustum.intersects_aabb(bounds), visit);
+    }
+
+    /// Find the closest primitive hit by a ray within `max_distance`.
+    ///
+    /// `hit` tests a primitive exactly and returns the distance along the
+    /// ray, which is measured in multiples of `direction`. Nodes are visited
+    /// nearest first and skipped once they are farther than the best hit.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::bounds::Aabb;
+    /// use amp_math::bvh::Bvh;
+    /// use glam::Vec3;
+    ///
+    /// let boxes = [
+    ///     Aabb::from_center_half_extents(Vec3::new(10.0, 0.0, 0.0), Vec3::ONE),
+    ///     Aabb::from_center_half_extents(Vec3::new(5.0, 0.0, 0.0), Vec3::ONE),
+    /// ];
+    /// let bvh = Bvh::build(&boxes);
+    /// let hit = bvh.raycast(Vec3::ZERO, Vec3::X, 100.0, |i| {
+    ///     Some(boxes[i].min.x)
+    /// });
+    /// assert_eq!(hit, Some((1, 4.0)));
+    /// ```
+    pub fn raycast(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+        mut hit: impl FnMut(usize) -> Option<f32>,
+    ) -> Option<(usize, f32)> {
+        let root = self.nodes.first()?;
+        let inverse = direction.recip();
+        let mut best: Option<(usize, f32)> = None;
+        let mut limit = max_distance;
+
+        let mut stack = Vec::new();
+        if let Some(entry) = ray_aabb(origin, inverse, &root.bounds, limit) {
+            stack.push((0, entry));
+        }
+        while let Some((index, entry)) = stack.pop() {
+            if entry > limit {
+                continue;
+            }
+            let node = &self.nodes[index];
+            match node.children() {
+                Some((left, right)) => {
+                    let left_entry = ray_aabb(origin, inverse, &self.nodes[left].bounds, limit);
+                    let right_entry = ray_aabb(origin, inverse, &self.nodes[right].bounds, limit);
+                    // Push the farther child first so the nearer one is popped next
+                    let mut children = [(left, left_entry), (right, right_entry)];
+                    if left_entry.unwrap_or(f32::INFINITY) < right_entry.unwrap_or(f32::INFINITY) {
+                        children.swap(0, 1);
+                    }
+                    for (child, child_entry) in children {
+                        if let Some(child_entry) = child_entry {
+                            stack.push((child, child_entry));
+                        }
+                    }
+                }
+                None => {
+                    for &primitive in &self.primitives[node.first as usize..][..node.count as usize]
+                    {
+                        let primitive = primitive as usize;
+                        if let Some(distance) = hit(primitive) {
+                            if (0.0..=limit).contains(&distance) {
+                                limit = distance;
+                                best = Some((primitive, distance));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        best
+    }
+}
+
+/// Best binned split of a node's primitives.
+struct Split {
+    axis: usize,
+    bin: usize,
+    min: f32,
+    scale: f32,
+    /// Sum over both sides of half surface area times primitive count
+    cost: f32,
+}
+
+impl Split {
+    fn bin_of(&self, centroid: Vec3) -> usize {
+        bin_index(centroid[self.axis], self.min, self.scale)
+    }
+}
+
+fn best_split(aabbs: &[Aabb], centroids: &[Vec3], primitives: &[u32]) -> Option<Split> {
+    if primitives.len() < 2 {
+        return None;
+    }
+    let mut centroid_bounds = Aabb::empty();
+    for &primitive in primitives {
+        centroid_bounds.expand_to_include_point(centroids[primitive as usize]);
+    }
+
+    let mut best: Option<Split> = None;
+    for axis in 0..3 {
+        let min = centroid_bounds.min[axis];
+        let extent = centroid_bounds.max[axis] - min;
+        if extent <= f32::EPSILON {
+            continue;
+        }
+        let scale = SAH_BINS as f32 / extent;
+
+        let mut bins = [(Aabb::empty(), 0usize); SAH_BINS];
+        for &primitive in primitives {
+            let primitive = primitive as usize;
+            let (bounds, count) = &mut bins[bin_index(centroids[primitive][axis], min, scale)];
+            bounds.expand_to_include_aabb(&aabbs[primitive]);
+            *count += 1;
+        }
+
+        // Sweep from the right to get the cost of every right-hand side
+        let mut right_costs = [0.0; SAH_BINS];
+        let (mut bounds, mut count) = (Aabb::empty(), 0);
+        for bin in (1..SAH_BINS).rev() {
+            bounds.expand_to_include_aabb(&bins[bin].0);
+            count += bins[bin].1;
+            right_costs[bin] = half_area(&bounds) * count as f32;
+        }
+
+        let (mut bounds, mut count) = (Aabb::empty(), 0);
+        for bin in 1..SAH_BINS {
+            bounds.expand_to_include_aabb(&bins[bin - 1].0);
+            count += bins[bin - 1].1;
+            if count == 0 || count == primitives.len() {
+                continue;
+            }
+            let cost = half_area(&bounds) * count as f32 + right_costs[bin];
+            if best.as_ref().map_or(true, |best| cost < best.cost) {
+                best = Some(Split {
+                    axis,
+                    bin,
+                    min,
+                    scale,
+                    cost,
+                });
+            }
+        }
+    }
+    best
+}
+
+fn bin_index(value: f32, min: f32, scale: f32) -> usize {
+    (((value - min) * scale) as usize).min(SAH_BINS - 1)
+}
+
+fn bounds_of(aabbs: &[Aabb], primitives: &[u32]) -> Aabb {
+    let mut bounds = Aabb::empty();
+    for &primitive in primitives {
+        bounds.expand_to_include_aabb(&aabbs[primitive as usize]);
+    }
+    bounds
+}
+
+/// Half the surface area, which is proportional to the chance a random ray
+/// hits the box.
+fn half_area(aabb: &Aabb) -> f32 {
+    if aabb.is_empty() {
+        return 0.0;
+    }
+    let size = aabb.size();
+    size.x * size.y + size.y * size.z + size.z * size.x
+}
+
+/// Move elements matching `predicate` to the front; returns how many matched.
+fn partition(values: &mut [u32], predicate: impl Fn(&u32) -> bool) -> usize {
+    let mut split = 0;
+    for i in 0..values.len() {
+        if predicate(&values[i]) {
+            values.swap(i, split);
+            split += 1;
+        }
+    }
+    split
+}
+
+/// Distance along a ray to where it enters a box, or `None` if it misses
+/// the box before `max_distance`. Starts inside the box return zero.
+fn ray_aabb(origin: Vec3, inverse_direction: Vec3, aabb: &Aabb, max_distance: f32) -> Option<f32> {
+    let to_min = (aabb.min - origin) * inverse_direction;
+    let to_max = (aabb.max - origin) * inverse_direction;
+    // NaN from 0 * inf on an axis the ray runs along a face is ignored by
+    // min/max, which keeps the slab test conservative
+    let entry = to_min.min(to_max).max_element().max(0.0);
+    let exit = to_min.max(to_max).min_element().min(max_distance);
+    (entry <= exit).then_some(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Mat4;
+
+    /// Deterministic scattered boxes of varying size
+    fn scattered_boxes(count: usize) -> Vec<Aabb> {
+        let mut state = 0x2545_f491_u32;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as f32 / u32::MAX as f32
+        };
+        (0..count)
+            .map(|_| {
+                let center = Vec3::new(next(), next() * 0.2, next()) * 500.0;
+                let half = Vec3::new(next(), next(), next()) * 4.0 + 0.5;
+                Aabb::from_center_half_extents(center, half)
+            })
+            .collect()
+    }
+
+    fn sorted_query(bvh: &Bvh, boxes: &[Aabb], region: &Aabb) -> Vec<usize> {
+        let mut hits = Vec::new();
+        bvh.query_aabb(region, |i| {
+            if boxes[i].intersects_aabb(region) {
+                hits.push(i);
+            }
+        });
+        hits.sort_unstable();
+        hits
+    }
+
+    fn brute_force(boxes: &[Aabb], region: &Aabb) -> Vec<usize> {
+        (0..boxes.len())
+            .filter(|&i| boxes[i].intersects_aabb(region))
+            .collect()
+    }
+
+    #[test]
+    fn test_build_covers_every_primitive_once() {
+        let boxes = scattered_boxes(1000);
+        let bvh = Bvh::build(&boxes);
+        assert_eq!(bvh.len(), 1000);
+
+        let mut seen = vec![0; boxes.len()];
+        for node in bvh.nodes() {
+            if let Some(range) = node.primitive_range() {
+                assert!(range.len() <= MAX_LEAF_SIZE, "leaf of {}", range.len());
+                for &primitive in &bvh.primitives()[range] {
+                    seen[primitive as usize] += 1;
+                    assert!(node.bounds.contains_aabb(&boxes[primitive as usize]));
+                }
+            }
+            if let Some((left, right)) = node.children() {
+                assert!(node.bounds.contains_aabb(&bvh.nodes()[left].bounds));
+                assert!(node.bounds.contains_aabb(&bvh.nodes()[right].bounds));
+            }
+        }
+        assert!(seen.iter().all(|&count| count == 1));
+    }
+
+    #[test]
+    fn test_queries_match_brute_force() {
+        let boxes = scattered_boxes(600);
+        let bvh = Bvh::build(&boxes);
+        for region in [
+            Aabb::new(Vec3::new(100.0, 0.0, 100.0), Vec3::new(180.0, 100.0, 220.0)),
+            Aabb::new(Vec3::splat(-50.0), Vec3::splat(-10.0)),
+            Aabb::new(Vec3::ZERO, Vec3::splat(500.0)),
+        ] {
+            assert_eq!(
+                sorted_query(&bvh, &boxes, &region),
+                brute_force(&boxes, &region)
+            );
+        }
+
+        let sphere = Sphere::new(Vec3::new(250.0, 50.0, 250.0), 60.0);
+        let mut hits = Vec::new();
+        bvh.query_sphere(&sphere, |i| {
+            if boxes[i].intersects_sphere(&sphere) {
+                hits.push(i);
+            }
+        });
+        hits.sort_unstable();
+        let expected: Vec<_> = (0..boxes.len())
+            .filter(|&i| boxes[i].intersects_sphere(&sphere))
+            .collect();
+        assert_eq!(hits, expected);
+    }
+
+    #[test]
+    fn test_frustum_query_culls_hidden_boxes() {
+        let boxes = scattered_boxes(400);
+        let bvh = Bvh::build(&boxes);
+        let view = Mat4::look_at_rh(
+            Vec3::new(250.0, 50.0, -50.0),
+            Vec3::new(250.0, 50.0, 250.0),
+            Vec3::Y,
+        );
+        let projection = Mat4::perspective_rh(0.6, 1.0, 0.1, 200.0);
+        let frustum = Frustum::from_view_projection(&(projection * view));
+
+        let mut visible = Vec::new();
+        bvh.query_frustum(&frustum, |i| {
+            if frustum.intersects_aabb(&boxes[i]) {
+                visible.push(i);
+            }
+        });
+        visible.sort_unstable();
+        let expected: Vec<_> = (0..boxes.len())
+            .filter(|&i| frustum.intersects_aabb(&boxes[i]))
+            .collect();
+        assert_eq!(visible, expected);
+        assert!(!visible.is_empty() && visible.len() < boxes.len());
+    }
+
+    #[test]
+    fn test_refit_follows_moved_boxes() {
+        let mut boxes = scattered_boxes(300);
+        let mut bvh = Bvh::build(&boxes);
+        for (i, aabb) in boxes.iter_mut().enumerate() {
+            let offset = Vec3::new((i % 7) as f32 * 20.0, 0.0, -((i % 5) as f32) * 15.0);
+            *aabb = Aabb::new(aabb.min + offset, aabb.max + offset);
+        }
+        bvh.refit(&boxes);
+
+        let region = Aabb::new(Vec3::new(200.0, 0.0, 0.0), Vec3::new(320.0, 100.0, 150.0));
+        assert_eq!(
+            sorted_query(&bvh, &boxes, &region),
+            brute_force(&boxes, &region)
+        );
+        let mut all = Aabb::empty();
+        boxes
+            .iter()
+            .for_each(|aabb| all.expand_to_include_aabb(aabb));
+        assert_eq!(bvh.bounds(), all);
+    }
+
+    #[test]
+    fn test_raycast_returns_closest_hit() {
+        let boxes = scattered_boxes(500);
+        let bvh = Bvh::build(&boxes);
+        let origin = Vec3::new(-10.0, 20.0, 240.0);
+        let direction = (boxes[123].center() - origin).normalize();
+        let exact = |i: usize| ray_aabb(origin, direction.recip(), &boxes[i], f32::INFINITY);
+
+        let expected = (0..boxes.len())
+            .filter_map(|i| exact(i).map(|distance| (i, distance)))
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        assert_eq!(
+            bvh.raycast(origin, direction, f32::INFINITY, exact),
+            expected
+        );
+        assert!(expected.is_some());
+
+        let short = bvh.raycast(origin, direction, 1.0, exact);
+        assert_eq!(short, None);
+    }
+
+    #[test]
+    fn test_empty_and_degenerate_inputs() {
+        let bvh = Bvh::build(&[]);
+        assert!(bvh.is_empty());
+        assert!(bvh.bounds().is_empty());
+        bvh.query_aabb(&Aabb::infinite(), |_| panic!("no primitives"));
+        assert_eq!(bvh.raycast(Vec3::ZERO, Vec3::X, 10.0, |_| Some(1.0)), None);
+
+        // Identical boxes can't be split and end up in one leaf
+        let same = vec![Aabb::from_center_half_extents(Vec3::ZERO, Vec3::ONE); 10];
+        let bvh = Bvh::build(&same);
+        assert_eq!(bvh.nodes().len(), 1);
+        let mut count = 0;
+        bvh.query_sphere(&Sphere::new(Vec3::ZERO, 0.5), |_| count += 1);
+        assert_eq!(count, 10);
+    }
+}
@@ -0,0 +1,244 @@
+//! Integer keys for square world chunks on the XZ plane.
+//!
+//! Streaming and generation work chunk by chunk around the player. Besides
+//! converting between world positions and keys, [`ChunkKey`] iterates the
+//! chunks around a key by ring or in spiral order, nearest first, so load
+//! queues fill with the closest chunks before the far ones.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use amp_math::chunk_key::ChunkKey;
+//! use glam::Vec3;
+//!
+//! let player = ChunkKey::from_world(Vec3::new(130.0, 12.0, -20.0), 64.0);
+//! assert_eq!(player, ChunkKey::new(2, -1));
+//!
+//! let load_order: Vec<ChunkKey> = player.spiral(2).collect();
+//! assert_eq!(load_order.len(), 25);
+//! assert_eq!(load_order[0], player);
+//! ```
+
+use crate::bounds::Aabb;
+use glam::{IVec2, Vec3};
+use serde::{Deserialize, Serialize};
+
+/// Key of a square chunk on the XZ plane.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct ChunkKey {
+    /// Chunk column along X
+    pub x: i32,
+    /// Chunk row along Z
+    pub z: i32,
+}
+
+impl ChunkKey {
+    /// Create a key from chunk coordinates.
+    pub const fn new(x: i32, z: i32) -> Self {
+        Self { x, z }
+    }
+
+    /// Get the key of the chunk containing a world position.
+    pub fn from_world(position: Vec3, chunk_size: f32) -> Self {
+        Self::new(
+            (position.x / chunk_size).floor() as i32,
+            (position.z / chunk_size).floor() as i32,
+        )
+    }
+
+    /// Get the world position of the chunk's minimum corner, at height zero.
+    pub fn world_min(&self, chunk_size: f32) -> Vec3 {
+        Vec3::new(self.x as f32 * chunk_size, 0.0, self.z as f32 * chunk_size)
+    }
+
+    /// Get the world position of the chunk's center, at height zero.
+    pub fn world_center(&self, chunk_size: f32) -> Vec3 {
+        self.world_min(chunk_size) + Vec3::new(chunk_size, 0.0, chunk_size) * 0.5
+    }
+
+    /// Get the chunk's world bounds between two heights.
+    pub fn world_bounds(&self, chunk_size: f32, min_y: f32, max_y: f32) -> Aabb {
+        let min = self.world_min(chunk_size);
+        Aabb::new(
+            Vec3::new(min.x, min_y, min.z),
+            Vec3::new(min.x + chunk_size, max_y, min.z + chunk_size),
+        )
+    }
+
+    /// Get the key offset by a number of chunks.
+    pub fn offset(&self, dx: i32, dz: i32) -> Self {
+        Self::new(self.x + dx, self.z + dz)
+    }
+
+    /// Get the number of chunks between two keys counting diagonal steps
+    /// as one, which is the ring one key is on around the other.
+    pub fn chebyshev_distance(&self, other: ChunkKey) -> u32 {
+        self.x.abs_diff(other.x).max(self.z.abs_diff(other.z))
+    }
+
+    /// Get the squared distance between two keys in chunks.
+    pub fn distance_squared(&self, other: ChunkKey) -> i64 {
+        let dx = i64::from(self.x) - i64::from(other.x);
+        let dz = i64::from(self.z) - i64::from(other.z);
+        dx * dx + dz * dz
+    }
+
+    /// Iterate over the chunks within `radius` rings of this one, excluding
+    /// it, row by row.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::chunk_key::ChunkKey;
+    ///
+    /// let neighbors: Vec<ChunkKey> = ChunkKey::new(0, 0).neighbors(1).collect();
+    /// assert_eq!(neighbors.len(), 8);
+    /// assert_eq!(neighbors[0], ChunkKey::new(-1, -1));
+    /// ```
+    pub fn neighbors(&self, radius: u32) -> impl Iterator<Item = ChunkKey> {
+        let center = *self;
+        let radius = radius as i32;
+        (-radius..=radius)
+            .flat_map(move |dz| (-radius..=radius).map(move |dx| center.offset(dx, dz)))
+            .filter(move |&key| key != center)
+    }
+
+    /// Iterate over the chunks exactly `radius` rings from this one, walking
+    /// the ring's edges from its minimum corner.
+    ///
+    /// Ring zero is this chunk alone; ring `r` has `8 * r` chunks.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::chunk_key::ChunkKey;
+    ///
+    /// let center = ChunkKey::new(5, 5);
+    /// let ring: Vec<ChunkKey> = center.ring(2).collect();
+    /// assert_eq!(ring.len(), 16);
+    /// assert!(ring.iter().all(|key| key.chebyshev_distance(center) == 2));
+    /// ```
+    pub fn ring(&self, radius: u32) -> impl Iterator<Item = ChunkKey> {
+        let center = *self;
+        let r = radius as i32;
+        let count = if radius == 0 { 1 } else { 8 * radius };
+        (0..count as i32).map(move |i| {
+            if r == 0 {
+                return center;
+            }
+            let side = 2 * r;
+            let (dx, dz) = match i / side {
+                // Minimum Z edge, towards +X
+                0 => (-r + i, -r),
+                // Maximum X edge, towards +Z
+                1 => (r, -r + (i - side)),
+                // Maximum Z edge, towards -X
+                2 => (r - (i - 2 * side), r),
+                // Minimum X edge, towards -Z
+                _ => (-r, r - (i - 3 * side)),
+            };
+            center.offset(dx, dz)
+        })
+    }
+
+    /// Iterate over the chunks within `radius` rings of this one, nearest
+    /// first, starting with this chunk.
+    ///
+    /// Rings come out in order. Within a ring, chunks are ordered by
+    /// straight-line distance, so the middles of its edges come before its
+    /// corners.
+    pub fn spiral(&self, radius: u32) -> impl Iterator<Item = ChunkKey> {
+        let center = *self;
+        (0..=radius).flat_map(move |ring| {
+            let mut keys: Vec<ChunkKey> = center.ring(ring).collect();
+            keys.sort_by_key(|key| key.distance_squared(center));
+            keys
+        })
+    }
+}
+
+impl From<IVec2> for ChunkKey {
+    fn from(value: IVec2) -> Self {
+        Self::new(value.x, value.y)
+    }
+}
+
+impl From<ChunkKey> for IVec2 {
+    fn from(key: ChunkKey) -> Self {
+        IVec2::new(key.x, key.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_world_conversion() {
+        assert_eq!(
+            ChunkKey::from_world(Vec3::new(-0.5, 0.0, 63.9), 64.0),
+            ChunkKey::new(-1, 0)
+        );
+        let key = ChunkKey::new(-2, 3);
+        assert_eq!(key.world_min(32.0), Vec3::new(-64.0, 0.0, 96.0));
+        assert_eq!(key.world_center(32.0), Vec3::new(-48.0, 0.0, 112.0));
+        assert_eq!(ChunkKey::from_world(key.world_center(32.0), 32.0), key);
+        let bounds = key.world_bounds(32.0, -10.0, 50.0);
+        assert_eq!(bounds.size(), Vec3::new(32.0, 60.0, 32.0));
+        assert_eq!(IVec2::from(key), IVec2::new(-2, 3));
+        assert_eq!(ChunkKey::from(IVec2::new(-2, 3)), key);
+    }
+
+    #[test]
+    fn test_rings_partition_neighbors() {
+        let center = ChunkKey::new(-7, 12);
+        let mut from_rings = HashSet::new();
+        for radius in 1..=4 {
+            let ring: Vec<ChunkKey> = center.ring(radius).collect();
+            assert_eq!(ring.len(), 8 * radius as usize);
+            for key in ring {
+                assert_eq!(key.chebyshev_distance(center), radius);
+                assert!(from_rings.insert(key), "{key:?} repeated");
+            }
+        }
+        let neighbors: HashSet<ChunkKey> = center.neighbors(4).collect();
+        assert_eq!(neighbors.len(), 80);
+        assert_eq!(neighbors, from_rings);
+        assert_eq!(center.ring(0).collect::<Vec<_>>(), [center]);
+        assert_eq!(center.neighbors(0).count(), 0);
+    }
+
+    #[test]
+    fn test_ring_walk_is_connected() {
+        let ring: Vec<ChunkKey> = ChunkKey::new(0, 0).ring(3).collect();
+        assert_eq!(ring[0], ChunkKey::new(-3, -3));
+        for (i, key) in ring.iter().enumerate() {
+            let next = ring[(i + 1) % ring.len()];
+            assert_eq!(key.chebyshev_distance(next), 1);
+        }
+    }
+
+    #[test]
+    fn test_spiral_is_nearest_first() {
+        let center = ChunkKey::new(3, -3);
+        let spiral: Vec<ChunkKey> = center.spiral(5).collect();
+        assert_eq!(spiral.len(), 121);
+        assert_eq!(spiral[0], center);
+        assert_eq!(spiral.iter().collect::<HashSet<_>>().len(), 121);
+        for pair in spiral.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            let (ring_a, ring_b) = (a.chebyshev_distance(center), b.chebyshev_distance(center));
+            assert!(
+                ring_a < ring_b
+                    || (ring_a == ring_b
+                        && a.distance_squared(center) <= b.distance_squared(center))
+            );
+        }
+        // Edge middles before corners
+        assert_eq!(spiral[1].distance_squared(center), 1);
+        assert_eq!(spiral[8].distance_squared(center), 2);
+    }
+}
@@ -0,0 +1,390 @@
+//! Conversion between WGS84 latitude/longitude and local world meters.
+//!
+//! A [`GeoProjection`] places a real-world origin at world zero and maps
+//! geodetic coordinates onto the tangent plane there: +X is east, +Y is up,
+//! and -Z is north, matching the engine's -Z forward convention. The
+//! conversion goes through Earth-centered coordinates, so it stays accurate
+//! to millimeters across a city-sized map. [`TileId`] covers the web map
+//! tiles OpenStreetMap data is fetched in.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use amp_math::coordinate_conversion::{GeoProjection, LatLon};
+//!
+//! let projection = GeoProjection::new(LatLon::new(40.7580, -73.9855));
+//! let position = projection.to_world(LatLon::new(40.7590, -73.9855), 0.0);
+//! // About 111 m north
+//! assert!((position.0.z + 111.0).abs() < 0.5);
+//! ```
+
+use crate::bounds::Aabb;
+use crate::world_pos::WorldPos;
+use glam::{DVec3, Vec3};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+/// WGS84 semi-major axis in meters.
+pub const WGS84_A: f64 = 6_378_137.0;
+
+/// WGS84 flattening.
+pub const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+/// WGS84 first eccentricity squared.
+const WGS84_E2: f64 = WGS84_F * (2.0 - WGS84_F);
+
+/// Latitude the Web Mercator tiles stop at, in degrees.
+pub const MAX_TILE_LATITUDE: f64 = 85.051_128_779_806_59;
+
+/// Geodetic position in degrees.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatLon {
+    /// Latitude in degrees, positive north
+    pub lat: f64,
+    /// Longitude in degrees, positive east
+    pub lon: f64,
+}
+
+impl LatLon {
+    /// Create a position from latitude and longitude in degrees.
+    pub const fn new(lat: f64, lon: f64) -> Self {
+        Self { lat, lon }
+    }
+
+    /// Get the Earth-centered, Earth-fixed position at `altitude` meters
+    /// above the ellipsoid.
+    pub fn to_ecef(self, altitude: f64) -> DVec3 {
+        let (lat, lon) = (self.lat.to_radians(), self.lon.to_radians());
+        let (sin_lat, cos_lat) = lat.sin_cos();
+        let (sin_lon, cos_lon) = lon.sin_cos();
+        let n = WGS84_A / (1.0 - WGS84_E2 * sin_lat * sin_lat).sqrt();
+        DVec3::new(
+            (n + altitude) * cos_lat * cos_lon,
+            (n + altitude) * cos_lat * sin_lon,
+            (n * (1.0 - WGS84_E2) + altitude) * sin_lat,
+        )
+    }
+
+    /// Get the position and altitude of an Earth-centered, Earth-fixed
+    /// point.
+    pub fn from_ecef(ecef: DVec3) -> (Self, f64) {
+        let p = ecef.x.hypot(ecef.y);
+        let lon = ecef.y.atan2(ecef.x);
+        let mut lat = ecef.z.atan2(p * (1.0 - WGS84_E2));
+        let mut altitude = 0.0;
+        for _ in 0..5 {
+            let sin_lat = lat.sin();
+            let n = WGS84_A / (1.0 - WGS84_E2 * sin_lat * sin_lat).sqrt();
+            altitude = if lat.cos().abs() > 1e-12 {
+                p / lat.cos() - n
+            } else {
+                ecef.z.abs() - n * (1.0 - WGS84_E2)
+            };
+            lat = ecef.z.atan2(p * (1.0 - WGS84_E2 * n / (n + altitude)));
+        }
+        (Self::new(lat.to_degrees(), lon.to_degrees()), altitude)
+    }
+}
+
+/// Local tangent plane projection around a real-world origin.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeoProjection {
+    origin: LatLon,
+    origin_altitude: f64,
+    origin_ecef: DVec3,
+    /// Rows of the Earth-centered to east/north/up rotation
+    east: DVec3,
+    north: DVec3,
+    up: DVec3,
+}
+
+impl GeoProjection {
+    /// Create a projection with `origin` at world zero, on the ellipsoid.
+    pub fn new(origin: LatLon) -> Self {
+        Self::with_altitude(origin, 0.0)
+    }
+
+    /// Create a projection with `origin` at `altitude` meters above the
+    /// ellipsoid at world zero.
+    pub fn with_altitude(origin: LatLon, altitude: f64) -> Self {
+        let (sin_lat, cos_lat) = origin.lat.to_radians().sin_cos();
+        let (sin_lon, cos_lon) = origin.lon.to_radians().sin_cos();
+        Self {
+            origin,
+            origin_altitude: altitude,
+            origin_ecef: origin.to_ecef(altitude),
+            east: DVec3::new(-sin_lon, cos_lon, 0.0),
+            north: DVec3::new(-sin_lat * cos_lon, -sin_lat * sin_lon, cos_lat),
+            up: DVec3::new(cos_lat * cos_lon, cos_lat * sin_lon, sin_lat),
+        }
+    }
+
+    /// Get the real-world position at world zero.
+    pub fn origin(&self) -> LatLon {
+        self.origin
+    }
+
+    /// Get the altitude at world zero.
+    pub fn origin_altitude(&self) -> f64 {
+        self.origin_altitude
+    }
+
+    /// Get the world position of a geodetic position at `altitude` meters
+    /// above the ellipsoid.
+    pub fn to_world(&self, position: LatLon, altitude: f64) -> WorldPos {
+        let offset = position.to_ecef(altitude) - self.origin_ecef;
+        WorldPos::new(
+            offset.dot(self.east),
+            offset.dot(self.up),
+            -offset.dot(self.north),
+        )
+    }
+
+    /// Get the geodetic position and altitude of a world position.
+    pub fn to_geodetic(&self, position: WorldPos) -> (LatLon, f64) {
+        let local = position.0;
+        let ecef =
+            self.origin_ecef + self.east * local.x + self.up * local.y - self.north * local.z;
+        LatLon::from_ecef(ecef)
+    }
+
+    /// Get the world bounds of a geodetic box, between two altitudes.
+    ///
+    /// The box's edges curve slightly on the tangent plane, so the bounds
+    /// enclose its corners and edge midpoints. Bounds are `f32` and lose
+    /// precision far from the origin.
+    pub fn bounds_to_world(
+        &self,
+        bounds: &GeoBounds,
+        min_altitude: f64,
+        max_altitude: f64,
+    ) -> Aabb {
+        let mut aabb = Aabb::empty();
+        let lats = [
+            bounds.min.lat,
+            (bounds.min.lat + bounds.max.lat) * 0.5,
+            bounds.max.lat,
+        ];
+        let lons = [
+            bounds.min.lon,
+            (bounds.min.lon + bounds.max.lon) * 0.5,
+            bounds.max.lon,
+        ];
+        for lat in lats {
+            for lon in lons {
+                for altitude in [min_altitude, max_altitude] {
+                    let point = self.to_world(LatLon::new(lat, lon), altitude).0;
+                    aabb.expand_to_include_point(point.as_vec3());
+                }
+            }
+        }
+        aabb
+    }
+
+    /// Get the geodetic box enclosing world bounds, ignoring height.
+    pub fn bounds_to_geodetic(&self, aabb: &Aabb) -> GeoBounds {
+        let corners = [
+            Vec3::new(aabb.min.x, 0.0, aabb.min.z),
+            Vec3::new(aabb.max.x, 0.0, aabb.min.z),
+            Vec3::new(aabb.min.x, 0.0, aabb.max.z),
+            Vec3::new(aabb.max.x, 0.0, aabb.max.z),
+        ];
+        let mut bounds: Option<GeoBounds> = None;
+        for corner in corners {
+            let (position, _) = self.to_geodetic(WorldPos(corner.as_dvec3()));
+            bounds = Some(match bounds {
+                Some(bounds) => bounds.including(position),
+                None => GeoBounds::new(position, position),
+            });
+        }
+        bounds.unwrap_or_default()
+    }
+}
+
+/// Latitude/longitude box, not crossing the antimeridian.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct GeoBounds {
+    /// South-west corner
+    pub min: LatLon,
+    /// North-east corner
+    pub max: LatLon,
+}
+
+impl GeoBounds {
+    /// Create a box from two corners in any order.
+    pub fn new(a: LatLon, b: LatLon) -> Self {
+        Self {
+            min: LatLon::new(a.lat.min(b.lat), a.lon.min(b.lon)),
+            max: LatLon::new(a.lat.max(b.lat), a.lon.max(b.lon)),
+        }
+    }
+
+    /// Check if a position is inside the box.
+    pub fn contains(&self, position: LatLon) -> bool {
+        (self.min.lat..=self.max.lat).contains(&position.lat)
+            && (self.min.lon..=self.max.lon).contains(&position.lon)
+    }
+
+    /// Check if this box overlaps another.
+    pub fn intersects(&self, other: &GeoBounds) -> bool {
+        self.min.lat <= other.max.lat
+            && self.max.lat >= other.min.lat
+            && self.min.lon <= other.max.lon
+            && self.max.lon >= other.min.lon
+    }
+
+    /// Get the box grown to include a position.
+    pub fn including(self, position: LatLon) -> Self {
+        Self::new(
+            LatLon::new(
+                self.min.lat.min(position.lat),
+                self.min.lon.min(position.lon),
+            ),
+            LatLon::new(
+                self.max.lat.max(position.lat),
+                self.max.lon.max(position.lon),
+            ),
+        )
+    }
+
+    /// Get the Web Mercator tiles covering the box at a zoom level.
+    pub fn tiles(&self, zoom: u8) -> impl Iterator<Item = TileId> {
+        let north_west = TileId::containing(LatLon::new(self.max.lat, self.min.lon), zoom);
+        let south_east = TileId::containing(LatLon::new(self.min.lat, self.max.lon), zoom);
+        (north_west.y..=south_east.y)
+            .flat_map(move |y| (north_west.x..=south_east.x).map(move |x| TileId { x, y, zoom }))
+    }
+}
+
+/// Web Mercator map tile, as used by OpenStreetMap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TileId {
+    /// Column from the antimeridian eastwards
+    pub x: u32,
+    /// Row from the north edge southwards
+    pub y: u32,
+    /// Zoom level, with `2^zoom` tiles per axis
+    pub zoom: u8,
+}
+
+impl TileId {
+    /// Get the tile containing a position at a zoom level.
+    ///
+    /// Latitudes beyond [`MAX_TILE_LATITUDE`] clamp to the edge rows.
+    pub fn containing(position: LatLon, zoom: u8) -> Self {
+        let zoom = zoom.min(30);
+        let tiles = f64::from(1u32 << zoom);
+        let lat = position
+            .lat
+            .clamp(-MAX_TILE_LATITUDE, MAX_TILE_LATITUDE)
+            .to_radians();
+        let lon = (position.lon + 180.0).rem_euclid(360.0);
+        let x = (lon / 360.0 * tiles).floor();
+        let y = ((1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / PI) / 2.0 * tiles).floor();
+        let last = tiles - 1.0;
+        Self {
+            x: x.clamp(0.0, last) as u32,
+            y: y.clamp(0.0, last) as u32,
+            zoom,
+        }
+    }
+
+    /// Get the tile's latitude/longitude box.
+    pub fn bounds(&self) -> GeoBounds {
+        let tiles = f64::from(1u32 << self.zoom);
+        let lon = |x: u32| f64::from(x) / tiles * 360.0 - 180.0;
+        let lat = |y: u32| {
+            (PI * (1.0 - 2.0 * f64::from(y) / tiles))
+                .sinh()
+                .atan()
+                .to_degrees()
+        };
+        GeoBounds::new(
+            LatLon::new(lat(self.y + 1), lon(self.x)),
+            LatLon::new(lat(self.y), lon(self.x + 1)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_projection_axes() {
+        let projection = GeoProjection::new(LatLon::new(51.5074, -0.1278));
+        assert!(projection.to_world(projection.origin(), 0.0).0.length() < 1e-6);
+
+        let north = projection.to_world(LatLon::new(51.5084, -0.1278), 0.0).0;
+        let east = projection.to_world(LatLon::new(51.5074, -0.1268), 0.0).0;
+        let up = projection.to_world(projection.origin(), 25.0).0;
+        // A thousandth of a degree is ~111 m of latitude and ~69 m of
+        // longitude at London's latitude
+        assert!((north.z + 111.26).abs() < 0.1 && north.x.abs() < 1e-3);
+        assert!((east.x - 69.4).abs() < 0.1 && east.z.abs() < 0.01);
+        assert!((up.y - 25.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let projection = GeoProjection::with_altitude(LatLon::new(-33.8688, 151.2093), 40.0);
+        for (lat, lon, altitude) in [
+            (-33.8688, 151.2093, 40.0),
+            (-33.9, 151.25, 0.0),
+            (-33.7, 151.0, 350.0),
+        ] {
+            let world = projection.to_world(LatLon::new(lat, lon), altitude);
+            let (position, back_altitude) = projection.to_geodetic(world);
+            assert!((position.lat - lat).abs() < 1e-9, "{position:?}");
+            assert!((position.lon - lon).abs() < 1e-9, "{position:?}");
+            assert!((back_altitude - altitude).abs() < 1e-4);
+        }
+        assert_eq!(projection.origin_altitude(), 40.0);
+    }
+
+    #[test]
+    fn test_ecef_known_point() {
+        // The equator at the prime meridian is one semi-major axis out
+        let ecef = LatLon::new(0.0, 0.0).to_ecef(0.0);
+        assert!((ecef - DVec3::new(WGS84_A, 0.0, 0.0)).length() < 1e-6);
+        let (pole, altitude) = LatLon::from_ecef(LatLon::new(90.0, 0.0).to_ecef(10.0));
+        assert!((pole.lat - 90.0).abs() < 1e-9);
+        assert!((altitude - 10.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_tiles() {
+        let london = LatLon::new(51.5074, -0.1278);
+        let tile = TileId::containing(london, 10);
+        assert_eq!((tile.x, tile.y), (511, 340));
+        assert!(tile.bounds().contains(london));
+        assert_eq!(
+            TileId::containing(LatLon::new(89.9, 179.99), 2),
+            TileId {
+                x: 3,
+                y: 0,
+                zoom: 2
+            }
+        );
+
+        let area = GeoBounds::new(LatLon::new(51.49, -0.15), LatLon::new(51.52, -0.10));
+        let tiles: Vec<TileId> = area.tiles(14).collect();
+        assert!(tiles.len() >= 4);
+        assert!(tiles.iter().all(|tile| tile.bounds().intersects(&area)));
+    }
+
+    #[test]
+    fn test_bounds_conversion() {
+        let projection = GeoProjection::new(LatLon::new(35.6762, 139.6503));
+        let tile = TileId::containing(projection.origin(), 15);
+        let aabb = projection.bounds_to_world(&tile.bounds(), 0.0, 100.0);
+        assert!(aabb.contains_point(Vec3::ZERO));
+        // Zoom 15 tiles are about 1223 m wide at the equator
+        assert!((aabb.size().x - 1223.0 * 35.6762_f32.to_radians().cos()).abs() < 5.0);
+        assert_eq!(aabb.size().y.round(), 100.0);
+
+        let back = projection.bounds_to_geodetic(&aabb);
+        assert!(back.contains(projection.origin()));
+        assert!(back.intersects(&tile.bounds()));
+    }
+}
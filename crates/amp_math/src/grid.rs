@@ -0,0 +1,552 @@
+//! 2D rectangles and uniform grids.
+//!
+//! City layout, minimap rasterization and parking generation all work on a
+//! regular grid laid over the ground plane. [`Grid2D`] maps between world
+//! coordinates and cells and stores a value per cell, with flood fill,
+//! line rasterization and nearest-cell search on top. World coordinates are
+//! 2D here; for the ground plane pass `(x, z)`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use amp_math::grid::Grid2D;
+//! use glam::{IVec2, Vec2};
+//!
+//! // 10 x 10 cells of 4 m each, starting at the origin
+//! let mut occupied = Grid2D::new(Vec2::ZERO, 4.0, 10, 10, false);
+//! let cell = occupied.world_to_cell(Vec2::new(9.0, 1.0));
+//! assert_eq!(cell, IVec2::new(2, 0));
+//!
+//! occupied.set(cell, true);
+//! let free = occupied.nearest(cell, |taken| !taken).unwrap();
+//! assert_eq!(free.as_vec2().distance(cell.as_vec2()), 1.0);
+//! ```
+
+use glam::{IVec2, Vec2};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Axis-aligned 2D rectangle.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rect {
+    /// Minimum corner
+    pub min: Vec2,
+    /// Maximum corner
+    pub max: Vec2,
+}
+
+impl Rect {
+    /// Create a rectangle from its minimum and maximum corners.
+    pub fn new(min: Vec2, max: Vec2) -> Self {
+        Self { min, max }
+    }
+
+    /// Create a rectangle from any two opposite corners.
+    pub fn from_corners(a: Vec2, b: Vec2) -> Self {
+        Self::new(a.min(b), a.max(b))
+    }
+
+    /// Create a rectangle from its center and full size.
+    pub fn from_center_size(center: Vec2, size: Vec2) -> Self {
+        let half = size * 0.5;
+        Self::new(center - half, center + half)
+    }
+
+    /// Get the size of the rectangle.
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+
+    /// Get the center of the rectangle.
+    pub fn center(&self) -> Vec2 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Get the area of the rectangle.
+    pub fn area(&self) -> f32 {
+        let size = self.size();
+        size.x * size.y
+    }
+
+    /// Check if a point is inside the rectangle, edges included.
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    /// Check if two rectangles overlap, touching edges included.
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.min.cmple(other.max).all() && self.max.cmpge(other.min).all()
+    }
+
+    /// Get the overlap of two rectangles, if any.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        self.intersects(other)
+            .then(|| Rect::new(self.min.max(other.min), self.max.min(other.max)))
+    }
+
+    /// Get the smallest rectangle containing both rectangles.
+    pub fn union(&self, other: &Rect) -> Rect {
+        Rect::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    /// Grow the rectangle by `margin` on every side; negative values shrink it.
+    pub fn expand(&self, margin: f32) -> Rect {
+        Rect::new(
+            self.min - Vec2::splat(margin),
+            self.max + Vec2::splat(margin),
+        )
+    }
+
+    /// Get the point inside the rectangle closest to `point`.
+    pub fn clamp(&self, point: Vec2) -> Vec2 {
+        point.clamp(self.min, self.max)
+    }
+}
+
+/// A uniform grid of cells over a rectangle, with a value per cell.
+///
+/// Cell `(0, 0)` starts at `origin`; cells grow towards positive X and Y.
+/// Cell coordinates are signed so that positions outside the grid map to
+/// cells outside it instead of wrapping.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Grid2D<T> {
+    origin: Vec2,
+    cell_size: f32,
+    width: u32,
+    height: u32,
+    cells: Vec<T>,
+}
+
+impl<T> Grid2D<T> {
+    /// Create a grid with every cell set to `value`.
+    pub fn new(origin: Vec2, cell_size: f32, width: u32, height: u32, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(origin, cell_size, width, height, |_| value.clone())
+    }
+
+    /// Create a grid with each cell set from its coordinates.
+    pub fn from_fn(
+        origin: Vec2,
+        cell_size: f32,
+        width: u32,
+        height: u32,
+        mut value: impl FnMut(IVec2) -> T,
+    ) -> Self {
+        let cells = (0..height as i32)
+            .flat_map(|y| (0..width as i32).map(move |x| IVec2::new(x, y)))
+            .map(&mut value)
+            .collect();
+        Self {
+            origin,
+            cell_size,
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Create a grid of cells of `cell_size` covering `rect`.
+    ///
+    /// The grid is rounded up to whole cells, so it may extend past the
+    /// maximum corner.
+    pub fn covering(rect: Rect, cell_size: f32, value: T) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (rect.size() / cell_size).ceil().max(Vec2::ZERO);
+        Self::new(rect.min, cell_size, cells.x as u32, cells.y as u32, value)
+    }
+
+    /// Get the world position of the grid's minimum corner.
+    pub fn origin(&self) -> Vec2 {
+        self.origin
+    }
+
+    /// Get the size of each cell.
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Get the number of columns.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Get the number of rows.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Get the world rectangle covered by the grid.
+    pub fn bounds(&self) -> Rect {
+        let size = Vec2::new(self.width as f32, self.height as f32) * self.cell_size;
+        Rect::new(self.origin, self.origin + size)
+    }
+
+    /// Check if a cell is inside the grid.
+    pub fn in_bounds(&self, cell: IVec2) -> bool {
+        cell.x >= 0 && cell.y >= 0 && (cell.x as u32) < self.width && (cell.y as u32) < self.height
+    }
+
+    /// Get the cell containing a world position; may be outside the grid.
+    pub fn world_to_cell(&self, position: Vec2) -> IVec2 {
+        ((position - self.origin) / self.cell_size)
+            .floor()
+            .as_ivec2()
+    }
+
+    /// Get the world position of a cell's center.
+    pub fn cell_to_world(&self, cell: IVec2) -> Vec2 {
+        self.origin + (cell.as_vec2() + 0.5) * self.cell_size
+    }
+
+    /// Get the world rectangle covered by a cell.
+    pub fn cell_rect(&self, cell: IVec2) -> Rect {
+        let min = self.origin + cell.as_vec2() * self.cell_size;
+        Rect::new(min, min + Vec2::splat(self.cell_size))
+    }
+
+    /// Get the range of in-bounds cells overlapping a world rectangle.
+    ///
+    /// Returns the minimum and maximum cell, both inclusive, or `None` if the
+    /// rectangle is outside the grid.
+    pub fn cell_range(&self, rect: Rect) -> Option<(IVec2, IVec2)> {
+        if self.cells.is_empty() {
+            return None;
+        }
+        let overlap = rect.intersection(&self.bounds())?;
+        let max_cell = IVec2::new(self.width as i32 - 1, self.height as i32 - 1);
+        let min = self.world_to_cell(overlap.min).clamp(IVec2::ZERO, max_cell);
+        let max = self.world_to_cell(overlap.max).clamp(IVec2::ZERO, max_cell);
+        Some((min, max))
+    }
+
+    /// Get the value of a cell, or `None` outside the grid.
+    pub fn get(&self, cell: IVec2) -> Option<&T> {
+        self.index(cell).map(|index| &self.cells[index])
+    }
+
+    /// Get the value of a cell mutably, or `None` outside the grid.
+    pub fn get_mut(&mut self, cell: IVec2) -> Option<&mut T> {
+        self.index(cell).map(|index| &mut self.cells[index])
+    }
+
+    /// Set the value of a cell; returns `false` if it is outside the grid.
+    pub fn set(&mut self, cell: IVec2, value: T) -> bool {
+        match self.get_mut(cell) {
+            Some(slot) => {
+                *slot = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set every in-bounds cell overlapping a world rectangle.
+    pub fn fill_rect(&mut self, rect: Rect, value: T)
+    where
+        T: Clone,
+    {
+        if let Some((min, max)) = self.cell_range(rect) {
+            for y in min.y..=max.y {
+                for x in min.x..=max.x {
+                    self.set(IVec2::new(x, y), value.clone());
+                }
+            }
+        }
+    }
+
+    /// Get all cell values, row by row.
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Iterate over all cells and their values, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (IVec2, &T)> + '_ {
+        let width = self.width as usize;
+        self.cells.iter().enumerate().map(move |(index, value)| {
+            let cell = IVec2::new((index % width) as i32, (index / width) as i32);
+            (cell, value)
+        })
+    }
+
+    /// Iterate over the in-bounds edge neighbors of a cell.
+    pub fn neighbors(&self, cell: IVec2) -> impl Iterator<Item = IVec2> + '_ {
+        [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y]
+            .into_iter()
+            .map(move |offset| cell + offset)
+            .filter(|&neighbor| self.in_bounds(neighbor))
+    }
+
+    /// Get the cells connected to `start` through edge neighbors that match
+    /// `predicate`, in breadth-first order.
+    ///
+    /// Returns nothing if `start` is outside the grid or does not match.
+    pub fn flood_fill(&self, start: IVec2, predicate: impl Fn(&T) -> bool) -> Vec<IVec2> {
+        let Some(start_index) = self
+            .index(start)
+            .filter(|&index| predicate(&self.cells[index]))
+        else {
+            return Vec::new();
+        };
+
+        let mut visited = vec![false; self.cells.len()];
+        let mut queue = VecDeque::from([start]);
+        let mut region = Vec::new();
+        visited[start_index] = true;
+
+        while let Some(cell) = queue.pop_front() {
+            region.push(cell);
+            for neighbor in self.neighbors(cell) {
+                let Some(index) = self.index(neighbor) else {
+                    continue;
+                };
+                if !visited[index] && predicate(&self.cells[index]) {
+                    visited[index] = true;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        region
+    }
+
+    /// Find the in-bounds cell nearest to `start` whose value matches
+    /// `predicate`, by Euclidean distance between cell centers.
+    ///
+    /// Ties are broken by row, then column. `start` itself is returned if it
+    /// matches.
+    pub fn nearest(&self, start: IVec2, predicate: impl Fn(&T) -> bool) -> Option<IVec2> {
+        let mut best: Option<(i32, IVec2)> = None;
+        let far_corner = IVec2::new(self.width as i32 - 1, self.height as i32 - 1);
+        let max_radius = (start.abs().max((far_corner - start).abs())).max_element();
+
+        for radius in 0..=max_radius {
+            // Every cell on ring `radius` is at least `radius` away.
+            if best.is_some_and(|(distance, _)| distance < radius * radius) {
+                break;
+            }
+            for cell in ring(start, radius) {
+                let Some(value) = self.get(cell) else {
+                    continue;
+                };
+                if !predicate(value) {
+                    continue;
+                }
+                let distance = (cell - start).length_squared();
+                let better = best.map_or(true, |(best_distance, best_cell)| {
+                    (distance, cell.y, cell.x) < (best_distance, best_cell.y, best_cell.x)
+                });
+                if better {
+                    best = Some((distance, cell));
+                }
+            }
+        }
+        best.map(|(_, cell)| cell)
+    }
+
+    /// Iterate over the in-bounds cells crossed by a world-space segment.
+    pub fn line_world(&self, from: Vec2, to: Vec2) -> impl Iterator<Item = IVec2> + '_ {
+        line_cells(self.world_to_cell(from), self.world_to_cell(to))
+            .filter(|&cell| self.in_bounds(cell))
+    }
+
+    fn index(&self, cell: IVec2) -> Option<usize> {
+        self.in_bounds(cell)
+            .then(|| cell.y as usize * self.width as usize + cell.x as usize)
+    }
+}
+
+/// Iterate over the cells of a line from `from` to `to`, both included.
+///
+/// Uses Bresenham's algorithm, so consecutive cells share an edge or a
+/// corner and each step advances along the major axis.
+///
+/// # Examples
+///
+/// ```rust
+/// use amp_math::grid::line_cells;
+/// use glam::IVec2;
+///
+/// let cells: Vec<IVec2> = line_cells(IVec2::ZERO, IVec2::new(4, 2)).collect();
+/// assert_eq!(cells.len(), 5);
+/// assert_eq!(cells[2], IVec2::new(2, 1));
+/// ```
+pub fn line_cells(from: IVec2, to: IVec2) -> impl Iterator<Item = IVec2> {
+    let delta = (to - from).abs();
+    let step = (to - from).signum();
+    let mut error = delta.x - delta.y;
+    let mut cell = from;
+    let mut done = false;
+
+    std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+        let current = cell;
+        if cell == to {
+            done = true;
+        } else {
+            let doubled = 2 * error;
+            if doubled > -delta.y {
+                error -= delta.y;
+                cell.x += step.x;
+            }
+            if doubled < delta.x {
+                error += delta.x;
+                cell.y += step.y;
+            }
+        }
+        Some(current)
+    })
+}
+
+/// Cells at Chebyshev distance exactly `radius` from `center`.
+fn ring(center: IVec2, radius: i32) -> Vec<IVec2> {
+    if radius == 0 {
+        return vec![center];
+    }
+    let mut cells = Vec::with_capacity(8 * radius as usize);
+    for y in -radius..=radius {
+        if y.abs() == radius {
+            cells.extend((-radius..=radius).map(|x| center + IVec2::new(x, y)));
+        } else {
+            cells.push(center + IVec2::new(-radius, y));
+            cells.push(center + IVec2::new(radius, y));
+        }
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid2D<u8> {
+        Grid2D::new(Vec2::new(-10.0, -10.0), 2.0, 10, 10, 0)
+    }
+
+    #[test]
+    fn test_rect_operations() {
+        let a = Rect::from_corners(Vec2::new(2.0, 0.0), Vec2::new(0.0, 2.0));
+        let b = Rect::from_center_size(Vec2::new(2.0, 2.0), Vec2::splat(2.0));
+        assert_eq!(a.min, Vec2::ZERO);
+        assert_eq!(a.area(), 4.0);
+        assert!(a.contains(Vec2::new(2.0, 1.0)));
+        assert!(!a.contains(Vec2::new(2.1, 1.0)));
+        assert_eq!(
+            a.intersection(&b),
+            Some(Rect::new(Vec2::ONE, Vec2::splat(2.0)))
+        );
+        assert_eq!(a.union(&b), Rect::new(Vec2::ZERO, Vec2::splat(3.0)));
+        assert!(a
+            .intersection(&Rect::from_center_size(Vec2::splat(5.0), Vec2::ONE))
+            .is_none());
+        assert_eq!(a.clamp(Vec2::new(5.0, -1.0)), Vec2::new(2.0, 0.0));
+    }
+
+    #[test]
+    fn test_world_cell_round_trip() {
+        let grid = grid();
+        assert_eq!(
+            grid.bounds(),
+            Rect::new(Vec2::splat(-10.0), Vec2::splat(10.0))
+        );
+        assert_eq!(grid.world_to_cell(Vec2::new(-10.0, -9.0)), IVec2::ZERO);
+        assert_eq!(grid.world_to_cell(Vec2::new(-10.5, 0.0)), IVec2::new(-1, 5));
+        assert!(!grid.in_bounds(IVec2::new(-1, 5)));
+
+        for (cell, _) in grid.iter() {
+            assert_eq!(grid.world_to_cell(grid.cell_to_world(cell)), cell);
+            assert!(grid.cell_rect(cell).contains(grid.cell_to_world(cell)));
+        }
+
+        let covering = Grid2D::covering(Rect::new(Vec2::ZERO, Vec2::new(5.0, 4.0)), 2.0, 0u8);
+        assert_eq!((covering.width(), covering.height()), (3, 2));
+    }
+
+    #[test]
+    fn test_fill_rect_clips_to_grid() {
+        let mut grid = grid();
+        grid.fill_rect(Rect::new(Vec2::new(5.0, 5.0), Vec2::new(50.0, 50.0)), 1);
+        let filled = grid.cells().iter().filter(|&&value| value == 1).count();
+        assert_eq!(filled, 9);
+        assert_eq!(grid.get(IVec2::new(9, 9)), Some(&1));
+        assert!(!grid.set(IVec2::new(10, 0), 1));
+    }
+
+    #[test]
+    fn test_flood_fill_stops_at_walls() {
+        let mut grid = Grid2D::new(Vec2::ZERO, 1.0, 5, 5, 0u8);
+        for y in 0..5 {
+            grid.set(IVec2::new(2, y), 1);
+        }
+        let left = grid.flood_fill(IVec2::ZERO, |&value| value == 0);
+        assert_eq!(left.len(), 10);
+        assert!(left.iter().all(|cell| cell.x < 2));
+        assert_eq!(left[0], IVec2::ZERO);
+
+        assert!(grid
+            .flood_fill(IVec2::new(2, 0), |&value| value == 0)
+            .is_empty());
+        assert!(grid.flood_fill(IVec2::new(-1, 0), |_| true).is_empty());
+    }
+
+    #[test]
+    fn test_line_cells_are_connected() {
+        let cases = [
+            (IVec2::ZERO, IVec2::new(7, 3)),
+            (IVec2::new(3, -2), IVec2::new(-4, 5)),
+            (IVec2::new(1, 1), IVec2::new(1, 1)),
+            (IVec2::new(0, 6), IVec2::new(0, -2)),
+        ];
+        for (from, to) in cases {
+            let cells: Vec<_> = line_cells(from, to).collect();
+            let delta = (to - from).abs();
+            assert_eq!(cells.len() as i32, delta.x.max(delta.y) + 1);
+            assert_eq!((cells[0], *cells.last().unwrap()), (from, to));
+            for pair in cells.windows(2) {
+                let step = (pair[1] - pair[0]).abs();
+                assert!(step.max_element() == 1);
+            }
+        }
+
+        let grid = grid();
+        let crossed: Vec<_> = grid
+            .line_world(Vec2::new(-20.0, -9.0), Vec2::new(-5.0, -9.0))
+            .collect();
+        assert_eq!(crossed.first(), Some(&IVec2::ZERO));
+        assert_eq!(crossed.len(), 3);
+    }
+
+    #[test]
+    fn test_nearest_matching_cell() {
+        let mut grid = Grid2D::new(Vec2::ZERO, 1.0, 9, 9, true);
+        grid.fill_rect(Rect::new(Vec2::splat(2.5), Vec2::splat(6.5)), false);
+
+        let start = IVec2::splat(4);
+        let found = grid.nearest(start, |&free| free).unwrap();
+        assert_eq!((found - start).length_squared(), 9);
+        assert_eq!(found, IVec2::new(4, 1));
+
+        assert_eq!(grid.nearest(IVec2::ZERO, |&free| free), Some(IVec2::ZERO));
+        assert_eq!(
+            grid.nearest(IVec2::new(-3, 0), |&free| free),
+            Some(IVec2::ZERO)
+        );
+        assert_eq!(grid.nearest(start, |_| false), None);
+    }
+
+    #[test]
+    fn test_ring_cells() {
+        assert_eq!(ring(IVec2::ZERO, 0), [IVec2::ZERO]);
+        for radius in 1..4 {
+            let cells = ring(IVec2::new(2, -1), radius);
+            assert_eq!(cells.len() as i32, 8 * radius);
+            assert!(cells
+                .iter()
+                .all(|&cell| (cell - IVec2::new(2, -1)).abs().max_element() == radius));
+        }
+    }
+}
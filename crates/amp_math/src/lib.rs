@@ -1,9 +1,19 @@
 //! High-performance math library for spatial calculations and Morton encoding.
 //!
 //! This crate provides efficient implementations for:
-//! - Morton encoding/decoding for spatial indexing
-//! - Axis-aligned bounding boxes (AABB) and spheres
+//! - Morton encoding/decoding for spatial indexing, at full 21-bit precision
+//!   over configurable world bounds
+//! - Chunk keys with ring and nearest-first spiral iteration
+//! - Bounding volumes: AABBs, spheres, OBBs, and capsules
+//! - SAH bounding volume hierarchies with refit and query callbacks
+//! - 2D rectangles and grids with flood fill, line rasterization, and
+//!   nearest-cell search
+//! - Seedable Perlin and simplex noise with FBM and domain warping
+//! - Batched AABB transform and intersection kernels
+//! - Cubic splines with arc-length lookup and parallel-transport frames
 //! - Transform utilities wrapping glam
+//! - Double-precision world positions with floating-origin conversion
+//! - WGS84 latitude/longitude to local meters, and map tiles
 //!
 //! # Examples
 //!
@@ -17,7 +27,15 @@
 //! ```
 
 pub mod bounds;
+pub mod bvh;
+pub mod chunk_key;
+pub mod coordinate_conversion;
+pub mod grid;
 pub mod morton;
+pub mod noise;
+pub mod simd;
+pub mod spline;
 pub mod transforms;
+pub mod world_pos;
 
 pub use glam::*;
@@ -2,6 +2,8 @@
 //!
 //! Morton encoding maps 3D coordinates to a single integer value that preserves
 //! spatial locality. Points that are close in 3D space will have similar Morton codes.
+//! [`MortonBox`] queries the codes inside a box of cells without visiting
+//! the codes between them that fall outside it.
 //!
 //! # Examples
 //!
@@ -15,7 +17,9 @@
 //! assert!((decoded - pos).length() < 0.001);
 //! ```
 
-use glam::Vec3;
+use crate::bounds::Aabb;
+use glam::{IVec3, UVec2, UVec3, Vec3};
+use serde::{Deserialize, Serialize};
 
 /// Morton encoding for 2D coordinates using 32-bit codes.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -180,6 +184,419 @@ impl Morton3D {
     }
 }
 
+/// Dilated bits of the X axis in a 3D Morton code; Y and Z are shifted
+/// one and two bits left.
+const X_MASK: u64 = 0x1249_2492_4924_9249;
+
+impl Morton3D {
+    /// Offset a Morton code by whole cells without decoding it.
+    ///
+    /// Each axis is added in its dilated form, so the other axes' bits are
+    /// untouched. Returns `None` if any axis leaves [0, MAX_COORD].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::morton::Morton3D;
+    /// use glam::IVec3;
+    ///
+    /// let code = Morton3D::encode_normalized(10, 20, 30);
+    /// let moved = Morton3D::offset(code, IVec3::new(1, -2, 5)).unwrap();
+    /// assert_eq!(moved, Morton3D::encode_normalized(11, 18, 35));
+    /// assert_eq!(Morton3D::offset(code, IVec3::new(-11, 0, 0)), None);
+    /// ```
+    pub fn offset(code: u64, offset: IVec3) -> Option<u64> {
+        let x = Self::offset_axis(code, offset.x, 0)?;
+        let y = Self::offset_axis(code, offset.y, 1)?;
+        let z = Self::offset_axis(code, offset.z, 2)?;
+        Some(x | y | z)
+    }
+
+    /// Get the codes of the up to 26 cells touching a cell, skipping those
+    /// outside the encodable range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::morton::Morton3D;
+    ///
+    /// assert_eq!(Morton3D::neighbors(Morton3D::encode_normalized(5, 5, 5)).count(), 26);
+    /// assert_eq!(Morton3D::neighbors(0).count(), 7);
+    /// ```
+    pub fn neighbors(code: u64) -> impl Iterator<Item = u64> {
+        (-1..=1)
+            .flat_map(|z| (-1..=1).flat_map(move |y| (-1..=1).map(move |x| IVec3::new(x, y, z))))
+            .filter(|&offset| offset != IVec3::ZERO)
+            .filter_map(move |offset| Self::offset(code, offset))
+    }
+
+    /// Offset one axis of a code, returning only that axis' bits.
+    fn offset_axis(code: u64, delta: i32, axis: u32) -> Option<u64> {
+        let mask = X_MASK << axis;
+        let bits = code & mask;
+        if delta == 0 {
+            return Some(bits);
+        }
+        if delta.unsigned_abs() > Self::MAX_COORD {
+            return None;
+        }
+        let step = Self::spread_bits(delta.unsigned_abs() as u64) << axis;
+        // Dilated values order like the integers they encode, so wrapping
+        // shows up as the result moving the wrong way
+        if delta > 0 {
+            let sum = (bits | !mask).wrapping_add(step) & mask;
+            (sum > bits).then_some(sum)
+        } else {
+            let difference = bits.wrapping_sub(step) & mask;
+            (difference < bits).then_some(difference)
+        }
+    }
+}
+
+/// Full-precision 3D Morton encoding over fixed world bounds.
+///
+/// [`Morton3D::encode`] treats each world unit as one cell, which wastes
+/// resolution in a small world and clamps negative coordinates. A grid
+/// instead divides its bounds into 2^21 cells per axis, so a 10 km world
+/// keeps keys at about 5 mm resolution.
+///
+/// # Examples
+///
+/// ```rust
+/// use amp_math::bounds::Aabb;
+/// use amp_math::morton::MortonGrid;
+/// use glam::Vec3;
+///
+/// let grid = MortonGrid::new(Aabb::new(Vec3::splat(-5000.0), Vec3::splat(5000.0)));
+/// let pos = Vec3::new(-1234.567, 12.5, 4321.0);
+/// let code = grid.encode(pos);
+/// assert!(grid.decode(code).distance(pos) < grid.cell_size().length());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MortonGrid {
+    bounds: Aabb,
+    cell_size: Vec3,
+}
+
+impl MortonGrid {
+    /// Number of cells along each axis.
+    pub const CELLS_PER_AXIS: u32 = Morton3D::MAX_COORD + 1;
+
+    /// Create a grid covering `bounds`.
+    ///
+    /// Flat or empty axes get a single-unit extent so every position still
+    /// encodes.
+    pub fn new(bounds: Aabb) -> Self {
+        let bounds = if bounds.is_empty() {
+            Aabb::new(Vec3::ZERO, Vec3::ONE)
+        } else {
+            bounds
+        };
+        let size = bounds.size().max(Vec3::splat(f32::EPSILON));
+        Self {
+            bounds,
+            cell_size: size / Self::CELLS_PER_AXIS as f32,
+        }
+    }
+
+    /// Get the world bounds the grid covers.
+    pub fn bounds(&self) -> Aabb {
+        self.bounds
+    }
+
+    /// Get the size of one cell.
+    pub fn cell_size(&self) -> Vec3 {
+        self.cell_size
+    }
+
+    /// Get the integer cell of a position, clamped to the bounds.
+    pub fn quantize(&self, pos: Vec3) -> UVec3 {
+        let cell = ((pos - self.bounds.min) / self.cell_size).floor();
+        cell.clamp(Vec3::ZERO, Vec3::splat(Morton3D::MAX_COORD as f32))
+            .as_uvec3()
+    }
+
+    /// Encode a position into a Morton code, clamping it to the bounds.
+    pub fn encode(&self, pos: Vec3) -> u64 {
+        let cell = self.quantize(pos);
+        Morton3D::encode_normalized(cell.x, cell.y, cell.z)
+    }
+
+    /// Get the integer cell of a Morton code.
+    pub fn cell(&self, code: u64) -> UVec3 {
+        UVec3::new(
+            Morton3D::compact_bits(code),
+            Morton3D::compact_bits(code >> 1),
+            Morton3D::compact_bits(code >> 2),
+        )
+    }
+
+    /// Decode a Morton code to the center of its cell.
+    pub fn decode(&self, code: u64) -> Vec3 {
+        self.bounds.min + (self.cell(code).as_vec3() + 0.5) * self.cell_size
+    }
+
+    /// Get the world bounds of a Morton code's cell.
+    pub fn cell_bounds(&self, code: u64) -> Aabb {
+        let min = self.bounds.min + self.cell(code).as_vec3() * self.cell_size;
+        Aabb::new(min, min + self.cell_size)
+    }
+}
+
+/// Dilated bits of the X axis in a 2D Morton code; Y is shifted one bit
+/// left.
+const X_MASK_2D: u64 = 0x5555_5555;
+
+/// Box of grid cells for range queries over Morton codes.
+///
+/// The codes inside a box aren't contiguous: a Z-order walk from the
+/// minimum corner's code to the maximum corner's leaves and re-enters the
+/// box many times. [`bigmin`](Self::bigmin) and [`litmax`](Self::litmax)
+/// compute where the walk next re-enters the box going forwards or
+/// backwards, so scans jump over the codes outside it.
+///
+/// # Examples
+///
+/// ```rust
+/// use amp_math::morton::{Morton3D, MortonBox};
+/// use glam::UVec3;
+///
+/// let query = MortonBox::new_3d(UVec3::new(2, 2, 2), UVec3::new(3, 5, 3));
+/// let mut codes: Vec<u64> = (0..8)
+///     .flat_map(|x| (0..8).map(move |y| Morton3D::encode_normalized(x, y, 2)))
+///     .collect();
+/// codes.sort_unstable();
+/// assert_eq!(query.query_sorted(&codes).count(), 8);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MortonBox {
+    min: u64,
+    max: u64,
+    dims: u32,
+    axis_mask: u64,
+}
+
+impl MortonBox {
+    /// Create a box of [`Morton3D`] cells, inclusive of both corners.
+    pub fn new_3d(min: UVec3, max: UVec3) -> Self {
+        let (min, max) = (min.min(max), min.max(max));
+        Self {
+            min: Morton3D::encode_normalized(min.x, min.y, min.z),
+            max: Morton3D::encode_normalized(max.x, max.y, max.z),
+            dims: 3,
+            axis_mask: X_MASK,
+        }
+    }
+
+    /// Create a box of [`Morton2D`] cells, inclusive of both corners.
+    pub fn new_2d(min: UVec2, max: UVec2) -> Self {
+        let (min, max) = (min.min(max), min.max(max));
+        Self {
+            min: Morton2D::encode(min.x, min.y),
+            max: Morton2D::encode(max.x, max.y),
+            dims: 2,
+            axis_mask: X_MASK_2D,
+        }
+    }
+
+    /// Get the code of the minimum corner, the smallest code in the box.
+    pub fn min_code(&self) -> u64 {
+        self.min
+    }
+
+    /// Get the code of the maximum corner, the largest code in the box.
+    pub fn max_code(&self) -> u64 {
+        self.max
+    }
+
+    /// Check if a code's cell is inside the box.
+    pub fn contains(&self, code: u64) -> bool {
+        (0..self.dims).all(|axis| {
+            let mask = self.axis_mask << axis;
+            let value = code & mask;
+            value >= self.min & mask && value <= self.max & mask
+        })
+    }
+
+    /// Get the smallest code inside the box greater than `code`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::morton::{Morton2D, MortonBox};
+    /// use glam::UVec2;
+    ///
+    /// let query = MortonBox::new_2d(UVec2::new(1, 0), UVec2::new(2, 1));
+    /// // Skips (0, 1), which comes between them in Z-order
+    /// assert_eq!(query.bigmin(Morton2D::encode(1, 0)), Some(Morton2D::encode(1, 1)));
+    /// assert_eq!(query.bigmin(query.max_code()), None);
+    /// ```
+    pub fn bigmin(&self, code: u64) -> Option<u64> {
+        if code >= self.max {
+            return None;
+        }
+        if self.contains(code + 1) {
+            return Some(code + 1);
+        }
+        let code = code + 1;
+        let (mut min, mut max) = (self.min, self.max);
+        let mut bigmin = None;
+        for bit in (0..self.bits()).rev() {
+            match self.bit_triple(code, min, max, bit) {
+                (false, false, true) => {
+                    bigmin = Some(self.load_first_upper(min, bit));
+                    max = self.load_last_lower(max, bit);
+                }
+                (false, true, true) => return Some(min),
+                (true, false, false) => return bigmin,
+                (true, false, true) => min = self.load_first_upper(min, bit),
+                _ => {}
+            }
+        }
+        bigmin
+    }
+
+    /// Get the largest code inside the box less than `code`.
+    pub fn litmax(&self, code: u64) -> Option<u64> {
+        if code <= self.min {
+            return None;
+        }
+        if self.contains(code - 1) {
+            return Some(code - 1);
+        }
+        let code = code - 1;
+        let (mut min, mut max) = (self.min, self.max);
+        let mut litmax = None;
+        for bit in (0..self.bits()).rev() {
+            match self.bit_triple(code, min, max, bit) {
+                (false, false, true) => max = self.load_last_lower(max, bit),
+                (false, true, true) => return litmax,
+                (true, false, false) => return Some(max),
+                (true, false, true) => {
+                    litmax = Some(self.load_last_lower(max, bit));
+                    min = self.load_first_upper(min, bit);
+                }
+                _ => {}
+            }
+        }
+        litmax
+    }
+
+    /// Iterate over every code inside the box in ascending order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::morton::{Morton2D, MortonBox};
+    /// use glam::UVec2;
+    ///
+    /// let query = MortonBox::new_2d(UVec2::new(1, 1), UVec2::new(2, 2));
+    /// let cells: Vec<_> = query.codes().map(Morton2D::decode).collect();
+    /// assert_eq!(cells, [(1, 1), (2, 1), (1, 2), (2, 2)]);
+    /// ```
+    pub fn codes(&self) -> impl Iterator<Item = u64> + '_ {
+        std::iter::successors(Some(self.min), |&code| self.bigmin(code))
+    }
+
+    /// Iterate over the indices of the codes inside the box in an
+    /// ascending sorted slice.
+    ///
+    /// Runs of codes outside the box are skipped with a binary search, in
+    /// either direction.
+    pub fn query_sorted<'a>(&'a self, codes: &'a [u64]) -> MortonQuery<'a> {
+        MortonQuery {
+            query: self,
+            codes,
+            front: codes.partition_point(|&code| code < self.min),
+            back: codes.partition_point(|&code| code <= self.max),
+        }
+    }
+
+    /// Get the number of bits in a code.
+    fn bits(&self) -> u32 {
+        if self.dims == 3 {
+            63
+        } else {
+            32
+        }
+    }
+
+    fn bit_triple(&self, code: u64, min: u64, max: u64, bit: u32) -> (bool, bool, bool) {
+        let bit = 1 << bit;
+        (code & bit != 0, min & bit != 0, max & bit != 0)
+    }
+
+    /// Get the bits of `bit`'s axis at `bit` and below.
+    fn axis_bits_below(&self, bit: u32) -> u64 {
+        let axis_mask = self.axis_mask << (bit % self.dims);
+        axis_mask & ((1 << bit << 1) - 1)
+    }
+
+    /// Set `bit` and clear the lower bits of its axis: the first code in
+    /// the upper half of the split.
+    fn load_first_upper(&self, value: u64, bit: u32) -> u64 {
+        (value & !self.axis_bits_below(bit)) | (1 << bit)
+    }
+
+    /// Clear `bit` and set the lower bits of its axis: the last code in the
+    /// lower half of the split.
+    fn load_last_lower(&self, value: u64, bit: u32) -> u64 {
+        (value | self.axis_bits_below(bit)) & !(1 << bit)
+    }
+}
+
+/// Indices of the codes inside a [`MortonBox`] in a sorted slice.
+///
+/// Created by [`MortonBox::query_sorted`].
+#[derive(Debug, Clone)]
+pub struct MortonQuery<'a> {
+    query: &'a MortonBox,
+    codes: &'a [u64],
+    front: usize,
+    back: usize,
+}
+
+impl Iterator for MortonQuery<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.front < self.back {
+            let code = self.codes[self.front];
+            if self.query.contains(code) {
+                self.front += 1;
+                return Some(self.front - 1);
+            }
+            match self.query.bigmin(code) {
+                Some(next) => {
+                    self.front += self.codes[self.front..self.back].partition_point(|&c| c < next)
+                }
+                None => self.front = self.back,
+            }
+        }
+        None
+    }
+}
+
+impl DoubleEndedIterator for MortonQuery<'_> {
+    fn next_back(&mut self) -> Option<usize> {
+        while self.front < self.back {
+            let code = self.codes[self.back - 1];
+            if self.query.contains(code) {
+                self.back -= 1;
+                return Some(self.back);
+            }
+            match self.query.litmax(code) {
+                Some(previous) => {
+                    self.back = self.front
+                        + self.codes[self.front..self.back].partition_point(|&c| c <= previous)
+                }
+                None => self.back = self.front,
+            }
+        }
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -362,4 +779,165 @@ mod tests {
 
         assert!(diff_close < diff_far);
     }
+
+    #[test]
+    fn test_offset_matches_decoded_arithmetic() {
+        let cells = [(0, 0, 0), (7, 8, 15), (1000, 65535, 123_456)];
+        let offsets = [
+            IVec3::new(1, 0, 0),
+            IVec3::new(-1, 1, -1),
+            IVec3::new(100, -7, 4096),
+        ];
+        for (x, y, z) in cells {
+            let code = Morton3D::encode_normalized(x, y, z);
+            for offset in offsets {
+                let expected = UVec3::new(x, y, z).as_ivec3() + offset;
+                let result = Morton3D::offset(code, offset);
+                if expected.min_element() < 0 {
+                    assert_eq!(result, None);
+                } else {
+                    let e = expected.as_uvec3();
+                    assert_eq!(result, Some(Morton3D::encode_normalized(e.x, e.y, e.z)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_offset_overflow() {
+        let max = Morton3D::MAX_COORD;
+        let code = Morton3D::encode_normalized(max, 3, max - 1);
+        assert_eq!(Morton3D::offset(code, IVec3::X), None);
+        assert_eq!(Morton3D::offset(code, IVec3::new(0, 0, 2)), None);
+        assert_eq!(
+            Morton3D::offset(code, IVec3::Z),
+            Some(Morton3D::encode_normalized(max, 3, max))
+        );
+        assert_eq!(Morton3D::offset(code, IVec3::new(0, i32::MIN, 0)), None);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let code = Morton3D::encode_normalized(10, 0, 10);
+        let neighbors: Vec<u64> = Morton3D::neighbors(code).collect();
+        assert_eq!(neighbors.len(), 17);
+        assert!(neighbors.contains(&Morton3D::encode_normalized(9, 1, 11)));
+        assert!(!neighbors.contains(&code));
+    }
+
+    #[test]
+    fn test_grid_round_trip() {
+        let grid = MortonGrid::new(Aabb::new(
+            Vec3::new(-8192.0, -512.0, -8192.0),
+            Vec3::new(8192.0, 1536.0, 8192.0),
+        ));
+        let cell = grid.cell_size();
+        assert!(cell.max_element() < 0.01);
+
+        let positions = [
+            Vec3::new(-8192.0, -512.0, -8192.0),
+            Vec3::new(-1234.567, 12.5, 4321.125),
+            Vec3::new(0.001, 0.0, -0.001),
+            Vec3::new(8191.99, 1535.9, 8191.99),
+        ];
+        for pos in positions {
+            let code = grid.encode(pos);
+            let decoded = grid.decode(code);
+            assert!(
+                ((decoded - pos).abs() - cell * 0.5).max_element() < 1e-3,
+                "{pos} decoded to {decoded}"
+            );
+            assert!(grid.cell_bounds(code).contains_point(pos));
+            assert_eq!(grid.encode(decoded), code);
+        }
+    }
+
+    #[test]
+    fn test_grid_clamps_and_neighbors() {
+        let grid = MortonGrid::new(Aabb::new(Vec3::ZERO, Vec3::splat(2048.0)));
+        let max = UVec3::splat(Morton3D::MAX_COORD);
+        assert_eq!(grid.cell(grid.encode(Vec3::splat(-10.0))), UVec3::ZERO);
+        assert_eq!(grid.cell(grid.encode(Vec3::splat(5000.0))), max);
+
+        let code = grid.encode(Vec3::new(100.0, 100.0, 100.0));
+        let east = Morton3D::offset(code, IVec3::X).unwrap();
+        assert!((grid.decode(east).x - grid.decode(code).x - grid.cell_size().x).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_grid_degenerate_bounds() {
+        let flat = MortonGrid::new(Aabb::new(Vec3::ZERO, Vec3::new(100.0, 0.0, 100.0)));
+        assert_eq!(flat.quantize(Vec3::new(50.0, 0.0, 50.0)).y, 0);
+        let empty = MortonGrid::new(Aabb::empty());
+        assert_eq!(empty.bounds(), Aabb::new(Vec3::ZERO, Vec3::ONE));
+    }
+
+    fn brute_force_codes(query: &MortonBox, limit: u64) -> Vec<u64> {
+        (0..limit).filter(|&code| query.contains(code)).collect()
+    }
+
+    #[test]
+    fn test_box_bigmin_litmax_match_brute_force() {
+        let boxes = [
+            MortonBox::new_2d(UVec2::new(3, 5), UVec2::new(9, 6)),
+            MortonBox::new_3d(UVec3::new(1, 2, 3), UVec3::new(6, 3, 7)),
+            MortonBox::new_3d(UVec3::new(5, 5, 5), UVec3::new(5, 5, 5)),
+        ];
+        for query in boxes {
+            let inside = brute_force_codes(&query, 1024);
+            assert_eq!(inside.first(), Some(&query.min_code()));
+            assert_eq!(inside.last(), Some(&query.max_code()));
+            for code in 0..1024 {
+                let bigmin = inside.iter().copied().find(|&c| c > code);
+                let litmax = inside.iter().copied().rev().find(|&c| c < code);
+                assert_eq!(query.bigmin(code), bigmin, "bigmin of {code}");
+                assert_eq!(query.litmax(code), litmax, "litmax of {code}");
+            }
+            assert_eq!(query.codes().collect::<Vec<_>>(), inside);
+        }
+    }
+
+    #[test]
+    fn test_box_corner_order() {
+        let query = MortonBox::new_3d(UVec3::new(4, 1, 9), UVec3::new(2, 3, 7));
+        assert!(query.contains(Morton3D::encode_normalized(3, 2, 8)));
+        assert!(!query.contains(Morton3D::encode_normalized(3, 0, 8)));
+        assert_eq!(query.codes().count(), 27);
+    }
+
+    #[test]
+    fn test_box_at_max_coord() {
+        let max = Morton3D::MAX_COORD;
+        let query = MortonBox::new_3d(UVec3::splat(max - 1), UVec3::splat(max));
+        assert_eq!(query.codes().count(), 8);
+        assert_eq!(query.bigmin(query.max_code()), None);
+    }
+
+    #[test]
+    fn test_query_sorted_both_directions() {
+        let mut codes: Vec<u64> = (0..16)
+            .flat_map(|x| (0..16).map(move |y| Morton2D::encode(x, y)))
+            .collect();
+        codes.extend_from_slice(&[Morton2D::encode(5, 5), Morton2D::encode(40, 40)]);
+        codes.sort_unstable();
+        let query = MortonBox::new_2d(UVec2::new(4, 3), UVec2::new(10, 5));
+
+        let forward: Vec<usize> = query.query_sorted(&codes).collect();
+        let expected: Vec<usize> = (0..codes.len())
+            .filter(|&i| query.contains(codes[i]))
+            .collect();
+        assert_eq!(forward, expected);
+        assert_eq!(forward.len(), 7 * 3 + 1);
+
+        let mut backward: Vec<usize> = query.query_sorted(&codes).rev().collect();
+        backward.reverse();
+        assert_eq!(backward, expected);
+
+        let mut both = query.query_sorted(&codes);
+        assert_eq!(both.next(), Some(expected[0]));
+        assert_eq!(both.next_back(), expected.last().copied());
+        assert_eq!(both.count(), expected.len() - 2);
+
+        assert_eq!(query.query_sorted(&[]).count(), 0);
+    }
 }
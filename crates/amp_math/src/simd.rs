@@ -0,0 +1,391 @@
+//! Batched bounding volume kernels for culling and spatial queries.
+//!
+//! Operates on eight AABBs at a time stored as structure-of-arrays lanes,
+//! so each step of a transform or intersection test is the same operation
+//! across eight `f32`s and compiles to vector instructions without any
+//! platform intrinsics.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use amp_math::bounds::{Aabb, Sphere};
+//! use amp_math::simd::AabbBatch;
+//! use glam::Vec3;
+//!
+//! let boxes: Vec<Aabb> = (0..20)
+//!     .map(|i| Aabb::from_center_half_extents(Vec3::new(i as f32 * 3.0, 0.0, 0.0), Vec3::ONE))
+//!     .collect();
+//! let sphere = Sphere::new(Vec3::ZERO, 4.0);
+//! let hits: u32 = AabbBatch::chunks(&boxes)
+//!     .map(|batch| batch.intersects_sphere(&sphere).count_ones())
+//!     .sum();
+//! assert_eq!(hits, 2);
+//! ```
+
+use crate::bounds::{Aabb, Sphere};
+use glam::{Mat4, Vec3, Vec3A, Vec4};
+use serde::{Deserialize, Serialize};
+
+/// Number of AABBs processed together.
+pub const LANES: usize = 8;
+
+type Lanes = [f32; LANES];
+
+/// Eight AABBs stored as structure-of-arrays lanes.
+///
+/// Batches with fewer than [`LANES`] boxes pad the rest with zero-sized
+/// boxes at the origin; every test masks those lanes out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(C, align(32))]
+pub struct AabbBatch {
+    min_x: Lanes,
+    min_y: Lanes,
+    min_z: Lanes,
+    max_x: Lanes,
+    max_y: Lanes,
+    max_z: Lanes,
+    len: usize,
+}
+
+impl AabbBatch {
+    /// Create a batch from up to [`LANES`] boxes; extra boxes are ignored.
+    pub fn new(aabbs: &[Aabb]) -> Self {
+        let mut batch = Self {
+            min_x: [0.0; LANES],
+            min_y: [0.0; LANES],
+            min_z: [0.0; LANES],
+            max_x: [0.0; LANES],
+            max_y: [0.0; LANES],
+            max_z: [0.0; LANES],
+            len: aabbs.len().min(LANES),
+        };
+        for (lane, aabb) in aabbs.iter().take(LANES).enumerate() {
+            batch.min_x[lane] = aabb.min.x;
+            batch.min_y[lane] = aabb.min.y;
+            batch.min_z[lane] = aabb.min.z;
+            batch.max_x[lane] = aabb.max.x;
+            batch.max_y[lane] = aabb.max.y;
+            batch.max_z[lane] = aabb.max.z;
+        }
+        batch
+    }
+
+    /// Split boxes into batches of [`LANES`], the last possibly partial.
+    pub fn chunks(aabbs: &[Aabb]) -> impl Iterator<Item = Self> + '_ {
+        aabbs.chunks(LANES).map(Self::new)
+    }
+
+    /// Get the number of boxes in the batch.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if the batch holds no boxes.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get a box from the batch.
+    pub fn get(&self, lane: usize) -> Option<Aabb> {
+        (lane < self.len).then(|| Aabb {
+            min: Vec3::new(self.min_x[lane], self.min_y[lane], self.min_z[lane]),
+            max: Vec3::new(self.max_x[lane], self.max_y[lane], self.max_z[lane]),
+        })
+    }
+
+    /// Get a bit mask with a bit set for each lane holding a box.
+    pub fn lane_mask(&self) -> u8 {
+        ((1u16 << self.len) - 1) as u8
+    }
+
+    /// Transform every box by a matrix, returning the boxes enclosing the
+    /// transformed boxes.
+    ///
+    /// Each center is transformed as a point and each half-extent by the
+    /// absolute value of the matrix's linear part, which gives the same
+    /// result as transforming all eight corners.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::bounds::Aabb;
+    /// use amp_math::simd::AabbBatch;
+    /// use glam::{Mat4, Vec3};
+    ///
+    /// let batch = AabbBatch::new(&[Aabb::new(Vec3::ZERO, Vec3::ONE)]);
+    /// let moved = batch.transform(&Mat4::from_translation(Vec3::X));
+    /// assert_eq!(moved.get(0), Some(Aabb::new(Vec3::X, Vec3::new(2.0, 1.0, 1.0))));
+    /// ```
+    pub fn transform(&self, matrix: &Mat4) -> Self {
+        let columns = [matrix.x_axis, matrix.y_axis, matrix.z_axis];
+        let translation = matrix.w_axis;
+        let mut out = *self;
+
+        let mut center = [[0.0; LANES]; 3];
+        let mut extent = [[0.0; LANES]; 3];
+        for lane in 0..LANES {
+            center[0][lane] = (self.min_x[lane] + self.max_x[lane]) * 0.5;
+            center[1][lane] = (self.min_y[lane] + self.max_y[lane]) * 0.5;
+            center[2][lane] = (self.min_z[lane] + self.max_z[lane]) * 0.5;
+            extent[0][lane] = (self.max_x[lane] - self.min_x[lane]) * 0.5;
+            extent[1][lane] = (self.max_y[lane] - self.min_y[lane]) * 0.5;
+            extent[2][lane] = (self.max_z[lane] - self.min_z[lane]) * 0.5;
+        }
+
+        let outputs = [
+            (&mut out.min_x, &mut out.max_x),
+            (&mut out.min_y, &mut out.max_y),
+            (&mut out.min_z, &mut out.max_z),
+        ];
+        for (row, (min, max)) in outputs.into_iter().enumerate() {
+            let m = [columns[0][row], columns[1][row], columns[2][row]];
+            let abs = m.map(f32::abs);
+            for lane in 0..LANES {
+                let c = translation[row]
+                    + m[0] * center[0][lane]
+                    + m[1] * center[1][lane]
+                    + m[2] * center[2][lane];
+                let e =
+                    abs[0] * extent[0][lane] + abs[1] * extent[1][lane] + abs[2] * extent[2][lane];
+                min[lane] = c - e;
+                max[lane] = c + e;
+            }
+        }
+        out
+    }
+
+    /// Test every box against an AABB, returning a mask of the lanes that
+    /// intersect it.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> u8 {
+        let mut mask = 0;
+        for lane in 0..LANES {
+            let hit = self.min_x[lane] <= aabb.max.x
+                && self.max_x[lane] >= aabb.min.x
+                && self.min_y[lane] <= aabb.max.y
+                && self.max_y[lane] >= aabb.min.y
+                && self.min_z[lane] <= aabb.max.z
+                && self.max_z[lane] >= aabb.min.z;
+            mask |= (hit as u8) << lane;
+        }
+        mask & self.lane_mask()
+    }
+
+    /// Test every box against a sphere, returning a mask of the lanes that
+    /// intersect it.
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> u8 {
+        let center = sphere.center;
+        let radius_squared = sphere.radius * sphere.radius;
+        let mut mask = 0;
+        for lane in 0..LANES {
+            let dx = center.x - center.x.clamp(self.min_x[lane], self.max_x[lane]);
+            let dy = center.y - center.y.clamp(self.min_y[lane], self.max_y[lane]);
+            let dz = center.z - center.z.clamp(self.min_z[lane], self.max_z[lane]);
+            let hit = dx * dx + dy * dy + dz * dz <= radius_squared;
+            mask |= (hit as u8) << lane;
+        }
+        mask & self.lane_mask()
+    }
+
+    /// Test every box against a frustum, returning a mask of the lanes that
+    /// are at least partly inside it.
+    ///
+    /// Like [`Frustum::intersects_aabb`], boxes near a frustum corner but
+    /// outside it may be reported as inside.
+    pub fn intersects_frustum(&self, frustum: &Frustum) -> u8 {
+        let mut inside = [true; LANES];
+        for plane in &frustum.planes {
+            let normal = plane.truncate();
+            for (lane, inside) in inside.iter_mut().enumerate() {
+                // The corner furthest along the plane normal
+                let x = if normal.x >= 0.0 {
+                    self.max_x[lane]
+                } else {
+                    self.min_x[lane]
+                };
+                let y = if normal.y >= 0.0 {
+                    self.max_y[lane]
+                } else {
+                    self.min_y[lane]
+                };
+                let z = if normal.z >= 0.0 {
+                    self.max_z[lane]
+                } else {
+                    self.min_z[lane]
+                };
+                *inside &= normal.x * x + normal.y * y + normal.z * z + plane.w >= 0.0;
+            }
+        }
+        let mut mask = 0;
+        for (lane, inside) in inside.into_iter().enumerate() {
+            mask |= (inside as u8) << lane;
+        }
+        mask & self.lane_mask()
+    }
+}
+
+/// View frustum as six inward-facing planes.
+///
+/// Each plane is stored as `(normal, distance)` with points `p` inside when
+/// `normal.dot(p) + distance >= 0`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Frustum {
+    /// Left, right, bottom, top, near, and far planes
+    pub planes: [Vec4; 6],
+}
+
+impl Frustum {
+    /// Extract the frustum from a view-projection matrix with a depth range
+    /// of 0 to 1, as built by glam's `perspective_*` and `orthographic_*`
+    /// functions.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::simd::Frustum;
+    /// use glam::{Mat4, Vec3};
+    ///
+    /// let projection = Mat4::perspective_rh(1.0, 1.0, 0.1, 100.0);
+    /// let frustum = Frustum::from_view_projection(&projection);
+    /// assert!(frustum.contains_point(Vec3::new(0.0, 0.0, -10.0)));
+    /// assert!(!frustum.contains_point(Vec3::new(0.0, 0.0, 10.0)));
+    /// ```
+    pub fn from_view_projection(view_projection: &Mat4) -> Self {
+        let rows = [
+            view_projection.row(0),
+            view_projection.row(1),
+            view_projection.row(2),
+            view_projection.row(3),
+        ];
+        let planes = [
+            rows[3] + rows[0],
+            rows[3] - rows[0],
+            rows[3] + rows[1],
+            rows[3] - rows[1],
+            rows[2],
+            rows[3] - rows[2],
+        ]
+        .map(|plane| {
+            let length = plane.truncate().length();
+            if length > 0.0 {
+                plane / length
+            } else {
+                plane
+            }
+        });
+        Self { planes }
+    }
+
+    /// Check if a point is inside the frustum.
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        let point = Vec3A::from(point);
+        self.planes
+            .iter()
+            .all(|plane| Vec3A::from(plane.truncate()).dot(point) + plane.w >= 0.0)
+    }
+
+    /// Check if an AABB is at least partly inside the frustum.
+    ///
+    /// Conservative: a box outside the frustum but not entirely behind any
+    /// single plane, near a corner, is reported as inside.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        let center = Vec3A::from(aabb.center());
+        let extent = Vec3A::from(aabb.half_extents());
+        self.planes.iter().all(|plane| {
+            let normal = Vec3A::from(plane.truncate());
+            normal.dot(center) + normal.abs().dot(extent) + plane.w >= 0.0
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounds::Obb;
+    use glam::Quat;
+
+    fn boxes() -> Vec<Aabb> {
+        (0..11)
+            .map(|i| {
+                let i = i as f32;
+                Aabb::new(
+                    Vec3::new(i * 2.0 - 10.0, -i, i * 0.5),
+                    Vec3::new(i * 2.0 - 9.0, 1.0 + i * 0.25, i + 2.0),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_chunks_and_lanes() {
+        let boxes = boxes();
+        let batches: Vec<_> = AabbBatch::chunks(&boxes).collect();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].len(), 8);
+        assert_eq!(batches[1].len(), 3);
+        assert_eq!(batches[1].lane_mask(), 0b111);
+        assert_eq!(batches[0].lane_mask(), 0xff);
+        assert_eq!(batches[1].get(2), Some(boxes[10]));
+        assert_eq!(batches[1].get(3), None);
+        assert!(AabbBatch::new(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_transform_matches_scalar() {
+        let matrix = Mat4::from_scale_rotation_translation(
+            Vec3::new(2.0, 0.5, 1.0),
+            Quat::from_euler(glam::EulerRot::YXZ, 0.7, -0.3, 0.2),
+            Vec3::new(5.0, -3.0, 8.0),
+        );
+        let boxes = boxes();
+        for (chunk, batch) in boxes.chunks(LANES).zip(AabbBatch::chunks(&boxes)) {
+            let transformed = batch.transform(&matrix);
+            for (lane, aabb) in chunk.iter().enumerate() {
+                let mut expected = Aabb::empty();
+                for corner in Obb::from_aabb(aabb).corners() {
+                    expected.expand_to_include_point(matrix.transform_point3(corner));
+                }
+                let actual = transformed.get(lane).unwrap();
+                assert!((actual.min - expected.min).abs().max_element() < 1e-4);
+                assert!((actual.max - expected.max).abs().max_element() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn test_intersections_match_scalar() {
+        let boxes = boxes();
+        let query = Aabb::new(Vec3::new(-4.0, -2.0, 0.0), Vec3::new(3.0, 0.5, 3.0));
+        let sphere = Sphere::new(Vec3::new(2.0, 1.0, 4.0), 2.5);
+        for (chunk, batch) in boxes.chunks(LANES).zip(AabbBatch::chunks(&boxes)) {
+            let mut aabb_mask = 0;
+            let mut sphere_mask = 0;
+            for (lane, aabb) in chunk.iter().enumerate() {
+                aabb_mask |= (aabb.intersects_aabb(&query) as u8) << lane;
+                sphere_mask |= (aabb.intersects_sphere(&sphere) as u8) << lane;
+            }
+            assert_eq!(batch.intersects_aabb(&query), aabb_mask);
+            assert_eq!(batch.intersects_sphere(&sphere), sphere_mask);
+        }
+    }
+
+    #[test]
+    fn test_frustum_culling() {
+        let view = Mat4::look_at_rh(Vec3::new(0.0, 2.0, 10.0), Vec3::new(0.0, 2.0, 0.0), Vec3::Y);
+        let projection = Mat4::perspective_rh(1.0, 16.0 / 9.0, 0.1, 50.0);
+        let frustum = Frustum::from_view_projection(&(projection * view));
+
+        let visible = Aabb::from_center_half_extents(Vec3::new(0.0, 2.0, 0.0), Vec3::ONE);
+        let behind = Aabb::from_center_half_extents(Vec3::new(0.0, 2.0, 20.0), Vec3::ONE);
+        let beyond_far = Aabb::from_center_half_extents(Vec3::new(0.0, 2.0, -60.0), Vec3::ONE);
+        let left = Aabb::from_center_half_extents(Vec3::new(-30.0, 2.0, 0.0), Vec3::ONE);
+        let straddling = Aabb::from_center_half_extents(Vec3::new(0.0, 2.0, 10.0), Vec3::ONE);
+        let boxes = [visible, behind, beyond_far, left, straddling];
+
+        let mask = AabbBatch::new(&boxes).intersects_frustum(&frustum);
+        assert_eq!(mask, 0b10001);
+        for (lane, aabb) in boxes.iter().enumerate() {
+            assert_eq!(frustum.intersects_aabb(aabb), mask & (1 << lane) != 0);
+        }
+        assert!(frustum.contains_point(Vec3::new(0.0, 2.0, 0.0)));
+    }
+}
@@ -0,0 +1,719 @@
+//! Cubic splines with arc-length parameterization for roads and paths.
+//!
+//! A [`Spline`] is a chain of cubic Bézier segments. Its curve parameter
+//! doesn't advance at a constant speed along curves, so the spline keeps a
+//! table of arc lengths to place points by distance, which keeps lane
+//! markings and vehicle spacing even. Parallel-transport [`Frame`]s give
+//! an orientation along the curve without the sudden flips of Frenet
+//! frames. Lane positions are lateral offsets from the centerline, laid out
+//! by a [`LaneLayout`], and [`Spline::project`] finds the distance and lateral
+//! offset of any point relative to the spline.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use amp_math::spline::Spline;
+//! use glam::Vec3;
+//!
+//! let road = Spline::catmull_rom(&[
+//!     Vec3::ZERO,
+//!     Vec3::new(50.0, 0.0, 0.0),
+//!     Vec3::new(80.0, 0.0, 40.0),
+//! ])
+//! .unwrap();
+//! let marking = road.point_at_distance(road.length() * 0.5);
+//! ```
+
+use glam::{Quat, Vec3};
+use serde::{Deserialize, Serialize};
+
+/// Arc-length samples taken per segment.
+const SAMPLES_PER_SEGMENT: usize = 32;
+
+/// Cubic Bézier curve segment.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CubicBezier {
+    /// Start point
+    pub p0: Vec3,
+    /// Control point leaving the start
+    pub p1: Vec3,
+    /// Control point arriving at the end
+    pub p2: Vec3,
+    /// End point
+    pub p3: Vec3,
+}
+
+impl CubicBezier {
+    /// Create a segment from its four control points.
+    pub fn new(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3) -> Self {
+        Self { p0, p1, p2, p3 }
+    }
+
+    /// Get the point at parameter `t` in [0, 1].
+    pub fn position(&self, t: f32) -> Vec3 {
+        let u = 1.0 - t;
+        self.p0 * (u * u * u)
+            + self.p1 * (3.0 * u * u * t)
+            + self.p2 * (3.0 * u * t * t)
+            + self.p3 * (t * t * t)
+    }
+
+    /// Get the first derivative at parameter `t`.
+    pub fn derivative(&self, t: f32) -> Vec3 {
+        let u = 1.0 - t;
+        (self.p1 - self.p0) * (3.0 * u * u)
+            + (self.p2 - self.p1) * (6.0 * u * t)
+            + (self.p3 - self.p2) * (3.0 * t * t)
+    }
+
+    /// Get the second derivative at parameter `t`.
+    pub fn second_derivative(&self, t: f32) -> Vec3 {
+        (self.p2 - self.p1 * 2.0 + self.p0) * (6.0 * (1.0 - t))
+            + (self.p3 - self.p2 * 2.0 + self.p1) * (6.0 * t)
+    }
+}
+
+/// Orientation at a point along a spline.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Frame {
+    /// Distance along the spline
+    pub distance: f32,
+    /// Point on the spline
+    pub position: Vec3,
+    /// Unit direction of travel
+    pub tangent: Vec3,
+    /// Unit vector perpendicular to the tangent, starting as close to world
+    /// up as possible, the road surface's up direction
+    pub normal: Vec3,
+    /// Unit vector completing the frame, `tangent × normal`, pointing to
+    /// the right of travel
+    pub binormal: Vec3,
+}
+
+impl Frame {
+    /// Roll the frame about its tangent by `angle` radians.
+    ///
+    /// Positive angles lower the right side, as on a curve turning right.
+    pub fn banked(&self, angle: f32) -> Frame {
+        let (sin, cos) = angle.sin_cos();
+        Frame {
+            normal: self.normal * cos + self.binormal * sin,
+            binormal: self.binormal * cos - self.normal * sin,
+            ..*self
+        }
+    }
+
+    /// Get the point `lateral` to the right of the frame's position, or to
+    /// the left for negative values.
+    pub fn offset(&self, lateral: f32) -> Vec3 {
+        self.position + self.binormal * lateral
+    }
+
+    /// Get the rotation taking -Z to the tangent and +Y to the normal.
+    pub fn rotation(&self) -> Quat {
+        Quat::from_mat3(&glam::Mat3::from_cols(
+            self.binormal,
+            self.normal,
+            -self.tangent,
+        ))
+    }
+}
+
+/// Closest point on a spline to a query point, from [`Spline::project`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SplineProjection {
+    /// Curve parameter of the closest point
+    pub t: f32,
+    /// Distance along the spline of the closest point
+    pub distance: f32,
+    /// Closest point on the spline
+    pub position: Vec3,
+    /// Signed offset of the query point to the right of the spline
+    pub lateral: f32,
+    /// Offset of the query point above the spline
+    pub height: f32,
+}
+
+/// Lanes of equal width laid side by side across a road's centerline.
+///
+/// Lanes are numbered from 0 on the far left, looking along the spline, so
+/// on a two-way road with right-hand traffic the forward lanes have the
+/// higher numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LaneLayout {
+    /// Number of lanes
+    pub lane_count: u32,
+    /// Width of each lane
+    pub lane_width: f32,
+}
+
+impl LaneLayout {
+    /// Create a layout of `lane_count` lanes of `lane_width` each.
+    pub fn new(lane_count: u32, lane_width: f32) -> Self {
+        Self {
+            lane_count,
+            lane_width,
+        }
+    }
+
+    /// Get the total width of the road.
+    pub fn width(&self) -> f32 {
+        self.lane_count as f32 * self.lane_width
+    }
+
+    /// Get the lateral offset of a lane's center from the centerline.
+    pub fn lane_offset(&self, lane: u32) -> f32 {
+        (lane as f32 + 0.5) * self.lane_width - self.width() * 0.5
+    }
+
+    /// Get the lane containing a lateral offset, or the nearest lane if the
+    /// offset is off the road; `None` if there are no lanes.
+    pub fn nearest_lane(&self, lateral: f32) -> Option<u32> {
+        if self.lane_count == 0 {
+            return None;
+        }
+        let lane = ((lateral + self.width() * 0.5) / self.lane_width).floor();
+        Some(lane.clamp(0.0, (self.lane_count - 1) as f32) as u32)
+    }
+}
+
+/// Chain of cubic Bézier segments with an arc-length table.
+///
+/// The curve parameter `t` runs from 0 at the start to the segment count at
+/// the end, with segment `i` covering `i..=i + 1`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spline {
+    segments: Vec<CubicBezier>,
+    /// Distance along the spline at every `1 / SAMPLES_PER_SEGMENT` of `t`
+    lengths: Vec<f32>,
+}
+
+impl Spline {
+    /// Create a spline from segments, or `None` if there are none.
+    pub fn new(segments: Vec<CubicBezier>) -> Option<Self> {
+        if segments.is_empty() {
+            return None;
+        }
+        let mut spline = Self {
+            segments,
+            lengths: Vec::new(),
+        };
+        spline.build_lengths();
+        Some(spline)
+    }
+
+    /// Create a spline passing through every point, with tangents from the
+    /// neighboring points, or `None` with fewer than two points.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::spline::Spline;
+    /// use glam::Vec3;
+    ///
+    /// let spline = Spline::catmull_rom(&[Vec3::ZERO, Vec3::X, Vec3::new(2.0, 0.0, 1.0)]).unwrap();
+    /// assert_eq!(spline.segment_count(), 2);
+    /// assert_eq!(spline.position(1.0), Vec3::X);
+    /// ```
+    pub fn catmull_rom(points: &[Vec3]) -> Option<Self> {
+        if points.len() < 2 {
+            return None;
+        }
+        let last = points.len() - 1;
+        let segments = (0..last)
+            .map(|i| {
+                let before = points[i.saturating_sub(1)];
+                let start = points[i];
+                let end = points[i + 1];
+                let after = points[(i + 2).min(last)];
+                CubicBezier::new(
+                    start,
+                    start + (end - before) / 6.0,
+                    end - (after - start) / 6.0,
+                    end,
+                )
+            })
+            .collect();
+        Self::new(segments)
+    }
+
+    /// Get the segments.
+    pub fn segments(&self) -> &[CubicBezier] {
+        &self.segments
+    }
+
+    /// Get the number of segments, the largest curve parameter.
+    pub fn segment_count(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Get the total length.
+    pub fn length(&self) -> f32 {
+        *self.lengths.last().unwrap_or(&0.0)
+    }
+
+    /// Get the point at curve parameter `t`, clamped to the spline.
+    pub fn position(&self, t: f32) -> Vec3 {
+        let (segment, local) = self.locate(t);
+        segment.position(local)
+    }
+
+    /// Get the unit tangent at curve parameter `t`.
+    pub fn tangent(&self, t: f32) -> Vec3 {
+        let (segment, local) = self.locate(t);
+        let derivative = segment.derivative(local);
+        if derivative.length_squared() > f32::EPSILON {
+            derivative.normalize()
+        } else {
+            // Coincident control points; fall back to the chord
+            (segment.p3 - segment.p0).normalize_or_zero()
+        }
+    }
+
+    /// Get the curvature at curve parameter `t`, the inverse of the turn
+    /// radius.
+    pub fn curvature(&self, t: f32) -> f32 {
+        let (segment, local) = self.locate(t);
+        let first = segment.derivative(local);
+        let speed = first.length();
+        if speed <= f32::EPSILON {
+            return 0.0;
+        }
+        first.cross(segment.second_derivative(local)).length() / (speed * speed * speed)
+    }
+
+    /// Get the curve parameter at a distance along the spline, clamped to
+    /// the spline.
+    pub fn parameter_at_distance(&self, distance: f32) -> f32 {
+        if distance >= self.length() {
+            return self.segments.len() as f32;
+        }
+        let distance = distance.max(0.0);
+        let sample = self
+            .lengths
+            .partition_point(|&length| length <= distance)
+            .clamp(1, self.lengths.len() - 1)
+            - 1;
+        let (before, after) = (self.lengths[sample], self.lengths[sample + 1]);
+        let fraction = if after > before {
+            (distance - before) / (after - before)
+        } else {
+            0.0
+        };
+
+        // One Newton step corrects the linear interpolation between samples
+        let segment = &self.segments[sample / SAMPLES_PER_SEGMENT];
+        let step = 1.0 / SAMPLES_PER_SEGMENT as f32;
+        let start = (sample % SAMPLES_PER_SEGMENT) as f32 * step;
+        let mut local = start + fraction * step;
+        let speed = segment.derivative(local).length();
+        if speed > f32::EPSILON {
+            let error = before + arc_length(segment, start, local) - distance;
+            local = (local - error / speed).clamp(start, start + step);
+        }
+        (sample / SAMPLES_PER_SEGMENT) as f32 + local
+    }
+
+    /// Get the point at a distance along the spline.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::spline::Spline;
+    /// use glam::Vec3;
+    ///
+    /// let spline = Spline::catmull_rom(&[Vec3::ZERO, Vec3::new(10.0, 0.0, 0.0)]).unwrap();
+    /// assert!(spline.point_at_distance(2.5).distance(Vec3::new(2.5, 0.0, 0.0)) < 1e-3);
+    /// ```
+    pub fn point_at_distance(&self, distance: f32) -> Vec3 {
+        self.position(self.parameter_at_distance(distance))
+    }
+
+    /// Get the unit tangent at a distance along the spline.
+    pub fn tangent_at_distance(&self, distance: f32) -> Vec3 {
+        self.tangent(self.parameter_at_distance(distance))
+    }
+
+    /// Get the curvature at a distance along the spline.
+    pub fn curvature_at_distance(&self, distance: f32) -> f32 {
+        self.curvature(self.parameter_at_distance(distance))
+    }
+
+    /// Get the frame at a distance along the spline.
+    ///
+    /// The normal is world up made perpendicular to the tangent, as for the
+    /// first of [`Spline::frames`], so it matches the transported frames on
+    /// roads that never turn vertical.
+    pub fn frame_at_distance(&self, distance: f32) -> Frame {
+        let distance = distance.clamp(0.0, self.length());
+        let t = self.parameter_at_distance(distance);
+        let tangent = self.tangent(t);
+        let normal = initial_normal(tangent);
+        Frame {
+            distance,
+            position: self.position(t),
+            tangent,
+            normal,
+            binormal: tangent.cross(normal),
+        }
+    }
+
+    /// Get the point `lateral` to the right of the spline at a distance
+    /// along it, on a surface banked by `bank` radians.
+    ///
+    /// Negative offsets are to the left. See [`Frame::banked`] for the sign
+    /// of the bank angle.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::spline::Spline;
+    /// use glam::Vec3;
+    ///
+    /// // Heading along +X, right is +Z
+    /// let road = Spline::catmull_rom(&[Vec3::ZERO, Vec3::new(100.0, 0.0, 0.0)]).unwrap();
+    /// let lane = road.offset_at_distance(40.0, 3.5, 0.0);
+    /// assert!(lane.distance(Vec3::new(40.0, 0.0, 3.5)) < 1e-3);
+    /// ```
+    pub fn offset_at_distance(&self, distance: f32, lateral: f32, bank: f32) -> Vec3 {
+        self.frame_at_distance(distance)
+            .banked(bank)
+            .offset(lateral)
+    }
+
+    /// Get the distance along the spline at curve parameter `t`.
+    pub fn distance_at_parameter(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, self.segments.len() as f32);
+        let step = 1.0 / SAMPLES_PER_SEGMENT as f32;
+        let sample = ((t * SAMPLES_PER_SEGMENT as f32) as usize).min(self.lengths.len() - 2);
+        let segment = &self.segments[sample / SAMPLES_PER_SEGMENT];
+        let start = (sample % SAMPLES_PER_SEGMENT) as f32 * step;
+        let local = t - (sample / SAMPLES_PER_SEGMENT) as f32;
+        self.lengths[sample] + arc_length(segment, start, local)
+    }
+
+    /// Find the point on the spline closest to `point`.
+    ///
+    /// Searches the arc-length samples for the nearest one, then refines it
+    /// with Newton steps on the squared distance.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use amp_math::spline::Spline;
+    /// use glam::Vec3;
+    ///
+    /// let road = Spline::catmull_rom(&[Vec3::ZERO, Vec3::new(100.0, 0.0, 0.0)]).unwrap();
+    /// let hit = road.project(Vec3::new(25.0, 1.0, -2.0));
+    /// assert!((hit.distance - 25.0).abs() < 1e-2);
+    /// assert!((hit.lateral + 2.0).abs() < 1e-3);
+    /// assert!((hit.height - 1.0).abs() < 1e-3);
+    /// ```
+    pub fn project(&self, point: Vec3) -> SplineProjection {
+        let step = 1.0 / SAMPLES_PER_SEGMENT as f32;
+        let nearest_sample = (0..self.lengths.len())
+            .map(|sample| {
+                let t = sample as f32 * step;
+                (t, self.position(t).distance_squared(point))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or(0.0, |(t, _)| t);
+
+        // The closest point lies within a sample of the nearest sample
+        let low = (nearest_sample - step).max(0.0);
+        let high = (nearest_sample + step).min(self.segments.len() as f32);
+        let mut t = nearest_sample;
+        for _ in 0..8 {
+            let (segment, local) = self.locate(t);
+            let offset = segment.position(local) - point;
+            let first = segment.derivative(local);
+            let slope = offset.dot(first);
+            let bend = first.length_squared() + offset.dot(segment.second_derivative(local));
+            if bend <= f32::EPSILON {
+                break;
+            }
+            let next = (t - slope / bend).clamp(low, high);
+            if (next - t).abs() < 1e-6 {
+                t = next;
+                break;
+            }
+            t = next;
+        }
+
+        let distance = self.distance_at_parameter(t);
+        let position = self.position(t);
+        let tangent = self.tangent(t);
+        let normal = initial_normal(tangent);
+        let offset = point - position;
+        SplineProjection {
+            t,
+            distance,
+            position,
+            lateral: offset.dot(tangent.cross(normal)),
+            height: offset.dot(normal),
+        }
+    }
+
+    /// Get frames every `spacing` along the spline, plus one at the end.
+    ///
+    /// Each frame's normal is the previous one rotated by the change in
+    /// tangent, so the frames twist as little as possible. The first normal
+    /// is world up made perpendicular to the tangent, or +X if the spline
+    /// starts vertically.
+    pub fn frames(&self, spacing: f32) -> Vec<Frame> {
+        let length = self.length();
+        let spacing = spacing.max(length / 65_536.0).max(f32::EPSILON);
+        let count = (length / spacing).ceil() as usize;
+
+        let mut frames: Vec<Frame> = Vec::with_capacity(count + 1);
+        for i in 0..=count {
+            let distance = (i as f32 * spacing).min(length);
+            let t = self.parameter_at_distance(distance);
+            let tangent = self.tangent(t);
+            let normal = match frames.last() {
+                Some(previous) => {
+                    let turn = Quat::from_rotation_arc(previous.tangent, tangent);
+                    let normal = turn * previous.normal;
+                    // Remove drift so the frame stays orthonormal
+                    (normal - tangent * normal.dot(tangent)).normalize_or_zero()
+                }
+                None => initial_normal(tangent),
+            };
+            frames.push(Frame {
+                distance,
+                position: self.position(t),
+                tangent,
+                normal,
+                binormal: tangent.cross(normal),
+            });
+        }
+        frames
+    }
+
+    /// Split a curve parameter into a segment and its local parameter.
+    fn locate(&self, t: f32) -> (&CubicBezier, f32) {
+        let t = t.clamp(0.0, self.segments.len() as f32);
+        let index = (t.floor() as usize).min(self.segments.len() - 1);
+        (&self.segments[index], t - index as f32)
+    }
+
+    fn build_lengths(&mut self) {
+        let step = 1.0 / SAMPLES_PER_SEGMENT as f32;
+        let mut lengths = Vec::with_capacity(self.segments.len() * SAMPLES_PER_SEGMENT + 1);
+        let mut total = 0.0;
+        lengths.push(total);
+        for segment in &self.segments {
+            for i in 0..SAMPLES_PER_SEGMENT {
+                let from = i as f32 * step;
+                total += arc_length(segment, from, from + step);
+                lengths.push(total);
+            }
+        }
+        self.lengths = lengths;
+    }
+}
+
+/// Get the length of a segment between two nearby local parameters, by
+/// Simpson's rule.
+fn arc_length(segment: &CubicBezier, from: f32, to: f32) -> f32 {
+    let speed = |t: f32| segment.derivative(t).length();
+    (to - from) / 6.0 * (speed(from) + 4.0 * speed((from + to) * 0.5) + speed(to))
+}
+
+fn initial_normal(tangent: Vec3) -> Vec3 {
+    let up = Vec3::Y - tangent * Vec3::Y.dot(tangent);
+    if up.length_squared() > 1e-6 {
+        up.normalize()
+    } else {
+        (Vec3::X - tangent * Vec3::X.dot(tangent)).normalize_or_zero()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    /// Bézier approximation of a quarter circle in the XZ plane
+    fn quarter_circle(radius: f32) -> CubicBezier {
+        let k = 0.552_284_8 * radius;
+        CubicBezier::new(
+            Vec3::new(radius, 0.0, 0.0),
+            Vec3::new(radius, 0.0, k),
+            Vec3::new(k, 0.0, radius),
+            Vec3::new(0.0, 0.0, radius),
+        )
+    }
+
+    #[test]
+    fn test_straight_line_length_and_distance() {
+        let spline = Spline::catmull_rom(&[
+            Vec3::ZERO,
+            Vec3::new(10.0, 0.0, 0.0),
+            Vec3::new(30.0, 0.0, 0.0),
+        ])
+        .unwrap();
+        assert!((spline.length() - 30.0).abs() < 1e-3);
+        for distance in [0.0, 5.0, 10.0, 17.5, 30.0] {
+            let point = spline.point_at_distance(distance);
+            assert!((point.x - distance).abs() < 1e-2, "{distance}: {point}");
+        }
+        assert_eq!(spline.point_at_distance(100.0), Vec3::new(30.0, 0.0, 0.0));
+        assert_eq!(spline.curvature_at_distance(12.0), 0.0);
+    }
+
+    #[test]
+    fn test_curve_spacing_is_even() {
+        let radius = 50.0;
+        let spline = Spline::new(vec![quarter_circle(radius)]).unwrap();
+        assert!((spline.length() - FRAC_PI_2 * radius).abs() < 0.05);
+
+        let points: Vec<Vec3> = (0..=70)
+            .map(|i| spline.point_at_distance(i as f32))
+            .collect();
+        for pair in points.windows(2) {
+            // Chord of a 1 m arc on a 50 m radius
+            assert!((pair[0].distance(pair[1]) - 1.0).abs() < 2e-3);
+        }
+        // The raw parameter isn't evenly spaced
+        let raw = spline.position(0.1).distance(spline.position(0.0));
+        let middle = spline.position(0.55).distance(spline.position(0.45));
+        assert!((raw - middle).abs() > 0.05);
+    }
+
+    #[test]
+    fn test_curvature_of_circle() {
+        let spline = Spline::new(vec![quarter_circle(20.0)]).unwrap();
+        for t in [0.0, 0.25, 0.5, 1.0] {
+            // Within the Bézier approximation's error
+            assert!((spline.curvature(t) * 20.0 - 1.0).abs() < 0.03);
+        }
+        let tangent = spline.tangent_at_distance(spline.length() * 0.5);
+        assert!(tangent.dot(Vec3::new(-1.0, 0.0, 1.0).normalize()) > 0.9999);
+    }
+
+    #[test]
+    fn test_flat_road_frames_stay_up() {
+        let spline = Spline::catmull_rom(&[
+            Vec3::ZERO,
+            Vec3::new(20.0, 0.0, 0.0),
+            Vec3::new(40.0, 0.0, 20.0),
+            Vec3::new(40.0, 0.0, 60.0),
+        ])
+        .unwrap();
+        let frames = spline.frames(2.0);
+        assert_eq!(frames.last().unwrap().distance, spline.length());
+        for frame in &frames {
+            assert!((frame.normal - Vec3::Y).length() < 1e-3);
+            assert!(frame.tangent.dot(frame.normal).abs() < 1e-4);
+            assert!((frame.binormal.length() - 1.0).abs() < 1e-4);
+            let rotation = frame.rotation();
+            assert!((rotation * Vec3::NEG_Z - frame.tangent).length() < 1e-3);
+        }
+        // Right of travel along +X is +Z
+        assert!((frames[0].binormal - Vec3::Z).length() < 1e-3);
+    }
+
+    #[test]
+    fn test_helix_frames_dont_flip() {
+        let points: Vec<Vec3> = (0..=16)
+            .map(|i| {
+                let angle = i as f32 * PI / 4.0;
+                Vec3::new(angle.cos() * 10.0, i as f32 * 2.0, angle.sin() * 10.0)
+            })
+            .collect();
+        let spline = Spline::catmull_rom(&points).unwrap();
+        let frames = spline.frames(0.5);
+        for pair in frames.windows(2) {
+            assert!(pair[0].normal.dot(pair[1].normal) > 0.99);
+            assert!(pair[1].normal.dot(pair[1].tangent).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_degenerate_input() {
+        assert!(Spline::catmull_rom(&[Vec3::ONE]).is_none());
+        assert!(Spline::new(Vec::new()).is_none());
+        let point = Spline::catmull_rom(&[Vec3::ONE, Vec3::ONE]).unwrap();
+        assert_eq!(point.length(), 0.0);
+        assert_eq!(point.point_at_distance(3.0), Vec3::ONE);
+        assert_eq!(point.frames(1.0).len(), 1);
+    }
+
+    #[test]
+    fn test_lane_offsets_on_curve() {
+        let radius = 50.0;
+        let spline = Spline::new(vec![quarter_circle(radius)]).unwrap();
+        let lanes = LaneLayout::new(4, 3.5);
+        assert_eq!(lanes.lane_offset(0), -5.25);
+        assert_eq!(lanes.lane_offset(2), 1.75);
+
+        // The curve turns right, so lanes to the right are nearer its center
+        for distance in [0.0, 20.0, 40.0, spline.length()] {
+            let point = spline.offset_at_distance(distance, lanes.lane_offset(2), 0.0);
+            let lane_radius = Vec3::new(point.x, 0.0, point.z).length();
+            assert!((lane_radius - (radius - 1.75)).abs() < 0.1, "{lane_radius}");
+        }
+    }
+
+    #[test]
+    fn test_banking_tilts_lateral_offset() {
+        let spline = Spline::catmull_rom(&[Vec3::ZERO, Vec3::new(0.0, 0.0, -100.0)]).unwrap();
+        let frame = spline.frame_at_distance(10.0);
+        assert!(frame.binormal.abs_diff_eq(Vec3::X, 1e-5));
+
+        let bank = 0.1;
+        let point = spline.offset_at_distance(10.0, 4.0, bank);
+        assert!((point.y + 4.0 * bank.sin()).abs() < 1e-4, "{point}");
+        assert!((point.x - 4.0 * bank.cos()).abs() < 1e-4);
+
+        let banked = frame.banked(bank);
+        assert!((banked.normal.dot(banked.binormal)).abs() < 1e-6);
+        assert!(banked.normal.x > 0.0);
+    }
+
+    #[test]
+    fn test_project_round_trips_offsets() {
+        let spline = Spline::catmull_rom(&[
+            Vec3::ZERO,
+            Vec3::new(40.0, 0.0, 0.0),
+            Vec3::new(70.0, 2.0, -30.0),
+            Vec3::new(70.0, 4.0, -80.0),
+        ])
+        .unwrap();
+        let lanes = LaneLayout::new(3, 3.0);
+
+        for distance in [5.0, 37.0, 61.5, 90.0, 110.0] {
+            for lane in 0..3 {
+                let lateral = lanes.lane_offset(lane);
+                let point = spline.offset_at_distance(distance, lateral, 0.0) + Vec3::Y * 0.5;
+                let hit = spline.project(point);
+                assert!(
+                    (hit.distance - distance).abs() < 0.05,
+                    "{distance}: {hit:?}"
+                );
+                assert!((hit.lateral - lateral).abs() < 0.02, "{lateral}: {hit:?}");
+                assert!((hit.height - 0.5).abs() < 0.02);
+                assert_eq!(lanes.nearest_lane(hit.lateral), Some(lane));
+            }
+        }
+
+        let before_start = spline.project(Vec3::new(-10.0, 0.0, 1.0));
+        assert_eq!(before_start.t, 0.0);
+        assert_eq!(before_start.distance, 0.0);
+        assert!(
+            (spline.distance_at_parameter(spline.segment_count() as f32) - spline.length()).abs()
+                < 1e-3
+        );
+    }
+
+    #[test]
+    fn test_nearest_lane_clamps_to_road() {
+        let lanes = LaneLayout::new(2, 3.5);
+        assert_eq!(lanes.nearest_lane(-20.0), Some(0));
+        assert_eq!(lanes.nearest_lane(-0.1), Some(0));
+        assert_eq!(lanes.nearest_lane(0.1), Some(1));
+        assert_eq!(lanes.nearest_lane(20.0), Some(1));
+        assert_eq!(LaneLayout::new(0, 3.5).nearest_lane(0.0), None);
+    }
+}
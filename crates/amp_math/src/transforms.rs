@@ -1,7 +1,9 @@
 //! Transform utilities wrapping glam for common 3D operations.
 //!
 //! Provides high-level wrappers around glam's Mat4 for common transform operations
-//! with convenient builder patterns and game-specific functionality.
+//! with convenient builder patterns and game-specific functionality, plus
+//! frame-rate independent smoothing: exponential smoothing by half-life,
+//! smooth damping, and critically damped springs.
 //!
 //! # Examples
 //!
@@ -16,6 +18,7 @@
 
 use glam::{Mat4, Quat, Vec3};
 use serde::{Deserialize, Serialize};
+use std::ops::{Add, Mul, Sub};
 
 /// A transform in 3D space with translation, rotation, and scale.
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -492,9 +495,145 @@ impl CameraTransform {
     }
 }
 
+/// Values that can be smoothed: anything that can be added, subtracted,
+/// and scaled, such as `f32` and the glam vectors.
+pub trait Smoothable:
+    Copy + Add<Output = Self> + Sub<Output = Self> + Mul<f32, Output = Self>
+{
+}
+
+impl<T> Smoothable for T where T: Copy + Add<Output = T> + Sub<Output = T> + Mul<f32, Output = T> {}
+
+/// Move `current` towards `target` so half the remaining distance is
+/// covered every `half_life` seconds.
+///
+/// Unlike `lerp(current, target, factor)` called every frame, the result
+/// doesn't depend on the frame rate. A non-positive half-life snaps to the
+/// target.
+///
+/// # Examples
+///
+/// ```rust
+/// use amp_math::transforms::smooth_towards;
+///
+/// assert_eq!(smooth_towards(0.0_f32, 10.0, 0.5, 0.5), 5.0);
+/// ```
+pub fn smooth_towards<T: Smoothable>(current: T, target: T, half_life: f32, dt: f32) -> T {
+    if half_life <= 0.0 {
+        return target;
+    }
+    let remaining = (-dt / half_life).exp2();
+    target + (current - target) * remaining
+}
+
+/// Move `current` towards `target` like a critically damped spring that
+/// settles in about `smooth_time` seconds, updating `velocity`.
+///
+/// Starts and stops smoothly and never overshoots a still target. A
+/// non-positive smooth time snaps to the target.
+///
+/// # Examples
+///
+/// ```rust
+/// use amp_math::transforms::smooth_damp;
+/// use glam::Vec3;
+///
+/// let mut position = Vec3::ZERO;
+/// let mut velocity = Vec3::ZERO;
+/// for _ in 0..120 {
+///     position = smooth_damp(position, Vec3::X, &mut velocity, 0.3, 1.0 / 60.0);
+/// }
+/// assert!(position.distance(Vec3::X) < 1e-3);
+/// ```
+pub fn smooth_damp<T: Smoothable>(
+    current: T,
+    target: T,
+    velocity: &mut T,
+    smooth_time: f32,
+    dt: f32,
+) -> T {
+    if smooth_time <= 0.0 {
+        *velocity = *velocity * 0.0;
+        return target;
+    }
+    critically_damped(current, target, velocity, 2.0 / smooth_time, dt)
+}
+
+/// Critically damped spring following a target.
+///
+/// Tuned by half-life: the distance to the target decays by half every
+/// half-life, slowed a little by the spring's momentum early on. Exact for
+/// any time step, so it behaves the same at every frame rate.
+///
+/// # Examples
+///
+/// ```rust
+/// use amp_math::transforms::Spring;
+///
+/// let mut fov = Spring::new(60.0_f32, 0.1);
+/// let value = fov.update(75.0, 1.0 / 60.0);
+/// assert!(value > 60.0 && value < 75.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Spring<T> {
+    /// Current value
+    pub value: T,
+    /// Current rate of change per second
+    pub velocity: T,
+    /// Seconds for the spring's decay to halve the distance to the target
+    pub half_life: f32,
+}
+
+impl<T: Smoothable> Spring<T> {
+    /// Create a spring at rest at `value`.
+    pub fn new(value: T, half_life: f32) -> Self {
+        Self {
+            value,
+            velocity: value * 0.0,
+            half_life,
+        }
+    }
+
+    /// Advance the spring towards `target` by `dt` seconds and return the
+    /// new value.
+    pub fn update(&mut self, target: T, dt: f32) -> T {
+        if self.half_life <= 0.0 {
+            self.value = target;
+            self.velocity = self.velocity * 0.0;
+        } else {
+            // The distance shrinks by e^(-rate * t) times a linear term
+            let rate = std::f32::consts::LN_2 / self.half_life;
+            self.value = critically_damped(self.value, target, &mut self.velocity, rate, dt);
+        }
+        self.value
+    }
+
+    /// Jump to `value` and stop.
+    pub fn reset(&mut self, value: T) {
+        self.value = value;
+        self.velocity = value * 0.0;
+    }
+}
+
+/// Exact step of a critically damped spring with decay `rate`.
+fn critically_damped<T: Smoothable>(
+    current: T,
+    target: T,
+    velocity: &mut T,
+    rate: f32,
+    dt: f32,
+) -> T {
+    let offset = current - target;
+    let j1 = *velocity + offset * rate;
+    let decay = (-rate * dt).exp();
+    *velocity = (*velocity - j1 * (rate * dt)) * decay;
+    target + (offset + j1 * dt) * decay
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use glam::Vec2;
     use std::f32::consts::PI;
 
     #[test]
@@ -632,4 +771,65 @@ mod tests {
         assert!((transform.rotation.dot(Quat::from_rotation_y(PI / 2.0))).abs() > 0.999);
         assert_eq!(transform.scale, Vec3::splat(2.0));
     }
+
+    #[test]
+    fn test_smooth_towards_is_frame_rate_independent() {
+        let mut fast = 0.0_f32;
+        for _ in 0..60 {
+            fast = smooth_towards(fast, 10.0, 0.25, 1.0 / 60.0);
+        }
+        let mut slow = 0.0_f32;
+        for _ in 0..10 {
+            slow = smooth_towards(slow, 10.0, 0.25, 0.1);
+        }
+        // Four half-lives leave a sixteenth of the distance
+        assert!((fast - 9.375).abs() < 1e-3);
+        assert!((slow - fast).abs() < 1e-3);
+        assert_eq!(smooth_towards(3.0_f32, 10.0, 0.0, 0.1), 10.0);
+    }
+
+    #[test]
+    fn test_smooth_damp_settles_without_overshoot() {
+        let target = Vec3::new(5.0, 0.0, -5.0);
+        let mut position = Vec3::ZERO;
+        let mut velocity = Vec3::ZERO;
+        let mut previous = position.distance(target);
+        for _ in 0..240 {
+            position = smooth_damp(position, target, &mut velocity, 0.5, 1.0 / 120.0);
+            let distance = position.distance(target);
+            assert!(distance <= previous + 1e-6);
+            assert!(position.x <= target.x + 1e-5);
+            previous = distance;
+        }
+        assert!(previous < 0.05);
+    }
+
+    #[test]
+    fn test_spring_matches_across_frame_rates() {
+        let mut fast = Spring::new(0.0_f32, 0.2);
+        for _ in 0..120 {
+            fast.update(1.0, 1.0 / 120.0);
+        }
+        let mut slow = Spring::new(0.0_f32, 0.2);
+        for _ in 0..20 {
+            slow.update(1.0, 1.0 / 20.0);
+        }
+        assert!((fast.value - slow.value).abs() < 1e-4);
+        assert!((fast.velocity - slow.velocity).abs() < 1e-3);
+        assert!(fast.value > 0.8 && fast.value <= 1.0);
+
+        fast.reset(3.0);
+        assert_eq!((fast.value, fast.velocity), (3.0, 0.0));
+    }
+
+    #[test]
+    fn test_spring_half_life() {
+        // Once moving, the remaining distance halves each half-life
+        let mut spring = Spring::new(Vec2::ZERO, 0.1);
+        spring.update(Vec2::ONE, 2.0);
+        let before = (Vec2::ONE - spring.value).length();
+        spring.update(Vec2::ONE, 0.1);
+        let after = (Vec2::ONE - spring.value).length();
+        assert!((after / before - 0.5).abs() < 0.03, "{}", after / before);
+    }
 }
@@ -0,0 +1,193 @@
+//! Double-precision absolute world positions.
+//!
+//! An `f32` keeps millimeter precision only within about 8 km of the
+//! origin, so rendering and physics work in local coordinates around a
+//! movable origin. Systems that need absolute coordinates, like GPS,
+//! persistence, and map import, store a [`WorldPos`] instead and convert it
+//! to local space relative to the current [`FloatingOrigin`].
+//!
+//! # Examples
+//!
+//! ```rust
+//! use amp_math::world_pos::WorldPos;
+//! use glam::Vec3;
+//!
+//! let origin = WorldPos::new(120_000.0, 0.0, -480_000.0);
+//! let shop = WorldPos::new(120_010.25, 3.5, -480_020.5);
+//! let local = shop.to_local(origin);
+//! assert_eq!(local, Vec3::new(10.25, 3.5, -20.5));
+//! assert_eq!(WorldPos::from_local(local, origin), shop);
+//! ```
+
+use crate::chunk_key::ChunkKey;
+use glam::{DVec3, Vec3};
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, Sub};
+
+/// Absolute position in the world with `f64` precision.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct WorldPos(pub DVec3);
+
+impl WorldPos {
+    /// The world's origin.
+    pub const ZERO: Self = Self(DVec3::ZERO);
+
+    /// Create a position from coordinates.
+    pub const fn new(x: f64, y: f64, z: f64) -> Self {
+        Self(DVec3::new(x, y, z))
+    }
+
+    /// Get the position relative to `origin` in local `f32` space.
+    pub fn to_local(self, origin: WorldPos) -> Vec3 {
+        (self.0 - origin.0).as_vec3()
+    }
+
+    /// Get the absolute position of a local position relative to `origin`.
+    pub fn from_local(local: Vec3, origin: WorldPos) -> Self {
+        Self(origin.0 + local.as_dvec3())
+    }
+
+    /// Get the distance to another position.
+    pub fn distance(self, other: WorldPos) -> f64 {
+        self.0.distance(other.0)
+    }
+
+    /// Get the key of the chunk containing the position.
+    ///
+    /// Unlike [`ChunkKey::from_world`] this stays exact far from the origin.
+    pub fn chunk_key(self, chunk_size: f64) -> ChunkKey {
+        ChunkKey::new(
+            (self.0.x / chunk_size).floor() as i32,
+            (self.0.z / chunk_size).floor() as i32,
+        )
+    }
+}
+
+impl From<DVec3> for WorldPos {
+    fn from(value: DVec3) -> Self {
+        Self(value)
+    }
+}
+
+impl Add<DVec3> for WorldPos {
+    type Output = WorldPos;
+
+    fn add(self, offset: DVec3) -> WorldPos {
+        WorldPos(self.0 + offset)
+    }
+}
+
+impl Sub for WorldPos {
+    type Output = DVec3;
+
+    fn sub(self, other: WorldPos) -> DVec3 {
+        self.0 - other.0
+    }
+}
+
+/// Origin of local render and physics space, moved in whole grid steps
+/// when the focus strays too far from it.
+///
+/// # Examples
+///
+/// ```rust
+/// use amp_math::world_pos::{FloatingOrigin, WorldPos};
+/// use glam::Vec3;
+///
+/// let mut origin = FloatingOrigin::new(WorldPos::ZERO, 1024.0, 2048.0);
+/// assert_eq!(origin.recenter(Vec3::new(100.0, 0.0, 0.0)), None);
+///
+/// // Subtract the shift from every local position
+/// let shift = origin.recenter(Vec3::new(2500.0, 10.0, -300.0)).unwrap();
+/// assert_eq!(shift, Vec3::new(2048.0, 0.0, 0.0));
+/// assert_eq!(origin.origin(), WorldPos::new(2048.0, 0.0, 0.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FloatingOrigin {
+    origin: WorldPos,
+    grid: f64,
+    threshold: f32,
+}
+
+impl FloatingOrigin {
+    /// Create an origin at `origin`, snapping moves to multiples of `grid`
+    /// and moving once the focus is more than `threshold` from it.
+    pub fn new(origin: WorldPos, grid: f64, threshold: f32) -> Self {
+        Self {
+            origin,
+            grid: grid.max(f64::EPSILON),
+            threshold: threshold.max(0.0),
+        }
+    }
+
+    /// Get the current origin.
+    pub fn origin(&self) -> WorldPos {
+        self.origin
+    }
+
+    /// Convert an absolute position to local space.
+    pub fn to_local(&self, position: WorldPos) -> Vec3 {
+        position.to_local(self.origin)
+    }
+
+    /// Convert a local position to an absolute position.
+    pub fn to_world(&self, local: Vec3) -> WorldPos {
+        WorldPos::from_local(local, self.origin)
+    }
+
+    /// Move the origin under the focus if the focus, in local space, is
+    /// beyond the threshold on the horizontal plane.
+    ///
+    /// Returns the local offset every local position has to move back by,
+    /// or `None` if the origin stayed. Height is never shifted.
+    pub fn recenter(&mut self, focus: Vec3) -> Option<Vec3> {
+        if focus.x.abs() <= self.threshold && focus.z.abs() <= self.threshold {
+            return None;
+        }
+        let snap = |value: f32| (f64::from(value) / self.grid).round() * self.grid;
+        let shift = DVec3::new(snap(focus.x), 0.0, snap(focus.z));
+        if shift == DVec3::ZERO {
+            return None;
+        }
+        self.origin = self.origin + shift;
+        Some(shift.as_vec3())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_precision_far_from_origin() {
+        // 40,000 km out, beyond any f32 world coordinate
+        let origin = WorldPos::new(4.0e7, 0.0, -4.0e7);
+        let a = WorldPos::new(4.0e7 + 0.001, 1.0, -4.0e7 - 0.002);
+        let local = a.to_local(origin);
+        assert!((local - Vec3::new(0.001, 1.0, -0.002)).abs().max_element() < 1e-6);
+        assert!((WorldPos::from_local(local, origin).distance(a)) < 1e-6);
+        assert!(((a - origin).x - 0.001).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_chunk_key_far_from_origin() {
+        let position = WorldPos::new(1.0e9 + 10.0, 0.0, -(1.0e9 + 10.0));
+        let key = position.chunk_key(64.0);
+        assert_eq!(key, ChunkKey::new(15_625_000, -15_625_001));
+    }
+
+    #[test]
+    fn test_recenter_keeps_world_positions() {
+        let mut origin = FloatingOrigin::new(WorldPos::new(1.0e6, 0.0, 1.0e6), 512.0, 1000.0);
+        let car = origin.to_world(Vec3::new(1800.0, 5.0, -1200.0));
+
+        let shift = origin.recenter(Vec3::new(1800.0, 5.0, -1200.0)).unwrap();
+        assert_eq!(shift, Vec3::new(2048.0, 0.0, -1024.0));
+        let local = Vec3::new(1800.0, 5.0, -1200.0) - shift;
+        assert_eq!(origin.to_world(local), car);
+        assert_eq!(origin.to_local(car), local);
+
+        assert_eq!(origin.recenter(local), None);
+    }
+}
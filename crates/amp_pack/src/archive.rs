@@ -0,0 +1,513 @@
+//! Pack file format
+//!
+//! A pack is a single file holding many assets:
+//!
+//! ```text
+//! header   magic "AMPPACK\0", version: u32, entry count: u32, index offset: u64
+//! data     entry contents, back to back
+//! index    per entry: path length: u16, path (UTF-8, `/`-separated),
+//!          offset: u64, stored size: u64, size: u64, compression: u8
+//! ```
+//!
+//! All integers are little-endian. Entries are stored uncompressed or, with
+//! the `zstd` feature, zstd-compressed when that makes them smaller.
+
+use amp_core::{Error, Result};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+const MAGIC: &[u8; 8] = b"AMPPACK\0";
+const VERSION: u32 = 1;
+const HEADER_LEN: u64 = 24;
+
+/// Largest decompressed entry size accepted from an index
+pub const MAX_ENTRY_SIZE: u64 = 1 << 30;
+
+/// Longest entry path in bytes, bounded by the index's `u16` path length
+pub const MAX_PATH_LEN: usize = u16::MAX as usize;
+
+/// How an entry's bytes are stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// Stored as is
+    None,
+    /// Compressed with zstd
+    Zstd,
+}
+
+impl Compression {
+    fn to_byte(self) -> u8 {
+        match self {
+            Compression::None => 0,
+            Compression::Zstd => 1,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Result<Self> {
+        match byte {
+            0 => Ok(Compression::None),
+            1 => Ok(Compression::Zstd),
+            _ => Err(Error::validation(format!(
+                "Unknown pack compression {byte}"
+            ))),
+        }
+    }
+}
+
+/// Location of an entry in a pack
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackEntry {
+    /// Offset of the stored bytes from the start of the pack
+    pub offset: u64,
+    /// Number of stored bytes
+    pub stored_size: u64,
+    /// Number of bytes after decompression
+    pub size: u64,
+    /// How the bytes are stored
+    pub compression: Compression,
+}
+
+impl PackEntry {
+    /// Check that the entry lies within the data section, which ends at
+    /// `data_end`, and that its sizes are plausible
+    fn validate(&self, data_end: u64) -> std::result::Result<(), String> {
+        let end = self
+            .offset
+            .checked_add(self.stored_size)
+            .filter(|&end| self.offset >= HEADER_LEN && end <= data_end);
+        if end.is_none() {
+            return Err(format!(
+                "at offset {} with {} stored bytes is outside the data section",
+                self.offset, self.stored_size
+            ));
+        }
+        if self.size > MAX_ENTRY_SIZE {
+            return Err(format!(
+                "size {} exceeds the {MAX_ENTRY_SIZE} byte limit",
+                self.size
+            ));
+        }
+        if self.compression == Compression::None && self.size != self.stored_size {
+            return Err(format!(
+                "is uncompressed but has size {} and {} stored bytes",
+                self.size, self.stored_size
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Builds a pack file
+#[derive(Debug, Default)]
+pub struct PackWriter {
+    entries: BTreeMap<String, (Vec<u8>, Compression, u64)>,
+    #[cfg(feature = "zstd")]
+    compress: bool,
+}
+
+impl PackWriter {
+    /// Create a writer storing entries uncompressed
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compress entries with zstd when that makes them smaller
+    #[cfg(feature = "zstd")]
+    pub fn with_compression(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+
+    /// Add an entry, replacing any entry with the same path
+    ///
+    /// Fails if the path is longer than [`MAX_PATH_LEN`] bytes.
+    pub fn add(&mut self, path: &str, bytes: Vec<u8>) -> Result<()> {
+        let path = normalize_path(path)?;
+        if path.len() > MAX_PATH_LEN {
+            return Err(Error::validation(format!(
+                "Pack entry path is {} bytes, longer than {MAX_PATH_LEN}",
+                path.len()
+            )));
+        }
+        let size = bytes.len() as u64;
+        let (stored, compression) = self.encode(bytes)?;
+        self.entries.insert(path, (stored, compression, size));
+        Ok(())
+    }
+
+    /// Get the number of entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if no entries were added
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    #[cfg(feature = "zstd")]
+    fn encode(&self, bytes: Vec<u8>) -> Result<(Vec<u8>, Compression)> {
+        if self.compress {
+            let compressed = zstd::bulk::compress(&bytes, 0)?;
+            if compressed.len() < bytes.len() {
+                return Ok((compressed, Compression::Zstd));
+            }
+        }
+        Ok((bytes, Compression::None))
+    }
+
+    #[cfg(not(feature = "zstd"))]
+    fn encode(&self, bytes: Vec<u8>) -> Result<(Vec<u8>, Compression)> {
+        Ok((bytes, Compression::None))
+    }
+
+    /// Write the pack
+    pub fn write_to(&self, mut out: impl Write) -> Result<()> {
+        let data_len: u64 = self
+            .entries
+            .values()
+            .map(|(stored, ..)| stored.len() as u64)
+            .sum();
+
+        out.write_all(MAGIC)?;
+        out.write_all(&VERSION.to_le_bytes())?;
+        out.write_all(&(self.entries.len() as u32).to_le_bytes())?;
+        out.write_all(&(HEADER_LEN + data_len).to_le_bytes())?;
+        for (stored, ..) in self.entries.values() {
+            out.write_all(stored)?;
+        }
+
+        let mut offset = HEADER_LEN;
+        for (path, (stored, compression, size)) in &self.entries {
+            out.write_all(&(path.len() as u16).to_le_bytes())?;
+            out.write_all(path.as_bytes())?;
+            out.write_all(&offset.to_le_bytes())?;
+            out.write_all(&(stored.len() as u64).to_le_bytes())?;
+            out.write_all(&size.to_le_bytes())?;
+            out.write_all(&[compression.to_byte()])?;
+            offset += stored.len() as u64;
+        }
+        out.flush()?;
+        Ok(())
+    }
+}
+
+/// An open pack file
+///
+/// Only the index is read up front; entry contents are read on demand.
+#[derive(Debug)]
+pub struct PackArchive {
+    file: Mutex<File>,
+    index: BTreeMap<String, PackEntry>,
+}
+
+impl PackArchive {
+    /// Open a pack and read its index
+    ///
+    /// Fails if any entry lies outside the data section or declares a size
+    /// above [`MAX_ENTRY_SIZE`], so later reads never trust a corrupt index.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let mut file = File::open(path)
+            .map_err(|e| Error::resource_load(path.display().to_string(), e.to_string()))?;
+
+        let mut header = [0; HEADER_LEN as usize];
+        file.read_exact(&mut header)?;
+        if &header[..8] != MAGIC {
+            return Err(Error::resource_load(
+                path.display().to_string(),
+                "not a pack file",
+            ));
+        }
+        let version = u32::from_le_bytes(header[8..12].try_into().unwrap());
+        if version != VERSION {
+            return Err(Error::resource_load(
+                path.display().to_string(),
+                format!("pack version {version}, expected {VERSION}"),
+            ));
+        }
+        let count = u32::from_le_bytes(header[12..16].try_into().unwrap());
+        let index_offset = u64::from_le_bytes(header[16..24].try_into().unwrap());
+        let file_len = file.metadata()?.len();
+        if !(HEADER_LEN..=file_len).contains(&index_offset) {
+            return Err(Error::resource_load(
+                path.display().to_string(),
+                format!("index offset {index_offset} is outside the {file_len} byte file"),
+            ));
+        }
+
+        file.seek(SeekFrom::Start(index_offset))?;
+        let mut reader = std::io::BufReader::new(&mut file);
+        let mut index = BTreeMap::new();
+        for _ in 0..count {
+            let mut path_bytes = vec![0; read_u16(&mut reader)? as usize];
+            reader.read_exact(&mut path_bytes)?;
+            let entry_path = String::from_utf8(path_bytes).map_err(|_| {
+                Error::resource_load(path.display().to_string(), "entry path is not UTF-8")
+            })?;
+            let entry = PackEntry {
+                offset: read_u64(&mut reader)?,
+                stored_size: read_u64(&mut reader)?,
+                size: read_u64(&mut reader)?,
+                compression: Compression::from_byte(read_u8(&mut reader)?)?,
+            };
+            if let Err(reason) = entry.validate(index_offset) {
+                return Err(Error::resource_load(
+                    path.display().to_string(),
+                    format!("entry '{entry_path}' {reason}"),
+                ));
+            }
+            index.insert(entry_path, entry);
+        }
+
+        Ok(Self {
+            file: Mutex::new(file),
+            index,
+        })
+    }
+
+    /// Get an entry's location
+    pub fn entry(&self, path: &str) -> Option<&PackEntry> {
+        self.index.get(path)
+    }
+
+    /// Iterate over entry paths in sorted order
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.index.keys().map(String::as_str)
+    }
+
+    /// Get the number of entries
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Check if the pack has no entries
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Check if any entry lies below the directory `path`
+    pub fn is_directory(&self, path: &str) -> bool {
+        let prefix = directory_prefix(path);
+        self.index
+            .range(prefix.clone()..)
+            .next()
+            .is_some_and(|(entry, _)| entry.starts_with(&prefix))
+    }
+
+    /// List the files and subdirectories directly inside the directory `path`
+    pub fn read_directory(&self, path: &str) -> Vec<String> {
+        let prefix = directory_prefix(path);
+        let mut children: Vec<String> = self
+            .index
+            .range(prefix.clone()..)
+            .take_while(|(entry, _)| entry.starts_with(&prefix))
+            .map(|(entry, _)| {
+                let rest = &entry[prefix.len()..];
+                let child = rest.split('/').next().unwrap_or(rest);
+                format!("{prefix}{child}")
+            })
+            .collect();
+        children.dedup();
+        children
+    }
+
+    /// Read and decompress an entry
+    pub fn read(&self, path: &str) -> Result<Vec<u8>> {
+        let entry = self
+            .entry(path)
+            .ok_or_else(|| Error::resource_load(path, "not in pack"))?;
+
+        let mut stored = vec![0; entry.stored_size as usize];
+        {
+            let mut file = self.file.lock().unwrap();
+            file.seek(SeekFrom::Start(entry.offset))?;
+            file.read_exact(&mut stored)?;
+        }
+
+        match entry.compression {
+            Compression::None => Ok(stored),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Ok(zstd::bulk::decompress(&stored, entry.size as usize)?),
+            #[cfg(not(feature = "zstd"))]
+            Compression::Zstd => Err(Error::resource_load(
+                path,
+                "entry is zstd-compressed, but the zstd feature is disabled",
+            )),
+        }
+    }
+}
+
+/// Convert a relative path to the `/`-separated form used in the index
+pub(crate) fn normalize_path(path: &str) -> Result<String> {
+    let normalized = path.replace('\\', "/");
+    let valid = !normalized.is_empty()
+        && !normalized.starts_with('/')
+        && normalized
+            .split('/')
+            .all(|segment| !segment.is_empty() && segment != "." && segment != "..");
+    if valid {
+        Ok(normalized)
+    } else {
+        Err(Error::validation(format!(
+            "Invalid pack entry path '{path}'"
+        )))
+    }
+}
+
+fn directory_prefix(path: &str) -> String {
+    let path = path.trim_matches('/');
+    if path.is_empty() {
+        String::new()
+    } else {
+        format!("{path}/")
+    }
+}
+
+fn read_u8(reader: &mut impl Read) -> Result<u8> {
+    let mut bytes = [0; 1];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes[0])
+}
+
+fn read_u16(reader: &mut impl Read) -> Result<u16> {
+    let mut bytes = [0; 2];
+    reader.read_exact(&mut bytes)?;
+    Ok(u16::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_pack(writer: &PackWriter) -> (tempfile::TempDir, PackArchive) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("assets.pack");
+        writer.write_to(File::create(&path).unwrap()).unwrap();
+        let archive = PackArchive::open(&path).unwrap();
+        (dir, archive)
+    }
+
+    #[test]
+    fn test_round_trip_entries() {
+        let mut writer = PackWriter::new();
+        writer
+            .add("models/car.glb", b"glTF binary".to_vec())
+            .unwrap();
+        writer
+            .add("prefabs\\car.ron", b"RonPrefab()".to_vec())
+            .unwrap();
+        writer.add("empty.txt", Vec::new()).unwrap();
+        let (_dir, archive) = write_pack(&writer);
+
+        assert_eq!(
+            archive.paths().collect::<Vec<_>>(),
+            vec!["empty.txt", "models/car.glb", "prefabs/car.ron"]
+        );
+        assert_eq!(archive.read("models/car.glb").unwrap(), b"glTF binary");
+        assert_eq!(archive.read("prefabs/car.ron").unwrap(), b"RonPrefab()");
+        assert!(archive.read("empty.txt").unwrap().is_empty());
+        assert!(archive.read("missing.glb").is_err());
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_compresses_only_when_smaller() {
+        let mut writer = PackWriter::new().with_compression(true);
+        writer.add("city.ron", vec![b'a'; 4096]).unwrap();
+        writer.add("tiny.txt", b"x".to_vec()).unwrap();
+        let (_dir, archive) = write_pack(&writer);
+
+        let city = archive.entry("city.ron").unwrap();
+        assert_eq!(city.compression, Compression::Zstd);
+        assert!(city.stored_size < city.size);
+        assert_eq!(archive.read("city.ron").unwrap(), vec![b'a'; 4096]);
+        assert_eq!(
+            archive.entry("tiny.txt").unwrap().compression,
+            Compression::None
+        );
+    }
+
+    #[test]
+    fn test_directories() {
+        let mut writer = PackWriter::new();
+        for path in ["models/car.glb", "models/props/lamp.glb", "modelsx.txt"] {
+            writer.add(path, Vec::new()).unwrap();
+        }
+        let (_dir, archive) = write_pack(&writer);
+
+        assert!(archive.is_directory("models"));
+        assert!(archive.is_directory(""));
+        assert!(!archive.is_directory("models/car.glb"));
+        assert_eq!(
+            archive.read_directory("models"),
+            vec!["models/car.glb", "models/props"]
+        );
+        assert_eq!(archive.read_directory(""), vec!["models", "modelsx.txt"]);
+    }
+
+    #[test]
+    fn test_rejects_invalid_paths_and_files() {
+        let mut writer = PackWriter::new();
+        assert!(writer.add("../secret", Vec::new()).is_err());
+        assert!(writer.add("/abs.txt", Vec::new()).is_err());
+        assert!(writer
+            .add(&"a".repeat(MAX_PATH_LEN + 1), Vec::new())
+            .is_err());
+        assert!(writer.is_empty());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("not.pack");
+        std::fs::write(&path, b"definitely not a pack file").unwrap();
+        assert!(PackArchive::open(&path)
+            .unwrap_err()
+            .to_string()
+            .contains("not a pack file"));
+    }
+
+    #[test]
+    fn test_rejects_corrupt_index() {
+        let mut writer = PackWriter::new();
+        writer.add("a.txt", b"hello".to_vec()).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let mut bytes = Vec::new();
+        writer.write_to(&mut bytes).unwrap();
+
+        // The only index entry: path length, path, offset, stored size, size
+        let index = u64::from_le_bytes(bytes[16..24].try_into().unwrap()) as usize;
+        let offset = index + 2 + "a.txt".len();
+        let corruptions: [(usize, u64); 4] = [
+            (offset, u64::MAX),
+            (offset + 8, u64::MAX - 10),
+            (offset + 8, 6),
+            (offset + 16, MAX_ENTRY_SIZE + 1),
+        ];
+        for (at, value) in corruptions {
+            let mut corrupt = bytes.clone();
+            corrupt[at..at + 8].copy_from_slice(&value.to_le_bytes());
+            let path = dir.path().join("corrupt.pack");
+            std::fs::write(&path, &corrupt).unwrap();
+
+            let error = PackArchive::open(&path).unwrap_err();
+            assert!(matches!(error, Error::ResourceLoad { .. }), "{at}: {error}");
+        }
+
+        let mut corrupt = bytes.clone();
+        corrupt[16..24].copy_from_slice(&u64::MAX.to_le_bytes());
+        let path = dir.path().join("corrupt.pack");
+        std::fs::write(&path, &corrupt).unwrap();
+        assert!(matches!(
+            PackArchive::open(&path),
+            Err(Error::ResourceLoad { .. })
+        ));
+    }
+}
@@ -0,0 +1,15 @@
+//! Packed asset archives
+//!
+//! Shipping thousands of loose asset files is slow on some platforms. This
+//! crate stores them in a single pack file with an index, optionally
+//! compressing each entry with zstd. [`PackWriter`] builds packs, which
+//! `cargo xtask pack-assets` does for the assets directory, and
+//! [`PackAssetReader`] serves them to Bevy's asset server.
+
+#![deny(missing_docs)]
+
+pub mod archive;
+pub mod reader;
+
+pub use archive::{Compression, PackArchive, PackEntry, PackWriter, MAX_ENTRY_SIZE, MAX_PATH_LEN};
+pub use reader::PackAssetReader;
@@ -0,0 +1,179 @@
+//! Bevy asset reader backed by a pack file
+
+use crate::PackArchive;
+use bevy_asset::io::{AssetReader, AssetReaderError, PathStream, Reader, VecReader};
+use bevy_asset::BoxedFuture;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// [`AssetReader`] serving assets from a [`PackArchive`]
+///
+/// Register it as an asset source to load assets from a pack instead of
+/// loose files. Asset metadata is read from `<path>.meta` entries.
+#[derive(Debug, Clone)]
+pub struct PackAssetReader {
+    archive: Arc<PackArchive>,
+}
+
+impl PackAssetReader {
+    /// Create a reader for an open pack
+    pub fn new(archive: Arc<PackArchive>) -> Self {
+        Self { archive }
+    }
+
+    /// Get the pack this reader serves
+    pub fn archive(&self) -> &PackArchive {
+        &self.archive
+    }
+
+    fn read_entry(&self, path: &Path) -> Result<Box<Reader<'static>>, AssetReaderError> {
+        let key = entry_key(path);
+        if self.archive.entry(&key).is_none() {
+            return Err(AssetReaderError::NotFound(path.to_path_buf()));
+        }
+        let bytes = self
+            .archive
+            .read(&key)
+            .map_err(|e| AssetReaderError::Io(Arc::new(std::io::Error::other(e.to_string()))))?;
+        Ok(Box::new(VecReader::new(bytes)))
+    }
+}
+
+impl AssetReader for PackAssetReader {
+    fn read<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<Box<Reader<'a>>, AssetReaderError>> {
+        Box::pin(async move {
+            self.read_entry(path)
+                .map(|reader| reader as Box<Reader<'a>>)
+        })
+    }
+
+    fn read_meta<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<Box<Reader<'a>>, AssetReaderError>> {
+        Box::pin(async move {
+            let mut meta = path.as_os_str().to_owned();
+            meta.push(".meta");
+            self.read_entry(Path::new(&meta))
+                .map(|reader| reader as Box<Reader<'a>>)
+        })
+    }
+
+    fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<Box<PathStream>, AssetReaderError>> {
+        Box::pin(async move {
+            let key = entry_key(path);
+            if !self.archive.is_directory(&key) {
+                return Err(AssetReaderError::NotFound(path.to_path_buf()));
+            }
+            let children: Vec<PathBuf> = self
+                .archive
+                .read_directory(&key)
+                .into_iter()
+                .filter(|child| !child.ends_with(".meta"))
+                .map(PathBuf::from)
+                .collect();
+            let stream: Box<PathStream> = Box::new(futures_lite::stream::iter(children));
+            Ok(stream)
+        })
+    }
+
+    fn is_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<bool, AssetReaderError>> {
+        Box::pin(async move {
+            let key = entry_key(path);
+            if self.archive.is_directory(&key) {
+                Ok(true)
+            } else if self.archive.entry(&key).is_some() {
+                Ok(false)
+            } else {
+                Err(AssetReaderError::NotFound(path.to_path_buf()))
+            }
+        })
+    }
+}
+
+/// Convert an asset path to its pack index key
+fn entry_key(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PackWriter;
+    use futures_lite::{future::block_on, AsyncReadExt, StreamExt};
+
+    fn reader() -> (tempfile::TempDir, PackAssetReader) {
+        let mut writer = PackWriter::new();
+        writer.add("models/car.glb", b"glTF".to_vec()).unwrap();
+        writer
+            .add("models/car.glb.meta", b"(meta)".to_vec())
+            .unwrap();
+        writer
+            .add("models/props/lamp.glb", b"lamp".to_vec())
+            .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("assets.pack");
+        writer
+            .write_to(std::fs::File::create(&path).unwrap())
+            .unwrap();
+        let archive = PackArchive::open(&path).unwrap();
+        (dir, PackAssetReader::new(Arc::new(archive)))
+    }
+
+    #[test]
+    fn test_reads_assets_and_meta() {
+        let (_dir, reader) = reader();
+        block_on(async {
+            let mut bytes = Vec::new();
+            let mut asset = reader.read(Path::new("models/car.glb")).await.unwrap();
+            asset.read_to_end(&mut bytes).await.unwrap();
+            assert_eq!(bytes, b"glTF");
+
+            bytes.clear();
+            let mut meta = reader.read_meta(Path::new("models/car.glb")).await.unwrap();
+            meta.read_to_end(&mut bytes).await.unwrap();
+            assert_eq!(bytes, b"(meta)");
+
+            assert!(matches!(
+                reader.read(Path::new("models/bus.glb")).await,
+                Err(AssetReaderError::NotFound(_))
+            ));
+        });
+    }
+
+    #[test]
+    fn test_lists_directories() {
+        let (_dir, reader) = reader();
+        block_on(async {
+            assert!(reader.is_directory(Path::new("models")).await.unwrap());
+            assert!(!reader
+                .is_directory(Path::new("models/car.glb"))
+                .await
+                .unwrap());
+
+            let children: Vec<PathBuf> = reader
+                .read_directory(Path::new("models"))
+                .await
+                .unwrap()
+                .collect()
+                .await;
+            assert_eq!(
+                children,
+                vec![
+                    PathBuf::from("models/car.glb"),
+                    PathBuf::from("models/props")
+                ]
+            );
+        });
+    }
+}
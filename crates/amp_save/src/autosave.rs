@@ -0,0 +1,329 @@
+//! Autosaves
+//!
+//! Gameplay systems send an [`AutosaveRequest`] when a mission is completed
+//! or a safehouse entered, and [`tick_autosave`] sends one every
+//! [`AutosaveSettings::interval_seconds`]. [`run_autosave`] captures the
+//! game state on the main thread, then compresses and writes it on the
+//! [`IoTaskPool`] so saving does not hitch the frame. Autosaves rotate
+//! through their own ring of slots, separate from the player's saves, and
+//! [`Autosave::indicator_visible`] tells the HUD when to show its spinner.
+
+use crate::{SaveGameState, SaveMetadata, SaveSlots};
+use amp_core::Result;
+use bevy_ecs::event::ManualEventReader;
+use bevy_ecs::prelude::*;
+use bevy_tasks::{IoTaskPool, Task, TaskPool};
+use bevy_time::Time;
+use config_core::{Config, ConfigHandle, Validate, ValidationErrors};
+use serde::{Deserialize, Serialize};
+
+/// Prefix of autosave slot names, followed by the 1-based ring index
+pub const AUTOSAVE_SLOT_PREFIX: &str = "autosave-";
+
+/// Reason to autosave
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutosaveRequest {
+    /// A mission was completed
+    MissionComplete,
+    /// The player entered a safehouse
+    SafehouseEntered,
+    /// The autosave interval passed
+    Timer,
+}
+
+/// Autosave options
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutosaveSettings {
+    /// Whether autosaves are written at all
+    pub enabled: bool,
+    /// Seconds between timed autosaves; 0 disables the timer
+    pub interval_seconds: f32,
+    /// Number of autosave slots to rotate through
+    pub slots: u32,
+    /// Seconds the HUD indicator stays visible after a save finishes
+    pub indicator_seconds: f32,
+}
+
+impl Default for AutosaveSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_seconds: 600.0,
+            slots: 3,
+            indicator_seconds: 2.0,
+        }
+    }
+}
+
+impl Config for AutosaveSettings {
+    const FILE_NAME: &'static str = "autosave.ron";
+}
+
+impl Validate for AutosaveSettings {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.check_range("interval_seconds", self.interval_seconds, 0.0..=7200.0);
+        errors.check_range("slots", self.slots, 1..=10);
+        errors.check_range("indicator_seconds", self.indicator_seconds, 0.0..=10.0);
+    }
+}
+
+/// Game state that can be captured from the world for saving
+pub trait CaptureSave: SaveGameState + Send + 'static {
+    /// Capture the current game state and its metadata
+    fn capture(world: &mut World) -> (SaveMetadata, Self);
+}
+
+/// Get the name of an autosave slot from its 0-based ring index
+pub fn autosave_slot_name(index: u32) -> String {
+    format!("{AUTOSAVE_SLOT_PREFIX}{}", index + 1)
+}
+
+/// Autosave state: the timer, the save being written and the HUD indicator
+#[derive(Resource, Default)]
+pub struct Autosave {
+    requests: ManualEventReader<AutosaveRequest>,
+    pending: Option<AutosaveRequest>,
+    task: Option<Task<Result<String>>>,
+    last_index: Option<u32>,
+    last_slot: Option<String>,
+    since_last: f32,
+    indicator_remaining: f32,
+}
+
+impl Autosave {
+    /// Check if an autosave is being written
+    pub fn is_saving(&self) -> bool {
+        self.task.is_some()
+    }
+
+    /// Check if the HUD should show the autosave indicator
+    ///
+    /// The indicator stays up for [`AutosaveSettings::indicator_seconds`]
+    /// after the save finishes, so quick saves are still noticed.
+    pub fn indicator_visible(&self) -> bool {
+        self.is_saving() || self.indicator_remaining > 0.0
+    }
+
+    /// Get the slot of the last completed autosave
+    pub fn last_slot(&self) -> Option<&str> {
+        self.last_slot.as_deref()
+    }
+
+    /// Get the seconds since the last autosave started
+    pub fn since_last(&self) -> f32 {
+        self.since_last
+    }
+
+    /// Collect the save task if it has finished
+    fn poll_task(&mut self, indicator_seconds: f32) {
+        if !self.task.as_ref().is_some_and(Task::is_finished) {
+            return;
+        }
+        let task = self.task.take().unwrap();
+        match bevy_tasks::block_on(task) {
+            Ok(slot) => self.last_slot = Some(slot),
+            Err(e) => log::warn!("autosave failed: {e}"),
+        }
+        self.indicator_remaining = indicator_seconds;
+    }
+
+    /// Pick the ring index to write, continuing after the newest autosave
+    ///
+    /// Autosaves are ordered by their sub-second save time, so a ring
+    /// written within one second still continues after the last one.
+    fn next_index(&mut self, slots: &SaveSlots, ring: u32) -> u32 {
+        let last = *self.last_index.get_or_insert_with(|| {
+            (0..ring)
+                .filter_map(|index| {
+                    let metadata = slots.metadata(&autosave_slot_name(index)).ok()?;
+                    Some((metadata.saved_time(), index))
+                })
+                .max()
+                .map_or(ring - 1, |(_, index)| index)
+        });
+        let next = (last + 1) % ring;
+        self.last_index = Some(next);
+        next
+    }
+}
+
+/// Send timed [`AutosaveRequest`]s and count down the HUD indicator
+pub fn tick_autosave(
+    time: Res<Time>,
+    settings: Option<Res<ConfigHandle<AutosaveSettings>>>,
+    mut autosave: ResMut<Autosave>,
+    mut requests: EventWriter<AutosaveRequest>,
+) {
+    let default_settings = AutosaveSettings::default();
+    let settings = settings
+        .as_deref()
+        .map_or(&default_settings, ConfigHandle::get);
+    let dt = time.delta_seconds();
+
+    autosave.indicator_remaining = (autosave.indicator_remaining - dt).max(0.0);
+    autosave.since_last += dt;
+    if settings.enabled
+        && settings.interval_seconds > 0.0
+        && autosave.since_last >= settings.interval_seconds
+        && autosave.pending.is_none()
+        && !autosave.is_saving()
+    {
+        autosave.since_last = 0.0;
+        requests.send(AutosaveRequest::Timer);
+    }
+}
+
+/// Start an autosave of `T` for the latest [`AutosaveRequest`]
+///
+/// Requests arriving while a save is being written are merged into one
+/// save started once it finishes. Does nothing without a [`SaveSlots`]
+/// resource.
+pub fn run_autosave<T: CaptureSave>(world: &mut World) {
+    let settings = world
+        .get_resource::<ConfigHandle<AutosaveSettings>>()
+        .map_or_else(AutosaveSettings::default, |handle| handle.get().clone());
+
+    world.resource_scope(|world, mut autosave: Mut<Autosave>| {
+        autosave.poll_task(settings.indicator_seconds);
+
+        let events = world.resource::<Events<AutosaveRequest>>();
+        let autosave = &mut *autosave;
+        if let Some(request) = autosave.requests.read(events).last() {
+            if settings.enabled {
+                autosave.pending = Some(*request);
+            }
+        }
+        if autosave.is_saving() {
+            return;
+        }
+        let Some(request) = autosave.pending.take() else {
+            return;
+        };
+        let Some(slots) = world.get_resource::<SaveSlots>().cloned() else {
+            log::warn!("autosave requested without a SaveSlots resource");
+            return;
+        };
+
+        let (metadata, state) = T::capture(world);
+        let slot = autosave_slot_name(autosave.next_index(&slots, settings.slots.max(1)));
+        log::info!("autosaving to '{slot}' ({request:?})");
+        autosave.since_last = 0.0;
+        autosave.task = Some(IoTaskPool::get_or_init(TaskPool::new).spawn(async move {
+            slots.save(&slot, &metadata, &state, None)?;
+            Ok(slot)
+        }));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::system::RunSystemOnce;
+    use std::time::{Duration, Instant};
+
+    #[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct State {
+        mission: u32,
+    }
+
+    impl SaveGameState for State {}
+
+    impl CaptureSave for State {
+        fn capture(world: &mut World) -> (SaveMetadata, Self) {
+            let metadata = SaveMetadata::new("Docks", 0, Duration::ZERO);
+            (metadata, world.resource::<State>().clone())
+        }
+    }
+
+    fn world(dir: &std::path::Path) -> World {
+        let mut world = World::new();
+        world.insert_resource(SaveSlots::new(dir));
+        world.insert_resource(State { mission: 1 });
+        world.init_resource::<Events<AutosaveRequest>>();
+        world.init_resource::<Autosave>();
+        config_core::init_config(
+            &mut world,
+            AutosaveSettings {
+                slots: 2,
+                ..AutosaveSettings::default()
+            },
+        );
+        world
+    }
+
+    /// Request an autosave and wait for it to be written
+    fn autosave(world: &mut World, mission: u32) -> String {
+        world.resource_mut::<State>().mission = mission;
+        world.send_event(AutosaveRequest::MissionComplete);
+        world.run_system_once(run_autosave::<State>);
+        assert!(world.resource::<Autosave>().indicator_visible());
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while world.resource::<Autosave>().is_saving() {
+            assert!(Instant::now() < deadline, "autosave did not finish in time");
+            std::thread::sleep(Duration::from_millis(1));
+            world.run_system_once(run_autosave::<State>);
+        }
+        world
+            .resource::<Autosave>()
+            .last_slot()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_autosaves_rotate_through_ring() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut world = world(dir.path());
+
+        assert_eq!(autosave(&mut world, 1), "autosave-1");
+        assert_eq!(autosave(&mut world, 2), "autosave-2");
+        assert_eq!(autosave(&mut world, 3), "autosave-1");
+
+        let slots = SaveSlots::new(dir.path());
+        assert_eq!(slots.load::<State>("autosave-1").unwrap().1.mission, 3);
+        assert_eq!(slots.load::<State>("autosave-2").unwrap().1.mission, 2);
+
+        // A new session continues after the newest autosave, even if
+        // all three were written within the same second
+        let mut autosave = Autosave::default();
+        assert_eq!(autosave.next_index(&slots, 2), 1);
+    }
+
+    #[test]
+    fn test_timer_requests_autosave() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut world = world(dir.path());
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_secs(400));
+        world.insert_resource(time);
+
+        world.run_system_once(tick_autosave);
+        assert!(world.resource::<Events<AutosaveRequest>>().is_empty());
+        world.run_system_once(tick_autosave);
+        let events = world.resource::<Events<AutosaveRequest>>();
+        assert_eq!(
+            events.iter_current_update_events().collect::<Vec<_>>(),
+            [&AutosaveRequest::Timer]
+        );
+        assert_eq!(world.resource::<Autosave>().since_last(), 0.0);
+    }
+
+    #[test]
+    fn test_disabled_ignores_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut world = world(dir.path());
+        config_core::init_config(
+            &mut world,
+            AutosaveSettings {
+                enabled: false,
+                ..AutosaveSettings::default()
+            },
+        );
+        world.send_event(AutosaveRequest::SafehouseEntered);
+        world.run_system_once(run_autosave::<State>);
+        assert!(!world.resource::<Autosave>().is_saving());
+        assert!(SaveSlots::new(dir.path()).list().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,307 @@
+//! Persistent nearby vehicles and NPCs
+//!
+//! Traffic, pedestrians and police are spawned around the player and not
+//! part of any sector layout, so a save made mid-chase would otherwise load
+//! into an empty street. [`NearbyDynamics::capture`] records the entities
+//! marked [`SaveDynamic`] within a radius of the player: their prefab,
+//! transform and the components registered in [`DynamicComponents`], such
+//! as velocity and AI state. [`NearbyDynamics::restore`] respawns them
+//! through the [`Factory`] after loading.
+
+use crate::SavedTransform;
+use amp_core::{Error, Result};
+use bevy_ecs::prelude::*;
+use bevy_ecs::system::{CommandQueue, EntityCommands};
+use bevy_math::Vec3;
+use bevy_transform::components::Transform;
+use gameplay_factory::{Factory, PrefabNames};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Marks a dynamic entity to be kept in saves, with the prefab it was
+/// spawned from
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct SaveDynamic {
+    /// Name of the prefab, as registered in [`PrefabNames`]
+    pub prefab: String,
+}
+
+type CaptureFn = fn(&World, Entity) -> Option<Result<String>>;
+type RestoreFn = fn(&str, &mut EntityCommands) -> Result<()>;
+
+/// Components saved with dynamic entities, by name
+///
+/// Register the components that make up an entity's live state and are not
+/// set up by its prefab, such as velocity and AI state.
+#[derive(Resource, Default)]
+pub struct DynamicComponents {
+    components: BTreeMap<&'static str, (CaptureFn, RestoreFn)>,
+}
+
+impl DynamicComponents {
+    /// Save component `T` under `name`
+    ///
+    /// The name is stored in saves, so keep it when renaming the type.
+    pub fn register<T>(&mut self, name: &'static str) -> &mut Self
+    where
+        T: Component + Serialize + DeserializeOwned,
+    {
+        let capture: CaptureFn = |world, entity| {
+            let component = world.get::<T>(entity)?;
+            Some(ron::to_string(component).map_err(|e| Error::serialization(e.to_string())))
+        };
+        let restore: RestoreFn = |text, entity| {
+            let component: T =
+                ron::from_str(text).map_err(|e| Error::serialization(e.to_string()))?;
+            entity.insert(component);
+            Ok(())
+        };
+        self.components.insert(name, (capture, restore));
+        self
+    }
+}
+
+/// Saved state of one dynamic entity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DynamicEntityState {
+    /// Name of the prefab to respawn
+    pub prefab: String,
+    /// Position and orientation
+    pub transform: SavedTransform,
+    /// Registered components, as RON by registered name
+    #[serde(default)]
+    pub components: BTreeMap<String, String>,
+}
+
+/// Dynamic entities near the player, stored in the save state
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct NearbyDynamics {
+    /// Saved entities
+    pub entities: Vec<DynamicEntityState>,
+}
+
+impl NearbyDynamics {
+    /// Capture the [`SaveDynamic`] entities within `radius` of `center`
+    pub fn capture(world: &mut World, center: Vec3, radius: f32) -> Result<Self> {
+        let mut query = world.query::<(Entity, &SaveDynamic, &Transform)>();
+        let mut nearby: Vec<_> = query
+            .iter(world)
+            .filter(|(_, _, transform)| transform.translation.distance(center) <= radius)
+            .map(|(entity, saved, transform)| (entity, saved.prefab.clone(), *transform))
+            .collect();
+        // Nearest first, so the player's surroundings respawn first
+        nearby.sort_by(|a, b| {
+            let distance = |transform: &Transform| transform.translation.distance_squared(center);
+            distance(&a.2).total_cmp(&distance(&b.2))
+        });
+
+        let registered = world.get_resource::<DynamicComponents>();
+        let mut entities = Vec::with_capacity(nearby.len());
+        for (entity, prefab, transform) in nearby {
+            let mut components = BTreeMap::new();
+            for (name, (capture, _)) in registered.iter().flat_map(|r| &r.components) {
+                if let Some(text) = capture(world, entity) {
+                    components.insert(name.to_string(), text?);
+                }
+            }
+            entities.push(DynamicEntityState {
+                prefab,
+                transform: (&transform).into(),
+                components,
+            });
+        }
+        Ok(Self { entities })
+    }
+
+    /// Respawn the saved entities through the [`Factory`]
+    ///
+    /// Requires the [`Factory`] and [`PrefabNames`] resources. Entities whose
+    /// prefab no longer exists are skipped with a warning, as are saved
+    /// components that are no longer registered or fail to deserialize; the
+    /// rest of the entity is still restored. Returns the spawned entities.
+    pub fn restore(&self, world: &mut World) -> Result<Vec<Entity>> {
+        let mut queue = CommandQueue::default();
+        let mut spawned = Vec::with_capacity(self.entities.len());
+        {
+            let factory = world
+                .get_resource::<Factory>()
+                .ok_or_else(|| Error::invalid_state("restoring dynamics requires a Factory"))?;
+            let names = world
+                .get_resource::<PrefabNames>()
+                .ok_or_else(|| Error::invalid_state("restoring dynamics requires PrefabNames"))?;
+            let registered = world.get_resource::<DynamicComponents>();
+            let mut commands = Commands::new(&mut queue, world);
+
+            for saved in &self.entities {
+                let entity = match factory.spawn_named(&mut commands, names, &saved.prefab) {
+                    Ok(entity) => entity,
+                    Err(e) => {
+                        log::warn!("skipping saved '{}': {e}", saved.prefab);
+                        continue;
+                    }
+                };
+                let mut transform = Transform::default();
+                saved.transform.apply(&mut transform);
+                let mut entity_commands = commands.entity(entity);
+                entity_commands.insert((
+                    transform,
+                    SaveDynamic {
+                        prefab: saved.prefab.clone(),
+                    },
+                ));
+
+                for (name, text) in &saved.components {
+                    let restore = registered
+                        .and_then(|r| r.components.get(name.as_str()))
+                        .map(|(_, restore)| restore);
+                    match restore.map(|restore| restore(text, &mut entity_commands)) {
+                        Some(Ok(())) => {}
+                        Some(Err(e)) => log::warn!(
+                            "skipping saved component '{name}' of '{}': {e}",
+                            saved.prefab
+                        ),
+                        None => log::warn!("skipping unregistered saved component '{name}'"),
+                    }
+                }
+                spawned.push(entity);
+            }
+        }
+        queue.apply(world);
+        Ok(spawned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Component, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    struct Velocity([f32; 3]);
+
+    #[derive(Component, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    enum Pursuit {
+        Idle,
+        Chasing { aggression: f32 },
+    }
+
+    #[derive(Component, Clone)]
+    struct Police;
+
+    /// Create a world with a police car prefab; prefab ids are global, so
+    /// each test registers its own
+    fn world(prefab: &str) -> World {
+        let mut world = World::new();
+        let mut names = PrefabNames::new();
+        let mut factory = Factory::new();
+        let police = names.register(prefab).unwrap();
+        factory.register_bundle(police, Police).unwrap();
+        world.insert_resource(names);
+        world.insert_resource(factory);
+
+        let mut components = DynamicComponents::default();
+        components
+            .register::<Velocity>("velocity")
+            .register::<Pursuit>("pursuit");
+        world.insert_resource(components);
+        world
+    }
+
+    fn police_car(world: &mut World, x: f32, pursuit: Pursuit) -> Entity {
+        world
+            .spawn((
+                Police,
+                SaveDynamic {
+                    prefab: "save_dynamics/vehicles/police".to_string(),
+                },
+                Transform::from_xyz(x, 0.0, 0.0),
+                Velocity([0.0, 0.0, 20.0]),
+                pursuit,
+            ))
+            .id()
+    }
+
+    #[test]
+    fn test_captures_nearby_and_respawns_through_factory() {
+        let mut world = world("save_dynamics/vehicles/police");
+        police_car(&mut world, 80.0, Pursuit::Idle);
+        police_car(&mut world, 10.0, Pursuit::Chasing { aggression: 0.8 });
+        police_car(&mut world, 500.0, Pursuit::Idle);
+
+        let saved = NearbyDynamics::capture(&mut world, Vec3::ZERO, 100.0).unwrap();
+        assert_eq!(saved.entities.len(), 2);
+        assert_eq!(saved.entities[0].transform.translation, [10.0, 0.0, 0.0]);
+
+        let text = ron::to_string(&saved).unwrap();
+        let loaded: NearbyDynamics = ron::from_str(&text).unwrap();
+
+        // Load into the same world after the save's entities are gone
+        let cars: Vec<_> = world
+            .query_filtered::<Entity, With<Police>>()
+            .iter(&world)
+            .collect();
+        for car in cars {
+            world.despawn(car);
+        }
+        let spawned = loaded.restore(&mut world).unwrap();
+        assert_eq!(spawned.len(), 2);
+        let chaser = spawned[0];
+        assert!(world.get::<Police>(chaser).is_some());
+        assert_eq!(
+            world.get::<Transform>(chaser).unwrap().translation,
+            Vec3::new(10.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            world.get::<Velocity>(chaser),
+            Some(&Velocity([0.0, 0.0, 20.0]))
+        );
+        assert_eq!(
+            world.get::<Pursuit>(chaser),
+            Some(&Pursuit::Chasing { aggression: 0.8 })
+        );
+    }
+
+    #[test]
+    fn test_restore_skips_malformed_components() {
+        let mut world = world("save_dynamics/vehicles/police_malformed");
+        let components = BTreeMap::from([
+            ("velocity".to_string(), "not a velocity".to_string()),
+            ("pursuit".to_string(), "Idle".to_string()),
+        ]);
+        let saved = NearbyDynamics {
+            entities: vec![
+                DynamicEntityState {
+                    prefab: "save_dynamics/vehicles/police_malformed".to_string(),
+                    transform: (&Transform::default()).into(),
+                    components,
+                },
+                DynamicEntityState {
+                    prefab: "save_dynamics/vehicles/police_malformed".to_string(),
+                    transform: (&Transform::default()).into(),
+                    components: BTreeMap::new(),
+                },
+            ],
+        };
+
+        let spawned = saved.restore(&mut world).unwrap();
+        assert_eq!(spawned.len(), 2);
+        assert!(world.get::<Police>(spawned[0]).is_some());
+        assert!(world.get::<Velocity>(spawned[0]).is_none());
+        assert_eq!(world.get::<Pursuit>(spawned[0]), Some(&Pursuit::Idle));
+        assert!(world.get::<Police>(spawned[1]).is_some());
+    }
+
+    #[test]
+    fn test_restore_skips_unknown_prefabs() {
+        let mut world = world("save_dynamics/vehicles/police_unused");
+        let saved = NearbyDynamics {
+            entities: vec![DynamicEntityState {
+                prefab: "save_dynamics/vehicles/removed".to_string(),
+                transform: (&Transform::default()).into(),
+                components: BTreeMap::new(),
+            }],
+        };
+        assert!(saved.restore(&mut world).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,81 @@
+//! Compressed, checksummed save files
+//!
+//! A save file is a 16-byte header followed by the zstd-compressed data.
+//! The header holds a magic number, the CRC-32 of the uncompressed data and
+//! its length, so a truncated or corrupted file is detected on load instead
+//! of being deserialized into a broken game state.
+
+use amp_core::{Error, Result};
+
+/// Identifies a save file
+const MAGIC: &[u8; 4] = b"AMPS";
+
+/// Magic, checksum and uncompressed length
+const HEADER_LEN: usize = 16;
+
+/// Largest uncompressed size accepted, guarding against corrupt lengths
+const MAX_SIZE: u64 = 256 * 1024 * 1024;
+
+/// zstd level; saves are small, so favour ratio over speed
+const LEVEL: i32 = 9;
+
+/// Compress `data` into a save file
+pub(crate) fn encode(data: &[u8]) -> Result<Vec<u8>> {
+    let compressed = zstd::bulk::compress(data, LEVEL)?;
+    let mut bytes = Vec::with_capacity(HEADER_LEN + compressed.len());
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&crc32fast::hash(data).to_le_bytes());
+    bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&compressed);
+    Ok(bytes)
+}
+
+/// Decompress a save file of slot `name`, verifying its checksum
+pub(crate) fn decode(name: &str, bytes: &[u8]) -> Result<Vec<u8>> {
+    let corrupt = |reason: &str| Error::resource_load(name, format!("corrupt save: {reason}"));
+
+    if bytes.len() < HEADER_LEN || &bytes[..4] != MAGIC {
+        return Err(corrupt("not a save file"));
+    }
+    let checksum = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+    let size = u64::from_le_bytes(bytes[8..16].try_into().unwrap());
+    if size > MAX_SIZE {
+        return Err(corrupt("invalid length"));
+    }
+
+    let data = zstd::bulk::decompress(&bytes[HEADER_LEN..], size as usize)
+        .map_err(|e| corrupt(&e.to_string()))?;
+    if data.len() as u64 != size || crc32fast::hash(&data) != checksum {
+        return Err(corrupt("checksum mismatch"));
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_compresses() {
+        let data = "(money: 100)\n".repeat(100);
+        let bytes = encode(data.as_bytes()).unwrap();
+        assert!(bytes.len() < data.len());
+        assert_eq!(decode("a", &bytes).unwrap(), data.as_bytes());
+    }
+
+    #[test]
+    fn test_detects_corruption() {
+        let bytes = encode(b"(mission: 3, money: 1500)").unwrap();
+
+        let mut flipped = bytes.clone();
+        *flipped.last_mut().unwrap() ^= 0x40;
+        assert!(decode("a", &flipped).is_err());
+
+        let mut wrong_checksum = bytes.clone();
+        wrong_checksum[4] ^= 1;
+        assert!(decode("a", &wrong_checksum).is_err());
+
+        assert!(decode("a", &bytes[..bytes.len() - 3]).is_err());
+        assert!(decode("a", b"(mission: 3)").is_err());
+    }
+}
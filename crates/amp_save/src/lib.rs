@@ -0,0 +1,56 @@
+//! Save slots
+//!
+//! A save is stored in a named slot: a directory holding the game state, a
+//! small metadata file the load screen lists without reading the state, and
+//! optionally a thumbnail image captured when saving. [`SaveSlots`] manages
+//! the slots in a save directory. The state is any [`SaveGameState`], which
+//! is written with a version header and migrated on load. State files are
+//! zstd-compressed and checksummed, and each slot keeps rotating backups
+//! that loading falls back to when the save is corrupt. [`run_autosave`]
+//! writes autosaves to a separate ring of slots in the background, which
+//! loading also falls back to when a slot has no intact backup.
+//! [`WorldDeltas`] keeps changes to streamed sectors across loads.
+//! [`NearbyDynamics`] keeps the traffic and NPCs around the player.
+
+#![deny(missing_docs)]
+
+pub mod autosave;
+pub mod dynamics;
+mod file;
+pub mod slots;
+pub mod state;
+pub mod world_deltas;
+
+pub use autosave::{
+    autosave_slot_name, run_autosave, tick_autosave, Autosave, AutosaveRequest, AutosaveSettings,
+    CaptureSave, AUTOSAVE_SLOT_PREFIX,
+};
+pub use dynamics::{DynamicComponents, DynamicEntityState, NearbyDynamics, SaveDynamic};
+pub use slots::{SaveMetadata, SaveSlot, SaveSlots, SAVE_VERSION};
+pub use state::SaveGameState;
+pub use world_deltas::{
+    apply_world_deltas, record_world_deltas, EntityDelta, OpenState, SavedTransform,
+    WorldDeltaEvent, WorldDeltas, WorldEntityId,
+};
+
+use bevy_ecs::schedule::{IntoSystemConfigs, SystemConfigs, SystemSet};
+
+/// System set of the per-frame save systems added by [`systems`]
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SaveSystems;
+
+/// Get the per-frame save systems for game state `T`, ordered and in
+/// [`SaveSystems`]
+///
+/// World deltas are replayed and recorded before an autosave captures
+/// the frame.
+pub fn systems<T: CaptureSave>() -> SystemConfigs {
+    (
+        apply_world_deltas,
+        record_world_deltas,
+        tick_autosave,
+        run_autosave::<T>,
+    )
+        .chain()
+        .in_set(SaveSystems)
+}
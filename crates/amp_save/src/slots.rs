@@ -0,0 +1,593 @@
+//! Named save slots on disk
+
+use crate::autosave::AUTOSAVE_SLOT_PREFIX;
+use crate::file::{decode, encode};
+use crate::state::{parse_state, render_state};
+use crate::SaveGameState;
+use amp_core::{Error, Result};
+use bevy_ecs::system::Resource;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Version written to new saves
+pub const SAVE_VERSION: u32 = 1;
+
+const METADATA_FILE: &str = "meta.ron";
+const STATE_FILE: &str = "state.sav";
+/// Uncompressed state written before saves were compressed
+const LEGACY_STATE_FILE: &str = "state.ron";
+const BACKUP_DIR: &str = "backups";
+const THUMBNAIL_FILE: &str = "thumbnail.png";
+const MAX_NAME_LEN: usize = 64;
+
+/// Summary of a save shown on the load screen
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaveMetadata {
+    /// Save format version
+    pub version: u32,
+    /// Time of saving, in seconds since the Unix epoch
+    pub saved_at: u64,
+    /// Sub-second part of the time of saving, in nanoseconds
+    ///
+    /// Orders saves made within the same second; 0 in older saves.
+    #[serde(default)]
+    pub saved_at_nanos: u32,
+    /// Total time played
+    pub playtime: Duration,
+    /// Name of the player's location
+    pub location: String,
+    /// Player's money
+    pub money: i64,
+}
+
+impl SaveMetadata {
+    /// Create metadata for a save made now
+    pub fn new(location: impl Into<String>, money: i64, playtime: Duration) -> Self {
+        let since = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        Self {
+            version: SAVE_VERSION,
+            saved_at: since.as_secs(),
+            saved_at_nanos: since.subsec_nanos(),
+            playtime,
+            location: location.into(),
+            money,
+        }
+    }
+
+    /// Get the time of saving since the Unix epoch, including the sub-second part
+    pub fn saved_time(&self) -> Duration {
+        Duration::new(self.saved_at, self.saved_at_nanos)
+    }
+}
+
+/// A listed save slot
+#[derive(Debug, Clone, PartialEq)]
+pub struct SaveSlot {
+    /// Slot name
+    pub name: String,
+    /// Metadata of the save in the slot
+    pub metadata: SaveMetadata,
+    /// Whether the save has a thumbnail
+    pub has_thumbnail: bool,
+}
+
+/// Save slots stored as subdirectories of a save directory
+///
+/// Each slot holds `meta.ron`, the compressed and checksummed `state.sav`
+/// and optionally `thumbnail.png`. Files are written to a temporary file
+/// and renamed into place, and the metadata is written last, so a save
+/// interrupted part way does not replace the metadata of the previous save
+/// in the slot.
+///
+/// Before a slot is overwritten its intact save is copied to
+/// `backups/1`, shifting older backups up to the configured count. Loading
+/// a slot whose save is corrupt falls back to the newest intact backup, and
+/// then to the newest intact `autosave-N` slot.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct SaveSlots {
+    dir: PathBuf,
+    backups: usize,
+}
+
+impl SaveSlots {
+    /// Default number of backups kept per slot
+    pub const DEFAULT_BACKUPS: usize = 3;
+
+    /// Manage the slots in `dir`, which is created on the first save
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            backups: Self::DEFAULT_BACKUPS,
+        }
+    }
+
+    /// Keep `backups` previous saves per slot; 0 disables backups
+    pub fn with_backups(mut self, backups: usize) -> Self {
+        self.backups = backups;
+        self
+    }
+
+    /// Get the number of backups kept per slot
+    pub fn backups(&self) -> usize {
+        self.backups
+    }
+
+    /// Get the save directory in the user data directory (`$XDG_DATA_HOME/amp/saves`)
+    ///
+    /// Returns `None` if the platform has no user data directory.
+    pub fn user_dir() -> Option<PathBuf> {
+        dirs::data_dir().map(|data_dir| data_dir.join("amp").join("saves"))
+    }
+
+    /// Get the save directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Save `state` to a slot, replacing any save in it
+    ///
+    /// The thumbnail is encoded image data, normally a PNG screenshot
+    /// captured when saving; without one any previous thumbnail is removed.
+    pub fn save<T: SaveGameState>(
+        &self,
+        name: &str,
+        metadata: &SaveMetadata,
+        state: &T,
+        thumbnail: Option<&[u8]>,
+    ) -> Result<()> {
+        let slot = self.slot_dir(name)?;
+        fs::create_dir_all(&slot)?;
+        if is_intact(name, &slot) {
+            self.rotate_backups(&slot)?;
+        }
+
+        let data = encode(render_state(state)?.as_bytes())?;
+        write_atomic(&slot.join(STATE_FILE), &data)?;
+        remove_if_exists(&slot.join(LEGACY_STATE_FILE))?;
+        match thumbnail {
+            Some(image) => write_atomic(&slot.join(THUMBNAIL_FILE), image)?,
+            None => remove_if_exists(&slot.join(THUMBNAIL_FILE))?,
+        }
+        write_atomic(&slot.join(METADATA_FILE), to_ron(metadata)?.as_bytes())
+    }
+
+    /// Load the metadata and state of a slot, migrating an older state
+    ///
+    /// If the save is missing or corrupt, the newest backup that loads is
+    /// returned instead and a warning is logged. Without a usable backup the
+    /// newest autosave that loads is returned, even though it may be older
+    /// or newer than the slot's save.
+    ///
+    /// A slot that has never been saved to has no fallback, so loading it
+    /// fails.
+    pub fn load<T: SaveGameState>(&self, name: &str) -> Result<(SaveMetadata, T)> {
+        let slot = self.slot_dir(name)?;
+        let error = match load_from(name, &slot) {
+            Ok(save) => return Ok(save),
+            Err(e) => e,
+        };
+
+        for index in 1..=self.backups {
+            let backup = slot.join(BACKUP_DIR).join(index.to_string());
+            if !backup.is_dir() {
+                break;
+            }
+            if let Ok(save) = load_from(name, &backup) {
+                log::warn!("save slot '{name}' failed to load ({error}); restored backup {index}");
+                return Ok(save);
+            }
+        }
+
+        // Only fall back for slots that held a save
+        if !slot.is_dir() {
+            return Err(error);
+        }
+        for autosave in self.autosaves_newest_first(name) {
+            if let Ok(save) = load_from(&autosave, &self.dir.join(&autosave)) {
+                log::warn!("save slot '{name}' failed to load ({error}); restored '{autosave}'");
+                return Ok(save);
+            }
+        }
+        Err(error)
+    }
+
+    /// Load the metadata of a slot
+    pub fn metadata(&self, name: &str) -> Result<SaveMetadata> {
+        read_metadata(name, &self.slot_dir(name)?)
+    }
+
+    /// Load the thumbnail of a slot, if it has one
+    pub fn thumbnail(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        let slot = self.slot_dir(name)?;
+        if !slot.join(METADATA_FILE).is_file() {
+            return Err(not_found(name));
+        }
+        match fs::read(slot.join(THUMBNAIL_FILE)) {
+            Ok(image) => Ok(Some(image)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Check if a slot holds a save
+    pub fn exists(&self, name: &str) -> bool {
+        self.slot_dir(name)
+            .is_ok_and(|slot| slot.join(METADATA_FILE).is_file())
+    }
+
+    /// List the slots holding readable saves, most recent first
+    pub fn list(&self) -> Result<Vec<SaveSlot>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut slots = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            let Some(name) = entry.file_name().to_str().map(str::to_owned) else {
+                continue;
+            };
+            // Skip stray directories and saves from newer versions
+            let Ok(metadata) = self.metadata(&name) else {
+                continue;
+            };
+            slots.push(SaveSlot {
+                has_thumbnail: entry.path().join(THUMBNAIL_FILE).is_file(),
+                name,
+                metadata,
+            });
+        }
+        slots.sort_by(|a, b| {
+            b.metadata
+                .saved_time()
+                .cmp(&a.metadata.saved_time())
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        Ok(slots)
+    }
+
+    /// Delete the save in a slot
+    pub fn delete(&self, name: &str) -> Result<()> {
+        if !self.exists(name) {
+            return Err(not_found(name));
+        }
+        fs::remove_dir_all(self.slot_dir(name)?)?;
+        Ok(())
+    }
+
+    /// Get the directory of a slot, rejecting names that are not plain
+    /// file names
+    fn slot_dir(&self, name: &str) -> Result<PathBuf> {
+        let valid = !name.is_empty()
+            && name.len() <= MAX_NAME_LEN
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ' '))
+            && name.trim() == name;
+        if !valid {
+            return Err(Error::validation(format!(
+                "invalid save slot name '{name}': use up to {MAX_NAME_LEN} letters, digits, \
+                 spaces, '-' or '_'"
+            )));
+        }
+        Ok(self.dir.join(name))
+    }
+
+    /// Get the names of the autosave slots other than `except`, newest first
+    fn autosaves_newest_first(&self, except: &str) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut autosaves: Vec<(Duration, String)> = entries
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter(|name| {
+                name != except
+                    && name
+                        .strip_prefix(AUTOSAVE_SLOT_PREFIX)
+                        .is_some_and(|index| index.parse::<u32>().is_ok())
+            })
+            .filter_map(|name| Some((self.metadata(&name).ok()?.saved_time(), name)))
+            .collect();
+        autosaves.sort_by(|a, b| b.cmp(a));
+        autosaves.into_iter().map(|(_, name)| name).collect()
+    }
+
+    /// Copy the save in `slot` to the newest backup, dropping the oldest
+    fn rotate_backups(&self, slot: &Path) -> Result<()> {
+        if self.backups == 0 {
+            return Ok(());
+        }
+        let backups = slot.join(BACKUP_DIR);
+        let oldest = backups.join(self.backups.to_string());
+        if oldest.is_dir() {
+            fs::remove_dir_all(oldest)?;
+        }
+        for index in (1..self.backups).rev() {
+            let from = backups.join(index.to_string());
+            if from.is_dir() {
+                fs::rename(from, backups.join((index + 1).to_string()))?;
+            }
+        }
+
+        let newest = backups.join("1");
+        fs::create_dir_all(&newest)?;
+        for file in [METADATA_FILE, STATE_FILE, LEGACY_STATE_FILE] {
+            let path = slot.join(file);
+            if path.is_file() {
+                fs::copy(path, newest.join(file))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn read_metadata(name: &str, dir: &Path) -> Result<SaveMetadata> {
+    let text = read_text(name, &dir.join(METADATA_FILE))?;
+    let metadata: SaveMetadata =
+        ron::from_str(&text).map_err(|e| Error::resource_load(name, e.to_string()))?;
+    if metadata.version > SAVE_VERSION {
+        return Err(Error::resource_load(
+            name,
+            format!(
+                "save version {} is newer than supported version {SAVE_VERSION}",
+                metadata.version
+            ),
+        ));
+    }
+    Ok(metadata)
+}
+
+/// Read the state text in `dir`, from the compressed file or a legacy
+/// uncompressed one
+fn read_state_text(name: &str, dir: &Path) -> Result<String> {
+    let data = match fs::read(dir.join(STATE_FILE)) {
+        Ok(bytes) => decode(name, &bytes)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return read_text(name, &dir.join(LEGACY_STATE_FILE));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    String::from_utf8(data).map_err(|e| Error::resource_load(name, e.to_string()))
+}
+
+fn load_from<T: SaveGameState>(name: &str, dir: &Path) -> Result<(SaveMetadata, T)> {
+    let metadata = read_metadata(name, dir)?;
+    let state = parse_state(name, &read_state_text(name, dir)?)?;
+    Ok((metadata, state))
+}
+
+/// Check if the save in `dir` is readable and passes its checksum
+fn is_intact(name: &str, dir: &Path) -> bool {
+    read_metadata(name, dir).is_ok() && read_state_text(name, dir).is_ok()
+}
+
+fn not_found(name: &str) -> Error {
+    Error::resource_load(name, "save slot is empty")
+}
+
+fn to_ron<T: Serialize>(value: &T) -> Result<String> {
+    ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+        .map_err(|e| Error::serialization(e.to_string()))
+}
+
+fn read_text(name: &str, path: &Path) -> Result<String> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(text),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(not_found(name)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Write a file through a temporary file so readers never see it half written
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    fs::write(&temp, bytes)?;
+    fs::rename(&temp, path)?;
+    Ok(())
+}
+
+fn remove_if_exists(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct State {
+        mission: u32,
+        position: [f32; 3],
+    }
+
+    impl SaveGameState for State {}
+
+    fn state(mission: u32) -> State {
+        State {
+            mission,
+            position: [0.0; 3],
+        }
+    }
+
+    fn metadata(location: &str, saved_at: u64) -> SaveMetadata {
+        SaveMetadata {
+            saved_at,
+            saved_at_nanos: 0,
+            ..SaveMetadata::new(location, 1500, Duration::from_secs(3600))
+        }
+    }
+
+    #[test]
+    fn test_save_load_list_and_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let slots = SaveSlots::new(dir.path().join("saves"));
+        assert!(slots.list().unwrap().is_empty());
+
+        let state = State {
+            mission: 4,
+            position: [10.0, 0.0, -3.5],
+        };
+        slots
+            .save("slot 1", &metadata("Downtown", 100), &state, Some(b"png"))
+            .unwrap();
+        slots
+            .save("autosave", &metadata("Docks", 200), &state, None)
+            .unwrap();
+
+        let (loaded_metadata, loaded) = slots.load::<State>("slot 1").unwrap();
+        assert_eq!(loaded, state);
+        assert_eq!(loaded_metadata, metadata("Downtown", 100));
+        assert_eq!(
+            slots.thumbnail("slot 1").unwrap().as_deref(),
+            Some(&b"png"[..])
+        );
+        assert_eq!(slots.thumbnail("autosave").unwrap(), None);
+
+        let listed = slots.list().unwrap();
+        let names: Vec<_> = listed.iter().map(|slot| slot.name.as_str()).collect();
+        assert_eq!(names, ["autosave", "slot 1"]);
+        assert!(listed[1].has_thumbnail);
+
+        slots.delete("slot 1").unwrap();
+        assert!(!slots.exists("slot 1"));
+        assert!(matches!(
+            slots.load::<State>("slot 1"),
+            Err(Error::ResourceLoad { .. })
+        ));
+        assert!(slots.delete("slot 1").is_err());
+    }
+
+    #[test]
+    fn test_overwrite_drops_old_thumbnail() {
+        let dir = tempfile::tempdir().unwrap();
+        let slots = SaveSlots::new(dir.path());
+        slots
+            .save("a", &metadata("Downtown", 1), &state(1), Some(b"png"))
+            .unwrap();
+        slots
+            .save("a", &metadata("Docks", 2), &state(2), None)
+            .unwrap();
+
+        assert_eq!(slots.load::<State>("a").unwrap().1, state(2));
+        assert_eq!(slots.thumbnail("a").unwrap(), None);
+        assert_eq!(slots.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_rotates_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let slots = SaveSlots::new(dir.path()).with_backups(2);
+        for mission in 1..=4 {
+            slots
+                .save(
+                    "a",
+                    &metadata("Docks", mission.into()),
+                    &state(mission),
+                    None,
+                )
+                .unwrap();
+        }
+
+        let backups = dir.path().join("a").join(BACKUP_DIR);
+        let (_, newest) = load_from::<State>("a", &backups.join("1")).unwrap();
+        let (_, oldest) = load_from::<State>("a", &backups.join("2")).unwrap();
+        assert_eq!((newest, oldest), (state(3), state(2)));
+        assert!(!backups.join("3").exists());
+    }
+
+    #[test]
+    fn test_corrupt_save_falls_back_to_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let slots = SaveSlots::new(dir.path());
+        slots
+            .save("a", &metadata("Docks", 1), &state(1), None)
+            .unwrap();
+        slots
+            .save("a", &metadata("Docks", 2), &state(2), None)
+            .unwrap();
+
+        let path = dir.path().join("a").join(STATE_FILE);
+        let mut bytes = fs::read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        fs::write(&path, bytes).unwrap();
+
+        let (saved, restored) = slots.load::<State>("a").unwrap();
+        assert_eq!((saved.saved_at, restored), (1, state(1)));
+
+        // The corrupt save is not rotated over the intact backup
+        slots
+            .save("a", &metadata("Docks", 3), &state(3), None)
+            .unwrap();
+        let backup = dir.path().join("a").join(BACKUP_DIR).join("1");
+        assert_eq!(load_from::<State>("a", &backup).unwrap().1, state(1));
+
+        let no_backups = SaveSlots::new(dir.path().join("b")).with_backups(0);
+        no_backups
+            .save("a", &metadata("Docks", 1), &state(1), None)
+            .unwrap();
+        fs::write(dir.path().join("b/a").join(STATE_FILE), b"garbage").unwrap();
+        assert!(no_backups.load::<State>("a").is_err());
+    }
+
+    #[test]
+    fn test_corrupt_save_falls_back_to_newest_autosave() {
+        let dir = tempfile::tempdir().unwrap();
+        let slots = SaveSlots::new(dir.path()).with_backups(0);
+        slots
+            .save("a", &metadata("Docks", 1), &state(1), None)
+            .unwrap();
+        slots
+            .save("autosave-1", &metadata("Docks", 3), &state(3), None)
+            .unwrap();
+        slots
+            .save("autosave-2", &metadata("Docks", 4), &state(4), None)
+            .unwrap();
+        slots
+            .save("autosave-3", &metadata("Docks", 2), &state(2), None)
+            .unwrap();
+        slots
+            .save("autosave-old", &metadata("Docks", 9), &state(9), None)
+            .unwrap();
+        fs::write(dir.path().join("a").join(STATE_FILE), b"garbage").unwrap();
+        fs::write(dir.path().join("autosave-2").join(STATE_FILE), b"garbage").unwrap();
+
+        // autosave-2 is newest but corrupt, and autosave-old is not in the ring
+        let (saved, restored) = slots.load::<State>("a").unwrap();
+        assert_eq!((saved.saved_at, restored), (3, state(3)));
+        let (_, restored) = slots.load::<State>("autosave-2").unwrap();
+        assert_eq!(restored, state(3));
+
+        assert!(slots.load::<State>("never saved").is_err());
+    }
+
+    #[test]
+    fn test_rejects_unsafe_names_and_newer_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let slots = SaveSlots::new(dir.path());
+        for name in ["", "../escape", "a/b", " padded"] {
+            assert!(matches!(
+                slots.save(name, &metadata("Docks", 1), &state(0), None),
+                Err(Error::Validation { .. })
+            ));
+        }
+
+        let newer = SaveMetadata {
+            version: SAVE_VERSION + 1,
+            ..metadata("Docks", 1)
+        };
+        slots.save("future", &newer, &state(0), None).unwrap();
+        assert!(slots.metadata("future").is_err());
+        assert!(slots.list().unwrap().is_empty());
+    }
+}
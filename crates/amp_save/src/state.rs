@@ -0,0 +1,135 @@
+//! Save state versioning and migration
+//!
+//! The state file of a save starts with a `version` field holding the
+//! [`SaveGameState::VERSION`] it was written in; files without one predate
+//! versioning and are treated as [`INITIAL_VERSION`]. Older states are
+//! upgraded on load through the type's [`MigrationRegistry`], the same
+//! mechanism config_core uses for config documents.
+
+use amp_core::{Error, Result};
+use config_core::{document_version, MigrationRegistry, INITIAL_VERSION, VERSION_KEY};
+use ron::Value;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Game state stored in a save slot
+///
+/// Bump [`SaveGameState::VERSION`] whenever the serialized form changes and
+/// register a migration from the previous version, so saves from earlier
+/// releases keep loading. Keep a save of every old version in the test
+/// fixtures.
+///
+/// # Examples
+///
+/// ```rust
+/// use amp_save::SaveGameState;
+/// use config_core::MigrationRegistry;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct GameState {
+///     money: i64,
+/// }
+///
+/// impl SaveGameState for GameState {
+///     const VERSION: u32 = 2;
+///
+///     fn migrations() -> MigrationRegistry {
+///         // Version 2 renamed `cash` to `money`
+///         MigrationRegistry::new().with_migration(1, |doc| {
+///             MigrationRegistry::rename_field(doc, "cash", "money");
+///             Ok(())
+///         })
+///     }
+/// }
+/// ```
+pub trait SaveGameState: Serialize + DeserializeOwned {
+    /// Current version of the serialized state
+    const VERSION: u32 = INITIAL_VERSION;
+
+    /// Migrations upgrading older states to [`SaveGameState::VERSION`]
+    fn migrations() -> MigrationRegistry {
+        MigrationRegistry::new()
+    }
+}
+
+/// Render a state with a leading version field
+pub(crate) fn render_state<T: SaveGameState>(state: &T) -> Result<String> {
+    let body = ron::ser::to_string_pretty(state, ron::ser::PrettyConfig::default())
+        .map_err(|e| Error::serialization(e.to_string()))?;
+    let fields = body
+        .strip_prefix('(')
+        .ok_or_else(|| Error::serialization("save states must serialize as structs"))?;
+    Ok(format!("(\n    {VERSION_KEY}: {},{fields}\n", T::VERSION))
+}
+
+/// Parse the state of slot `name`, migrating it if it is older
+///
+/// Current states are deserialized directly so typed RON such as enum
+/// variants is preserved.
+pub(crate) fn parse_state<T: SaveGameState>(name: &str, text: &str) -> Result<T> {
+    let invalid = |e: &dyn std::fmt::Display| Error::resource_load(name, e.to_string());
+
+    let mut doc: Value = ron::from_str(text).map_err(|e| invalid(&e))?;
+    if document_version(&doc).map_err(|e| invalid(&e))? == T::VERSION {
+        return ron::from_str(text).map_err(|e| invalid(&e));
+    }
+
+    T::migrations()
+        .migrate(&mut doc, T::VERSION)
+        .map_err(|e| invalid(&e))?;
+    doc.into_rust().map_err(|e| invalid(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct State {
+        money: i64,
+    }
+
+    impl SaveGameState for State {
+        const VERSION: u32 = 3;
+
+        fn migrations() -> MigrationRegistry {
+            MigrationRegistry::new()
+                .with_migration(1, |doc| {
+                    MigrationRegistry::rename_field(doc, "cash", "dollars");
+                    Ok(())
+                })
+                .with_migration(2, |doc| {
+                    MigrationRegistry::rename_field(doc, "dollars", "money");
+                    Ok(())
+                })
+        }
+    }
+
+    #[test]
+    fn test_round_trip_writes_version_header() {
+        let text = render_state(&State { money: 5 }).unwrap();
+        assert!(text.starts_with("(\n    version: 3,"));
+        assert_eq!(
+            parse_state::<State>("a", &text).unwrap(),
+            State { money: 5 }
+        );
+    }
+
+    #[test]
+    fn test_migrates_unversioned_and_rejects_newer() {
+        assert_eq!(
+            parse_state::<State>("a", "(cash: 7)").unwrap(),
+            State { money: 7 }
+        );
+        assert_eq!(
+            parse_state::<State>("a", "(version: 2, dollars: 8)").unwrap(),
+            State { money: 8 }
+        );
+        assert!(matches!(
+            parse_state::<State>("a", "(version: 4, money: 1)"),
+            Err(Error::ResourceLoad { .. })
+        ));
+    }
+}
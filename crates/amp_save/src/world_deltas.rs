@@ -0,0 +1,292 @@
+//! Persistent world changes
+//!
+//! Sectors are rebuilt from their layout every time they stream in, so
+//! without help a destroyed lamp post or a moved car reappears as it was
+//! placed. Placed entities that can change carry a [`WorldEntityId`], their
+//! sector's region id and their index in its layout. [`WorldDeltas`] records
+//! how they differ from the layout: [`record_world_deltas`] tracks moves and
+//! [`WorldDeltaEvent`]s from gameplay record destruction and opened gates.
+//! [`apply_world_deltas`] replays the recorded changes when a sector's
+//! entities spawn again. Store [`WorldDeltas`] in the game's save state to
+//! keep the changes across loads.
+
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::DespawnRecursiveExt;
+use bevy_math::{Quat, Vec3};
+use bevy_transform::components::Transform;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Identity of a placed world entity that persists across streaming
+#[derive(
+    Component, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub struct WorldEntityId {
+    /// Region id of the sector that places the entity
+    pub sector: u64,
+    /// Index of the entity in the sector's layout
+    pub index: u32,
+}
+
+/// Whether a gate, door or barrier is open
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OpenState {
+    /// Whether it is open
+    pub open: bool,
+}
+
+/// Change to a placed entity made by gameplay
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorldDeltaEvent {
+    /// The entity was destroyed and must not respawn
+    Destroyed(WorldEntityId),
+    /// The entity was opened or closed
+    SetOpen(WorldEntityId, bool),
+}
+
+/// Position and orientation of a moved entity
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SavedTransform {
+    /// Translation
+    pub translation: [f32; 3],
+    /// Rotation quaternion, `[x, y, z, w]`
+    pub rotation: [f32; 4],
+}
+
+impl From<&Transform> for SavedTransform {
+    fn from(transform: &Transform) -> Self {
+        Self {
+            translation: transform.translation.to_array(),
+            rotation: transform.rotation.to_array(),
+        }
+    }
+}
+
+impl SavedTransform {
+    /// Apply to a transform, keeping its scale
+    pub fn apply(&self, transform: &mut Transform) {
+        transform.translation = Vec3::from_array(self.translation);
+        transform.rotation = Quat::from_array(self.rotation);
+    }
+}
+
+/// How a placed entity differs from its sector's layout
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EntityDelta {
+    /// Destroyed, so it is not spawned again
+    pub destroyed: bool,
+    /// Moved away from its placed transform
+    pub transform: Option<SavedTransform>,
+    /// Opened or closed
+    pub open: Option<bool>,
+}
+
+/// Recorded changes to placed entities, by sector
+#[derive(Resource, Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct WorldDeltas {
+    sectors: BTreeMap<u64, BTreeMap<u32, EntityDelta>>,
+}
+
+impl WorldDeltas {
+    /// Get the recorded change to an entity
+    pub fn get(&self, id: WorldEntityId) -> Option<&EntityDelta> {
+        self.sectors.get(&id.sector)?.get(&id.index)
+    }
+
+    /// Get the recorded changes in a sector, by layout index
+    pub fn sector(&self, sector: u64) -> impl Iterator<Item = (u32, &EntityDelta)> {
+        self.sectors
+            .get(&sector)
+            .into_iter()
+            .flatten()
+            .map(|(index, delta)| (*index, delta))
+    }
+
+    /// Record that an entity was destroyed
+    pub fn record_destroyed(&mut self, id: WorldEntityId) {
+        *self.entry(id) = EntityDelta {
+            destroyed: true,
+            ..EntityDelta::default()
+        };
+    }
+
+    /// Record that an entity moved
+    pub fn record_moved(&mut self, id: WorldEntityId, transform: &Transform) {
+        self.entry(id).transform = Some(transform.into());
+    }
+
+    /// Record that an entity was opened or closed
+    pub fn record_open(&mut self, id: WorldEntityId, open: bool) {
+        self.entry(id).open = Some(open);
+    }
+
+    /// Forget the changes in a sector, such as when a mission resets it
+    pub fn clear_sector(&mut self, sector: u64) {
+        self.sectors.remove(&sector);
+    }
+
+    /// Get the number of changed entities
+    pub fn len(&self) -> usize {
+        self.sectors.values().map(BTreeMap::len).sum()
+    }
+
+    /// Check if no entity has changed
+    pub fn is_empty(&self) -> bool {
+        self.sectors.is_empty()
+    }
+
+    fn entry(&mut self, id: WorldEntityId) -> &mut EntityDelta {
+        self.sectors
+            .entry(id.sector)
+            .or_default()
+            .entry(id.index)
+            .or_default()
+    }
+}
+
+/// Record moves of placed entities and changes reported by gameplay
+///
+/// Destroyed entities are despawned with their children. Transform changes in the frame an
+/// entity spawns are the sector placing it, not moves, and are ignored.
+pub fn record_world_deltas(
+    mut commands: Commands,
+    mut deltas: ResMut<WorldDeltas>,
+    mut events: EventReader<WorldDeltaEvent>,
+    moved: Query<(&WorldEntityId, Ref<Transform>), Changed<Transform>>,
+    mut entities: Query<(Entity, &WorldEntityId, Option<&mut OpenState>)>,
+) {
+    for (id, transform) in &moved {
+        if !transform.is_added() {
+            deltas.record_moved(*id, &transform);
+        }
+    }
+
+    for event in events.read() {
+        let (id, open) = match *event {
+            WorldDeltaEvent::Destroyed(id) => {
+                deltas.record_destroyed(id);
+                (id, None)
+            }
+            WorldDeltaEvent::SetOpen(id, open) => {
+                deltas.record_open(id, open);
+                (id, Some(open))
+            }
+        };
+        for (entity, _, state) in entities.iter_mut().filter(|(_, other, _)| **other == id) {
+            match (open, state) {
+                (None, _) => commands.entity(entity).despawn_recursive(),
+                (Some(open), Some(mut state)) => state.open = open,
+                (Some(open), None) => {
+                    commands.entity(entity).insert(OpenState { open });
+                }
+            }
+        }
+    }
+}
+
+type SpawnedEntity<'a> = (
+    Entity,
+    &'a WorldEntityId,
+    Option<&'a mut Transform>,
+    Option<&'a mut OpenState>,
+);
+
+/// Replay recorded changes on placed entities as their sector spawns them
+pub fn apply_world_deltas(
+    mut commands: Commands,
+    deltas: Res<WorldDeltas>,
+    mut spawned: Query<SpawnedEntity, Added<WorldEntityId>>,
+) {
+    for (entity, id, transform, state) in &mut spawned {
+        let Some(delta) = deltas.get(*id) else {
+            continue;
+        };
+        if delta.destroyed {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        if let (Some(saved), Some(mut transform)) = (delta.transform, transform) {
+            saved.apply(&mut transform);
+        }
+        match (delta.open, state) {
+            (Some(open), Some(mut state)) => state.open = open,
+            (Some(open), None) => {
+                commands.entity(entity).insert(OpenState { open });
+            }
+            (None, _) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::schedule::Schedule;
+    use bevy_hierarchy::BuildWorldChildren;
+
+    fn id(index: u32) -> WorldEntityId {
+        WorldEntityId { sector: 7, index }
+    }
+
+    fn streaming_schedule() -> Schedule {
+        let mut schedule = Schedule::default();
+        schedule.add_systems((apply_world_deltas, record_world_deltas).chain());
+        schedule
+    }
+
+    /// Spawn the sector's layout: a lamp with its light, a car and a gate
+    fn stream_in(world: &mut World) -> [Entity; 4] {
+        let light = world.spawn(Transform::default()).id();
+        [
+            world
+                .spawn((id(0), Transform::default()))
+                .add_child(light)
+                .id(),
+            light,
+            world.spawn((id(1), Transform::default())).id(),
+            world
+                .spawn((id(2), Transform::default(), OpenState::default()))
+                .id(),
+        ]
+    }
+
+    #[test]
+    fn test_deltas_replay_when_sector_streams_in() {
+        let mut world = World::new();
+        world.init_resource::<WorldDeltas>();
+        world.init_resource::<Events<WorldDeltaEvent>>();
+        let mut schedule = streaming_schedule();
+
+        let [lamp, light, car, gate] = stream_in(&mut world);
+        schedule.run(&mut world);
+        assert!(world.resource::<WorldDeltas>().is_empty());
+
+        world.send_event(WorldDeltaEvent::Destroyed(id(0)));
+        world.send_event(WorldDeltaEvent::SetOpen(id(2), true));
+        world.get_mut::<Transform>(car).unwrap().translation = Vec3::new(4.0, 0.0, 2.0);
+        schedule.run(&mut world);
+        assert!(world.get_entity(lamp).is_none());
+        assert!(world.get_entity(light).is_none());
+        assert!(world.get::<OpenState>(gate).unwrap().open);
+        assert_eq!(world.resource::<WorldDeltas>().len(), 3);
+
+        // Save, stream the sector out and load into a fresh world
+        let saved = ron::to_string(world.resource::<WorldDeltas>()).unwrap();
+        let mut world = World::new();
+        world.insert_resource(ron::from_str::<WorldDeltas>(&saved).unwrap());
+        world.init_resource::<Events<WorldDeltaEvent>>();
+        let [lamp, light, car, gate] = stream_in(&mut world);
+        streaming_schedule().run(&mut world);
+
+        assert!(world.get_entity(lamp).is_none());
+        assert!(world.get_entity(light).is_none());
+        assert_eq!(
+            world.get::<Transform>(car).unwrap().translation,
+            Vec3::new(4.0, 0.0, 2.0)
+        );
+        assert!(world.get::<OpenState>(gate).unwrap().open);
+        assert_eq!(world.resource::<WorldDeltas>().len(), 3);
+    }
+}
@@ -0,0 +1,78 @@
+//! Saves written by earlier versions must keep loading
+//!
+//! Every directory in `tests/fixtures/saves` is a save slot in an old
+//! format. When the state format changes, add a fixture of the previous
+//! version and a migration; never edit existing fixtures.
+
+use amp_save::{SaveGameState, SaveSlots};
+use config_core::MigrationRegistry;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct GameState {
+    mission: u32,
+    money: i64,
+    position: [f32; 3],
+    #[serde(default)]
+    wanted_level: u8,
+}
+
+impl SaveGameState for GameState {
+    const VERSION: u32 = 3;
+
+    fn migrations() -> MigrationRegistry {
+        MigrationRegistry::new()
+            // Version 2 renamed `cash` to `money`
+            .with_migration(1, |doc| {
+                MigrationRegistry::rename_field(doc, "cash", "money");
+                Ok(())
+            })
+            // Version 3 added `wanted_level`, defaulting to 0
+            .with_migration(2, |_| Ok(()))
+    }
+}
+
+fn fixtures() -> SaveSlots {
+    SaveSlots::new(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/saves"))
+}
+
+#[test]
+fn test_every_fixture_loads() {
+    let slots = fixtures();
+    let listed = slots.list().unwrap();
+    assert_eq!(listed.len(), 2);
+    for slot in listed {
+        if let Err(e) = slots.load::<GameState>(&slot.name) {
+            panic!("fixture '{}' no longer loads: {e}", slot.name);
+        }
+    }
+}
+
+#[test]
+fn test_unversioned_save_is_migrated() {
+    let (metadata, state) = fixtures().load::<GameState>("unversioned").unwrap();
+    assert_eq!(metadata.location, "Downtown");
+    assert_eq!(
+        state,
+        GameState {
+            mission: 3,
+            money: 1500,
+            position: [12.5, 0.0, -40.0],
+            wanted_level: 0,
+        }
+    );
+}
+
+#[test]
+fn test_migrated_save_is_rewritten_in_current_version() {
+    let (metadata, state) = fixtures().load::<GameState>("v2").unwrap();
+    assert_eq!(state.money, 2750);
+
+    let dir = tempfile::tempdir().unwrap();
+    let slots = SaveSlots::new(dir.path());
+    slots.save("v2", &metadata, &state, None).unwrap();
+    assert!(dir.path().join("v2/state.sav").is_file());
+    assert!(!dir.path().join("v2/state.ron").exists());
+    assert_eq!(slots.load::<GameState>("v2").unwrap().1, state);
+}
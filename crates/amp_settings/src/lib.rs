@@ -0,0 +1,39 @@
+//! Player settings and the settings menu
+//!
+//! [`GameSettings`] holds the graphics, audio and gameplay options in a
+//! config_core configuration, `settings.ron`; the controls page edits the
+//! input crate's [`InputSettings`](amp_input::InputSettings). The
+//! [`SettingsMenu`] is a UI-independent model of the menu: it is driven by
+//! the menu actions, publishes every change immediately so listeners of
+//! [`ConfigChanged`](config_core::ConfigChanged) (the renderer, audio mixer
+//! and culling) apply it live, and writes the user layer files through
+//! config_core when closed, so the next launch loads them.
+//!
+//! # Features
+//!
+//! - `unstable_advanced_input`: the settings menu pushes the menu input
+//!   context while it is open, see `amp_input::contexts`
+
+#![deny(missing_docs)]
+
+pub mod menu;
+pub mod settings;
+
+pub use menu::{
+    apply_settings_menu, navigate_settings_menu, SettingItem, SettingValue, SettingsMenu,
+    SettingsPage, SettingsPaths,
+};
+pub use settings::{AudioSettings, GameSettings, GameplaySettings, GraphicsSettings};
+
+use bevy_ecs::schedule::{IntoSystemConfigs, SystemConfigs, SystemSet};
+
+/// System set of the per-frame settings menu systems added by [`systems`]
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SettingsSystems;
+
+/// Get the per-frame settings menu systems, ordered and in [`SettingsSystems`]
+pub fn systems() -> SystemConfigs {
+    (navigate_settings_menu, apply_settings_menu)
+        .chain()
+        .in_set(SettingsSystems)
+}
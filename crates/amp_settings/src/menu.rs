@@ -0,0 +1,693 @@
+//! Settings menu model
+//!
+//! The menu is a list of pages, each a list of items the player selects
+//! with the menu actions and changes with left/right or confirm. Row 0 of
+//! every page is the page selector. A UI draws [`SettingsMenu::items`];
+//! none of the logic here depends on how it is drawn.
+
+use crate::settings::{GameSettings, GraphicsSettings};
+use amp_input::{Action, ActionInput, InputSettings};
+#[cfg(feature = "unstable_advanced_input")]
+use amp_input::{InputContext, InputContextRequest};
+use bevy_ecs::prelude::*;
+use config_core::{publish_config, save_config, ConfigHandle, ConfigLoader};
+use std::path::PathBuf;
+
+/// A page of the settings menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SettingsPage {
+    /// Resolution, vsync and view distance
+    #[default]
+    Graphics,
+    /// Volume levels
+    Audio,
+    /// Sensitivity and accessibility options
+    Controls,
+    /// Subtitles, units and camera shake
+    Gameplay,
+}
+
+impl SettingsPage {
+    /// Every page, in menu order
+    pub const ALL: [SettingsPage; 4] = [
+        SettingsPage::Graphics,
+        SettingsPage::Audio,
+        SettingsPage::Controls,
+        SettingsPage::Gameplay,
+    ];
+
+    /// Get the page title
+    pub fn title(self) -> &'static str {
+        match self {
+            SettingsPage::Graphics => "Graphics",
+            SettingsPage::Audio => "Audio",
+            SettingsPage::Controls => "Controls",
+            SettingsPage::Gameplay => "Gameplay",
+        }
+    }
+
+    fn fields(self) -> &'static [Field] {
+        match self {
+            SettingsPage::Graphics => GRAPHICS,
+            SettingsPage::Audio => AUDIO,
+            SettingsPage::Controls => CONTROLS,
+            SettingsPage::Gameplay => GAMEPLAY,
+        }
+    }
+}
+
+/// Current value of a menu item, for display
+#[derive(Debug, Clone, PartialEq)]
+pub enum SettingValue {
+    /// An on/off option
+    Toggle(bool),
+    /// A number within a range
+    Slider {
+        /// Current value
+        value: f32,
+        /// Lowest value
+        min: f32,
+        /// Highest value
+        max: f32,
+    },
+    /// One of a list of options
+    Choice {
+        /// Option labels
+        options: Vec<String>,
+        /// Index of the selected option
+        index: usize,
+    },
+}
+
+/// A labelled menu item
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingItem {
+    /// Label shown to the player
+    pub label: &'static str,
+    /// Current value
+    pub value: SettingValue,
+}
+
+/// Settings being edited
+#[derive(Debug, Clone, Default)]
+struct Drafts {
+    game: GameSettings,
+    input: InputSettings,
+}
+
+/// Which configuration a field edits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Target {
+    Game,
+    Input,
+}
+
+/// How a field is shown and changed
+enum Kind {
+    Toggle(fn(&mut Drafts) -> &mut bool),
+    Slider {
+        value: fn(&mut Drafts) -> &mut f32,
+        min: f32,
+        max: f32,
+        step: f32,
+    },
+    Resolution,
+}
+
+struct Field {
+    label: &'static str,
+    target: Target,
+    kind: Kind,
+}
+
+const fn toggle(label: &'static str, target: Target, value: fn(&mut Drafts) -> &mut bool) -> Field {
+    Field {
+        label,
+        target,
+        kind: Kind::Toggle(value),
+    }
+}
+
+const fn slider(
+    label: &'static str,
+    target: Target,
+    value: fn(&mut Drafts) -> &mut f32,
+    (min, max, step): (f32, f32, f32),
+) -> Field {
+    Field {
+        label,
+        target,
+        kind: Kind::Slider {
+            value,
+            min,
+            max,
+            step,
+        },
+    }
+}
+
+const VOLUME: (f32, f32, f32) = (0.0, 1.0, 0.1);
+
+const GRAPHICS: &[Field] = &[
+    Field {
+        label: "Resolution",
+        target: Target::Game,
+        kind: Kind::Resolution,
+    },
+    toggle("Fullscreen", Target::Game, |d| {
+        &mut d.game.graphics.fullscreen
+    }),
+    toggle("VSync", Target::Game, |d| &mut d.game.graphics.vsync),
+    slider(
+        "View distance",
+        Target::Game,
+        |d| &mut d.game.graphics.cull_distance,
+        (100.0, 2000.0, 50.0),
+    ),
+];
+
+const AUDIO: &[Field] = &[
+    slider(
+        "Master volume",
+        Target::Game,
+        |d| &mut d.game.audio.master_volume,
+        VOLUME,
+    ),
+    slider(
+        "Music volume",
+        Target::Game,
+        |d| &mut d.game.audio.music_volume,
+        VOLUME,
+    ),
+    slider(
+        "Effects volume",
+        Target::Game,
+        |d| &mut d.game.audio.effects_volume,
+        VOLUME,
+    ),
+    slider(
+        "Radio volume",
+        Target::Game,
+        |d| &mut d.game.audio.radio_volume,
+        VOLUME,
+    ),
+    slider(
+        "Dialogue volume",
+        Target::Game,
+        |d| &mut d.game.audio.dialogue_volume,
+        VOLUME,
+    ),
+];
+
+const CONTROLS: &[Field] = &[
+    slider(
+        "Mouse sensitivity",
+        Target::Input,
+        |d| &mut d.input.mouse_sensitivity,
+        (0.0005, 0.01, 0.0005),
+    ),
+    slider(
+        "Stick sensitivity",
+        Target::Input,
+        |d| &mut d.input.camera_sensitivity,
+        (0.5, 10.0, 0.5),
+    ),
+    toggle("Invert look", Target::Input, |d| {
+        &mut d.input.invert_camera_y
+    }),
+    slider(
+        "Stick dead zone",
+        Target::Input,
+        |d| &mut d.input.stick_dead_zone,
+        (0.0, 0.5, 0.05),
+    ),
+    toggle("Toggle sprint", Target::Input, |d| {
+        &mut d.input.accessibility.toggle_sprint
+    }),
+    toggle("Toggle crouch", Target::Input, |d| {
+        &mut d.input.accessibility.toggle_crouch
+    }),
+    toggle("Toggle aim", Target::Input, |d| {
+        &mut d.input.accessibility.toggle_aim
+    }),
+    slider(
+        "Steering assist",
+        Target::Input,
+        |d| &mut d.input.accessibility.steering_assist,
+        (0.0, 1.0, 0.25),
+    ),
+    toggle("Auto-accelerate", Target::Input, |d| {
+        &mut d.input.accessibility.auto_accelerate
+    }),
+];
+
+const GAMEPLAY: &[Field] = &[
+    toggle("Subtitles", Target::Game, |d| {
+        &mut d.game.gameplay.subtitles
+    }),
+    toggle("Metric units", Target::Game, |d| {
+        &mut d.game.gameplay.metric_units
+    }),
+    toggle("Camera shake", Target::Game, |d| {
+        &mut d.game.gameplay.camera_shake
+    }),
+];
+
+/// Files the settings menu saves to when closed
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct SettingsPaths {
+    /// User layer of [`GameSettings`]
+    pub game: Option<PathBuf>,
+    /// User layer of [`InputSettings`]
+    pub input: Option<PathBuf>,
+}
+
+impl Default for SettingsPaths {
+    fn default() -> Self {
+        Self {
+            game: ConfigLoader::user_path::<GameSettings>(),
+            input: ConfigLoader::user_path::<InputSettings>(),
+        }
+    }
+}
+
+/// State of the settings menu
+#[derive(Resource, Debug, Default)]
+pub struct SettingsMenu {
+    open: bool,
+    page: SettingsPage,
+    selected: usize,
+    drafts: Drafts,
+    game_changed: bool,
+    input_changed: bool,
+    save_pending: bool,
+    #[cfg(feature = "unstable_advanced_input")]
+    context_requests: Vec<InputContextRequest>,
+}
+
+impl SettingsMenu {
+    /// Open the menu on the first page, editing copies of the given settings
+    ///
+    /// With the `unstable_advanced_input` feature, [`apply_settings_menu`]
+    /// then pushes `InputContext::Menu`.
+    pub fn open(&mut self, game: &GameSettings, input: &InputSettings) {
+        #[cfg(feature = "unstable_advanced_input")]
+        let mut context_requests = std::mem::take(&mut self.context_requests);
+        #[cfg(feature = "unstable_advanced_input")]
+        if !self.open {
+            context_requests.push(InputContextRequest::Push(InputContext::Menu));
+        }
+        *self = Self {
+            open: true,
+            drafts: Drafts {
+                game: game.clone(),
+                input: input.clone(),
+            },
+            #[cfg(feature = "unstable_advanced_input")]
+            context_requests,
+            ..Self::default()
+        };
+    }
+
+    /// Close the menu; [`apply_settings_menu`] then saves the settings
+    ///
+    /// With the `unstable_advanced_input` feature, it also pops
+    /// `InputContext::Menu` again.
+    pub fn close(&mut self) {
+        if self.open {
+            self.open = false;
+            self.save_pending = true;
+            #[cfg(feature = "unstable_advanced_input")]
+            self.context_requests
+                .push(InputContextRequest::Pop(InputContext::Menu));
+        }
+    }
+
+    /// Check if the menu is open
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Get the page shown
+    pub fn page(&self) -> SettingsPage {
+        self.page
+    }
+
+    /// Show a page, selecting its page selector row
+    pub fn set_page(&mut self, page: SettingsPage) {
+        self.page = page;
+        self.selected = 0;
+    }
+
+    /// Get the selected row; 0 is the page selector and item `i` is row `i + 1`
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Move the selection by `rows`, wrapping around
+    pub fn select(&mut self, rows: i32) {
+        let len = self.page.fields().len() as i32 + 1;
+        self.selected = (self.selected as i32 + rows).rem_euclid(len) as usize;
+    }
+
+    /// Get the items of the page shown
+    pub fn items(&self) -> Vec<SettingItem> {
+        // Reading a field goes through the same accessor as writing it
+        let mut drafts = self.drafts.clone();
+        self.page
+            .fields()
+            .iter()
+            .map(|field| SettingItem {
+                label: field.label,
+                value: match field.kind {
+                    Kind::Toggle(value) => SettingValue::Toggle(*value(&mut drafts)),
+                    Kind::Slider {
+                        value, min, max, ..
+                    } => SettingValue::Slider {
+                        value: *value(&mut drafts),
+                        min,
+                        max,
+                    },
+                    Kind::Resolution => {
+                        let (options, index) = resolutions(drafts.game.graphics.resolution);
+                        SettingValue::Choice {
+                            options: options
+                                .iter()
+                                .map(|(width, height)| format!("{width}x{height}"))
+                                .collect(),
+                            index,
+                        }
+                    }
+                },
+            })
+            .collect()
+    }
+
+    /// Change the selected row by `steps`: the page on the page selector,
+    /// otherwise the item's value
+    pub fn adjust(&mut self, steps: i32) {
+        let Some(field) = self.selected_field() else {
+            let index = SettingsPage::ALL
+                .iter()
+                .position(|page| *page == self.page)
+                .unwrap_or_default() as i32;
+            let len = SettingsPage::ALL.len() as i32;
+            self.set_page(SettingsPage::ALL[(index + steps).rem_euclid(len) as usize]);
+            return;
+        };
+
+        match field.kind {
+            Kind::Toggle(value) => {
+                if steps != 0 {
+                    let value = value(&mut self.drafts);
+                    *value = !*value;
+                }
+            }
+            Kind::Slider {
+                value,
+                min,
+                max,
+                step,
+            } => {
+                let value = value(&mut self.drafts);
+                let stepped = ((*value / step).round() + steps as f32) * step;
+                *value = stepped.clamp(min, max);
+            }
+            Kind::Resolution => {
+                let resolution = &mut self.drafts.game.graphics.resolution;
+                let (options, index) = resolutions(*resolution);
+                let len = options.len() as i32;
+                *resolution = options[(index as i32 + steps).rem_euclid(len) as usize];
+            }
+        }
+        self.mark_changed(field.target);
+    }
+
+    /// Get the settings being edited
+    pub fn game(&self) -> &GameSettings {
+        &self.drafts.game
+    }
+
+    /// Get the input settings being edited
+    pub fn input(&self) -> &InputSettings {
+        &self.drafts.input
+    }
+
+    fn selected_field(&self) -> Option<&'static Field> {
+        self.selected
+            .checked_sub(1)
+            .and_then(|index| self.page.fields().get(index))
+    }
+
+    fn mark_changed(&mut self, target: Target) {
+        match target {
+            Target::Game => self.game_changed = true,
+            Target::Input => self.input_changed = true,
+        }
+    }
+}
+
+/// Offered resolutions, including `current` if it is not a preset, and the
+/// index of `current`
+fn resolutions(current: (u32, u32)) -> (Vec<(u32, u32)>, usize) {
+    let mut options = GraphicsSettings::RESOLUTIONS.to_vec();
+    if !options.contains(&current) {
+        options.push(current);
+        options.sort();
+    }
+    let index = options
+        .iter()
+        .position(|option| *option == current)
+        .unwrap_or_default();
+    (options, index)
+}
+
+/// Drive the open [`SettingsMenu`] with the menu actions
+///
+/// Up/down select a row, left/right change it, confirm toggles or cycles
+/// forward and back closes the menu.
+pub fn navigate_settings_menu(actions: ActionInput, mut menu: ResMut<SettingsMenu>) {
+    if !menu.is_open() {
+        return;
+    }
+    if actions.just_pressed(Action::MenuBack) {
+        menu.close();
+        return;
+    }
+
+    let pressed = |action| i32::from(actions.just_pressed(action));
+    let rows = pressed(Action::MenuDown) - pressed(Action::MenuUp);
+    if rows != 0 {
+        menu.select(rows);
+    }
+    let steps = pressed(Action::MenuRight) - pressed(Action::MenuLeft);
+    if steps != 0 {
+        menu.adjust(steps);
+    } else if actions.just_pressed(Action::MenuConfirm) && menu.selected() > 0 {
+        menu.adjust(1);
+    }
+}
+
+/// Publish changes made in the [`SettingsMenu`] and save them once it closes
+///
+/// Changes are published to [`ConfigHandle<GameSettings>`] and
+/// [`ConfigHandle<InputSettings>`] as they are made, so they apply live.
+/// Changes that fail validation are logged and not applied, so they are
+/// never saved either.
+/// Closing writes both to the [`SettingsPaths`], or to the user config
+/// directory if that resource is missing; save failures are logged.
+/// With the `unstable_advanced_input` feature, opening and closing the menu
+/// push and pop `InputContext::Menu` through `InputContextRequest` events,
+/// if the app has registered them.
+///
+/// # Panics
+///
+/// Panics if either config handle has not been initialized.
+pub fn apply_settings_menu(world: &mut World) {
+    let Some(mut menu) = world.get_resource_mut::<SettingsMenu>() else {
+        return;
+    };
+    let game = std::mem::take(&mut menu.game_changed).then(|| menu.drafts.game.clone());
+    let input = std::mem::take(&mut menu.input_changed).then(|| menu.drafts.input.clone());
+    let save = std::mem::take(&mut menu.save_pending);
+    #[cfg(feature = "unstable_advanced_input")]
+    {
+        let requests = std::mem::take(&mut menu.context_requests);
+        if let Some(mut events) = world.get_resource_mut::<Events<InputContextRequest>>() {
+            events.extend(requests);
+        }
+    }
+
+    if let Some(game) = game {
+        if let Err(e) = publish_config(world, game) {
+            log::warn!("failed to apply settings: {e}");
+        }
+    }
+    if let Some(input) = input {
+        if let Err(e) = publish_config(world, input) {
+            log::warn!("failed to apply input settings: {e}");
+        }
+    }
+    if !save {
+        return;
+    }
+
+    let paths = world
+        .get_resource::<SettingsPaths>()
+        .cloned()
+        .unwrap_or_default();
+    if let Some(path) = &paths.game {
+        let game = world.resource::<ConfigHandle<GameSettings>>().get();
+        if let Err(e) = save_config(game, path) {
+            log::warn!("failed to save {}: {e}", path.display());
+        }
+    }
+    if let Some(path) = &paths.input {
+        let input = world.resource::<ConfigHandle<InputSettings>>().get();
+        if let Err(e) = save_config(input, path) {
+            log::warn!("failed to save {}: {e}", path.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amp_input::{ActiveGamepad, InputBindings};
+    use bevy_ecs::schedule::Schedule;
+    use bevy_input::gamepad::{GamepadAxis, GamepadButton};
+    use bevy_input::keyboard::KeyCode;
+    use bevy_input::mouse::MouseButton;
+    use bevy_input::{Axis, ButtonInput};
+    use config_core::{init_config, ConfigChanged, ConfigLayer};
+
+    fn world(dir: &std::path::Path) -> World {
+        let mut world = World::new();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<ButtonInput<MouseButton>>();
+        world.init_resource::<ButtonInput<GamepadButton>>();
+        world.init_resource::<Axis<GamepadButton>>();
+        world.init_resource::<Axis<GamepadAxis>>();
+        world.init_resource::<ActiveGamepad>();
+        init_config(&mut world, InputBindings::default());
+        init_config(&mut world, InputSettings::default());
+        init_config(&mut world, GameSettings::default());
+        world.insert_resource(SettingsPaths {
+            game: Some(dir.join("settings.ron")),
+            input: Some(dir.join("input_settings.ron")),
+        });
+        world.init_resource::<SettingsMenu>();
+        world
+            .resource_mut::<SettingsMenu>()
+            .open(&GameSettings::default(), &InputSettings::default());
+        world
+    }
+
+    fn press(world: &mut World, schedule: &mut Schedule, key: KeyCode) {
+        let mut keys = world.resource_mut::<ButtonInput<KeyCode>>();
+        keys.clear();
+        keys.release_all();
+        keys.press(key);
+        schedule.run(world);
+    }
+
+    #[test]
+    fn test_changes_apply_live_and_save_on_close() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut world = world(dir.path());
+        let mut schedule = Schedule::default();
+        schedule.add_systems((navigate_settings_menu, apply_settings_menu).chain());
+
+        // Graphics: select the resolution and step it down
+        press(&mut world, &mut schedule, KeyCode::ArrowDown);
+        press(&mut world, &mut schedule, KeyCode::ArrowLeft);
+        let game = world.resource::<ConfigHandle<GameSettings>>();
+        assert_eq!(game.get().graphics.resolution, (1600, 900));
+        let changed = world.resource::<Events<ConfigChanged<GameSettings>>>();
+        assert_eq!(changed.len(), 1);
+
+        // Controls: turn on toggle sprint
+        world
+            .resource_mut::<SettingsMenu>()
+            .set_page(SettingsPage::Controls);
+        world.resource_mut::<SettingsMenu>().select(5);
+        press(&mut world, &mut schedule, KeyCode::Enter);
+        let input = world.resource::<ConfigHandle<InputSettings>>();
+        assert!(input.get().accessibility.toggle_sprint);
+        assert!(!dir.path().join("settings.ron").exists());
+
+        press(&mut world, &mut schedule, KeyCode::Escape);
+        assert!(!world.resource::<SettingsMenu>().is_open());
+
+        let loader = ConfigLoader::new();
+        let game_layer = [ConfigLayer::new("user", dir.path().join("settings.ron"))];
+        let (game, _) = loader.load_layers::<GameSettings>(&game_layer).unwrap();
+        assert_eq!(game.graphics.resolution, (1600, 900));
+        let input_layer = [ConfigLayer::new(
+            "user",
+            dir.path().join("input_settings.ron"),
+        )];
+        let (input, _) = loader.load_layers::<InputSettings>(&input_layer).unwrap();
+        assert!(input.accessibility.toggle_sprint);
+    }
+
+    #[cfg(feature = "unstable_advanced_input")]
+    #[test]
+    fn test_open_menu_uses_menu_input_context() {
+        use amp_input::{apply_input_context_requests, ActiveInputContexts};
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut world = world(dir.path());
+        world.init_resource::<ActiveInputContexts>();
+        world.init_resource::<Events<InputContextRequest>>();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            (
+                navigate_settings_menu,
+                apply_settings_menu,
+                apply_input_context_requests,
+            )
+                .chain(),
+        );
+
+        schedule.run(&mut world);
+        let contexts = world.resource::<ActiveInputContexts>();
+        assert_eq!(contexts.current(), InputContext::Menu);
+
+        press(&mut world, &mut schedule, KeyCode::Escape);
+        assert!(!world.resource::<SettingsMenu>().is_open());
+        let contexts = world.resource::<ActiveInputContexts>();
+        assert_eq!(contexts.current(), InputContext::OnFoot);
+    }
+
+    #[test]
+    fn test_items_and_sliders() {
+        let mut menu = SettingsMenu::default();
+        menu.open(&GameSettings::default(), &InputSettings::default());
+        menu.adjust(1);
+        assert_eq!(menu.page(), SettingsPage::Audio);
+        assert_eq!(menu.items().len(), 5);
+
+        menu.select(2);
+        for _ in 0..5 {
+            menu.adjust(1);
+        }
+        assert_eq!(menu.game().audio.music_volume, 1.0);
+        assert_eq!(
+            menu.items()[1].value,
+            SettingValue::Slider {
+                value: 1.0,
+                min: 0.0,
+                max: 1.0
+            }
+        );
+
+        menu.select(-2);
+        menu.adjust(-1);
+        assert_eq!(menu.page(), SettingsPage::Graphics);
+        assert!(matches!(
+            menu.items()[0].value,
+            SettingValue::Choice { index: 2, .. }
+        ));
+    }
+}
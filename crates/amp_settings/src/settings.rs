@@ -0,0 +1,142 @@
+//! Graphics, audio and gameplay settings
+
+use config_core::{Config, Validate, ValidationErrors};
+use serde::{Deserialize, Serialize};
+
+/// Player settings other than controls
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameSettings {
+    /// Display and rendering options
+    pub graphics: GraphicsSettings,
+    /// Volume levels
+    pub audio: AudioSettings,
+    /// Gameplay options
+    pub gameplay: GameplaySettings,
+}
+
+impl Config for GameSettings {
+    const FILE_NAME: &'static str = "settings.ron";
+}
+
+impl Validate for GameSettings {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.nested("graphics", |errors| self.graphics.validate(errors));
+        errors.nested("audio", |errors| self.audio.validate(errors));
+    }
+}
+
+/// Display and rendering options
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GraphicsSettings {
+    /// Window or fullscreen resolution, width and height in pixels
+    pub resolution: (u32, u32),
+    /// Use the whole screen
+    pub fullscreen: bool,
+    /// Wait for vertical sync when presenting frames
+    pub vsync: bool,
+    /// Distance beyond which objects are culled, in meters
+    pub cull_distance: f32,
+}
+
+impl GraphicsSettings {
+    /// Resolutions offered by the settings menu
+    pub const RESOLUTIONS: [(u32, u32); 6] = [
+        (1280, 720),
+        (1600, 900),
+        (1920, 1080),
+        (2560, 1440),
+        (3440, 1440),
+        (3840, 2160),
+    ];
+}
+
+impl Default for GraphicsSettings {
+    fn default() -> Self {
+        Self {
+            resolution: (1920, 1080),
+            fullscreen: false,
+            vsync: true,
+            cull_distance: 500.0,
+        }
+    }
+}
+
+impl Validate for GraphicsSettings {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        let (width, height) = self.resolution;
+        if width < 640 || height < 480 {
+            errors.push_with_allowed(
+                "resolution",
+                format!("{width}x{height} is too small"),
+                "at least 640x480",
+            );
+        }
+        errors.check_range("cull_distance", self.cull_distance, 50.0..=2000.0);
+    }
+}
+
+/// Volume levels, each from 0 (muted) to 1
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    /// Overall volume, scaling every other level
+    pub master_volume: f32,
+    /// Music and score
+    pub music_volume: f32,
+    /// Sound effects
+    pub effects_volume: f32,
+    /// In-car radio
+    pub radio_volume: f32,
+    /// Character dialogue
+    pub dialogue_volume: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            music_volume: 0.8,
+            effects_volume: 1.0,
+            radio_volume: 0.8,
+            dialogue_volume: 1.0,
+        }
+    }
+}
+
+impl Validate for AudioSettings {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        for (field, volume) in [
+            ("master_volume", self.master_volume),
+            ("music_volume", self.music_volume),
+            ("effects_volume", self.effects_volume),
+            ("radio_volume", self.radio_volume),
+            ("dialogue_volume", self.dialogue_volume),
+        ] {
+            errors.check_range(field, volume, 0.0..=1.0);
+        }
+    }
+}
+
+/// Gameplay options
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameplaySettings {
+    /// Show subtitles for dialogue
+    pub subtitles: bool,
+    /// Show speeds and distances in metric units instead of imperial
+    pub metric_units: bool,
+    /// Shake the camera on impacts and explosions
+    pub camera_shake: bool,
+}
+
+impl Default for GameplaySettings {
+    fn default() -> Self {
+        Self {
+            subtitles: true,
+            metric_units: true,
+            camera_shake: true,
+        }
+    }
+}
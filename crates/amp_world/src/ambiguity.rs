@@ -0,0 +1,252 @@
+//! System ordering ambiguity detection
+//!
+//! Two systems are ambiguous when they access the same data, at least one of
+//! them mutably, and nothing orders them. Their relative order can then change
+//! between runs, which shows up as one-frame lag or flickering state.
+//! [`AmbiguityReport`] runs Bevy's ambiguity checker over a schedule and groups
+//! the findings by crate. Tests compare it against a list of known ambiguities
+//! so that new unordered conflicts fail the build.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use bevy_ecs::prelude::*;
+use bevy_ecs::schedule::{LogLevel, NodeId, ScheduleBuildError};
+
+/// A pair of systems with conflicting access and no ordering between them
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Ambiguity {
+    /// Fully qualified name of the first system
+    pub first: String,
+    /// Fully qualified name of the second system
+    pub second: String,
+    /// Names of the components and resources both systems access
+    ///
+    /// Empty when one of the systems has exclusive world access.
+    pub conflicts: Vec<String>,
+}
+
+impl Ambiguity {
+    /// Check whether this is the given pair of systems, in either order
+    ///
+    /// Names are compared by suffix, so `status::update_status_hud` matches
+    /// `amp_hud::status::update_status_hud`.
+    pub fn is_between(&self, a: &str, b: &str) -> bool {
+        let matches =
+            |name: &str, pattern: &str| name == pattern || name.ends_with(&format!("::{pattern}"));
+        (matches(&self.first, a) && matches(&self.second, b))
+            || (matches(&self.first, b) && matches(&self.second, a))
+    }
+
+    /// Crates the two systems are defined in, without duplicates
+    pub fn crates(&self) -> Vec<&str> {
+        let first = crate_name(&self.first);
+        let second = crate_name(&self.second);
+        if first == second {
+            vec![first]
+        } else {
+            vec![first, second]
+        }
+    }
+}
+
+impl fmt::Display for Ambiguity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} <-> {}", self.first, self.second)?;
+        if self.conflicts.is_empty() {
+            write!(f, " (World)")
+        } else {
+            write!(f, " ({})", self.conflicts.join(", "))
+        }
+    }
+}
+
+/// Ambiguities found in a single schedule
+#[derive(Debug, Clone, Default)]
+pub struct AmbiguityReport {
+    ambiguities: Vec<Ambiguity>,
+}
+
+impl AmbiguityReport {
+    /// Build the schedule and collect its ambiguities
+    ///
+    /// The schedule's own ambiguity setting is left untouched; this only reads
+    /// the conflicts computed while building.
+    pub fn from_schedule(
+        schedule: &mut Schedule,
+        world: &mut World,
+    ) -> Result<Self, ScheduleBuildError> {
+        schedule.initialize(world)?;
+
+        // Systems move out of the graph into the executable schedule when it
+        // is built, so names are looked up there.
+        let names: HashMap<NodeId, String> = schedule
+            .systems()
+            .map_err(|_| ScheduleBuildError::Uninitialized)?
+            .map(|(id, system)| (id, system.name().to_string()))
+            .collect();
+        let system_name = |id: &NodeId| names.get(id).cloned().unwrap_or_else(|| format!("{id:?}"));
+        let components = world.components();
+
+        let mut ambiguities: Vec<_> = schedule
+            .graph()
+            .conflicting_systems()
+            .iter()
+            .map(|(a, b, conflicts)| {
+                let (first, second) = {
+                    let (a, b) = (system_name(a), system_name(b));
+                    if a <= b {
+                        (a, b)
+                    } else {
+                        (b, a)
+                    }
+                };
+                let mut conflicts: Vec<_> = conflicts
+                    .iter()
+                    .map(|id| components.get_name(*id).unwrap_or("<unknown>").to_string())
+                    .collect();
+                conflicts.sort();
+                Ambiguity {
+                    first,
+                    second,
+                    conflicts,
+                }
+            })
+            .collect();
+        ambiguities.sort();
+
+        Ok(Self { ambiguities })
+    }
+
+    /// Get all ambiguities, sorted by system name
+    pub fn ambiguities(&self) -> &[Ambiguity] {
+        &self.ambiguities
+    }
+
+    /// Get the number of ambiguities
+    pub fn len(&self) -> usize {
+        self.ambiguities.len()
+    }
+
+    /// Check whether the schedule has no ambiguities
+    pub fn is_empty(&self) -> bool {
+        self.ambiguities.is_empty()
+    }
+
+    /// Group ambiguities by the crates their systems are defined in
+    ///
+    /// An ambiguity between systems from two crates is listed under both.
+    pub fn by_crate(&self) -> BTreeMap<&str, Vec<&Ambiguity>> {
+        let mut groups: BTreeMap<&str, Vec<&Ambiguity>> = BTreeMap::new();
+        for ambiguity in &self.ambiguities {
+            for name in ambiguity.crates() {
+                groups.entry(name).or_default().push(ambiguity);
+            }
+        }
+        groups
+    }
+
+    /// Get ambiguities that are not in the list of known system pairs
+    pub fn unexpected<'a>(
+        &'a self,
+        known: &'a [(&str, &str)],
+    ) -> impl Iterator<Item = &'a Ambiguity> {
+        self.ambiguities
+            .iter()
+            .filter(move |ambiguity| !known.iter().any(|(a, b)| ambiguity.is_between(a, b)))
+    }
+}
+
+impl fmt::Display for AmbiguityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} system ordering ambiguities", self.len())?;
+        for (name, ambiguities) in self.by_crate() {
+            writeln!(f, "{name}:")?;
+            for ambiguity in ambiguities {
+                writeln!(f, "  {ambiguity}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Enable Bevy's ambiguity warnings on a schedule in debug builds
+///
+/// Ambiguities are logged whenever the schedule is built. Release builds are
+/// left unchanged, since checking adds to schedule build time.
+pub fn detect_ambiguities(schedule: &mut Schedule) {
+    if cfg!(debug_assertions) {
+        let mut settings = schedule.get_build_settings();
+        settings.ambiguity_detection = LogLevel::Warn;
+        schedule.set_build_settings(settings);
+    }
+}
+
+fn crate_name(system: &str) -> &str {
+    system.split("::").next().unwrap_or(system)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Counter(u32);
+
+    fn increment(mut counter: ResMut<Counter>) {
+        counter.0 += 1;
+    }
+
+    fn double(mut counter: ResMut<Counter>) {
+        counter.0 *= 2;
+    }
+
+    fn read(_counter: Res<Counter>) {}
+
+    fn report(schedule: &mut Schedule) -> AmbiguityReport {
+        AmbiguityReport::from_schedule(schedule, &mut World::new()).unwrap()
+    }
+
+    #[test]
+    fn test_unordered_writers_are_reported() {
+        let mut schedule = Schedule::default();
+        schedule.add_systems((increment, double));
+
+        let report = report(&mut schedule);
+        assert_eq!(report.len(), 1);
+        let ambiguity = &report.ambiguities()[0];
+        assert!(ambiguity.is_between("double", "increment"));
+        assert!(ambiguity.is_between("tests::increment", "tests::double"));
+        assert!(ambiguity.conflicts[0].ends_with("Counter"));
+        assert_eq!(ambiguity.crates(), ["amp_world"]);
+    }
+
+    #[test]
+    fn test_ordered_and_read_only_systems_are_not_reported() {
+        let mut schedule = Schedule::default();
+        schedule.add_systems((increment, double).chain());
+        schedule.add_systems(read.after(double));
+        assert!(report(&mut schedule).is_empty());
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems((increment, double.ambiguous_with(increment)));
+        assert!(report(&mut schedule).is_empty());
+    }
+
+    #[test]
+    fn test_known_ambiguities_are_filtered() {
+        let mut schedule = Schedule::default();
+        schedule.add_systems((increment, double, read));
+
+        let report = report(&mut schedule);
+        assert_eq!(report.len(), 3);
+        let unexpected: Vec<_> = report
+            .unexpected(&[("double", "increment"), ("read", "double")])
+            .collect();
+        assert_eq!(unexpected.len(), 1);
+        assert!(unexpected[0].is_between("increment", "read"));
+
+        let text = report.to_string();
+        assert!(text.starts_with("3 system ordering ambiguities\namp_world:\n"));
+    }
+}
@@ -0,0 +1,205 @@
+//! Deferred batch despawning
+//!
+//! Despawning a whole sector in a single frame causes frame time spikes. The
+//! [`DespawnQueue`] resource accepts entity batches and retires them over
+//! multiple frames, spending at most a fixed time budget per frame. Queued
+//! entities are despawned together with their descendants.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::DespawnRecursiveExt;
+
+/// Queue of entities waiting to be despawned
+///
+/// Entities are despawned in the order they were queued. Each call to
+/// [`DespawnQueue::process`] despawns entities until the per-frame budget is
+/// spent, always retiring at least one entity so the queue keeps draining.
+#[derive(Resource, Debug)]
+pub struct DespawnQueue {
+    pending: VecDeque<Entity>,
+    frame_budget: Duration,
+}
+
+impl DespawnQueue {
+    /// Default time spent despawning per frame
+    pub const DEFAULT_FRAME_BUDGET: Duration = Duration::from_micros(500);
+
+    /// Create an empty queue with [`DespawnQueue::DEFAULT_FRAME_BUDGET`]
+    pub fn new() -> Self {
+        Self::with_frame_budget(Self::DEFAULT_FRAME_BUDGET)
+    }
+
+    /// Create an empty queue with the given per-frame time budget
+    pub fn with_frame_budget(frame_budget: Duration) -> Self {
+        Self {
+            pending: VecDeque::new(),
+            frame_budget,
+        }
+    }
+
+    /// Get the per-frame time budget
+    pub fn frame_budget(&self) -> Duration {
+        self.frame_budget
+    }
+
+    /// Set the per-frame time budget
+    pub fn set_frame_budget(&mut self, frame_budget: Duration) {
+        self.frame_budget = frame_budget;
+    }
+
+    /// Queue a single entity for despawning
+    pub fn push(&mut self, entity: Entity) {
+        self.pending.push_back(entity);
+    }
+
+    /// Queue a batch of entities for despawning
+    pub fn push_batch(&mut self, entities: impl IntoIterator<Item = Entity>) {
+        self.pending.extend(entities);
+    }
+
+    /// Get the number of entities waiting to be despawned
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Check if no entities are waiting to be despawned
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Despawn queued entities until the frame budget is spent
+    ///
+    /// Each entity is despawned with all of its descendants and removed from
+    /// its parent's children. Entities that no longer exist are skipped.
+    /// Returns the number of queued entities despawned, not counting
+    /// descendants.
+    pub fn process(&mut self, world: &mut World) -> usize {
+        let start = Instant::now();
+        let mut despawned = 0;
+
+        while let Some(entity) = self.pending.pop_front() {
+            if let Some(entity) = world.get_entity_mut(entity) {
+                entity.despawn_recursive();
+                despawned += 1;
+            }
+            if start.elapsed() >= self.frame_budget {
+                break;
+            }
+        }
+
+        despawned
+    }
+}
+
+impl Default for DespawnQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// System that retires queued entities within the [`DespawnQueue`] budget
+///
+/// Does nothing if the [`DespawnQueue`] resource has not been inserted.
+pub fn process_despawn_queue(world: &mut World) {
+    if !world.contains_resource::<DespawnQueue>() {
+        return;
+    }
+    world.resource_scope(|world, mut queue: Mut<DespawnQueue>| {
+        queue.process(world);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_hierarchy::{BuildWorldChildren, Children};
+
+    fn spawn_batch(world: &mut World, count: usize) -> Vec<Entity> {
+        (0..count).map(|_| world.spawn_empty().id()).collect()
+    }
+
+    #[test]
+    fn test_zero_budget_despawns_one_per_frame() {
+        let mut world = World::new();
+        let entities = spawn_batch(&mut world, 3);
+        let mut queue = DespawnQueue::with_frame_budget(Duration::ZERO);
+        queue.push_batch(entities.clone());
+
+        assert_eq!(queue.process(&mut world), 1);
+        assert!(world.get_entity(entities[0]).is_none());
+        assert!(world.get_entity(entities[1]).is_some());
+        assert_eq!(queue.len(), 2);
+
+        queue.process(&mut world);
+        queue.process(&mut world);
+        assert!(queue.is_empty());
+        assert_eq!(world.entities().len(), 0);
+    }
+
+    #[test]
+    fn test_generous_budget_drains_queue() {
+        let mut world = World::new();
+        let entities = spawn_batch(&mut world, 100);
+        let mut queue = DespawnQueue::with_frame_budget(Duration::from_secs(60));
+        queue.push_batch(entities);
+
+        assert_eq!(queue.process(&mut world), 100);
+        assert!(queue.is_empty());
+        assert_eq!(world.entities().len(), 0);
+    }
+
+    #[test]
+    fn test_missing_entities_are_skipped() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        world.despawn(entity);
+
+        let mut queue = DespawnQueue::with_frame_budget(Duration::from_secs(60));
+        queue.push(entity);
+
+        assert_eq!(queue.process(&mut world), 0);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_children_are_despawned_with_parent() {
+        let mut world = World::new();
+        let root = world.spawn_empty().id();
+        let parent = world.spawn_empty().id();
+        let child = world.spawn_empty().id();
+        let grandchild = world.spawn_empty().id();
+        world.entity_mut(root).add_child(parent);
+        world.entity_mut(parent).add_child(child);
+        world.entity_mut(child).add_child(grandchild);
+
+        let mut queue = DespawnQueue::with_frame_budget(Duration::from_secs(60));
+        queue.push(parent);
+
+        assert_eq!(queue.process(&mut world), 1);
+        assert!(world.get_entity(parent).is_none());
+        assert!(world.get_entity(child).is_none());
+        assert!(world.get_entity(grandchild).is_none());
+        assert!(world
+            .get::<Children>(root)
+            .map_or(true, |children| !children.contains(&parent)));
+        assert_eq!(world.entities().len(), 1);
+    }
+
+    #[test]
+    fn test_system_processes_queue_resource() {
+        let mut world = World::new();
+        let entities = spawn_batch(&mut world, 4);
+        let mut queue = DespawnQueue::with_frame_budget(Duration::from_secs(60));
+        queue.push_batch(entities);
+        world.insert_resource(queue);
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(process_despawn_queue);
+        schedule.run(&mut world);
+
+        assert!(world.resource::<DespawnQueue>().is_empty());
+        assert_eq!(world.entities().len(), 0);
+    }
+}
@@ -0,0 +1,446 @@
+//! Background job scheduling
+//!
+//! The [`JobSystem`] resource runs background work such as streaming,
+//! navmesh baking and mesh generation on the [`AsyncComputeTaskPool`].
+//! Jobs are dispatched by priority once their dependencies have completed,
+//! and at most a fixed number of finished jobs are collected per frame so
+//! that completion handling does not spike frame time.
+
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+use std::marker::PhantomData;
+
+use amp_core::{Error, Result};
+use bevy_ecs::prelude::*;
+use bevy_tasks::{AsyncComputeTaskPool, Task};
+
+type JobOutput = Box<dyn Any + Send + Sync>;
+type JobFn = Box<dyn FnOnce() -> JobOutput + Send + Sync>;
+
+/// Scheduling priority of a job
+///
+/// Ready jobs with higher priority are dispatched first; jobs with equal
+/// priority run in submission order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum JobPriority {
+    /// Work that can wait, such as cache warming
+    Low,
+    /// Regular background work
+    #[default]
+    Normal,
+    /// Work needed soon, such as streaming in nearby sectors
+    High,
+    /// Work blocking the player's view
+    Critical,
+}
+
+/// Untyped identifier of a submitted job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JobId(u64);
+
+/// Typed handle to a submitted job
+///
+/// Used to declare dependencies and to take the job's result once it has
+/// completed.
+#[derive(Debug)]
+pub struct JobHandle<T> {
+    id: JobId,
+    _output: PhantomData<fn() -> T>,
+}
+
+impl<T> JobHandle<T> {
+    /// Get the untyped job identifier
+    pub fn id(&self) -> JobId {
+        self.id
+    }
+}
+
+impl<T> Clone for JobHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for JobHandle<T> {}
+
+/// Current state of a job
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    /// Waiting for dependencies or a free slot
+    Pending,
+    /// Running on the task pool
+    Running,
+    /// Finished, with its result not yet taken or a pending job depending
+    /// on it
+    Completed,
+    /// Not known to this job system
+    ///
+    /// Completed jobs are forgotten once their result has been taken and no
+    /// pending job depends on them.
+    Unknown,
+}
+
+struct PendingJob {
+    id: JobId,
+    priority: JobPriority,
+    dependencies: Vec<JobId>,
+    work: JobFn,
+}
+
+/// Priority job scheduler over the [`AsyncComputeTaskPool`]
+///
+/// Call [`JobSystem::update`] once per frame, or add [`run_jobs`] to a
+/// schedule. The task pool must have been initialized before jobs are
+/// dispatched.
+#[derive(Resource)]
+pub struct JobSystem {
+    next_id: u64,
+    pending: Vec<PendingJob>,
+    running: Vec<(JobId, Task<JobOutput>)>,
+    completed: HashSet<JobId>,
+    results: HashMap<JobId, JobOutput>,
+    max_in_flight: usize,
+    completion_budget: usize,
+}
+
+impl JobSystem {
+    /// Default number of jobs running on the pool at once
+    pub const DEFAULT_MAX_IN_FLIGHT: usize = 8;
+
+    /// Default number of finished jobs collected per frame
+    pub const DEFAULT_COMPLETION_BUDGET: usize = 16;
+
+    /// Create a job system with the default limits
+    pub fn new() -> Self {
+        Self::with_limits(Self::DEFAULT_MAX_IN_FLIGHT, Self::DEFAULT_COMPLETION_BUDGET)
+    }
+
+    /// Create a job system with explicit concurrency and completion limits
+    ///
+    /// Both limits are clamped to at least one so the system always makes
+    /// progress.
+    pub fn with_limits(max_in_flight: usize, completion_budget: usize) -> Self {
+        Self {
+            next_id: 0,
+            pending: Vec::new(),
+            running: Vec::new(),
+            completed: HashSet::new(),
+            results: HashMap::new(),
+            max_in_flight: max_in_flight.max(1),
+            completion_budget: completion_budget.max(1),
+        }
+    }
+
+    /// Submit a job without dependencies
+    pub fn submit<T, F>(&mut self, priority: JobPriority, work: F) -> JobHandle<T>
+    where
+        T: Send + Sync + 'static,
+        F: FnOnce() -> T + Send + Sync + 'static,
+    {
+        self.push_job(priority, Vec::new(), work)
+    }
+
+    /// Submit a job that runs only after all `dependencies` have completed
+    ///
+    /// Fails if a dependency is not known to this job system, including
+    /// completed jobs that have already been forgotten.
+    pub fn submit_after<T, F>(
+        &mut self,
+        priority: JobPriority,
+        dependencies: &[JobId],
+        work: F,
+    ) -> Result<JobHandle<T>>
+    where
+        T: Send + Sync + 'static,
+        F: FnOnce() -> T + Send + Sync + 'static,
+    {
+        if let Some(unknown) = dependencies
+            .iter()
+            .find(|dependency| self.status(**dependency) == JobStatus::Unknown)
+        {
+            return Err(Error::validation(format!(
+                "job depends on unknown job {unknown:?}"
+            )));
+        }
+
+        Ok(self.push_job(priority, dependencies.to_vec(), work))
+    }
+
+    fn push_job<T, F>(
+        &mut self,
+        priority: JobPriority,
+        dependencies: Vec<JobId>,
+        work: F,
+    ) -> JobHandle<T>
+    where
+        T: Send + Sync + 'static,
+        F: FnOnce() -> T + Send + Sync + 'static,
+    {
+        let id = JobId(self.next_id);
+        self.next_id += 1;
+
+        self.pending.push(PendingJob {
+            id,
+            priority,
+            dependencies,
+            work: Box::new(move || Box::new(work()) as JobOutput),
+        });
+
+        JobHandle {
+            id,
+            _output: PhantomData,
+        }
+    }
+
+    /// Get the status of a job
+    pub fn status(&self, id: JobId) -> JobStatus {
+        if self.completed.contains(&id) {
+            JobStatus::Completed
+        } else if self.running.iter().any(|(running, _)| *running == id) {
+            JobStatus::Running
+        } else if self.pending.iter().any(|job| job.id == id) {
+            JobStatus::Pending
+        } else {
+            JobStatus::Unknown
+        }
+    }
+
+    /// Take the result of a completed job
+    ///
+    /// Returns `None` if the job has not completed yet or its result was
+    /// already taken.
+    pub fn take_result<T: 'static>(&mut self, handle: JobHandle<T>) -> Option<T> {
+        let output = self.results.remove(&handle.id)?;
+        self.forget_if_unused(handle.id);
+        // The handle type matches the closure output by construction
+        output.downcast::<T>().ok().map(|value| *value)
+    }
+
+    /// Get the number of jobs waiting to be dispatched
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Get the number of jobs running on the task pool
+    pub fn running_count(&self) -> usize {
+        self.running.len()
+    }
+
+    /// Check if there is no pending or running work
+    pub fn is_idle(&self) -> bool {
+        self.pending.is_empty() && self.running.is_empty()
+    }
+
+    /// Collect finished jobs and dispatch ready ones
+    ///
+    /// At most the completion budget of finished jobs is collected per call.
+    /// Returns the number of jobs collected.
+    pub fn update(&mut self) -> usize {
+        let collected = self.collect_finished();
+        self.dispatch_ready();
+        collected
+    }
+
+    fn collect_finished(&mut self) -> usize {
+        let mut collected = 0;
+        let mut index = 0;
+
+        while index < self.running.len() && collected < self.completion_budget {
+            if !self.running[index].1.is_finished() {
+                index += 1;
+                continue;
+            }
+
+            let (id, task) = self.running.swap_remove(index);
+            let output = bevy_tasks::block_on(task);
+            self.completed.insert(id);
+            self.results.insert(id, output);
+            collected += 1;
+        }
+
+        collected
+    }
+
+    fn dispatch_ready(&mut self) {
+        if self.running.len() >= self.max_in_flight {
+            return;
+        }
+
+        // Highest priority first, then oldest first
+        self.pending
+            .sort_by(|a, b| b.priority.cmp(&a.priority).then(a.id.cmp(&b.id)));
+
+        let pool = AsyncComputeTaskPool::get();
+        let mut index = 0;
+        while index < self.pending.len() && self.running.len() < self.max_in_flight {
+            let ready = self.pending[index]
+                .dependencies
+                .iter()
+                .all(|dependency| self.completed.contains(dependency));
+            if !ready {
+                index += 1;
+                continue;
+            }
+
+            let job = self.pending.remove(index);
+            let work = job.work;
+            let task = pool.spawn(async move { work() });
+            self.running.push((job.id, task));
+            for dependency in job.dependencies {
+                self.forget_if_unused(dependency);
+            }
+        }
+    }
+
+    /// Drop a completed job whose result was taken and that no pending job
+    /// depends on
+    fn forget_if_unused(&mut self, id: JobId) {
+        let needed = self.results.contains_key(&id)
+            || self
+                .pending
+                .iter()
+                .any(|job| job.dependencies.contains(&id));
+        if !needed {
+            self.completed.remove(&id);
+        }
+    }
+}
+
+impl Default for JobSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// System that advances the [`JobSystem`] once per frame
+pub fn run_jobs(mut jobs: ResMut<JobSystem>) {
+    jobs.update();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_tasks::TaskPool;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    fn init_pool() {
+        AsyncComputeTaskPool::get_or_init(TaskPool::new);
+    }
+
+    fn run_until_idle(jobs: &mut JobSystem) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !jobs.is_idle() {
+            assert!(Instant::now() < deadline, "jobs did not finish in time");
+            jobs.update();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_job_result_is_returned() {
+        init_pool();
+        let mut jobs = JobSystem::new();
+        let handle = jobs.submit(JobPriority::Normal, || 6 * 7);
+
+        assert_eq!(jobs.status(handle.id()), JobStatus::Pending);
+        run_until_idle(&mut jobs);
+
+        assert_eq!(jobs.status(handle.id()), JobStatus::Completed);
+        assert_eq!(jobs.take_result(handle), Some(42));
+        assert_eq!(jobs.take_result(handle), None);
+    }
+
+    #[test]
+    fn test_dependencies_run_first() {
+        init_pool();
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let mut jobs = JobSystem::new();
+
+        let first_order = order.clone();
+        let first = jobs.submit(JobPriority::Low, move || {
+            std::thread::sleep(Duration::from_millis(20));
+            first_order.lock().unwrap().push("first");
+        });
+        let second_order = order.clone();
+        let second = jobs
+            .submit_after(JobPriority::Critical, &[first.id()], move || {
+                second_order.lock().unwrap().push("second");
+            })
+            .unwrap();
+
+        jobs.update();
+        assert_eq!(jobs.status(second.id()), JobStatus::Pending);
+
+        run_until_idle(&mut jobs);
+        assert_eq!(*order.lock().unwrap(), vec!["first", "second"]);
+    }
+
+    #[test]
+    fn test_priority_order_with_single_slot() {
+        init_pool();
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let mut jobs = JobSystem::with_limits(1, 1);
+
+        for (priority, name) in [
+            (JobPriority::Low, "low"),
+            (JobPriority::Critical, "critical"),
+            (JobPriority::Normal, "normal"),
+        ] {
+            let order = order.clone();
+            jobs.submit(priority, move || order.lock().unwrap().push(name));
+        }
+
+        run_until_idle(&mut jobs);
+        assert_eq!(*order.lock().unwrap(), vec!["critical", "normal", "low"]);
+    }
+
+    #[test]
+    fn test_completion_budget_limits_collection() {
+        init_pool();
+        let mut jobs = JobSystem::with_limits(4, 1);
+        for value in 0..4 {
+            jobs.submit(JobPriority::Normal, move || value);
+        }
+
+        jobs.update();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while jobs.running.iter().any(|(_, task)| !task.is_finished()) {
+            assert!(Instant::now() < deadline, "jobs did not finish in time");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        assert_eq!(jobs.update(), 1);
+        assert_eq!(jobs.running_count(), 3);
+    }
+
+    #[test]
+    fn test_taken_jobs_are_forgotten() {
+        init_pool();
+        let mut jobs = JobSystem::new();
+        let first = jobs.submit(JobPriority::Normal, || 1);
+        run_until_idle(&mut jobs);
+
+        // A pending dependent keeps the job known after its result is taken
+        let second = jobs
+            .submit_after(JobPriority::Normal, &[first.id()], || 2)
+            .unwrap();
+        assert_eq!(jobs.take_result(first), Some(1));
+        assert_eq!(jobs.status(first.id()), JobStatus::Completed);
+
+        run_until_idle(&mut jobs);
+        assert_eq!(jobs.status(first.id()), JobStatus::Unknown);
+        assert!(jobs.completed.contains(&second.id()));
+        assert_eq!(jobs.take_result(second), Some(2));
+        assert!(jobs.completed.is_empty());
+
+        let error = jobs
+            .submit_after(JobPriority::Normal, &[first.id()], || 3)
+            .unwrap_err();
+        assert!(error.to_string().contains("unknown job"));
+        assert!(jobs
+            .submit_after(JobPriority::Normal, &[JobId(99)], || 3)
+            .is_err());
+        assert_eq!(jobs.pending_count(), 0);
+    }
+}
@@ -5,9 +5,30 @@
 
 #![deny(missing_docs)]
 
+pub mod ambiguity;
+pub mod despawn;
+pub mod jobs;
+
+pub use ambiguity::{detect_ambiguities, Ambiguity, AmbiguityReport};
+pub use despawn::{process_despawn_queue, DespawnQueue};
+pub use jobs::{run_jobs, JobHandle, JobId, JobPriority, JobStatus, JobSystem};
+
 // Re-export commonly used ECS types
 pub use bevy_ecs::prelude::*;
 
+use bevy_ecs::schedule::SystemConfigs;
+
+/// System set of the per-frame world upkeep systems added by [`systems`]
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WorldSystems;
+
+/// Get the per-frame world upkeep systems, ordered and in [`WorldSystems`]
+pub fn systems() -> SystemConfigs {
+    (run_jobs, process_despawn_queue)
+        .chain()
+        .in_set(WorldSystems)
+}
+
 /// Future world management implementation
 pub struct WorldManager {
     /// The ECS world
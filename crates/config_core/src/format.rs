@@ -0,0 +1,189 @@
+//! Config file formats.
+//!
+//! Config documents may be authored in RON, TOML or JSON; the format is
+//! selected by file extension. A config whose default path is `game.ron` is
+//! also found as `game.toml` or `game.json`, so tools that emit TOML or JSON
+//! can produce the same typed config without converting to RON.
+
+use amp_core::{ConfigError, Error, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Serialization format of a config document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ConfigFormat {
+    /// Rusty Object Notation (`.ron`)
+    #[default]
+    Ron,
+    /// TOML (`.toml`)
+    Toml,
+    /// JSON (`.json`)
+    Json,
+}
+
+impl ConfigFormat {
+    /// All supported formats, in lookup order.
+    pub const ALL: [ConfigFormat; 3] = [ConfigFormat::Ron, ConfigFormat::Toml, ConfigFormat::Json];
+
+    /// File extension used by the format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            ConfigFormat::Ron => "ron",
+            ConfigFormat::Toml => "toml",
+            ConfigFormat::Json => "json",
+        }
+    }
+
+    /// Select the format from a path's extension, ignoring case.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|format| format.extension() == extension)
+    }
+
+    /// Deserialize a document.
+    pub fn parse<T: DeserializeOwned>(self, data: &str) -> Result<T> {
+        self.parse_raw(data)
+            .map_err(|message| Error::from(ConfigError::parse_error(message)))
+    }
+
+    /// Deserialize a document, returning the format's own error message.
+    pub(crate) fn parse_raw<T: DeserializeOwned>(
+        self,
+        data: &str,
+    ) -> std::result::Result<T, String> {
+        match self {
+            ConfigFormat::Ron => ron::from_str(data).map_err(|e| e.to_string()),
+            ConfigFormat::Toml => toml::from_str(data).map_err(|e| e.to_string()),
+            ConfigFormat::Json => serde_json::from_str(data).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Serialize a document in human-readable form.
+    pub fn render<T: Serialize>(self, value: &T) -> Result<String> {
+        let result = match self {
+            ConfigFormat::Ron => {
+                ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+                    .map_err(|e| e.to_string())
+            }
+            ConfigFormat::Toml => toml::to_string_pretty(value).map_err(|e| e.to_string()),
+            ConfigFormat::Json => serde_json::to_string_pretty(value).map_err(|e| e.to_string()),
+        };
+        result.map_err(|message| Error::from(ConfigError::parse_error(message)))
+    }
+}
+
+/// Find an existing config file at `path`, or at the same path with another
+/// supported extension.
+///
+/// Alternatives are tried in [`ConfigFormat::ALL`] order.
+pub(crate) fn locate(path: &Path) -> Option<PathBuf> {
+    if path.is_file() {
+        return Some(path.to_path_buf());
+    }
+    ConfigFormat::ALL
+        .into_iter()
+        .map(|format| path.with_extension(format.extension()))
+        .find(|candidate| candidate.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConfigLayer, ConfigLoader, FactorySettings, GameConfig};
+    use tempfile::TempDir;
+
+    fn expected() -> FactorySettings {
+        FactorySettings {
+            prefab_path: "/srv/prefabs/*.ron".to_string(),
+            hot_reload: false,
+        }
+    }
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("game.ron")),
+            Some(ConfigFormat::Ron)
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("game.linux.TOML")),
+            Some(ConfigFormat::Toml)
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("game.json")),
+            Some(ConfigFormat::Json)
+        );
+        assert_eq!(ConfigFormat::from_path(Path::new("game.yaml")), None);
+    }
+
+    #[test]
+    fn test_same_config_in_every_format() {
+        let documents = [
+            (
+                ConfigFormat::Ron,
+                r#"(factory: (prefab_path: "/srv/prefabs/*.ron", hot_reload: false))"#,
+            ),
+            (
+                ConfigFormat::Toml,
+                "[factory]\nprefab_path = \"/srv/prefabs/*.ron\"\nhot_reload = false\n",
+            ),
+            (
+                ConfigFormat::Json,
+                r#"{"factory": {"prefab_path": "/srv/prefabs/*.ron", "hot_reload": false}}"#,
+            ),
+        ];
+
+        for (format, document) in documents {
+            let config: GameConfig = format.parse(document).unwrap();
+            assert_eq!(config.factory, expected(), "{format:?}");
+
+            let rendered = format.render(&config).unwrap();
+            let reparsed: GameConfig = format.parse(&rendered).unwrap();
+            assert_eq!(reparsed, config, "{format:?}");
+        }
+    }
+
+    #[test]
+    fn test_loader_finds_alternative_extension() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("game.toml"),
+            "[factory]\nprefab_path = \"/srv/prefabs/*.ron\"\nhot_reload = false\n",
+        )
+        .unwrap();
+
+        let loader = ConfigLoader {
+            search_paths: vec![temp_dir.path().to_path_buf()],
+        };
+        let config: GameConfig = loader.load_with_merge().unwrap();
+        assert_eq!(config.factory, expected());
+    }
+
+    #[test]
+    fn test_layers_mix_formats() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path().join("game.json");
+        let user = temp_dir.path().join("user.ron");
+        std::fs::write(
+            &base,
+            r#"{"factory": {"prefab_path": "/srv/prefabs/*.ron", "hot_reload": true}}"#,
+        )
+        .unwrap();
+        std::fs::write(&user, "(factory: (hot_reload: false))").unwrap();
+
+        let loader = ConfigLoader {
+            search_paths: vec![temp_dir.path().to_path_buf()],
+        };
+        let (config, report): (GameConfig, _) = loader
+            .load_layers(&[
+                ConfigLayer::new("base", temp_dir.path().join("game.ron")),
+                ConfigLayer::new("user", &user),
+            ])
+            .unwrap();
+
+        assert_eq!(config.factory, expected());
+        assert_eq!(report.layers()[1].path.as_deref(), Some(base.as_path()));
+    }
+}
@@ -0,0 +1,232 @@
+//! Live configuration shared through the ECS world.
+//!
+//! A loaded configuration is stored in a [`ConfigHandle<T>`] resource. When a
+//! new version is loaded, for example after a file changed on disk,
+//! [`publish_config`] validates it, swaps it into the handle and sends a
+//! [`ConfigChanged<T>`] event carrying both versions, so systems such as
+//! culling or spawn budgets can react in the same frame instead of polling.
+
+use amp_core::Result;
+use bevy_ecs::event::{Event, Events};
+use bevy_ecs::system::Resource;
+use bevy_ecs::world::World;
+use ron::Value;
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::layers::{key_segment, to_value};
+use crate::validate::check;
+use crate::Config;
+
+/// The current value of a configuration type.
+#[derive(Resource)]
+pub struct ConfigHandle<T: Config> {
+    config: Arc<T>,
+    generation: u64,
+}
+
+impl<T: Config> ConfigHandle<T> {
+    /// Wrap an initial configuration.
+    pub fn new(config: T) -> Self {
+        Self {
+            config: Arc::new(config),
+            generation: 0,
+        }
+    }
+
+    /// The current configuration.
+    pub fn get(&self) -> &T {
+        &self.config
+    }
+
+    /// Shared pointer to the current configuration.
+    pub fn shared(&self) -> Arc<T> {
+        Arc::clone(&self.config)
+    }
+
+    /// Number of times a changed configuration has been published.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+/// Sent when a new configuration of type `T` is published.
+#[derive(Event)]
+pub struct ConfigChanged<T: Config> {
+    /// The configuration before the change
+    pub old: Arc<T>,
+    /// The configuration after the change
+    pub new: Arc<T>,
+    /// Dotted paths of every field whose value changed, sorted
+    pub changed_fields: Vec<String>,
+}
+
+impl<T: Config> ConfigChanged<T> {
+    /// Check if the field at a dotted path, or any field below it, changed.
+    pub fn field_changed(&self, path: &str) -> bool {
+        self.changed_fields.iter().any(|changed| {
+            changed == path
+                || changed
+                    .strip_prefix(path)
+                    .is_some_and(|rest| rest.starts_with('.'))
+        })
+    }
+}
+
+/// Insert the [`ConfigHandle<T>`] resource and its [`ConfigChanged<T>`]
+/// event queue.
+pub fn init_config<T: Config>(world: &mut World, config: T) {
+    world.insert_resource(ConfigHandle::new(config));
+    world.init_resource::<Events<ConfigChanged<T>>>();
+}
+
+/// Replace the configuration in [`ConfigHandle<T>`] and send a
+/// [`ConfigChanged<T>`] event.
+///
+/// The configuration is checked with its [`Validate`](crate::Validate)
+/// implementation first. If any field is invalid, the current configuration
+/// is kept, no event is sent and a validation error listing every invalid
+/// field is returned.
+///
+/// Publishing a configuration equal to the current one does nothing.
+/// Returns the changed field paths.
+///
+/// # Panics
+///
+/// Panics if [`init_config`] has not been called for `T`.
+pub fn publish_config<T: Config + Serialize>(world: &mut World, config: T) -> Result<Vec<String>> {
+    check(&config, |_| None)?;
+
+    let mut handle = world.resource_mut::<ConfigHandle<T>>();
+    let mut changed_fields = Vec::new();
+    diff_fields(
+        &to_value(handle.get())?,
+        &to_value(&config)?,
+        String::new(),
+        &mut changed_fields,
+    );
+    if changed_fields.is_empty() {
+        return Ok(changed_fields);
+    }
+    changed_fields.sort();
+
+    let new = Arc::new(config);
+    let old = std::mem::replace(&mut handle.config, Arc::clone(&new));
+    handle.generation += 1;
+
+    for field in &changed_fields {
+        log::info!("config {}: {field} changed", T::FILE_NAME);
+    }
+    world.send_event(ConfigChanged {
+        old,
+        new,
+        changed_fields: changed_fields.clone(),
+    });
+    Ok(changed_fields)
+}
+
+/// Collect the dotted paths of leaves that differ between two values.
+fn diff_fields(old: &Value, new: &Value, prefix: String, changed: &mut Vec<String>) {
+    match (old, new) {
+        (Value::Map(old_map), Value::Map(new_map)) => {
+            let child_path = |key: &Value| {
+                let segment = key_segment(key);
+                if prefix.is_empty() {
+                    segment
+                } else {
+                    format!("{prefix}.{segment}")
+                }
+            };
+
+            for (key, old_child) in old_map.iter() {
+                match new_map.iter().find(|(new_key, _)| *new_key == key) {
+                    Some((_, new_child)) => {
+                        diff_fields(old_child, new_child, child_path(key), changed)
+                    }
+                    None => changed.push(child_path(key)),
+                }
+            }
+            for (key, _) in new_map.iter() {
+                if !old_map.iter().any(|(old_key, _)| old_key == key) {
+                    changed.push(child_path(key));
+                }
+            }
+        }
+        (old, new) if old != new => changed.push(prefix),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FactorySettings, GameConfig};
+
+    fn reloaded() -> GameConfig {
+        GameConfig {
+            factory: FactorySettings {
+                hot_reload: false,
+                ..FactorySettings::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_publish_sends_event_with_old_and_new() {
+        let mut world = World::new();
+        init_config(&mut world, GameConfig::default());
+
+        let changed = publish_config(&mut world, reloaded()).unwrap();
+        assert_eq!(changed, vec!["factory.hot_reload"]);
+
+        let handle = world.resource::<ConfigHandle<GameConfig>>();
+        assert_eq!(handle.generation(), 1);
+        assert!(!handle.get().factory.hot_reload);
+
+        let events = world.resource::<Events<ConfigChanged<GameConfig>>>();
+        let mut reader = events.get_reader();
+        let received: Vec<_> = reader.read(events).collect();
+        assert_eq!(received.len(), 1);
+        assert!(received[0].old.factory.hot_reload);
+        assert!(!received[0].new.factory.hot_reload);
+        assert!(received[0].field_changed("factory"));
+        assert!(!received[0].field_changed("factory.prefab_path"));
+    }
+
+    #[test]
+    fn test_publishing_unchanged_config_is_silent() {
+        let mut world = World::new();
+        init_config(&mut world, GameConfig::default());
+
+        assert!(publish_config(&mut world, GameConfig::default())
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(world.resource::<ConfigHandle<GameConfig>>().generation(), 0);
+        assert!(world
+            .resource::<Events<ConfigChanged<GameConfig>>>()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_invalid_config_is_not_published() {
+        let mut world = World::new();
+        init_config(&mut world, GameConfig::default());
+
+        let invalid = GameConfig {
+            factory: FactorySettings {
+                prefab_path: " ".to_string(),
+                ..FactorySettings::default()
+            },
+        };
+        let error = publish_config(&mut world, invalid).unwrap_err();
+        assert!(error.to_string().contains("factory.prefab_path"));
+
+        let handle = world.resource::<ConfigHandle<GameConfig>>();
+        assert_eq!(handle.generation(), 0);
+        assert_eq!(*handle.get(), GameConfig::default());
+        assert!(world
+            .resource::<Events<ConfigChanged<GameConfig>>>()
+            .is_empty());
+    }
+}
@@ -0,0 +1,447 @@
+//! Layered configuration loading.
+//!
+//! Configuration is assembled from a stack of layers, lowest precedence
+//! first: embedded defaults, shipped base files, platform overrides and user
+//! overrides. Layers are deep-merged as RON values, so a layer only needs to
+//! spell out the fields it changes. A [`PrecedenceReport`] records which
+//! layers were found and which layer supplied each final value.
+
+use amp_core::{ConfigError, Error, Result};
+use ron::Value;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::{format, migrate, validate, Config, ConfigFormat, ConfigLoader};
+
+/// Name of the implicit lowest-precedence layer holding embedded defaults.
+pub const DEFAULTS_LAYER: &str = "defaults";
+
+/// A single configuration file in the layer stack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigLayer {
+    /// Human-readable layer name used in reports
+    pub name: String,
+    /// Path of the layer's file
+    pub path: PathBuf,
+}
+
+impl ConfigLayer {
+    /// Create a layer with the given name and file path.
+    pub fn new(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            name: name.into(),
+            path: path.into(),
+        }
+    }
+}
+
+/// Whether a layer contributed to the loaded configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerStatus {
+    /// Layer name
+    pub name: String,
+    /// Layer file path, `None` for embedded defaults
+    pub path: Option<PathBuf>,
+    /// Whether the layer file existed and was merged
+    pub loaded: bool,
+}
+
+/// Record of how a layered configuration was assembled.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrecedenceReport {
+    layers: Vec<LayerStatus>,
+    sources: BTreeMap<String, String>,
+}
+
+impl PrecedenceReport {
+    /// Layers in precedence order, lowest first.
+    pub fn layers(&self) -> &[LayerStatus] {
+        &self.layers
+    }
+
+    /// Name of the layer that supplied the value at a dotted key path,
+    /// such as `"factory.hot_reload"`.
+    pub fn source_of(&self, key: &str) -> Option<&str> {
+        self.sources.get(key).map(String::as_str)
+    }
+
+    /// Every leaf key path with the layer that supplied it, sorted by key.
+    pub fn sources(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.sources
+            .iter()
+            .map(|(key, layer)| (key.as_str(), layer.as_str()))
+    }
+
+    fn record_layer(&mut self, name: &str, path: Option<&Path>, loaded: bool) {
+        self.layers.push(LayerStatus {
+            name: name.to_string(),
+            path: path.map(Path::to_path_buf),
+            loaded,
+        });
+    }
+
+    /// File or source name that supplied the value at `key`.
+    ///
+    /// For a section key the source of its first field is used.
+    pub(crate) fn origin_of(&self, key: &str) -> Option<String> {
+        let section = format!("{key}.");
+        let layer = self.source_of(key).or_else(|| {
+            self.sources
+                .iter()
+                .find(|(leaf, _)| leaf.starts_with(&section))
+                .map(|(_, layer)| layer.as_str())
+        })?;
+
+        let path = self
+            .layers
+            .iter()
+            .rev()
+            .find(|status| status.name == layer)
+            .and_then(|status| status.path.as_ref());
+        Some(match path {
+            Some(path) => path.display().to_string(),
+            None => layer.to_string(),
+        })
+    }
+
+    pub(crate) fn record_source(&mut self, key: &str, layer: &str) {
+        self.sources.insert(key.to_string(), layer.to_string());
+    }
+
+    fn record_sources(&mut self, layer: &str, value: &Value) {
+        let mut keys = Vec::new();
+        collect_leaf_keys(value, String::new(), &mut keys);
+        for key in keys {
+            self.sources.insert(key, layer.to_string());
+        }
+    }
+}
+
+impl fmt::Display for PrecedenceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "layers (lowest precedence first):")?;
+        for layer in &self.layers {
+            let mark = if layer.loaded { "x" } else { " " };
+            match &layer.path {
+                Some(path) => writeln!(f, "  [{mark}] {} {}", layer.name, path.display())?,
+                None => writeln!(f, "  [{mark}] {} (embedded)", layer.name)?,
+            }
+        }
+        writeln!(f, "values:")?;
+        for (key, layer) in &self.sources {
+            writeln!(f, "  {key} <- {layer}")?;
+        }
+        Ok(())
+    }
+}
+
+impl ConfigLoader {
+    /// Default layer stack for a configuration type, lowest precedence first.
+    ///
+    /// - `base`: the shipped file in the first search path
+    /// - `platform`: `<stem>.<os>.<ext>` next to the base file, e.g. `game.linux.ron`
+    /// - `user`: the file in the user config directory (`$XDG_CONFIG_HOME/amp`)
+    ///
+    /// Unlike [`ConfigLoader::load_with_merge`], user files always take
+    /// precedence over shipped files here.
+    pub fn default_layers<T: Config>(&self) -> Vec<ConfigLayer> {
+        let base_dir = self
+            .search_paths
+            .first()
+            .cloned()
+            .unwrap_or_else(|| PathBuf::from("."));
+        let base = base_dir.join(T::default_path());
+        let platform = platform_variant(&base, std::env::consts::OS);
+
+        let mut layers = vec![
+            ConfigLayer::new("base", base),
+            ConfigLayer::new("platform", platform),
+        ];
+
+        if let Some(user) = Self::user_path::<T>() {
+            layers.push(ConfigLayer::new("user", user));
+        }
+        layers
+    }
+
+    /// Path of the user layer file for a configuration type, in the user
+    /// config directory (`$XDG_CONFIG_HOME/amp`).
+    ///
+    /// Returns `None` if the platform has no user config directory.
+    pub fn user_path<T: Config>() -> Option<PathBuf> {
+        dirs::config_dir().map(|config_dir| config_dir.join("amp").join(T::default_path()))
+    }
+
+    /// Load a configuration from the [default layer stack](ConfigLoader::default_layers).
+    pub fn load_layered<T: Config + Serialize>(&self) -> Result<(T, PrecedenceReport)> {
+        self.load_layers(&self.default_layers::<T>())
+    }
+
+    /// Load a configuration by deep-merging `layers` over the embedded defaults.
+    ///
+    /// Layers are applied in order, so later layers take precedence. A layer
+    /// file may use any [`ConfigFormat`]; if its path does not exist the same
+    /// path with another supported extension is tried. Missing layer files
+    /// are skipped and reported as not loaded.
+    pub fn load_layers<T: Config + Serialize>(
+        &self,
+        layers: &[ConfigLayer],
+    ) -> Result<(T, PrecedenceReport)> {
+        let (merged, report) = self.merge_layers::<T>(layers)?;
+        finish(merged, report)
+    }
+
+    /// Deep-merge `layers` over the embedded defaults without deserializing.
+    pub(crate) fn merge_layers<T: Config + Serialize>(
+        &self,
+        layers: &[ConfigLayer],
+    ) -> Result<(Value, PrecedenceReport)> {
+        let mut report = PrecedenceReport::default();
+
+        let mut merged = to_value(&T::embedded_defaults())?;
+        report.record_layer(DEFAULTS_LAYER, None, true);
+        report.record_sources(DEFAULTS_LAYER, &merged);
+
+        for layer in layers {
+            let Some(path) = format::locate(&layer.path) else {
+                report.record_layer(&layer.name, Some(&layer.path), false);
+                continue;
+            };
+
+            let data =
+                std::fs::read_to_string(&path).map_err(|e| Error::from(ConfigError::from(e)))?;
+            let mut value: Value = ConfigFormat::from_path(&path)
+                .unwrap_or_default()
+                .parse_raw(&data)
+                .map_err(|e| {
+                    Error::from(ConfigError::parse_error(format!(
+                        "{} layer {}: {e}",
+                        layer.name,
+                        path.display()
+                    )))
+                })?;
+            migrate::upgrade::<T>(&mut value, &path)?;
+
+            report.record_layer(&layer.name, Some(&path), true);
+            report.record_sources(&layer.name, &value);
+            deep_merge(&mut merged, value);
+        }
+
+        Ok((merged, report))
+    }
+}
+
+/// Deserialize a merged value and validate it, naming the source of each
+/// invalid field.
+pub(crate) fn finish<T: Config>(
+    merged: Value,
+    report: PrecedenceReport,
+) -> Result<(T, PrecedenceReport)> {
+    let config: T = merged
+        .into_rust()
+        .map_err(|e| Error::from(ConfigError::parse_error(e.to_string())))?;
+    validate::check(&config, |key| report.origin_of(key))?;
+    Ok((config, report))
+}
+
+/// Path of the platform-specific variant of a config file.
+fn platform_variant(base: &Path, os: &str) -> PathBuf {
+    let stem = base
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let file_name = match base.extension() {
+        Some(ext) => format!("{stem}.{os}.{}", ext.to_string_lossy()),
+        None => format!("{stem}.{os}"),
+    };
+    base.with_file_name(file_name)
+}
+
+/// Convert a serializable configuration into a RON value.
+pub(crate) fn to_value<T: Serialize>(config: &T) -> Result<Value> {
+    let text =
+        ron::to_string(config).map_err(|e| Error::from(ConfigError::parse_error(e.to_string())))?;
+    ron::from_str(&text).map_err(|e| Error::from(ConfigError::parse_error(e.to_string())))
+}
+
+/// Merge `overlay` into `base`, recursing into maps present in both.
+///
+/// Any non-map value in `overlay` replaces the value in `base`.
+pub(crate) fn deep_merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Map(base_map), Value::Map(overlay_map)) => {
+            for (key, value) in overlay_map {
+                match base_map.remove(&key) {
+                    Some(mut existing) => {
+                        deep_merge(&mut existing, value);
+                        base_map.insert(key, existing);
+                    }
+                    None => {
+                        base_map.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Render a map key as a path segment.
+pub(crate) fn key_segment(key: &Value) -> String {
+    match key {
+        Value::String(name) => name.clone(),
+        other => ron::to_string(other).unwrap_or_else(|_| "?".to_string()),
+    }
+}
+
+fn collect_leaf_keys(value: &Value, prefix: String, keys: &mut Vec<String>) {
+    match value {
+        Value::Map(map) if !map.is_empty() => {
+            for (key, child) in map.iter() {
+                let segment = key_segment(key);
+                let path = if prefix.is_empty() {
+                    segment
+                } else {
+                    format!("{prefix}.{segment}")
+                };
+                collect_leaf_keys(child, path, keys);
+            }
+        }
+        _ if !prefix.is_empty() => keys.push(prefix),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FactorySettings, GameConfig};
+    use tempfile::TempDir;
+
+    fn loader() -> ConfigLoader {
+        ConfigLoader {
+            search_paths: vec![PathBuf::from("/nonexistent")],
+        }
+    }
+
+    #[test]
+    fn test_layers_deep_merge_in_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path().join("game.ron");
+        let user = temp_dir.path().join("user.ron");
+        std::fs::write(
+            &base,
+            r#"(factory: (prefab_path: "/shipped/*.ron", hot_reload: false))"#,
+        )
+        .unwrap();
+        std::fs::write(&user, "(factory: (hot_reload: true))").unwrap();
+
+        let (config, report): (GameConfig, _) = loader()
+            .load_layers(&[
+                ConfigLayer::new("base", &base),
+                ConfigLayer::new("platform", temp_dir.path().join("missing.ron")),
+                ConfigLayer::new("user", &user),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            config.factory,
+            FactorySettings {
+                prefab_path: "/shipped/*.ron".to_string(),
+                hot_reload: true,
+            }
+        );
+        assert_eq!(report.source_of("factory.prefab_path"), Some("base"));
+        assert_eq!(report.source_of("factory.hot_reload"), Some("user"));
+
+        let loaded: Vec<_> = report
+            .layers()
+            .iter()
+            .map(|layer| (layer.name.as_str(), layer.loaded))
+            .collect();
+        assert_eq!(
+            loaded,
+            vec![
+                ("defaults", true),
+                ("base", true),
+                ("platform", false),
+                ("user", true)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_layers_yields_embedded_defaults() {
+        let (config, report): (GameConfig, _) = loader().load_layers(&[]).unwrap();
+
+        assert_eq!(config, GameConfig::default());
+        assert_eq!(
+            report.source_of("factory.prefab_path"),
+            Some(DEFAULTS_LAYER)
+        );
+    }
+
+    #[test]
+    fn test_invalid_layer_names_layer_in_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let broken = temp_dir.path().join("game.ron");
+        std::fs::write(&broken, "(factory: ").unwrap();
+
+        let result: Result<(GameConfig, _)> =
+            loader().load_layers(&[ConfigLayer::new("platform", &broken)]);
+
+        assert!(result.unwrap_err().to_string().contains("platform layer"));
+    }
+
+    #[test]
+    fn test_report_display() {
+        let temp_dir = TempDir::new().unwrap();
+        let user = temp_dir.path().join("game.ron");
+        std::fs::write(&user, "(factory: (hot_reload: false))").unwrap();
+
+        let (_, report): (GameConfig, _) = loader()
+            .load_layers(&[ConfigLayer::new("user", &user)])
+            .unwrap();
+        let rendered = report.to_string();
+
+        assert!(rendered.contains("[x] defaults (embedded)"));
+        assert!(rendered.contains("factory.hot_reload <- user"));
+        assert!(rendered.contains("factory.prefab_path <- defaults"));
+    }
+
+    #[test]
+    fn test_validation_names_supplying_layer() {
+        let temp_dir = TempDir::new().unwrap();
+        let platform = temp_dir.path().join("game.linux.ron");
+        std::fs::write(&platform, r#"(factory: (prefab_path: " "))"#).unwrap();
+
+        let err = loader()
+            .load_layers::<GameConfig>(&[ConfigLayer::new("platform", &platform)])
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains(&format!(
+            "{}: factory.prefab_path: must not be empty",
+            platform.display()
+        )));
+    }
+
+    #[test]
+    fn test_default_layers() {
+        let loader = ConfigLoader {
+            search_paths: vec![PathBuf::from("/shipped")],
+        };
+        let layers = loader.default_layers::<GameConfig>();
+
+        assert_eq!(layers[0], ConfigLayer::new("base", "/shipped/game.ron"));
+        assert_eq!(
+            layers[1].path,
+            PathBuf::from(format!("/shipped/game.{}.ron", std::env::consts::OS))
+        );
+        if let Some(config_dir) = dirs::config_dir() {
+            assert_eq!(layers[2].path, config_dir.join("amp").join("game.ron"));
+        }
+    }
+}
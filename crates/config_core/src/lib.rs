@@ -8,14 +8,33 @@
 //!
 //! - **GameConfig**: Main configuration structure with factory settings
 //! - **FactorySettings**: Configuration for entity and prefab management
+//! - **Multiple Formats**: Documents authored in RON, TOML or JSON, selected by extension
 //! - **Environment Override**: AMP_CONFIG environment variable support
 //! - **Default Values**: Serde-based default value handling for partial configs
 //! - **Hierarchical Search**: Searches current directory and XDG config paths
+//! - **Layered Loading**: Deep-merges defaults, platform and user overrides with a precedence report
+//! - **Validation**: Field-level checks reporting the file, field path and allowed values
+//! - **Schema Migration**: Versioned documents upgraded on load through a migration registry
+//! - **Change Events**: `ConfigChanged<T>` events with old and new values when a config is republished
+//! - **Value Overrides**: `AMP_CONFIG__section__field` env vars and `--config key=value` arguments
 
 use amp_core::{ConfigError, Error, Result};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::path::PathBuf;
 
+mod format;
+mod handle;
+mod layers;
+mod migrate;
+mod overrides;
+mod validate;
+pub use format::*;
+pub use handle::*;
+pub use layers::*;
+pub use migrate::*;
+pub use overrides::*;
+pub use validate::*;
+
 /// Factory configuration settings for entity and prefab management.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(default)]
@@ -56,6 +75,12 @@ impl FactorySettings {
     }
 }
 
+impl Validate for FactorySettings {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.check_non_empty("prefab_path", &self.prefab_path);
+    }
+}
+
 /// Main game configuration structure.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 #[serde(default)]
@@ -75,14 +100,27 @@ impl Config for GameConfig {
     const FILE_NAME: &'static str = "game.ron";
 }
 
+impl Validate for GameConfig {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.nested("factory", |errors| self.factory.validate(errors));
+    }
+}
+
 /// Trait for configuration types that can be loaded from RON files.
 ///
 /// This trait defines the interface for configuration objects that can be
 /// deserialized from RON format and provides metadata about their storage.
-pub trait Config: DeserializeOwned + Send + Sync + 'static + Default {
+/// Every loaded configuration is checked with its [`Validate`] implementation.
+pub trait Config: DeserializeOwned + Validate + Send + Sync + 'static + Default {
     /// The filename (without path) where this configuration should be stored.
     const FILE_NAME: &'static str;
 
+    /// Current schema version written to the document's `version` field.
+    ///
+    /// Bump this together with a new entry in [`Config::migrations`] whenever
+    /// the file format changes incompatibly.
+    const SCHEMA_VERSION: u32 = INITIAL_VERSION;
+
     /// Returns the default path for this configuration file.
     ///
     /// By default, this returns just the filename, but implementations can
@@ -109,6 +147,13 @@ pub trait Config: DeserializeOwned + Send + Sync + 'static + Default {
     fn merge(self, other: Self) -> Self {
         other
     }
+
+    /// Migrations upgrading older documents to [`Config::SCHEMA_VERSION`].
+    ///
+    /// The default implementation registers none.
+    fn migrations() -> MigrationRegistry {
+        MigrationRegistry::new()
+    }
 }
 
 /// Configuration loader that handles file discovery and caching.
@@ -164,35 +209,38 @@ impl ConfigLoader {
                 let data = std::fs::read_to_string(&path)
                     .map_err(|e| Error::from(ConfigError::from(e)))?;
 
-                let cfg = ron::from_str(&data)
-                    .map_err(|e| Error::from(ConfigError::parse_error(e.to_string())))?;
+                let cfg: T = migrate::parse_config(&data, &path)?;
 
+                validate::check(&cfg, |_| Some(path.display().to_string()))?;
                 return Ok(cfg);
             }
         }
 
         // Start with embedded defaults (compile-time fallback)
         let mut final_config = T::embedded_defaults();
+        let mut origin = None;
 
         // Hierarchical merge: collect configs from all search paths
         // Iterate in reverse order so higher priority paths (CWD) override lower priority (XDG)
         for dir in self.search_paths.iter().rev() {
-            let path = dir.join(T::default_path());
-            if !path.exists() {
+            let Some(path) = format::locate(&dir.join(T::default_path())) else {
                 continue;
-            }
+            };
 
             let data =
                 std::fs::read_to_string(&path).map_err(|e| Error::from(ConfigError::from(e)))?;
 
-            let cfg: T = ron::from_str(&data)
-                .map_err(|e| Error::from(ConfigError::parse_error(e.to_string())))?;
+            let cfg: T = migrate::parse_config(&data, &path)?;
 
             // Merge this config into the final result
             // Since we iterate in reverse, earlier configs (lower priority) merge into later ones (higher priority)
             final_config = final_config.merge(cfg);
+            origin = Some(path.display().to_string());
         }
 
+        // Errors name the highest-priority file that was loaded
+        validate::check(&final_config, |_| origin.clone())?;
+
         // Return final merged config (even if no files found, return embedded defaults)
         Ok(final_config)
     }
@@ -262,6 +310,8 @@ mod tests {
         }
     }
 
+    impl Validate for TestConfig {}
+
     #[test]
     fn test_config_trait() {
         assert_eq!(TestConfig::FILE_NAME, "test.ron");
@@ -402,6 +452,8 @@ mod tests {
         }
     }
 
+    impl Validate for CustomPathConfig {}
+
     #[test]
     fn test_default_path_override() {
         // Test that default_path() is respected instead of hard-coded FILE_NAME
@@ -697,14 +749,10 @@ mod tests {
 
         // On Unix systems, backslashes are treated as literal characters
         // On Windows, shellexpand should properly handle backslashes
-        let expanded = settings.expanded_prefab_path();
-        if expanded.is_ok() {
-            let expanded_str = expanded.unwrap();
+        // If expansion fails, that's acceptable for Windows-style paths on Unix
+        if let Ok(expanded_str) = settings.expanded_prefab_path() {
             assert!(!expanded_str.starts_with('~'));
             assert!(expanded_str.contains("prefabs") && expanded_str.contains("*.ron"));
-        } else {
-            // If expansion fails, that's acceptable for Windows-style paths on Unix
-            assert!(expanded.is_err());
         }
     }
 
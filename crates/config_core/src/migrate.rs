@@ -0,0 +1,426 @@
+//! Config schema versioning and migration.
+//!
+//! Config documents carry a top-level `version` field. Documents without one
+//! are treated as [`INITIAL_VERSION`]. When a config type bumps
+//! [`Config::SCHEMA_VERSION`] it registers one migration per version step in
+//! [`Config::migrations`]; older documents are upgraded on load with a
+//! warning, and `cargo xtask migrate-config` rewrites them in place with
+//! [`migrate_file`].
+//!
+//! # Examples
+//!
+//! ```rust
+//! use config_core::{Config, MigrationRegistry, Validate};
+//! use serde::Deserialize;
+//!
+//! #[derive(Default, Deserialize)]
+//! struct AudioConfig {
+//!     master_volume: f32,
+//! }
+//!
+//! impl Validate for AudioConfig {}
+//!
+//! impl Config for AudioConfig {
+//!     const FILE_NAME: &'static str = "audio.ron";
+//!     const SCHEMA_VERSION: u32 = 2;
+//!
+//!     fn migrations() -> MigrationRegistry {
+//!         // Version 2 renamed `volume` to `master_volume`
+//!         MigrationRegistry::new().with_migration(1, |doc| {
+//!             MigrationRegistry::rename_field(doc, "volume", "master_volume");
+//!             Ok(())
+//!         })
+//!     }
+//! }
+//! ```
+
+use amp_core::{ConfigError, Error, Result};
+use ron::Value;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::{Config, ConfigFormat};
+
+/// Name of the top-level schema version field.
+pub const VERSION_KEY: &str = "version";
+
+/// Schema version assumed for documents without a version field.
+pub const INITIAL_VERSION: u32 = 1;
+
+type MigrationFn = Box<dyn Fn(&mut Value) -> Result<()> + Send + Sync>;
+
+/// Ordered set of single-step document migrations.
+#[derive(Default)]
+pub struct MigrationRegistry {
+    steps: BTreeMap<u32, MigrationFn>,
+}
+
+impl MigrationRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the migration upgrading documents from `from_version` to
+    /// `from_version + 1`.
+    pub fn with_migration<F>(mut self, from_version: u32, migration: F) -> Self
+    where
+        F: Fn(&mut Value) -> Result<()> + Send + Sync + 'static,
+    {
+        self.steps.insert(from_version, Box::new(migration));
+        self
+    }
+
+    /// Upgrade `doc` to `target` and strip its version field.
+    ///
+    /// Returns the version the document was written in.
+    pub fn migrate(&self, doc: &mut Value, target: u32) -> Result<u32> {
+        let original = document_version(doc)?;
+        if original > target {
+            return Err(Error::from(ConfigError::invalid_format(format!(
+                "schema version {original} is newer than supported version {target}"
+            ))));
+        }
+
+        if let Value::Map(map) = doc {
+            map.remove(&Value::String(VERSION_KEY.to_string()));
+        }
+
+        for version in original..target {
+            let step = self.steps.get(&version).ok_or_else(|| {
+                Error::from(ConfigError::invalid_format(format!(
+                    "no migration from schema version {version} to {}",
+                    version + 1
+                )))
+            })?;
+            step(doc)?;
+        }
+        Ok(original)
+    }
+
+    /// Rename a top-level field, keeping its value. Does nothing if the
+    /// field is absent.
+    pub fn rename_field(doc: &mut Value, from: &str, to: &str) {
+        if let Value::Map(map) = doc {
+            if let Some(value) = map.remove(&Value::String(from.to_string())) {
+                map.insert(Value::String(to.to_string()), value);
+            }
+        }
+    }
+}
+
+/// Read the schema version of a document.
+pub fn document_version(doc: &Value) -> Result<u32> {
+    let Value::Map(map) = doc else {
+        return Ok(INITIAL_VERSION);
+    };
+    let key = Value::String(VERSION_KEY.to_string());
+    match map.iter().find(|(name, _)| **name == key) {
+        None => Ok(INITIAL_VERSION),
+        Some((_, Value::Number(number))) => number
+            .as_i64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| {
+                Error::from(ConfigError::invalid_format(format!(
+                    "`{VERSION_KEY}` must be a non-negative integer"
+                )))
+            }),
+        Some(_) => Err(Error::from(ConfigError::invalid_format(format!(
+            "`{VERSION_KEY}` must be a non-negative integer"
+        )))),
+    }
+}
+
+/// Upgrade a parsed document of type `T` loaded from `origin`, warning if
+/// it was written in an older schema.
+pub(crate) fn upgrade<T: Config>(doc: &mut Value, origin: &Path) -> Result<()> {
+    let original = T::migrations()
+        .migrate(doc, T::SCHEMA_VERSION)
+        .map_err(|e| with_origin(e, origin))?;
+    if original < T::SCHEMA_VERSION {
+        log::warn!(
+            "{}: migrated config from schema version {original} to {}; \
+             run `cargo xtask migrate-config` to update the file",
+            origin.display(),
+            T::SCHEMA_VERSION
+        );
+    }
+    Ok(())
+}
+
+/// Parse a config document of type `T`, migrating it if needed.
+///
+/// Current-version documents are deserialized directly so that typed RON
+/// features such as enum variants are preserved.
+pub(crate) fn parse_config<T: Config>(data: &str, origin: &Path) -> Result<T> {
+    let format = ConfigFormat::from_path(origin).unwrap_or_default();
+
+    let Ok(mut doc) = format.parse::<Value>(data) else {
+        return format.parse(data);
+    };
+    if document_version(&doc).map_err(|e| with_origin(e, origin))? == T::SCHEMA_VERSION {
+        return format.parse(data);
+    }
+
+    upgrade::<T>(&mut doc, origin)?;
+    doc.into_rust()
+        .map_err(|e| Error::from(ConfigError::parse_error(e.to_string())))
+}
+
+/// Rewrite the config file at `path` in the current schema version.
+///
+/// Returns the version the file was written in, or `None` if it was already
+/// current and left untouched. The file is re-serialized from `T`, so
+/// comments and unknown fields are not preserved.
+pub fn migrate_file<T: Config + Serialize>(path: &Path) -> Result<Option<u32>> {
+    let format = ConfigFormat::from_path(path).unwrap_or_default();
+    let data = std::fs::read_to_string(path).map_err(|e| Error::from(ConfigError::from(e)))?;
+    let mut doc: Value = format
+        .parse_raw(&data)
+        .map_err(|e| Error::from(ConfigError::parse_error(format!("{}: {e}", path.display()))))?;
+
+    let original = document_version(&doc).map_err(|e| with_origin(e, path))?;
+    if original == T::SCHEMA_VERSION {
+        return Ok(None);
+    }
+
+    T::migrations()
+        .migrate(&mut doc, T::SCHEMA_VERSION)
+        .map_err(|e| with_origin(e, path))?;
+    let config: T = doc
+        .into_rust()
+        .map_err(|e| Error::from(ConfigError::parse_error(e.to_string())))?;
+
+    std::fs::write(path, render_versioned(format, &config, T::SCHEMA_VERSION)?)
+        .map_err(|e| Error::from(ConfigError::from(e)))?;
+    Ok(Some(original))
+}
+
+/// Write a config to `path` in the current schema version.
+///
+/// The format is selected by the path's extension, defaulting to RON, and
+/// missing parent directories are created. Use this to persist settings
+/// changed at runtime, such as to the user layer from
+/// [`ConfigLoader::user_path`](crate::ConfigLoader::user_path).
+pub fn save_config<T: Config + Serialize>(config: &T, path: &Path) -> Result<()> {
+    let format = ConfigFormat::from_path(path).unwrap_or_default();
+    let data = render_versioned(format, config, T::SCHEMA_VERSION)?;
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent).map_err(|e| Error::from(ConfigError::from(e)))?;
+    }
+    std::fs::write(path, data).map_err(|e| Error::from(ConfigError::from(e)))
+}
+
+/// Render a config struct with a leading version field.
+fn render_versioned<T: Serialize>(
+    format: ConfigFormat,
+    config: &T,
+    version: u32,
+) -> Result<String> {
+    let not_a_struct = || {
+        Error::from(ConfigError::invalid_format(
+            "versioned configs must serialize as structs",
+        ))
+    };
+
+    match format {
+        ConfigFormat::Ron => {
+            let body = format.render(config)?;
+            let fields = body.strip_prefix('(').ok_or_else(not_a_struct)?;
+            Ok(format!("(\n    {VERSION_KEY}: {version},{fields}\n"))
+        }
+        ConfigFormat::Toml => {
+            let value = toml::Value::try_from(config)
+                .map_err(|e| Error::from(ConfigError::parse_error(e.to_string())))?;
+            let toml::Value::Table(mut table) = value else {
+                return Err(not_a_struct());
+            };
+            table.insert(
+                VERSION_KEY.to_string(),
+                toml::Value::Integer(version.into()),
+            );
+            format.render(&table)
+        }
+        ConfigFormat::Json => {
+            let mut value = serde_json::to_value(config)
+                .map_err(|e| Error::from(ConfigError::parse_error(e.to_string())))?;
+            value
+                .as_object_mut()
+                .ok_or_else(not_a_struct)?
+                .insert(VERSION_KEY.to_string(), version.into());
+            format.render(&value)
+        }
+    }
+}
+
+fn with_origin(error: Error, origin: &Path) -> Error {
+    match error {
+        Error::Config(ConfigError::InvalidFormat { message }) => Error::from(
+            ConfigError::invalid_format(format!("{}: {message}", origin.display())),
+        ),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConfigLoader, Validate};
+    use serde::Deserialize;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    /// Version 1 used `prefab_dir`, version 2 renamed it to `prefab_path`
+    /// and version 3 split `budget` out of `limits`.
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    #[serde(default)]
+    struct StreamingConfig {
+        prefab_path: String,
+        budget: u32,
+    }
+
+    impl Validate for StreamingConfig {}
+
+    impl Config for StreamingConfig {
+        const FILE_NAME: &'static str = "streaming.ron";
+        const SCHEMA_VERSION: u32 = 3;
+
+        fn migrations() -> MigrationRegistry {
+            MigrationRegistry::new()
+                .with_migration(1, |doc| {
+                    MigrationRegistry::rename_field(doc, "prefab_dir", "prefab_path");
+                    Ok(())
+                })
+                .with_migration(2, |doc| {
+                    MigrationRegistry::rename_field(doc, "limits", "budget");
+                    Ok(())
+                })
+        }
+    }
+
+    fn expected() -> StreamingConfig {
+        StreamingConfig {
+            prefab_path: "prefabs/*.ron".to_string(),
+            budget: 64,
+        }
+    }
+
+    #[test]
+    fn test_unversioned_document_is_migrated_on_load() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("streaming.ron"),
+            r#"(prefab_dir: "prefabs/*.ron", limits: 64)"#,
+        )
+        .unwrap();
+
+        let loader = ConfigLoader {
+            search_paths: vec![temp_dir.path().to_path_buf()],
+        };
+        assert_eq!(
+            loader.load_with_merge::<StreamingConfig>().unwrap(),
+            expected()
+        );
+    }
+
+    #[test]
+    fn test_layers_are_migrated_before_merging() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path().join("streaming.ron");
+        let user = temp_dir.path().join("user.ron");
+        std::fs::write(&base, r#"(version: 2, prefab_path: "prefabs/*.ron")"#).unwrap();
+        std::fs::write(&user, "(limits: 64)").unwrap();
+
+        let loader = ConfigLoader {
+            search_paths: vec![PathBuf::from("/nonexistent")],
+        };
+        let (config, report) = loader
+            .load_layers::<StreamingConfig>(&[
+                crate::ConfigLayer::new("base", &base),
+                crate::ConfigLayer::new("user", &user),
+            ])
+            .unwrap();
+
+        assert_eq!(config, expected());
+        assert_eq!(report.source_of("budget"), Some("user"));
+        assert_eq!(report.source_of(VERSION_KEY), None);
+    }
+
+    #[test]
+    fn test_newer_and_gapped_versions_rejected() {
+        let mut newer: Value = ron::from_str("(version: 4)").unwrap();
+        let err = StreamingConfig::migrations()
+            .migrate(&mut newer, 3)
+            .unwrap_err();
+        assert!(err.to_string().contains("newer than supported"));
+
+        let mut gapped: Value = ron::from_str("(version: 1)").unwrap();
+        let err = MigrationRegistry::new()
+            .migrate(&mut gapped, 2)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("no migration from schema version 1"));
+    }
+
+    #[test]
+    fn test_migrate_file_rewrites_in_place() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("streaming.ron");
+        std::fs::write(&path, r#"(prefab_dir: "prefabs/*.ron", limits: 64)"#).unwrap();
+
+        assert_eq!(migrate_file::<StreamingConfig>(&path).unwrap(), Some(1));
+
+        let rewritten = std::fs::read_to_string(&path).unwrap();
+        let doc: Value = ron::from_str(&rewritten).unwrap();
+        assert_eq!(document_version(&doc).unwrap(), 3);
+        assert_eq!(
+            ron::from_str::<StreamingConfig>(&rewritten).unwrap(),
+            expected()
+        );
+
+        assert_eq!(migrate_file::<StreamingConfig>(&path).unwrap(), None);
+    }
+
+    #[test]
+    fn test_migrate_file_keeps_format() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("streaming.toml");
+        std::fs::write(&path, "prefab_dir = \"prefabs/*.ron\"\nlimits = 64\n").unwrap();
+
+        assert_eq!(migrate_file::<StreamingConfig>(&path).unwrap(), Some(1));
+
+        let rewritten = std::fs::read_to_string(&path).unwrap();
+        let doc: Value = ConfigFormat::Toml.parse(&rewritten).unwrap();
+        assert_eq!(document_version(&doc).unwrap(), 3);
+        assert_eq!(
+            ConfigFormat::Toml
+                .parse::<StreamingConfig>(&rewritten)
+                .unwrap(),
+            expected()
+        );
+    }
+
+    #[test]
+    fn test_save_config_round_trips() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("user/streaming.ron");
+        save_config(&expected(), &path).unwrap();
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        let doc: Value = ron::from_str(&saved).unwrap();
+        assert_eq!(document_version(&doc).unwrap(), 3);
+
+        let loader = ConfigLoader {
+            search_paths: vec![temp_dir.path().join("user")],
+        };
+        assert_eq!(
+            loader.load_with_merge::<StreamingConfig>().unwrap(),
+            expected()
+        );
+    }
+}
@@ -0,0 +1,347 @@
+//! Environment variable and command-line overrides.
+//!
+//! Overrides are applied after all file layers have been merged, so a CI
+//! stress run or a server deployment can change individual values without
+//! shipping a config file:
+//!
+//! ```text
+//! AMP_CONFIG__FACTORY__HOT_RELOAD=false cargo run -- --config factory.prefab_path=/srv/prefabs/*.ron
+//! ```
+//!
+//! Environment variable names are split on `__` and lowercased, so both
+//! `AMP_CONFIG__factory__hot_reload` and `AMP_CONFIG__FACTORY__HOT_RELOAD`
+//! address `factory.hot_reload`. Values are written in RON syntax; values
+//! for string fields may be given unquoted.
+
+use amp_core::{ConfigError, Error, Result};
+use ron::Value;
+use serde::Serialize;
+
+use crate::layers::{deep_merge, finish, key_segment, to_value};
+use crate::{Config, ConfigLoader, PrecedenceReport};
+
+/// Prefix of environment variables treated as config overrides.
+pub const ENV_OVERRIDE_PREFIX: &str = "AMP_CONFIG__";
+
+/// Source name reported for environment variable overrides.
+pub const ENV_OVERRIDE_SOURCE: &str = "env";
+
+/// Source name reported for command-line overrides.
+pub const CLI_OVERRIDE_SOURCE: &str = "cli";
+
+/// Command-line flag introducing an override.
+pub const CLI_OVERRIDE_FLAG: &str = "--config";
+
+/// A single `key=value` override.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigOverride {
+    /// Dotted key path, such as `factory.hot_reload`
+    pub key: String,
+    /// Raw value in RON syntax
+    pub value: String,
+    /// Where the override came from, used in precedence reports
+    pub source: String,
+}
+
+/// An ordered set of overrides; later overrides win.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigOverrides {
+    overrides: Vec<ConfigOverride>,
+}
+
+impl ConfigOverrides {
+    /// Create an empty override set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collect overrides from the process environment.
+    pub fn from_env() -> Self {
+        Self::from_env_vars(std::env::vars())
+    }
+
+    /// Collect overrides from `(name, value)` pairs, ignoring names without
+    /// the [`ENV_OVERRIDE_PREFIX`].
+    ///
+    /// Variables are sorted by name so the result does not depend on
+    /// environment ordering.
+    pub fn from_env_vars<I, K, V>(vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let mut vars: Vec<(String, String)> = vars
+            .into_iter()
+            .map(|(name, value)| (name.into(), value.into()))
+            .collect();
+        vars.sort();
+
+        let mut overrides = Self::new();
+        for (name, value) in vars {
+            let Some(path) = name.strip_prefix(ENV_OVERRIDE_PREFIX) else {
+                continue;
+            };
+            let key = path
+                .split("__")
+                .map(str::to_lowercase)
+                .collect::<Vec<_>>()
+                .join(".");
+            overrides.push(ENV_OVERRIDE_SOURCE, key, value);
+        }
+        overrides
+    }
+
+    /// Collect overrides from command-line arguments.
+    ///
+    /// Accepts both `--config key=value` and `--config=key=value`; all other
+    /// arguments are ignored.
+    pub fn from_args<I, S>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut overrides = Self::new();
+        let mut args = args.into_iter().map(Into::into);
+
+        while let Some(arg) = args.next() {
+            let assignment = if arg == CLI_OVERRIDE_FLAG {
+                args.next().ok_or_else(|| {
+                    Error::from(ConfigError::invalid_format(format!(
+                        "{CLI_OVERRIDE_FLAG} requires a key=value argument"
+                    )))
+                })?
+            } else if let Some(assignment) = arg
+                .strip_prefix(CLI_OVERRIDE_FLAG)
+                .and_then(|rest| rest.strip_prefix('='))
+            {
+                assignment.to_string()
+            } else {
+                continue;
+            };
+
+            let (key, value) = assignment.split_once('=').ok_or_else(|| {
+                Error::from(ConfigError::invalid_format(format!(
+                    "expected key=value after {CLI_OVERRIDE_FLAG}, got `{assignment}`"
+                )))
+            })?;
+            overrides.push(CLI_OVERRIDE_SOURCE, key.trim(), value.trim());
+        }
+
+        Ok(overrides)
+    }
+
+    /// Add an override.
+    pub fn push(
+        &mut self,
+        source: impl Into<String>,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) {
+        self.overrides.push(ConfigOverride {
+            key: key.into(),
+            value: value.into(),
+            source: source.into(),
+        });
+    }
+
+    /// Append `other`, giving its overrides precedence.
+    pub fn extend(&mut self, other: ConfigOverrides) {
+        self.overrides.extend(other.overrides);
+    }
+
+    /// Iterate over overrides in application order.
+    pub fn iter(&self) -> impl Iterator<Item = &ConfigOverride> {
+        self.overrides.iter()
+    }
+
+    /// Number of overrides.
+    pub fn len(&self) -> usize {
+        self.overrides.len()
+    }
+
+    /// Check if there are no overrides.
+    pub fn is_empty(&self) -> bool {
+        self.overrides.is_empty()
+    }
+
+    /// Apply the overrides to an already loaded configuration and validate
+    /// the result.
+    pub fn apply<T: Config + Serialize>(&self, config: &T) -> Result<T> {
+        let mut value = to_value(config)?;
+        let mut report = PrecedenceReport::default();
+        self.apply_to_value(&mut value, &mut report)?;
+        finish(value, report).map(|(config, _)| config)
+    }
+
+    /// Apply the overrides to a merged RON value, recording their sources.
+    ///
+    /// Unknown keys are rejected so that typos do not silently fall back to
+    /// file values.
+    pub(crate) fn apply_to_value(
+        &self,
+        root: &mut Value,
+        report: &mut PrecedenceReport,
+    ) -> Result<()> {
+        for entry in &self.overrides {
+            let target = lookup_mut(root, &entry.key).ok_or_else(|| {
+                Error::from(ConfigError::invalid_format(format!(
+                    "unknown config key `{}` ({} override)",
+                    entry.key, entry.source
+                )))
+            })?;
+
+            let value = match target {
+                Value::String(_) if !entry.value.starts_with('"') => {
+                    Value::String(entry.value.clone())
+                }
+                _ => ron::from_str(&entry.value).map_err(|e| {
+                    Error::from(ConfigError::parse_error(format!(
+                        "invalid value for `{}` ({} override): {e}",
+                        entry.key, entry.source
+                    )))
+                })?,
+            };
+
+            deep_merge(target, value);
+            report.record_source(&entry.key, &entry.source);
+        }
+        Ok(())
+    }
+}
+
+impl ConfigLoader {
+    /// Load a configuration from the [default layer stack](ConfigLoader::default_layers)
+    /// and apply `overrides` on top.
+    pub fn load_layered_with_overrides<T: Config + Serialize>(
+        &self,
+        overrides: &ConfigOverrides,
+    ) -> Result<(T, PrecedenceReport)> {
+        let (mut merged, mut report) = self.merge_layers::<T>(&self.default_layers::<T>())?;
+        overrides.apply_to_value(&mut merged, &mut report)?;
+        finish(merged, report)
+    }
+}
+
+/// Find the value at a dotted key path.
+fn lookup_mut<'a>(root: &'a mut Value, key: &str) -> Option<&'a mut Value> {
+    let mut current = root;
+    for segment in key.split('.') {
+        let Value::Map(map) = current else {
+            return None;
+        };
+        current = map
+            .iter_mut()
+            .find_map(|(name, child)| (key_segment(name) == segment).then_some(child))?;
+    }
+    Some(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FactorySettings, GameConfig};
+
+    #[test]
+    fn test_env_vars_map_to_key_paths() {
+        let overrides = ConfigOverrides::from_env_vars([
+            ("PATH", "/usr/bin"),
+            ("AMP_CONFIG__FACTORY__HOT_RELOAD", "true"),
+            ("AMP_CONFIG", "/etc/amp/game.ron"),
+        ]);
+
+        let keys: Vec<_> = overrides
+            .iter()
+            .map(|o| (o.key.as_str(), o.source.as_str()))
+            .collect();
+        assert_eq!(keys, vec![("factory.hot_reload", ENV_OVERRIDE_SOURCE)]);
+    }
+
+    #[test]
+    fn test_cli_args_both_forms() {
+        let overrides = ConfigOverrides::from_args([
+            "amp_game",
+            "--config",
+            "factory.hot_reload=true",
+            "--verbose",
+            "--config=factory.prefab_path=/srv/*.ron",
+        ])
+        .unwrap();
+
+        let pairs: Vec<_> = overrides
+            .iter()
+            .map(|o| (o.key.as_str(), o.value.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("factory.hot_reload", "true"),
+                ("factory.prefab_path", "/srv/*.ron")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cli_args_malformed() {
+        assert!(ConfigOverrides::from_args(["--config"]).is_err());
+        assert!(ConfigOverrides::from_args(["--config", "factory.hot_reload"]).is_err());
+    }
+
+    #[test]
+    fn test_apply_overrides_typed_and_string_values() {
+        let mut overrides = ConfigOverrides::new();
+        overrides.push(CLI_OVERRIDE_SOURCE, "factory.hot_reload", "true");
+        overrides.push(CLI_OVERRIDE_SOURCE, "factory.prefab_path", "/srv/*.ron");
+
+        let config = overrides.apply(&GameConfig::default()).unwrap();
+        assert_eq!(
+            config.factory,
+            FactorySettings {
+                prefab_path: "/srv/*.ron".to_string(),
+                hot_reload: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_later_overrides_win() {
+        let mut overrides =
+            ConfigOverrides::from_env_vars([("AMP_CONFIG__factory__hot_reload", "true")]);
+        overrides
+            .extend(ConfigOverrides::from_args(["--config", "factory.hot_reload=false"]).unwrap());
+
+        let mut value = to_value(&GameConfig::default()).unwrap();
+        let mut report = PrecedenceReport::default();
+        overrides.apply_to_value(&mut value, &mut report).unwrap();
+
+        let config: GameConfig = value.into_rust().unwrap();
+        assert!(!config.factory.hot_reload);
+        assert_eq!(
+            report.source_of("factory.hot_reload"),
+            Some(CLI_OVERRIDE_SOURCE)
+        );
+    }
+
+    #[test]
+    fn test_unknown_key_and_bad_value_rejected() {
+        let mut unknown = ConfigOverrides::new();
+        unknown.push(ENV_OVERRIDE_SOURCE, "factory.hot_reloda", "true");
+        let err = unknown.apply(&GameConfig::default()).unwrap_err();
+        assert!(err.to_string().contains("factory.hot_reloda"));
+
+        let mut bad = ConfigOverrides::new();
+        bad.push(CLI_OVERRIDE_SOURCE, "factory.hot_reload", "yes please");
+        assert!(bad.apply(&GameConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_invalid_override_fails_validation() {
+        let mut overrides = ConfigOverrides::new();
+        overrides.push(CLI_OVERRIDE_SOURCE, "factory.prefab_path", "\"\"");
+
+        let err = overrides.apply(&GameConfig::default()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("cli: factory.prefab_path: must not be empty"));
+    }
+}
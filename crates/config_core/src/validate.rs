@@ -0,0 +1,292 @@
+//! Field-level configuration validation.
+//!
+//! RON parsing only guarantees that a file is well formed. Config types
+//! implement [`Validate`] to check ranges, allowed values and cross-field
+//! constraints; the loaders run it after every load and report each failing
+//! field together with the file that supplied it.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use config_core::{Validate, ValidationErrors};
+//!
+//! struct Streaming {
+//!     radius: f32,
+//!     quality: String,
+//! }
+//!
+//! impl Validate for Streaming {
+//!     fn validate(&self, errors: &mut ValidationErrors) {
+//!         errors.check_range("radius", self.radius, 50.0..=2000.0);
+//!         errors.check_one_of("quality", self.quality.as_str(), &["low", "medium", "high"]);
+//!     }
+//! }
+//!
+//! let mut errors = ValidationErrors::new();
+//! Streaming { radius: 10.0, quality: "ultra".into() }.validate(&mut errors);
+//! assert_eq!(errors.len(), 2);
+//! ```
+
+use amp_core::{Error, Result};
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// Configuration types that can check their own values.
+pub trait Validate {
+    /// Record every invalid field in `errors`.
+    ///
+    /// The default implementation accepts any value.
+    fn validate(&self, errors: &mut ValidationErrors) {
+        let _ = errors;
+    }
+}
+
+/// A single invalid field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    /// Dotted path of the field, such as `factory.prefab_path`
+    pub path: String,
+    /// What is wrong with the value
+    pub message: String,
+    /// Description of the accepted values, if known
+    pub allowed: Option<String>,
+    /// File or override source that supplied the value, if known
+    pub origin: Option<String>,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(origin) = &self.origin {
+            write!(f, "{origin}: ")?;
+        }
+        write!(f, "{}: {}", self.path, self.message)?;
+        if let Some(allowed) = &self.allowed {
+            write!(f, " (allowed: {allowed})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Collects [`FieldError`]s while a configuration is validated.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationErrors {
+    prefix: String,
+    errors: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    /// Create an empty collection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an invalid field.
+    pub fn push(&mut self, field: &str, message: impl Into<String>) {
+        self.push_error(field, message.into(), None);
+    }
+
+    /// Record an invalid field together with a description of the accepted values.
+    pub fn push_with_allowed(
+        &mut self,
+        field: &str,
+        message: impl Into<String>,
+        allowed: impl Into<String>,
+    ) {
+        self.push_error(field, message.into(), Some(allowed.into()));
+    }
+
+    /// Check that `value` lies within `range`.
+    pub fn check_range<T>(&mut self, field: &str, value: T, range: RangeInclusive<T>)
+    where
+        T: PartialOrd + fmt::Display,
+    {
+        if !range.contains(&value) {
+            self.push_with_allowed(
+                field,
+                format!("{value} is out of range"),
+                format!("{}..={}", range.start(), range.end()),
+            );
+        }
+    }
+
+    /// Check that `value` is one of `allowed`.
+    pub fn check_one_of<T>(&mut self, field: &str, value: T, allowed: &[T])
+    where
+        T: PartialEq + fmt::Display,
+    {
+        if !allowed.contains(&value) {
+            let allowed = allowed
+                .iter()
+                .map(|value| format!("`{value}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            self.push_with_allowed(field, format!("`{value}` is not supported"), allowed);
+        }
+    }
+
+    /// Check that a string value is not empty or whitespace.
+    pub fn check_non_empty(&mut self, field: &str, value: &str) {
+        if value.trim().is_empty() {
+            self.push(field, "must not be empty");
+        }
+    }
+
+    /// Validate a nested section, prefixing its field paths with `section`.
+    pub fn nested(&mut self, section: &str, validate: impl FnOnce(&mut Self)) {
+        let prefix = self.field_path(section);
+        let outer = std::mem::replace(&mut self.prefix, prefix);
+        validate(self);
+        self.prefix = outer;
+    }
+
+    /// Iterate over recorded errors in the order they were found.
+    pub fn iter(&self) -> impl Iterator<Item = &FieldError> {
+        self.errors.iter()
+    }
+
+    /// Number of recorded errors.
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Check if no errors were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Convert into `Ok(())` if empty, or a validation error listing every field.
+    pub fn into_result(self) -> Result<()> {
+        if self.errors.is_empty() {
+            return Ok(());
+        }
+
+        let mut message = format!("{} invalid config value(s)", self.errors.len());
+        for error in &self.errors {
+            message.push_str("\n  ");
+            message.push_str(&error.to_string());
+        }
+        Err(Error::validation(message))
+    }
+
+    fn push_error(&mut self, field: &str, message: String, allowed: Option<String>) {
+        let path = self.field_path(field);
+        self.errors.push(FieldError {
+            path,
+            message,
+            allowed,
+            origin: None,
+        });
+    }
+
+    fn field_path(&self, field: &str) -> String {
+        if self.prefix.is_empty() {
+            field.to_string()
+        } else {
+            format!("{}.{field}", self.prefix)
+        }
+    }
+}
+
+/// Validate `config`, attributing each error with `origin_of(field_path)`.
+pub(crate) fn check<T: Validate>(
+    config: &T,
+    origin_of: impl Fn(&str) -> Option<String>,
+) -> Result<()> {
+    let mut errors = ValidationErrors::new();
+    config.validate(&mut errors);
+    for error in &mut errors.errors {
+        error.origin = origin_of(&error.path);
+    }
+    errors.into_result()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Physics {
+        substeps: u32,
+        solver: String,
+        min_speed: f32,
+        max_speed: f32,
+    }
+
+    impl Validate for Physics {
+        fn validate(&self, errors: &mut ValidationErrors) {
+            errors.check_range("substeps", self.substeps, 1..=8);
+            errors.check_one_of("solver", self.solver.as_str(), &["pgs", "tgs"]);
+            if self.min_speed > self.max_speed {
+                errors.push(
+                    "min_speed",
+                    format!(
+                        "{} is greater than max_speed ({})",
+                        self.min_speed, self.max_speed
+                    ),
+                );
+            }
+        }
+    }
+
+    struct Root {
+        physics: Physics,
+    }
+
+    impl Validate for Root {
+        fn validate(&self, errors: &mut ValidationErrors) {
+            errors.nested("physics", |errors| self.physics.validate(errors));
+        }
+    }
+
+    fn invalid_root() -> Root {
+        Root {
+            physics: Physics {
+                substeps: 0,
+                solver: "euler".to_string(),
+                min_speed: 10.0,
+                max_speed: 5.0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_nested_field_paths_and_allowed_values() {
+        let mut errors = ValidationErrors::new();
+        invalid_root().validate(&mut errors);
+
+        let rendered: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            rendered,
+            vec![
+                "physics.substeps: 0 is out of range (allowed: 1..=8)",
+                "physics.solver: `euler` is not supported (allowed: `pgs`, `tgs`)",
+                "physics.min_speed: 10 is greater than max_speed (5)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_names_origin() {
+        let err = check(&invalid_root(), |path| {
+            (path == "physics.solver").then(|| "physics.linux.ron".to_string())
+        })
+        .unwrap_err()
+        .to_string();
+
+        assert!(err.contains("3 invalid config value(s)"));
+        assert!(err.contains("physics.linux.ron: physics.solver"));
+        assert!(err.contains("\n  physics.substeps"));
+    }
+
+    #[test]
+    fn test_valid_config_passes() {
+        let root = Root {
+            physics: Physics {
+                substeps: 4,
+                solver: "tgs".to_string(),
+                min_speed: 0.0,
+                max_speed: 5.0,
+            },
+        };
+        assert!(check(&root, |_| None).is_ok());
+    }
+}
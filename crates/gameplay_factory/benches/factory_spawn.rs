@@ -0,0 +1,142 @@
+//! Entity spawning benchmarks
+//!
+//! Run with `cargo xtask perf` for machine-readable output.
+
+use amp_core::Error;
+use bevy_ecs::prelude::*;
+use bevy_ecs::system::CommandQueue;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use gameplay_factory::{ComponentInit, EntityPool, Factory, Prefab, PrefabId};
+use std::any::Any;
+
+#[allow(dead_code)]
+#[derive(Component, Clone)]
+struct Health(u32);
+
+#[allow(dead_code)]
+#[derive(Component, Clone)]
+struct Position([f32; 3]);
+
+struct HealthInit;
+
+impl ComponentInit for HealthInit {
+    fn init(&self, cmd: &mut Commands, entity: Entity) -> Result<(), Error> {
+        cmd.entity(entity).insert(Health(100));
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+struct PositionInit;
+
+impl ComponentInit for PositionInit {
+    fn init(&self, cmd: &mut Commands, entity: Entity) -> Result<(), Error> {
+        cmd.entity(entity).insert(Position([0.0; 3]));
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+const PREFAB: PrefabId = PrefabId::new(0xBE7C_0001);
+const BUNDLE_PREFAB: PrefabId = PrefabId::new(0xBE7C_0002);
+
+fn factory() -> Factory {
+    let mut factory = Factory::new();
+    factory
+        .register(
+            PREFAB,
+            Prefab::new()
+                .with_component(Box::new(HealthInit))
+                .with_component(Box::new(PositionInit)),
+        )
+        .expect("benchmark prefab registers once");
+    factory
+        .register_bundle(BUNDLE_PREFAB, (Health(100), Position([0.0; 3])))
+        .expect("benchmark bundle registers once");
+    factory
+}
+
+fn spawn_entities(world: &mut World, factory: &Factory, count: usize) -> Vec<Entity> {
+    let mut queue = CommandQueue::default();
+    let mut cmd = Commands::new(&mut queue, world);
+    let entities = (0..count)
+        .map(|_| factory.spawn(&mut cmd, PREFAB).unwrap())
+        .collect();
+    queue.apply(world);
+    entities
+}
+
+/// Spawning fresh entities from a prefab
+fn bench_entity_spawn(c: &mut Criterion, factory: &Factory) {
+    let mut group = c.benchmark_group("entity_spawn");
+    for count in [1_000, 10_000] {
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, &count| {
+            b.iter_batched(
+                World::new,
+                |mut world| black_box(spawn_entities(&mut world, factory, count)),
+                BatchSize::LargeInput,
+            );
+        });
+        group.bench_with_input(
+            BenchmarkId::new("bundle_batch", count),
+            &count,
+            |b, &count| {
+                b.iter_batched(
+                    World::new,
+                    |mut world| black_box(factory.spawn_batch(&mut world, BUNDLE_PREFAB, count)),
+                    BatchSize::LargeInput,
+                );
+            },
+        );
+    }
+    group.finish();
+}
+
+/// Streaming churn: release a sector's worth of entities and spawn it back
+fn bench_pool_churn(c: &mut Criterion, factory: &Factory) {
+    const SECTOR: usize = 1_000;
+
+    let mut group = c.benchmark_group("streaming_churn");
+    group.bench_function("despawn_respawn", |b| {
+        let mut world = World::new();
+        let mut entities = spawn_entities(&mut world, factory, SECTOR);
+        b.iter(|| {
+            for entity in entities.drain(..) {
+                world.despawn(entity);
+            }
+            entities = spawn_entities(&mut world, factory, SECTOR);
+        });
+    });
+    group.bench_function("pooled", |b| {
+        let mut world = World::new();
+        let mut pool = EntityPool::with_default_capacity(SECTOR);
+        let mut entities = Vec::with_capacity(SECTOR);
+        b.iter(|| {
+            let mut queue = CommandQueue::default();
+            let mut cmd = Commands::new(&mut queue, &world);
+            for entity in entities.drain(..) {
+                pool.release(&mut cmd, entity, PREFAB);
+            }
+            for _ in 0..SECTOR {
+                entities.push(factory.spawn_pooled(&mut cmd, &mut pool, PREFAB).unwrap());
+            }
+            queue.apply(&mut world);
+        });
+    });
+    group.finish();
+}
+
+fn benches(c: &mut Criterion) {
+    let factory = factory();
+    bench_entity_spawn(c, &factory);
+    bench_pool_churn(c, &factory);
+}
+
+criterion_group!(factory_benches, benches);
+criterion_main!(factory_benches);
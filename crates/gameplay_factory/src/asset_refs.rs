@@ -0,0 +1,220 @@
+//! Asset reference counting
+//!
+//! Sectors and prefabs that stream in acquire the assets they use through
+//! [`AssetRefCounts`]; when they stream out they release them. An asset whose
+//! last user is gone is unloaded through [`DependencyResolver::release`] once
+//! a grace period passes without it being acquired again, so a sector that
+//! streams out and straight back in does not reload its meshes.
+
+use crate::{DependencyResolver, Factory, PrefabId};
+use amp_core::Error;
+use bevy_ecs::system::Resource;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+/// Owner of asset references
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetUser {
+    /// A streamed world sector, by region id
+    Sector(u64),
+    /// A prefab, for assets kept resident while it may be spawned
+    Prefab(PrefabId),
+}
+
+/// Reference counts of loaded assets with delayed unloading
+#[derive(Resource, Debug)]
+pub struct AssetRefCounts {
+    users: HashMap<String, HashSet<AssetUser>>,
+    assets: HashMap<AssetUser, HashSet<String>>,
+    unload_in: HashMap<String, Duration>,
+    grace_period: Duration,
+}
+
+impl Default for AssetRefCounts {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_GRACE_PERIOD)
+    }
+}
+
+impl AssetRefCounts {
+    /// Default time an unused asset stays loaded
+    pub const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+    /// Create an empty tracker that unloads assets `grace_period` after their
+    /// last user releases them
+    pub fn new(grace_period: Duration) -> Self {
+        Self {
+            users: HashMap::new(),
+            assets: HashMap::new(),
+            unload_in: HashMap::new(),
+            grace_period,
+        }
+    }
+
+    /// Get the time an unused asset stays loaded
+    pub fn grace_period(&self) -> Duration {
+        self.grace_period
+    }
+
+    /// Record that `user` uses `paths`, requesting assets that are not loaded
+    ///
+    /// Acquiring an asset waiting to be unloaded keeps it loaded.
+    pub fn acquire<I, S>(&mut self, user: AssetUser, paths: I, resolver: &dyn DependencyResolver)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        for path in paths {
+            let path = path.into();
+            if !self.assets.entry(user).or_default().insert(path.clone()) {
+                continue;
+            }
+
+            let users = self.users.entry(path.clone()).or_default();
+            if users.is_empty() && self.unload_in.remove(&path).is_none() {
+                resolver.request(&path);
+            }
+            users.insert(user);
+        }
+    }
+
+    /// Acquire the dependencies of a prefab and its children for `user`
+    pub fn acquire_prefab(
+        &mut self,
+        user: AssetUser,
+        factory: &Factory,
+        id: PrefabId,
+        resolver: &dyn DependencyResolver,
+    ) -> Result<(), Error> {
+        let dependencies = factory.dependencies(id)?;
+        self.acquire(user, dependencies, resolver);
+        Ok(())
+    }
+
+    /// Release every asset `user` acquired
+    ///
+    /// Assets left without users are scheduled for unloading after the grace
+    /// period.
+    pub fn release(&mut self, user: AssetUser) {
+        let Some(paths) = self.assets.remove(&user) else {
+            return;
+        };
+        for path in paths {
+            let Some(users) = self.users.get_mut(&path) else {
+                continue;
+            };
+            users.remove(&user);
+            if users.is_empty() {
+                self.users.remove(&path);
+                self.unload_in.insert(path, self.grace_period);
+            }
+        }
+    }
+
+    /// Advance the grace periods by `elapsed` and unload expired assets
+    ///
+    /// Returns the unloaded paths, sorted.
+    pub fn update(&mut self, elapsed: Duration, resolver: &dyn DependencyResolver) -> Vec<String> {
+        let mut unloaded = Vec::new();
+        self.unload_in.retain(|path, remaining| {
+            *remaining = remaining.saturating_sub(elapsed);
+            if remaining.is_zero() {
+                resolver.release(path);
+                unloaded.push(path.clone());
+                false
+            } else {
+                true
+            }
+        });
+        unloaded.sort();
+        unloaded
+    }
+
+    /// Get the number of users of an asset
+    pub fn ref_count(&self, path: &str) -> usize {
+        self.users.get(path).map_or(0, HashSet::len)
+    }
+
+    /// Check if an asset has no users and is waiting to be unloaded
+    pub fn is_unload_pending(&self, path: &str) -> bool {
+        self.unload_in.contains_key(path)
+    }
+
+    /// Get the number of assets with at least one user
+    pub fn len(&self) -> usize {
+        self.users.len()
+    }
+
+    /// Check if no asset has a user
+    pub fn is_empty(&self) -> bool {
+        self.users.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DependencyState;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingResolver {
+        requested: Mutex<Vec<String>>,
+        released: Mutex<Vec<String>>,
+    }
+
+    impl DependencyResolver for RecordingResolver {
+        fn request(&self, path: &str) {
+            self.requested.lock().unwrap().push(path.to_string());
+        }
+
+        fn state(&self, _path: &str) -> DependencyState {
+            DependencyState::Ready
+        }
+
+        fn release(&self, path: &str) {
+            self.released.lock().unwrap().push(path.to_string());
+        }
+    }
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn test_last_user_unloads_after_grace_period() {
+        let resolver = RecordingResolver::default();
+        let mut refs = AssetRefCounts::new(2 * SECOND);
+        refs.acquire(AssetUser::Sector(1), ["road.glb", "lamp.glb"], &resolver);
+        refs.acquire(AssetUser::Sector(2), ["road.glb"], &resolver);
+        assert_eq!(
+            *resolver.requested.lock().unwrap(),
+            ["road.glb", "lamp.glb"]
+        );
+        assert_eq!(refs.ref_count("road.glb"), 2);
+
+        refs.release(AssetUser::Sector(1));
+        assert_eq!(refs.ref_count("road.glb"), 1);
+        assert!(refs.is_unload_pending("lamp.glb"));
+        assert!(refs.update(SECOND, &resolver).is_empty());
+        assert_eq!(refs.update(SECOND, &resolver), ["lamp.glb"]);
+
+        refs.release(AssetUser::Sector(2));
+        assert_eq!(refs.update(2 * SECOND, &resolver), ["road.glb"]);
+        assert_eq!(*resolver.released.lock().unwrap(), ["lamp.glb", "road.glb"]);
+        assert!(refs.is_empty());
+    }
+
+    #[test]
+    fn test_reacquire_within_grace_period_keeps_asset() {
+        let resolver = RecordingResolver::default();
+        let mut refs = AssetRefCounts::new(2 * SECOND);
+        refs.acquire(AssetUser::Sector(1), ["road.glb"], &resolver);
+        refs.release(AssetUser::Sector(1));
+        refs.update(SECOND, &resolver);
+
+        refs.acquire(AssetUser::Sector(1), ["road.glb"], &resolver);
+        assert!(!refs.is_unload_pending("road.glb"));
+        assert!(refs.update(5 * SECOND, &resolver).is_empty());
+        assert_eq!(resolver.requested.lock().unwrap().len(), 1);
+        assert!(resolver.released.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,553 @@
+//! Asynchronous prefab spawning with dependency resolution
+//!
+//! [`Factory::spawn`] assumes every mesh, material and sound a prefab uses is
+//! already resident, so spawning a model for the first time mid-game stalls
+//! the frame while its assets load. [`Factory::spawn_async`] instead reserves
+//! the entity, asks a [`DependencyResolver`] to fetch the prefab's
+//! [dependencies](Prefab::dependencies) in the background and optionally
+//! spawns a placeholder prefab under it. [`Factory::process_async_spawns`],
+//! called once per frame, materializes every pending entity whose
+//! dependencies have become ready.
+
+use crate::{Factory, Prefab, PrefabId, SpawnSource, SpawnState};
+use amp_core::Error;
+use bevy_ecs::{component::Component, entity::Entity, system::Commands, system::Resource};
+use bevy_hierarchy::{BuildChildren, DespawnRecursiveExt};
+use bevy_tasks::{IoTaskPool, TaskPool};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Load state of a single asset dependency
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DependencyState {
+    /// Requested but not yet available
+    Pending,
+    /// Resident and ready to use
+    Ready,
+    /// Could not be loaded
+    Failed(String),
+}
+
+/// Source of background-loaded prefab dependencies
+///
+/// Implementations must not block in [`request`](DependencyResolver::request);
+/// loading happens off the main thread and progress is observed through
+/// [`state`](DependencyResolver::state).
+pub trait DependencyResolver: Send + Sync {
+    /// Start loading `path` if it is not already loading or loaded
+    fn request(&self, path: &str);
+
+    /// Get the current state of `path`
+    ///
+    /// Paths that were never requested are reported as pending.
+    fn state(&self, path: &str) -> DependencyState;
+
+    /// Drop a loaded dependency, so the next request loads it again
+    ///
+    /// The default implementation keeps everything loaded.
+    fn release(&self, _path: &str) {}
+}
+
+enum FileEntry {
+    Loading,
+    Loaded(Arc<[u8]>),
+    Failed(String),
+}
+
+/// [`DependencyResolver`] reading files below a root directory on the
+/// [`IoTaskPool`]
+///
+/// Loaded bytes stay cached until the dependency is
+/// [released](DependencyResolver::release).
+#[derive(Clone)]
+pub struct FileDependencyResolver {
+    root: PathBuf,
+    entries: Arc<Mutex<HashMap<String, FileEntry>>>,
+}
+
+impl FileDependencyResolver {
+    /// Create a resolver for dependency paths relative to `root`
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Get the contents of a loaded dependency
+    pub fn bytes(&self, path: &str) -> Option<Arc<[u8]>> {
+        match self.entries.lock().unwrap().get(path) {
+            Some(FileEntry::Loaded(bytes)) => Some(Arc::clone(bytes)),
+            _ => None,
+        }
+    }
+}
+
+impl DependencyResolver for FileDependencyResolver {
+    fn request(&self, path: &str) {
+        {
+            let mut entries = self.entries.lock().unwrap();
+            if entries.contains_key(path) {
+                return;
+            }
+            entries.insert(path.to_string(), FileEntry::Loading);
+        }
+
+        let full_path = self.root.join(path);
+        let entries = Arc::clone(&self.entries);
+        let key = path.to_string();
+        IoTaskPool::get_or_init(TaskPool::new)
+            .spawn(async move {
+                let entry = match std::fs::read(&full_path) {
+                    Ok(bytes) => FileEntry::Loaded(bytes.into()),
+                    Err(e) => FileEntry::Failed(format!("{}: {e}", full_path.display())),
+                };
+                entries.lock().unwrap().insert(key, entry);
+            })
+            .detach();
+    }
+
+    fn state(&self, path: &str) -> DependencyState {
+        match self.entries.lock().unwrap().get(path) {
+            None | Some(FileEntry::Loading) => DependencyState::Pending,
+            Some(FileEntry::Loaded(_)) => DependencyState::Ready,
+            Some(FileEntry::Failed(reason)) => DependencyState::Failed(reason.clone()),
+        }
+    }
+
+    fn release(&self, path: &str) {
+        // A file still loading is kept, since its task inserts it when done
+        let mut entries = self.entries.lock().unwrap();
+        if !matches!(entries.get(path), Some(FileEntry::Loading)) {
+            entries.remove(path);
+        }
+    }
+}
+
+/// Marker on entities reserved by [`Factory::spawn_async`] that have not been
+/// materialized yet
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingPrefab {
+    /// Prefab the entity will be built from
+    pub prefab: PrefabId,
+}
+
+struct PendingSpawn {
+    entity: Entity,
+    prefab: PrefabId,
+    dependencies: Vec<String>,
+    placeholder: Option<Entity>,
+}
+
+/// Entities waiting for their prefab dependencies
+#[derive(Resource, Default)]
+pub struct AsyncSpawnQueue {
+    pending: Vec<PendingSpawn>,
+}
+
+impl AsyncSpawnQueue {
+    /// Create an empty queue
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the number of entities still waiting
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Check if no entities are waiting
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Check if `entity` is still waiting for its dependencies
+    pub fn is_pending(&self, entity: Entity) -> bool {
+        self.pending.iter().any(|spawn| spawn.entity == entity)
+    }
+}
+
+/// Result of materializing an asynchronously spawned entity
+#[derive(Debug)]
+pub struct AsyncSpawnResult {
+    /// Entity reserved by [`Factory::spawn_async`]
+    pub entity: Entity,
+    /// Prefab the entity was built from
+    pub prefab: PrefabId,
+    /// `Ok` if the entity was built; on error it has been despawned
+    pub result: Result<(), Error>,
+}
+
+impl Factory {
+    /// Collect the asset dependencies of a prefab and all of its children
+    ///
+    /// Each path is listed once, in the order it is first encountered.
+    /// Bundle prefabs have no dependencies.
+    pub fn dependencies(&self, id: PrefabId) -> Result<Vec<String>, Error> {
+        let mut dependencies = Vec::new();
+        let mut seen = HashSet::new();
+        let mut visited = HashSet::new();
+        self.collect_dependencies(id, &mut dependencies, &mut seen, &mut visited)?;
+        Ok(dependencies)
+    }
+
+    fn collect_dependencies(
+        &self,
+        id: PrefabId,
+        dependencies: &mut Vec<String>,
+        seen: &mut HashSet<String>,
+        visited: &mut HashSet<PrefabId>,
+    ) -> Result<(), Error> {
+        if self.bundles.contains_key(&id) || !visited.insert(id) {
+            return Ok(());
+        }
+
+        let prefab: &Prefab = self.prefab(id)?;
+        for dependency in prefab.dependencies() {
+            if seen.insert(dependency.clone()) {
+                dependencies.push(dependency.clone());
+            }
+        }
+        for child in prefab.children() {
+            self.collect_dependencies(child.prefab, dependencies, seen, visited)?;
+        }
+        Ok(())
+    }
+
+    /// Reserve an entity for a prefab and start loading its dependencies
+    ///
+    /// The entity carries a [`PendingPrefab`] marker until its dependencies
+    /// are ready and [`process_async_spawns`](Factory::process_async_spawns)
+    /// builds it. If `placeholder` is given, that prefab is spawned as a
+    /// child in the meantime and despawned once the real prefab is built.
+    pub fn spawn_async(
+        &self,
+        cmd: &mut Commands,
+        queue: &mut AsyncSpawnQueue,
+        resolver: &dyn DependencyResolver,
+        id: PrefabId,
+        placeholder: Option<PrefabId>,
+    ) -> Result<Entity, Error> {
+        let dependencies = self.dependencies(id)?;
+        for dependency in &dependencies {
+            resolver.request(dependency);
+        }
+
+        let placeholder = placeholder.map(|p| self.spawn(cmd, p)).transpose()?;
+        let entity = cmd.spawn(PendingPrefab { prefab: id }).id();
+        if let Some(placeholder) = placeholder {
+            cmd.entity(entity).add_child(placeholder);
+        }
+
+        queue.pending.push(PendingSpawn {
+            entity,
+            prefab: id,
+            dependencies,
+            placeholder,
+        });
+        Ok(entity)
+    }
+
+    /// Build every queued entity whose dependencies are ready
+    ///
+    /// Entities with a failed dependency, or whose prefab fails to spawn, are
+    /// despawned together with their placeholder. Entities still waiting stay
+    /// queued and are not reported.
+    pub fn process_async_spawns(
+        &self,
+        cmd: &mut Commands,
+        queue: &mut AsyncSpawnQueue,
+        resolver: &dyn DependencyResolver,
+    ) -> Vec<AsyncSpawnResult> {
+        let mut results = Vec::new();
+
+        queue.pending.retain(|spawn| {
+            let mut ready = true;
+            for dependency in &spawn.dependencies {
+                match resolver.state(dependency) {
+                    DependencyState::Ready => {}
+                    DependencyState::Pending => ready = false,
+                    DependencyState::Failed(reason) => {
+                        cmd.entity(spawn.entity).despawn_recursive();
+                        results.push(AsyncSpawnResult {
+                            entity: spawn.entity,
+                            prefab: spawn.prefab,
+                            result: Err(Error::resource_load(dependency.as_str(), reason)),
+                        });
+                        return false;
+                    }
+                }
+            }
+            if !ready {
+                return true;
+            }
+
+            if let Some(placeholder) = spawn.placeholder {
+                cmd.entity(placeholder).despawn_recursive();
+            }
+            cmd.entity(spawn.entity).remove::<PendingPrefab>();
+
+            let result = self.build_tree(
+                cmd,
+                spawn.prefab,
+                spawn.entity,
+                None,
+                &mut SpawnState::new(SpawnSource::Async),
+            );
+            if result.is_err() {
+                cmd.entity(spawn.entity).despawn_recursive();
+            }
+            results.push(AsyncSpawnResult {
+                entity: spawn.entity,
+                prefab: spawn.prefab,
+                result,
+            });
+            false
+        });
+
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ComponentInit, PrefabSource};
+    use bevy_ecs::prelude::*;
+    use bevy_ecs::system::CommandQueue;
+    use bevy_hierarchy::Children;
+    use bevy_transform::components::Transform;
+    use std::any::Any;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    #[derive(Component, Clone, Copy, Debug, PartialEq)]
+    struct Model(u32);
+
+    impl ComponentInit for Model {
+        fn init(&self, cmd: &mut Commands, entity: Entity) -> Result<(), Error> {
+            cmd.entity(entity).insert(*self);
+            Ok(())
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    /// Resolver whose dependency states are set by the test
+    #[derive(Default)]
+    struct ManualResolver {
+        states: Mutex<HashMap<String, DependencyState>>,
+        requested: Mutex<Vec<String>>,
+    }
+
+    impl ManualResolver {
+        fn set(&self, path: &str, state: DependencyState) {
+            self.states.lock().unwrap().insert(path.to_string(), state);
+        }
+    }
+
+    impl DependencyResolver for ManualResolver {
+        fn request(&self, path: &str) {
+            self.requested.lock().unwrap().push(path.to_string());
+        }
+
+        fn state(&self, path: &str) -> DependencyState {
+            self.states
+                .lock()
+                .unwrap()
+                .get(path)
+                .cloned()
+                .unwrap_or(DependencyState::Pending)
+        }
+    }
+
+    struct Prefabs {
+        factory: Factory,
+        vehicle: PrefabId,
+        placeholder: PrefabId,
+    }
+
+    /// Register a vehicle with a wheel child and a placeholder, using ids
+    /// starting at `base` since prefab ids are unique per process
+    fn vehicle_factory(base: u64) -> Prefabs {
+        let (vehicle, wheel, placeholder) = (
+            PrefabId::new(base),
+            PrefabId::new(base + 1),
+            PrefabId::new(base + 2),
+        );
+        let mut factory = Factory::new();
+        factory
+            .register(
+                wheel,
+                Prefab::new()
+                    .with_component(Box::new(Model(2)))
+                    .with_dependency("models/wheel.glb")
+                    .with_dependency("materials/rubber.ron"),
+            )
+            .unwrap();
+        factory
+            .register(
+                vehicle,
+                Prefab::new()
+                    .with_component(Box::new(Model(1)))
+                    .with_dependency("models/car.glb")
+                    .with_dependency("materials/rubber.ron")
+                    .with_child(wheel, Transform::IDENTITY),
+            )
+            .unwrap();
+        factory
+            .register(
+                placeholder,
+                Prefab::new().with_component(Box::new(Model(0))),
+            )
+            .unwrap();
+        Prefabs {
+            factory,
+            vehicle,
+            placeholder,
+        }
+    }
+
+    fn with_commands<R>(world: &mut World, f: impl FnOnce(&mut Commands) -> R) -> R {
+        let mut queue = CommandQueue::default();
+        let result = {
+            let mut cmd = Commands::new(&mut queue, world);
+            f(&mut cmd)
+        };
+        queue.apply(world);
+        result
+    }
+
+    #[test]
+    fn test_dependencies_include_children_once() {
+        let Prefabs {
+            factory, vehicle, ..
+        } = vehicle_factory(0x4428_0100);
+        assert_eq!(
+            factory.dependencies(vehicle).unwrap(),
+            vec!["models/car.glb", "materials/rubber.ron", "models/wheel.glb"]
+        );
+    }
+
+    #[test]
+    fn test_entity_materializes_when_dependencies_ready() {
+        let Prefabs {
+            factory,
+            vehicle,
+            placeholder,
+        } = vehicle_factory(0x4428_0200);
+        let resolver = ManualResolver::default();
+        let mut queue = AsyncSpawnQueue::new();
+        let mut world = World::new();
+
+        let entity = with_commands(&mut world, |cmd| {
+            factory
+                .spawn_async(cmd, &mut queue, &resolver, vehicle, Some(placeholder))
+                .unwrap()
+        });
+        assert_eq!(resolver.requested.lock().unwrap().len(), 3);
+        assert_eq!(
+            world.get::<PendingPrefab>(entity),
+            Some(&PendingPrefab { prefab: vehicle })
+        );
+        let placeholder_entity = world.get::<Children>(entity).unwrap()[0];
+        assert_eq!(world.get::<Model>(placeholder_entity), Some(&Model(0)));
+
+        resolver.set("models/car.glb", DependencyState::Ready);
+        resolver.set("materials/rubber.ron", DependencyState::Ready);
+        let results = with_commands(&mut world, |cmd| {
+            factory.process_async_spawns(cmd, &mut queue, &resolver)
+        });
+        assert!(results.is_empty());
+        assert!(queue.is_pending(entity));
+
+        resolver.set("models/wheel.glb", DependencyState::Ready);
+        let results = with_commands(&mut world, |cmd| {
+            factory.process_async_spawns(cmd, &mut queue, &resolver)
+        });
+        assert_eq!(results.len(), 1);
+        assert!(results[0].result.is_ok());
+        assert!(queue.is_empty());
+
+        assert!(world.get_entity(placeholder_entity).is_none());
+        assert!(world.get::<PendingPrefab>(entity).is_none());
+        assert_eq!(world.get::<Model>(entity), Some(&Model(1)));
+        let wheel = world.get::<Children>(entity).unwrap()[0];
+        assert_eq!(world.get::<Model>(wheel), Some(&Model(2)));
+    }
+
+    #[test]
+    fn test_failed_dependency_despawns_entity() {
+        let Prefabs {
+            factory,
+            vehicle,
+            placeholder,
+        } = vehicle_factory(0x4428_0300);
+        let resolver = ManualResolver::default();
+        let mut queue = AsyncSpawnQueue::new();
+        let mut world = World::new();
+
+        let entity = with_commands(&mut world, |cmd| {
+            factory
+                .spawn_async(cmd, &mut queue, &resolver, vehicle, Some(placeholder))
+                .unwrap()
+        });
+        resolver.set(
+            "models/car.glb",
+            DependencyState::Failed("corrupt file".to_string()),
+        );
+        let results = with_commands(&mut world, |cmd| {
+            factory.process_async_spawns(cmd, &mut queue, &resolver)
+        });
+
+        assert_eq!(results.len(), 1);
+        let err = results[0].result.as_ref().unwrap_err().to_string();
+        assert!(err.contains("models/car.glb"));
+        assert!(queue.is_empty());
+        assert!(world.get_entity(entity).is_none());
+        assert_eq!(world.entities().len(), 0);
+    }
+
+    #[test]
+    fn test_file_resolver_loads_in_background() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir(temp_dir.path().join("models")).unwrap();
+        std::fs::write(temp_dir.path().join("models/car.glb"), b"glTF").unwrap();
+
+        let resolver = FileDependencyResolver::new(temp_dir.path());
+        resolver.request("models/car.glb");
+        resolver.request("models/missing.glb");
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while resolver.state("models/car.glb") == DependencyState::Pending
+            || resolver.state("models/missing.glb") == DependencyState::Pending
+        {
+            assert!(
+                Instant::now() < deadline,
+                "dependencies did not load in time"
+            );
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        assert_eq!(resolver.state("models/car.glb"), DependencyState::Ready);
+        assert_eq!(&*resolver.bytes("models/car.glb").unwrap(), b"glTF");
+        assert!(matches!(
+            resolver.state("models/missing.glb"),
+            DependencyState::Failed(_)
+        ));
+    }
+
+    #[cfg(feature = "ron")]
+    #[test]
+    fn test_ron_prefab_dependencies() {
+        let prefab = crate::RonLoader::new(
+            r#"RonPrefab(components: [], dependencies: ["models/car.glb"])"#.to_string(),
+        )
+        .load()
+        .unwrap();
+        assert_eq!(prefab.dependencies(), ["models/car.glb"]);
+    }
+}
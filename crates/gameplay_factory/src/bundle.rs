@@ -0,0 +1,140 @@
+//! Bundle-backed prefabs for batched spawning
+//!
+//! Component-initializer prefabs insert each component through commands after
+//! the entity exists, which moves every entity through a chain of archetypes.
+//! Bundle prefabs carry their complete component set up front, so a batch is
+//! spawned with a single `World::spawn_batch` call that reserves archetype and
+//! table storage for the whole batch before writing any entity.
+//!
+//! Component prefabs are not compiled into bundles yet, so only prefabs
+//! registered with [`Factory::register_bundle`](crate::Factory::register_bundle)
+//! take the batched path.
+
+use bevy_ecs::{bundle::Bundle, entity::Entity, system::Commands, world::World};
+
+/// A complete component set that can be spawned directly
+pub(crate) trait BundleTemplate: Send + Sync {
+    /// Spawn a single entity through commands
+    fn spawn(&self, cmd: &mut Commands) -> Entity;
+
+    /// Overwrite the bundle's components on an existing entity
+    fn apply(&self, cmd: &mut Commands, entity: Entity);
+
+    /// Spawn `count` entities directly into the world
+    fn spawn_batch(&self, world: &mut World, count: usize) -> Vec<Entity>;
+}
+
+/// [`BundleTemplate`] cloning a typed bundle for every entity
+pub(crate) struct TypedBundle<B>(pub(crate) B);
+
+impl<B: Bundle + Clone> BundleTemplate for TypedBundle<B> {
+    fn spawn(&self, cmd: &mut Commands) -> Entity {
+        cmd.spawn(self.0.clone()).id()
+    }
+
+    fn apply(&self, cmd: &mut Commands, entity: Entity) {
+        cmd.entity(entity).insert(self.0.clone());
+    }
+
+    fn spawn_batch(&self, world: &mut World, count: usize) -> Vec<Entity> {
+        world
+            .spawn_batch((0..count).map(|_| self.0.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ComponentInit, Factory, Prefab, PrefabId};
+    use amp_core::Error;
+    use bevy_ecs::prelude::*;
+    use bevy_ecs::system::CommandQueue;
+    use std::any::Any;
+
+    #[derive(Component, Clone, Debug, PartialEq)]
+    struct Health(u32);
+
+    #[derive(Component, Clone, Debug, PartialEq)]
+    struct Speed(f32);
+
+    struct FailingInit;
+
+    impl ComponentInit for FailingInit {
+        fn init(&self, _cmd: &mut Commands, _entity: Entity) -> Result<(), Error> {
+            Err(Error::validation("always fails"))
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn test_bundle_batch_spawns_complete_entities() {
+        let id = PrefabId::new(0x9419_0001);
+        let mut factory = Factory::new();
+        factory
+            .register_bundle(id, (Health(100), Speed(12.5)))
+            .unwrap();
+        assert!(factory.contains(id));
+
+        let mut world = World::new();
+        let entities = factory.spawn_batch(&mut world, id, 500).unwrap();
+
+        assert_eq!(entities.len(), 500);
+        for entity in entities {
+            assert_eq!(world.get::<Health>(entity), Some(&Health(100)));
+            assert_eq!(world.get::<Speed>(entity), Some(&Speed(12.5)));
+        }
+    }
+
+    #[test]
+    fn test_bundle_prefab_spawns_through_commands() {
+        let id = PrefabId::new(0x9419_0002);
+        let mut factory = Factory::new();
+        factory.register_bundle(id, Health(7)).unwrap();
+
+        let mut world = World::new();
+        let mut queue = CommandQueue::default();
+        let mut cmd = Commands::new(&mut queue, &world);
+        let entity = factory.spawn(&mut cmd, id).unwrap();
+        queue.apply(&mut world);
+
+        assert_eq!(world.get::<Health>(entity), Some(&Health(7)));
+    }
+
+    #[test]
+    fn test_batch_falls_back_to_component_prefabs() {
+        let id = PrefabId::new(0x9419_0003);
+        let mut factory = Factory::new();
+        factory.register(id, Prefab::new()).unwrap();
+
+        let mut world = World::new();
+        let entities = factory.spawn_batch(&mut world, id, 3).unwrap();
+
+        assert_eq!(entities.len(), 3);
+        assert!(entities.iter().all(|e| world.get_entity(*e).is_some()));
+    }
+
+    #[test]
+    fn test_failed_batch_despawns_everything() {
+        let id = PrefabId::new(0x9419_0004);
+        let mut factory = Factory::new();
+        factory
+            .register(id, Prefab::new().with_component(Box::new(FailingInit)))
+            .unwrap();
+
+        let mut world = World::new();
+        assert!(factory.spawn_batch(&mut world, id, 3).is_err());
+        assert_eq!(world.entities().len(), 0);
+    }
+
+    #[test]
+    fn test_bundle_id_collides_with_prefab_id() {
+        let id = PrefabId::new(0x9419_0005);
+        let mut factory = Factory::new();
+        factory.register(id, Prefab::new()).unwrap();
+
+        assert!(factory.register_bundle(id, Health(1)).is_err());
+    }
+}
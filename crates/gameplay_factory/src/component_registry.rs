@@ -1,8 +1,18 @@
 //! Thread-safe component registry for RON deserialization
+//!
+//! Components are either registered with a hand-written deserializer, or,
+//! for any `Reflect + Component` type, with [`register_reflect_component`],
+//! which deserializes prefab data through Bevy's type registry.
 
 use crate::Error;
-use bevy_ecs::{entity::Entity, system::Commands};
+use bevy_ecs::{component::Component, entity::Entity, system::Commands};
+use bevy_reflect::{
+    serde::TypedReflectDeserializer, FromReflect, GetTypeRegistration, Reflect, ReflectRef,
+    TypeInfo, TypePath, TypeRegistry, Typed,
+};
 use once_cell::sync::Lazy;
+use serde::de::DeserializeSeed;
+use std::any::TypeId;
 use std::collections::HashMap;
 use std::sync::RwLock;
 
@@ -14,6 +24,10 @@ pub type ComponentDeserializer =
 static COMPONENT_REGISTRY: Lazy<RwLock<HashMap<&'static str, ComponentDeserializer>>> =
     Lazy::new(|| RwLock::new(HashMap::new()));
 
+/// Type registry used to deserialize reflected components and their fields
+static REFLECT_REGISTRY: Lazy<RwLock<TypeRegistry>> =
+    Lazy::new(|| RwLock::new(TypeRegistry::default()));
+
 /// Register a component deserializer
 ///
 /// # Arguments
@@ -61,6 +75,83 @@ pub fn register_component(
     Ok(())
 }
 
+/// Register a `Reflect + Component` type for use in prefab RON
+///
+/// The component is registered under its short type name, such as `Health`,
+/// and its data is written as a RON struct with the component's field names.
+/// Field types other than primitives and `String` must be registered with
+/// [`register_reflect_type`] first.
+///
+/// # Examples
+///
+/// ```
+/// use bevy_ecs::component::Component;
+/// use bevy_reflect::Reflect;
+/// use gameplay_factory::register_reflect_component;
+///
+/// #[derive(Component, Reflect)]
+/// struct Fuel {
+///     litres: f32,
+/// }
+///
+/// register_reflect_component::<Fuel>().unwrap();
+/// ```
+pub fn register_reflect_component<T>() -> Result<(), Error>
+where
+    T: Component + Reflect + FromReflect + TypePath + Typed + GetTypeRegistration,
+{
+    register_reflect_type::<T>();
+
+    let name = T::short_type_path();
+    register_component(
+        name,
+        Box::new(move |value, cmd, entity| {
+            let component = deserialize_reflect::<T>(name, value)?;
+            cmd.entity(entity).insert(component);
+            Ok(())
+        }),
+    )
+}
+
+/// Register a type used in the fields of reflected components
+pub fn register_reflect_type<T: GetTypeRegistration>() {
+    REFLECT_REGISTRY.write().unwrap().register::<T>();
+}
+
+/// Deserialize RON data into `T` through the reflection registry
+fn deserialize_reflect<T>(name: &str, value: &ron::Value) -> Result<T, Error>
+where
+    T: FromReflect + Typed,
+{
+    let invalid = |message: String| {
+        Error::validation(format!("Invalid data for component '{name}': {message}"))
+    };
+
+    let registry = REFLECT_REGISTRY.read().unwrap();
+    let registration = registry
+        .get(TypeId::of::<T>())
+        .ok_or_else(|| invalid("type is not registered for reflection".to_string()))?;
+    let reflected = TypedReflectDeserializer::new(registration, &registry)
+        .deserialize(value.clone())
+        .map_err(|e| invalid(e.to_string()))?;
+
+    if let (TypeInfo::Struct(info), ReflectRef::Struct(data)) =
+        (T::type_info(), reflected.reflect_ref())
+    {
+        let missing: Vec<String> = info
+            .iter()
+            .filter(|field| data.field(field.name()).is_none())
+            .map(|field| format!("`{}`", field.name()))
+            .collect();
+        if !missing.is_empty() {
+            return Err(invalid(format!("missing field(s) {}", missing.join(", "))));
+        }
+    }
+
+    T::from_reflect(&*reflected)
+        .ok_or_else(|| invalid(format!("value does not match `{}`", T::type_path())))
+}
+
 /// Call a component deserializer by name
 ///
 /// # Arguments
@@ -77,9 +168,19 @@ pub fn register_component(
 /// # Examples
 ///
 /// ```
-/// use gameplay_factory::call_component_deserializer;
+/// use bevy_ecs::system::{CommandQueue, Commands};
+/// use bevy_ecs::world::World;
+/// use gameplay_factory::{call_component_deserializer, register_default_components};
+///
+/// register_default_components();
 ///
-/// call_component_deserializer("Transform", &ron_value, &mut commands, entity)?;
+/// let world = World::new();
+/// let mut queue = CommandQueue::default();
+/// let mut commands = Commands::new(&mut queue, &world);
+/// let entity = commands.spawn_empty().id();
+///
+/// let ron_value = ron::Value::String("Player".to_string());
+/// call_component_deserializer("Name", &ron_value, &mut commands, entity).unwrap();
 /// ```
 pub fn call_component_deserializer(
     name: &str,
@@ -130,6 +231,13 @@ pub fn clear_registry() {
     log::debug!("Component registry cleared");
 }
 
+/// Serialize tests that depend on the contents of the global registry
+#[cfg(test)]
+pub(crate) fn registry_test_guard() -> std::sync::MutexGuard<'static, ()> {
+    static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
 /// Register default Bevy components
 ///
 /// This function registers deserializers for basic Bevy components like Transform, Name, etc.
@@ -173,11 +281,13 @@ pub fn register_default_components() {
         }),
     );
 
+    crate::scene_assets::register_scene_asset_components();
+
     log::info!("Default components registered");
 }
 
 /// Deserialize a Transform component from RON data
-fn deserialize_transform(
+pub(crate) fn deserialize_transform(
     value: &ron::Value,
 ) -> Result<bevy_transform::components::Transform, Error> {
     use bevy_math::{Quat, Vec3};
@@ -315,6 +425,7 @@ mod tests {
 
     #[rstest]
     fn test_register_component_success() {
+        let _guard = registry_test_guard();
         clear_registry();
 
         let result = register_component("TestComponent", Box::new(|_, _, _| Ok(())));
@@ -334,6 +445,7 @@ mod tests {
 
     #[rstest]
     fn test_register_component_duplicate() {
+        let _guard = registry_test_guard();
         clear_registry();
 
         let first_result = register_component("TestComponent", Box::new(|_, _, _| Ok(())));
@@ -349,6 +461,7 @@ mod tests {
 
     #[rstest]
     fn test_component_deserializer_not_found() {
+        let _guard = registry_test_guard();
         clear_registry();
 
         let world = World::new();
@@ -364,6 +477,7 @@ mod tests {
 
     #[rstest]
     fn test_registered_components() {
+        let _guard = registry_test_guard();
         clear_registry();
 
         let _ = register_component("Component1", Box::new(|_, _, _| Ok(())));
@@ -377,6 +491,7 @@ mod tests {
 
     #[rstest]
     fn test_clear_registry() {
+        let _guard = registry_test_guard();
         clear_registry();
 
         let _ = register_component("TestComponent", Box::new(|_, _, _| Ok(())));
@@ -399,6 +514,7 @@ mod tests {
 
     #[rstest]
     fn test_thread_safety_concurrent_registration() {
+        let _guard = registry_test_guard();
         clear_registry();
 
         let success_count = Arc::new(AtomicUsize::new(0));
@@ -433,6 +549,7 @@ mod tests {
 
     #[rstest]
     fn test_thread_safety_duplicate_registration() {
+        let _guard = registry_test_guard();
         clear_registry();
 
         let success_count = Arc::new(AtomicUsize::new(0));
@@ -465,6 +582,7 @@ mod tests {
 
     #[rstest]
     fn test_thread_safety_concurrent_read_write() {
+        let _guard = registry_test_guard();
         clear_registry();
 
         // Register some initial components
@@ -524,6 +642,7 @@ mod tests {
 
     #[rstest]
     fn test_register_default_components() {
+        let _guard = registry_test_guard();
         clear_registry();
 
         register_default_components();
@@ -605,6 +724,7 @@ mod tests {
 
     #[rstest]
     fn test_component_deserializer_execution() {
+        let _guard = registry_test_guard();
         clear_registry();
         register_default_components();
 
@@ -617,4 +737,89 @@ mod tests {
         let result = call_component_deserializer("Name", &name_value, &mut cmd, entity);
         assert!(result.is_ok());
     }
+
+    #[derive(Component, Reflect, Debug, PartialEq)]
+    struct Engine {
+        horsepower: u32,
+        redline: f32,
+        model: String,
+    }
+
+    #[rstest]
+    fn test_reflect_component_from_prefab_ron() {
+        use crate::{PrefabSource, RonLoader};
+
+        let _guard = registry_test_guard();
+        clear_registry();
+        register_reflect_component::<Engine>().unwrap();
+
+        let prefab = RonLoader::new(
+            r#"RonPrefab(components: [RonComponent(
+                component_type: "Engine",
+                data: (horsepower: 310, redline: 7200.0, model: "V8"),
+            )])"#
+                .to_string(),
+        )
+        .load()
+        .unwrap();
+
+        let mut world = World::new();
+        let mut queue = CommandQueue::default();
+        let mut cmd = Commands::new(&mut queue, &world);
+        let entity = prefab.spawn(&mut cmd).unwrap();
+        queue.apply(&mut world);
+
+        assert_eq!(
+            world.get::<Engine>(entity),
+            Some(&Engine {
+                horsepower: 310,
+                redline: 7200.0,
+                model: "V8".to_string(),
+            })
+        );
+    }
+
+    #[rstest]
+    fn test_reflect_component_field_errors() {
+        register_reflect_type::<Engine>();
+
+        let missing: ron::Value = ron::from_str("(horsepower: 310)").unwrap();
+        let err = deserialize_reflect::<Engine>("Engine", &missing)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Invalid data for component 'Engine'"));
+        assert!(err.contains("missing field(s) `redline`, `model`"));
+
+        let unknown: ron::Value =
+            ron::from_str(r#"(horsepower: 310, redline: 7200.0, model: "V8", torque: 1)"#).unwrap();
+        let err = deserialize_reflect::<Engine>("Engine", &unknown)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("unknown field `torque`"));
+    }
+
+    #[rstest]
+    fn test_unknown_component_type_lists_available() {
+        use crate::{ComponentInit, RonComponent};
+
+        let _guard = registry_test_guard();
+        clear_registry();
+        register_default_components();
+
+        let component = RonComponent {
+            component_type: "Helth".to_string(),
+            data: ron::Value::Unit,
+        };
+        let world = World::new();
+        let mut queue = CommandQueue::default();
+        let mut cmd = Commands::new(&mut queue, &world);
+        let entity = cmd.spawn_empty().id();
+
+        let err = component.init(&mut cmd, entity).unwrap_err().to_string();
+        assert!(err.contains("Component type 'Helth' not found in registry"));
+        assert!(err.contains(
+            "Available types: BoxCollider, ConvexCollider, MaterialAsset, MeshAsset, Name, \
+             SphereCollider, Transform, Visibility"
+        ));
+    }
 }
@@ -0,0 +1,533 @@
+//! glTF scene to prefab conversion
+//!
+//! Artists deliver GLB scenes, while the factory spawns prefabs.
+//! [`convert_gltf`] walks a glTF scene and emits one [`RonPrefab`] per node
+//! under a namespace, linked through child references carrying the node
+//! transforms, plus a `scene` prefab holding the scene's root nodes:
+//!
+//! - nodes get a `Name` component with their glTF name
+//! - mesh nodes get [`MeshAsset`] and [`MaterialAsset`] labels; meshes with
+//!   several primitives get one child prefab per primitive
+//! - nodes named with a collider prefix get a collider instead of a mesh:
+//!   `UBX_` for a [`BoxCollider`], `USP_` for a [`SphereCollider`] and `UCX_`
+//!   for a [`ConvexCollider`], sized from the mesh bounds
+//!
+//! `cargo xtask gltf-to-prefab` writes the result next to a
+//! [`PrefabManifest`]; [`Factory::load_gltf`] converts and registers a scene
+//! at runtime.
+
+use crate::{
+    is_valid_prefab_name, BoxCollider, ConvexCollider, Factory, MaterialAsset, MeshAsset, Prefab,
+    PrefabId, PrefabManifest, PrefabNames, RonComponent, RonPrefab, RonPrefabChild, SphereCollider,
+    PREFAB_NAMESPACE_SEPARATOR,
+};
+use amp_core::Error;
+use ron::{Map, Value};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+/// Name of the prefab holding a scene's root nodes
+pub const GLTF_SCENE_PREFAB: &str = "scene";
+
+/// Tag added to prefabs converted from collider nodes
+pub const GLTF_COLLIDER_TAG: &str = "collider";
+
+/// Prefabs converted from one glTF scene
+#[derive(Debug, Clone)]
+pub struct GltfPrefabs {
+    /// Namespace of every prefab name
+    pub namespace: String,
+    /// Prefabs by name within the namespace
+    pub prefabs: BTreeMap<String, RonPrefab>,
+}
+
+impl GltfPrefabs {
+    /// Full name of the scene prefab
+    pub fn scene_name(&self) -> String {
+        self.full_name(GLTF_SCENE_PREFAB)
+    }
+
+    /// Id of the scene prefab
+    pub fn scene_id(&self) -> PrefabId {
+        PrefabId::from_name(&self.scene_name())
+    }
+
+    /// Manifest listing each prefab as `<namespace>/<name>.ron`, relative to
+    /// the directory the manifest is written to
+    pub fn manifest(&self) -> PrefabManifest {
+        PrefabManifest {
+            namespace: self.namespace.clone(),
+            prefabs: self
+                .prefabs
+                .keys()
+                .map(|name| (name.clone(), self.file_name(name)))
+                .collect(),
+        }
+    }
+
+    /// Path of a prefab's RON file relative to the manifest
+    pub fn file_name(&self, name: &str) -> String {
+        format!("{}/{name}.ron", self.namespace)
+    }
+
+    fn full_name(&self, name: &str) -> String {
+        format!("{}{PREFAB_NAMESPACE_SEPARATOR}{name}", self.namespace)
+    }
+}
+
+/// Derive a prefab namespace from a glTF file name, such as `sedan_01` for
+/// `models/Sedan-01.glb`
+pub fn gltf_namespace(path: &Path) -> String {
+    sanitize_name(
+        path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default(),
+    )
+}
+
+/// Convert the default scene of a glTF or GLB document into prefabs
+///
+/// `asset_path` is the path the game loads the file from; mesh and material
+/// labels and prefab dependencies refer to it.
+pub fn convert_gltf(bytes: &[u8], asset_path: &str, namespace: &str) -> Result<GltfPrefabs, Error> {
+    if !is_valid_prefab_name(&format!(
+        "{namespace}{PREFAB_NAMESPACE_SEPARATOR}{GLTF_SCENE_PREFAB}"
+    )) {
+        return Err(Error::validation(format!(
+            "Invalid prefab namespace '{namespace}'"
+        )));
+    }
+    let gltf = gltf::Gltf::from_slice(bytes)
+        .map_err(|e| Error::resource_load(asset_path, format!("glTF parse error: {e}")))?;
+    let scene = gltf
+        .default_scene()
+        .or_else(|| gltf.scenes().next())
+        .ok_or_else(|| Error::resource_load(asset_path, "glTF document has no scene"))?;
+
+    let mut converter = Converter {
+        output: GltfPrefabs {
+            namespace: namespace.to_string(),
+            prefabs: BTreeMap::new(),
+        },
+        asset_path,
+        used_names: HashSet::from([GLTF_SCENE_PREFAB.to_string()]),
+    };
+
+    let mut root = RonPrefab {
+        components: vec![component("Name", Value::String(scene_display_name(&scene)))],
+        children: Vec::new(),
+        dependencies: Vec::new(),
+        tags: Vec::new(),
+    };
+    for node in scene.nodes() {
+        let child = converter.convert_node(&node)?;
+        root.children.push(converter.child(&child, &node));
+    }
+    converter
+        .output
+        .prefabs
+        .insert(GLTF_SCENE_PREFAB.to_string(), root);
+    Ok(converter.output)
+}
+
+struct Converter<'a> {
+    output: GltfPrefabs,
+    asset_path: &'a str,
+    used_names: HashSet<String>,
+}
+
+impl Converter<'_> {
+    /// Convert a node and its descendants, returning the node's prefab name
+    fn convert_node(&mut self, node: &gltf::Node) -> Result<String, Error> {
+        let display_name = node
+            .name()
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("node{}", node.index()));
+        let name = self.unique_name(&display_name);
+        let mut prefab = RonPrefab {
+            components: vec![component("Name", Value::String(display_name.clone()))],
+            children: Vec::new(),
+            dependencies: Vec::new(),
+            tags: Vec::new(),
+        };
+
+        if let Some(mesh) = node.mesh() {
+            prefab.dependencies.push(self.asset_path.to_string());
+            match ColliderKind::from_node_name(&display_name) {
+                Some(kind) => {
+                    prefab.components.push(self.collider(kind, &mesh)?);
+                    prefab.tags.push(GLTF_COLLIDER_TAG.to_string());
+                }
+                None => self.add_mesh(&name, &mesh, &mut prefab)?,
+            }
+        }
+
+        for child in node.children() {
+            let child_name = self.convert_node(&child)?;
+            prefab.children.push(self.child(&child_name, &child));
+        }
+
+        self.output.prefabs.insert(name.clone(), prefab);
+        Ok(name)
+    }
+
+    /// Attach mesh primitives, directly for a single primitive and as child
+    /// prefabs otherwise
+    fn add_mesh(
+        &mut self,
+        name: &str,
+        mesh: &gltf::Mesh,
+        prefab: &mut RonPrefab,
+    ) -> Result<(), Error> {
+        let primitives: Vec<_> = mesh.primitives().collect();
+        if let [primitive] = primitives.as_slice() {
+            prefab
+                .components
+                .extend(self.primitive_components(mesh, primitive)?);
+            return Ok(());
+        }
+
+        for primitive in &primitives {
+            let primitive_name =
+                self.unique_name(&format!("{name}_primitive{}", primitive.index()));
+            let primitive_prefab = RonPrefab {
+                components: self.primitive_components(mesh, primitive)?,
+                children: Vec::new(),
+                dependencies: vec![self.asset_path.to_string()],
+                tags: Vec::new(),
+            };
+            prefab.children.push(RonPrefabChild {
+                prefab: PrefabId::from_name(&self.output.full_name(&primitive_name)).raw(),
+                transform: None,
+            });
+            self.output.prefabs.insert(primitive_name, primitive_prefab);
+        }
+        Ok(())
+    }
+
+    fn primitive_components(
+        &self,
+        mesh: &gltf::Mesh,
+        primitive: &gltf::Primitive,
+    ) -> Result<Vec<RonComponent>, Error> {
+        let mut components = vec![reflect_component(&MeshAsset {
+            path: self.primitive_label(mesh, primitive),
+        })?];
+        if let Some(material) = primitive.material().index() {
+            components.push(reflect_component(&MaterialAsset {
+                path: format!("{}#Material{material}", self.asset_path),
+            })?);
+        }
+        Ok(components)
+    }
+
+    /// Build a collider sized from the bounds of the mesh's first primitive
+    fn collider(&self, kind: ColliderKind, mesh: &gltf::Mesh) -> Result<RonComponent, Error> {
+        let primitive = mesh.primitives().next().ok_or_else(|| {
+            Error::validation(format!(
+                "Collider mesh {} in {} has no primitives",
+                mesh.index(),
+                self.asset_path
+            ))
+        })?;
+        let bounds = primitive.bounding_box();
+        let half_extents: [f32; 3] =
+            std::array::from_fn(|axis| (bounds.max[axis] - bounds.min[axis]) / 2.0);
+        let center: [f32; 3] =
+            std::array::from_fn(|axis| (bounds.max[axis] + bounds.min[axis]) / 2.0);
+
+        match kind {
+            ColliderKind::Box => reflect_component(&BoxCollider {
+                center,
+                half_extents,
+            }),
+            ColliderKind::Sphere => reflect_component(&SphereCollider {
+                center,
+                radius: half_extents.into_iter().fold(0.0, f32::max),
+            }),
+            ColliderKind::Convex => reflect_component(&ConvexCollider {
+                mesh: self.primitive_label(mesh, &primitive),
+            }),
+        }
+    }
+
+    fn primitive_label(&self, mesh: &gltf::Mesh, primitive: &gltf::Primitive) -> String {
+        format!(
+            "{}#Mesh{}/Primitive{}",
+            self.asset_path,
+            mesh.index(),
+            primitive.index()
+        )
+    }
+
+    /// Reference a converted node from its parent, carrying the node's local
+    /// transform
+    fn child(&self, name: &str, node: &gltf::Node) -> RonPrefabChild {
+        let (translation, rotation, scale) = node.transform().decomposed();
+        let mut transform = Map::new();
+        transform.insert(
+            Value::String("translation".into()),
+            vector(&["x", "y", "z"], &translation),
+        );
+        transform.insert(
+            Value::String("rotation".into()),
+            vector(&["x", "y", "z", "w"], &rotation),
+        );
+        transform.insert(
+            Value::String("scale".into()),
+            vector(&["x", "y", "z"], &scale),
+        );
+        RonPrefabChild {
+            prefab: PrefabId::from_name(&self.output.full_name(name)).raw(),
+            transform: Some(Value::Map(transform)),
+        }
+    }
+
+    /// Sanitize a name and make it unique within the namespace
+    fn unique_name(&mut self, name: &str) -> String {
+        let base = sanitize_name(name);
+        let mut name = base.clone();
+        let mut suffix = 1;
+        while !self.used_names.insert(name.clone()) {
+            suffix += 1;
+            name = format!("{base}_{suffix}");
+        }
+        name
+    }
+}
+
+/// Collider shapes selected by node name prefix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColliderKind {
+    Box,
+    Sphere,
+    Convex,
+}
+
+impl ColliderKind {
+    fn from_node_name(name: &str) -> Option<Self> {
+        let prefix = name.get(..4)?.to_ascii_uppercase();
+        match prefix.as_str() {
+            "UBX_" => Some(Self::Box),
+            "USP_" => Some(Self::Sphere),
+            "UCX_" => Some(Self::Convex),
+            _ => None,
+        }
+    }
+}
+
+fn scene_display_name(scene: &gltf::Scene) -> String {
+    scene
+        .name()
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("scene{}", scene.index()))
+}
+
+/// Lowercase a name and replace characters not allowed in prefab names
+fn sanitize_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| match c.to_ascii_lowercase() {
+            c @ ('a'..='z' | '0'..='9') => c,
+            _ => '_',
+        })
+        .collect();
+    if sanitized.is_empty() {
+        "node".to_string()
+    } else {
+        sanitized
+    }
+}
+
+fn component(component_type: &str, data: Value) -> RonComponent {
+    RonComponent {
+        component_type: component_type.to_string(),
+        data,
+    }
+}
+
+/// Serialize a reflected component into the data format its registered
+/// deserializer reads
+fn reflect_component<T: Serialize + bevy_reflect::TypePath>(
+    value: &T,
+) -> Result<RonComponent, Error> {
+    let data = ron::to_string(value)
+        .and_then(|text| ron::from_str::<Value>(&text).map_err(Into::into))
+        .map_err(|e| Error::serialization(format!("Failed to encode component: {e}")))?;
+    Ok(component(T::short_type_path(), data))
+}
+
+fn vector(keys: &[&str], values: &[f32]) -> Value {
+    let mut map = Map::new();
+    for (key, value) in keys.iter().zip(values) {
+        map.insert(
+            Value::String(key.to_string()),
+            Value::Number(ron::Number::from(f64::from(*value))),
+        );
+    }
+    Value::Map(map)
+}
+
+impl Factory {
+    /// Convert a glTF scene and register its prefabs under their names
+    ///
+    /// `asset_path` is relative to `assets_root`. The namespace is derived
+    /// from the file name with [`gltf_namespace`]. Returns the id of the
+    /// scene prefab.
+    pub fn load_gltf(
+        &mut self,
+        names: &mut PrefabNames,
+        assets_root: &Path,
+        asset_path: &str,
+    ) -> Result<PrefabId, Error> {
+        let path = assets_root.join(asset_path);
+        let bytes = std::fs::read(&path).map_err(|e| {
+            Error::resource_load(
+                format!("glTF scene {}", path.display()),
+                format!("IO error: {e}"),
+            )
+        })?;
+        let converted = convert_gltf(&bytes, asset_path, &gltf_namespace(&path))?;
+
+        for (name, ron_prefab) in &converted.prefabs {
+            let id = names.register(&converted.full_name(name))?;
+            self.register(id, Prefab::try_from(ron_prefab.clone())?)?;
+        }
+        Ok(converted.scene_id())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::component_registry::registry_test_guard;
+    use crate::register_default_components;
+    use bevy_core::Name;
+    use bevy_ecs::prelude::*;
+    use bevy_ecs::system::CommandQueue;
+    use bevy_hierarchy::Children;
+    use bevy_transform::components::Transform;
+
+    /// Minimal glTF document: a car with a two-primitive body, a wheel child
+    /// and a box collider; the buffer is never read since only accessor bounds
+    /// are used
+    const CAR_GLTF: &str = r#"{
+        "asset": {"version": "2.0"},
+        "scene": 0,
+        "scenes": [{"name": "Car", "nodes": [0, 3]}],
+        "nodes": [
+            {"name": "Body", "mesh": 0, "children": [1]},
+            {"name": "Wheel FL", "mesh": 1, "translation": [0.8, 0.3, 1.2]},
+            {"name": "unused"},
+            {"name": "UBX_Body", "mesh": 2}
+        ],
+        "meshes": [
+            {"primitives": [
+                {"attributes": {"POSITION": 0}, "material": 0},
+                {"attributes": {"POSITION": 0}, "material": 1}
+            ]},
+            {"primitives": [{"attributes": {"POSITION": 0}, "material": 1}]},
+            {"primitives": [{"attributes": {"POSITION": 1}}]}
+        ],
+        "materials": [{"name": "Paint"}, {"name": "Rubber"}],
+        "buffers": [{"uri": "car.bin", "byteLength": 36}],
+        "bufferViews": [{"buffer": 0, "byteLength": 36}],
+        "accessors": [
+            {"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+             "min": [-1.0, 0.0, -2.0], "max": [1.0, 1.0, 2.0]},
+            {"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+             "min": [-1.0, 0.0, -2.0], "max": [1.0, 1.5, 2.0]}
+        ]
+    }"#;
+
+    #[test]
+    fn test_convert_scene_nodes_to_prefabs() {
+        let converted = convert_gltf(CAR_GLTF.as_bytes(), "models/car.glb", "car").unwrap();
+        let names: Vec<&str> = converted.prefabs.keys().map(String::as_str).collect();
+        assert_eq!(
+            names,
+            vec![
+                "body",
+                "body_primitive0",
+                "body_primitive1",
+                "scene",
+                "ubx_body",
+                "wheel_fl"
+            ]
+        );
+        assert_eq!(converted.scene_id(), PrefabId::from_name("car/scene"));
+        assert_eq!(converted.manifest().prefabs["wheel_fl"], "car/wheel_fl.ron");
+
+        let collider = &converted.prefabs["ubx_body"];
+        assert_eq!(collider.tags, vec![GLTF_COLLIDER_TAG]);
+        assert_eq!(collider.components[1].component_type, "BoxCollider");
+    }
+
+    #[test]
+    fn test_converted_prefabs_round_trip_and_spawn() {
+        let _guard = registry_test_guard();
+        register_default_components();
+
+        let converted =
+            convert_gltf(CAR_GLTF.as_bytes(), "models/car.glb", "test_gltf_car").unwrap();
+        let mut factory = Factory::new();
+        for (name, ron_prefab) in &converted.prefabs {
+            // Written files must load back into the same prefab
+            let text = ron::ser::to_string_pretty(ron_prefab, Default::default()).unwrap();
+            let reloaded: RonPrefab = ron::from_str(&text).unwrap();
+            let id = PrefabId::from_name(&converted.full_name(name));
+            factory
+                .register(id, Prefab::try_from(reloaded).unwrap())
+                .unwrap();
+        }
+
+        let mut world = World::new();
+        let mut queue = CommandQueue::default();
+        let scene = factory
+            .spawn(&mut Commands::new(&mut queue, &world), converted.scene_id())
+            .unwrap();
+        queue.apply(&mut world);
+
+        let roots = world.get::<Children>(scene).unwrap().to_vec();
+        assert_eq!(world.get::<Name>(roots[0]).unwrap().as_str(), "Body");
+        assert_eq!(
+            world.get::<BoxCollider>(roots[1]),
+            Some(&BoxCollider {
+                center: [0.0, 0.75, 0.0],
+                half_extents: [1.0, 0.75, 2.0],
+            })
+        );
+
+        let body_children = world.get::<Children>(roots[0]).unwrap().to_vec();
+        // Primitive children are unnamed; the wheel node is named
+        let materials: Vec<_> = body_children
+            .iter()
+            .filter(|e| world.get::<Name>(**e).is_none())
+            .filter_map(|e| world.get::<MaterialAsset>(*e))
+            .map(|m| m.path.as_str())
+            .collect();
+        assert_eq!(
+            materials,
+            vec!["models/car.glb#Material0", "models/car.glb#Material1"]
+        );
+
+        let wheel = body_children
+            .iter()
+            .copied()
+            .find(|e| {
+                world
+                    .get::<Name>(*e)
+                    .is_some_and(|n| n.as_str() == "Wheel FL")
+            })
+            .unwrap();
+        assert_eq!(
+            world.get::<MeshAsset>(wheel).unwrap().path,
+            "models/car.glb#Mesh1/Primitive0"
+        );
+        assert_eq!(
+            world.get::<Transform>(wheel).unwrap().translation,
+            bevy_math::Vec3::new(0.8, 0.3, 1.2)
+        );
+    }
+}
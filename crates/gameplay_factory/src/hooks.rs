@@ -0,0 +1,234 @@
+//! Post-spawn hooks
+//!
+//! Gameplay code often needs to adjust an entity right after the factory
+//! creates it, such as assigning a car to a traffic lane or attaching a
+//! pickup to a mission. Hooks registered with [`Factory::on_spawn`] or
+//! [`Factory::on_spawn_tagged`] run for every entity built from a matching
+//! prefab, including entities spawned as nested children, and receive a
+//! [`SpawnContext`] describing how the entity was created.
+
+use crate::{Factory, PrefabId};
+use amp_core::Error;
+use bevy_ecs::{entity::Entity, system::Commands};
+use std::collections::HashMap;
+
+/// How the factory created an entity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SpawnSource {
+    /// [`Factory::spawn`] or a nested child of it
+    Direct,
+    /// [`Factory::spawn_batch`]
+    Batch,
+    /// [`Factory::spawn_pooled`]; `recycled` is true if a pooled entity was reused
+    Pooled {
+        /// Whether an existing pooled entity was reused
+        recycled: bool,
+    },
+    /// [`Factory::process_async_spawns`]
+    Async,
+    /// Rebuilt by [`Factory::reload_instances`]
+    HotReload,
+}
+
+/// Information passed to post-spawn hooks
+#[derive(Debug, Clone, Copy)]
+pub struct SpawnContext<'a> {
+    /// Prefab the entity was built from
+    pub prefab: PrefabId,
+    /// Tags of the prefab; empty for bundle prefabs
+    pub tags: &'a [String],
+    /// Parent entity if the entity was spawned as a nested child
+    pub parent: Option<Entity>,
+    /// How the entity was created
+    pub source: SpawnSource,
+}
+
+/// Post-spawn hook function type
+///
+/// Returning an error fails the spawn, and the entity is despawned together
+/// with the rest of its hierarchy.
+pub type PostSpawnHook =
+    Box<dyn Fn(&mut Commands, Entity, &SpawnContext) -> Result<(), Error> + Send + Sync>;
+
+/// Hooks registered per prefab id and per tag
+#[derive(Default)]
+pub(crate) struct PostSpawnHooks {
+    by_prefab: HashMap<PrefabId, Vec<PostSpawnHook>>,
+    by_tag: HashMap<String, Vec<PostSpawnHook>>,
+}
+
+impl PostSpawnHooks {
+    /// Check if any hook would run for a prefab with these tags
+    pub(crate) fn matches(&self, prefab: PrefabId, tags: &[String]) -> bool {
+        self.by_prefab.contains_key(&prefab) || tags.iter().any(|t| self.by_tag.contains_key(t))
+    }
+
+    /// Run prefab hooks, then tag hooks in the prefab's tag order
+    pub(crate) fn run(
+        &self,
+        cmd: &mut Commands,
+        entity: Entity,
+        context: &SpawnContext,
+    ) -> Result<(), Error> {
+        let by_prefab = self.by_prefab.get(&context.prefab).into_iter().flatten();
+        let by_tag = context
+            .tags
+            .iter()
+            .filter_map(|tag| self.by_tag.get(tag))
+            .flatten();
+        for hook in by_prefab.chain(by_tag) {
+            hook(cmd, entity, context)?;
+        }
+        Ok(())
+    }
+}
+
+impl Factory {
+    /// Register a hook run after every entity built from prefab `id`
+    pub fn on_spawn<F>(&mut self, id: PrefabId, hook: F)
+    where
+        F: Fn(&mut Commands, Entity, &SpawnContext) -> Result<(), Error> + Send + Sync + 'static,
+    {
+        self.hooks
+            .by_prefab
+            .entry(id)
+            .or_default()
+            .push(Box::new(hook));
+    }
+
+    /// Register a hook run after every entity built from a prefab with `tag`
+    pub fn on_spawn_tagged<F>(&mut self, tag: impl Into<String>, hook: F)
+    where
+        F: Fn(&mut Commands, Entity, &SpawnContext) -> Result<(), Error> + Send + Sync + 'static,
+    {
+        self.hooks
+            .by_tag
+            .entry(tag.into())
+            .or_default()
+            .push(Box::new(hook));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EntityPool, Prefab};
+    use bevy_ecs::prelude::*;
+    use bevy_ecs::system::CommandQueue;
+    use bevy_hierarchy::Children;
+    use bevy_transform::components::Transform;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Component, Clone, Debug, PartialEq)]
+    struct Lane(u32);
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Spawned(SpawnSource);
+
+    type Log = Arc<Mutex<Vec<(PrefabId, Option<Entity>, SpawnSource)>>>;
+
+    fn record(log: &Log) -> impl Fn(&mut Commands, Entity, &SpawnContext) -> Result<(), Error> {
+        let log = Arc::clone(log);
+        move |cmd, entity, context| {
+            log.lock()
+                .unwrap()
+                .push((context.prefab, context.parent, context.source));
+            cmd.entity(entity).insert(Spawned(context.source));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_hooks_run_for_prefab_and_tag() {
+        let car = PrefabId::new(0x4432_0001);
+        let wheel = PrefabId::new(0x4432_0002);
+        let mut factory = Factory::new();
+        factory
+            .register(wheel, Prefab::new().with_tag("physics"))
+            .unwrap();
+        factory
+            .register(
+                car,
+                Prefab::new()
+                    .with_tag("traffic")
+                    .with_tag("physics")
+                    .with_child(wheel, Transform::IDENTITY),
+            )
+            .unwrap();
+
+        let log = Log::default();
+        factory.on_spawn(car, record(&log));
+        factory.on_spawn_tagged("physics", record(&log));
+        factory.on_spawn_tagged("traffic", |cmd, entity, _| {
+            cmd.entity(entity).insert(Lane(3));
+            Ok(())
+        });
+
+        let mut world = World::new();
+        let mut queue = CommandQueue::default();
+        let car_entity = factory
+            .spawn(&mut Commands::new(&mut queue, &world), car)
+            .unwrap();
+        queue.apply(&mut world);
+
+        let wheel_entity = world.get::<Children>(car_entity).unwrap()[0];
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                (wheel, Some(car_entity), SpawnSource::Direct),
+                (car, None, SpawnSource::Direct),
+                (car, None, SpawnSource::Direct),
+            ]
+        );
+        assert_eq!(world.get::<Lane>(car_entity), Some(&Lane(3)));
+        assert!(world.get::<Lane>(wheel_entity).is_none());
+        assert!(world.get::<Spawned>(wheel_entity).is_some());
+    }
+
+    #[test]
+    fn test_failing_hook_despawns_hierarchy() {
+        let id = PrefabId::new(0x4432_0101);
+        let mut factory = Factory::new();
+        factory.register(id, Prefab::new()).unwrap();
+        factory.on_spawn(id, |_, _, _| Err(Error::validation("no free lane")));
+
+        let mut world = World::new();
+        let mut queue = CommandQueue::default();
+        let result = factory.spawn(&mut Commands::new(&mut queue, &world), id);
+        queue.apply(&mut world);
+
+        assert!(result.unwrap_err().to_string().contains("no free lane"));
+        assert_eq!(world.entities().len(), 0);
+    }
+
+    #[test]
+    fn test_hooks_report_spawn_source() {
+        let bundle = PrefabId::new(0x4432_0201);
+        let mut factory = Factory::new();
+        factory.register_bundle(bundle, Lane(1)).unwrap();
+        factory.on_spawn(bundle, |cmd, entity, context| {
+            cmd.entity(entity).insert(Spawned(context.source));
+            Ok(())
+        });
+
+        let mut world = World::new();
+        let batch = factory.spawn_batch(&mut world, bundle, 3).unwrap();
+        for entity in batch {
+            assert_eq!(
+                world.get::<Spawned>(entity),
+                Some(&Spawned(SpawnSource::Batch))
+            );
+        }
+
+        let mut pool = EntityPool::new();
+        let mut queue = CommandQueue::default();
+        let pooled = factory
+            .spawn_pooled(&mut Commands::new(&mut queue, &world), &mut pool, bundle)
+            .unwrap();
+        queue.apply(&mut world);
+        assert_eq!(
+            world.get::<Spawned>(pooled),
+            Some(&Spawned(SpawnSource::Pooled { recycled: false }))
+        );
+    }
+}
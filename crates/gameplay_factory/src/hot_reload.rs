@@ -1,14 +1,19 @@
 //! Hot-reload functionality for file watching and automatic prefab reloading
 //!
 //! This module provides file watching capabilities that trigger prefab reloads
-//! when files are modified, created, or deleted.
+//! when files are modified, created, or deleted. Reloaded definitions replace
+//! the registered prefab, and already-spawned instances flagged with
+//! [`HotReloadable`] are patched or rebuilt in place.
 
 use std::path::{Path, PathBuf};
 
+use crate::{Factory, PrefabId, SpawnSource, SpawnState};
 use amp_core::Error;
+use bevy_ecs::prelude::{Commands, Component, Entity, Resource};
 #[cfg(feature = "hot-reload")]
-use bevy_ecs::prelude::ResMut;
-use bevy_ecs::prelude::Resource;
+use bevy_ecs::prelude::{Query, ResMut};
+use bevy_hierarchy::{DespawnRecursiveExt, Parent};
+use bevy_transform::components::{GlobalTransform, Transform};
 
 /// Events that can trigger a hot-reload
 #[derive(Debug, Clone, PartialEq)]
@@ -189,13 +194,13 @@ pub mod watcher {
             tx,
             Config::default().with_poll_interval(Duration::from_millis(500)),
         )
-        .map_err(|e| Error::resource_load("file watcher", &e.to_string()))?;
+        .map_err(|e| Error::resource_load("file watcher", e.to_string()))?;
 
         // Start watching the directories
         for dir in &watch_dirs {
             watcher
                 .watch(dir, RecursiveMode::Recursive)
-                .map_err(|e| Error::resource_load("file watcher", &e.to_string()))?;
+                .map_err(|e| Error::resource_load("file watcher", e.to_string()))?;
             log::info!("Watching directory: {}", dir.display());
         }
 
@@ -227,13 +232,13 @@ pub mod watcher {
             for path in to_send {
                 if path.exists() {
                     let event = HotReloadEvent::Modified(path.clone());
-                    if let Err(_) = reload_tx.send(event) {
+                    if reload_tx.send(event).is_err() {
                         log::warn!("Hot-reload channel closed, stopping watcher");
                         break;
                     }
                 } else {
                     let event = HotReloadEvent::Deleted(path.clone());
-                    if let Err(_) = reload_tx.send(event) {
+                    if reload_tx.send(event).is_err() {
                         log::warn!("Hot-reload channel closed, stopping watcher");
                         break;
                     }
@@ -267,8 +272,9 @@ pub mod watcher {
                     debounce_map.insert(path.clone(), std::time::Instant::now());
                 }
                 EventKind::Remove(_) => {
-                    // Don't debounce remove events - send immediately
-                    debounce_map.remove(path);
+                    // Debounced too; the loop reports the path as deleted
+                    // once it is gone
+                    debounce_map.insert(path.clone(), std::time::Instant::now());
                 }
                 _ => {}
             }
@@ -306,27 +312,168 @@ pub mod watcher {
     }
 }
 
+/// How a [`HotReloadable`] instance is updated when its prefab is reloaded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReloadMode {
+    /// Overwrite the root entity's components with the new definition,
+    /// keeping runtime state, extra components and children
+    #[default]
+    Patch,
+    /// Rebuild the entity and its children from the new definition,
+    /// keeping only its entity id, placement and parent
+    Respawn,
+}
+
+/// Marks a spawned prefab instance to be updated when its prefab changes
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HotReloadable {
+    /// Prefab the instance was spawned from
+    pub prefab: PrefabId,
+    /// How the instance is updated
+    pub mode: ReloadMode,
+}
+
+impl HotReloadable {
+    /// Patch the instance when `prefab` is reloaded
+    pub fn patch(prefab: PrefabId) -> Self {
+        Self {
+            prefab,
+            mode: ReloadMode::Patch,
+        }
+    }
+
+    /// Rebuild the instance when `prefab` is reloaded
+    pub fn respawn(prefab: PrefabId) -> Self {
+        Self {
+            prefab,
+            mode: ReloadMode::Respawn,
+        }
+    }
+}
+
+impl Factory {
+    /// Spawn a prefab flagged with [`HotReloadable`]
+    pub fn spawn_hot_reloadable(
+        &self,
+        cmd: &mut Commands,
+        id: PrefabId,
+        mode: ReloadMode,
+    ) -> Result<Entity, Error> {
+        let entity = self.spawn(cmd, id)?;
+        cmd.entity(entity)
+            .insert(HotReloadable { prefab: id, mode });
+        Ok(entity)
+    }
+
+    /// Re-read a prefab file and replace its registered definition
+    ///
+    /// The prefab keeps the id it was loaded under by
+    /// [`load_directory`](Factory::load_directory), so `path` must be spelled
+    /// the same way as the paths matched by the prefab glob.
+    #[cfg(feature = "ron")]
+    pub fn reload_prefab_file(&mut self, path: &Path) -> Result<PrefabId, Error> {
+        let id = Self::path_prefab_id(path)?;
+        let prefab = self.load_prefab_file(path)?;
+
+        crate::GLOBAL_PREFAB_IDS.insert(id);
+        self.bundles.remove(&id);
+        self.registry.insert(id, prefab);
+        log::info!("Reloaded prefab {id:?} from {}", path.display());
+        Ok(id)
+    }
+
+    /// Apply a hot-reload event to the registry
+    ///
+    /// Returns the id of the created or modified prefab. Deleted prefabs are
+    /// unregistered and `None` is returned; their instances are left as-is.
+    #[cfg(feature = "ron")]
+    pub fn handle_hot_reload_event(
+        &mut self,
+        event: &HotReloadEvent,
+    ) -> Result<Option<PrefabId>, Error> {
+        match event {
+            HotReloadEvent::Created(path) | HotReloadEvent::Modified(path) => {
+                self.reload_prefab_file(path).map(Some)
+            }
+            HotReloadEvent::Deleted(path) => {
+                let id = Self::path_prefab_id(path)?;
+                if self.registry.remove(&id).is_some() {
+                    crate::GLOBAL_PREFAB_IDS.remove(&id);
+                    log::info!("Unregistered prefab {id:?} deleted at {}", path.display());
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// Update every instance of `id` from its current definition
+    ///
+    /// Returns the number of instances updated.
+    pub fn reload_instances<'a>(
+        &self,
+        cmd: &mut Commands,
+        id: PrefabId,
+        instances: impl IntoIterator<Item = (Entity, &'a HotReloadable)>,
+    ) -> Result<usize, Error> {
+        let mut updated = 0;
+        for (entity, reloadable) in instances {
+            if reloadable.prefab != id {
+                continue;
+            }
+
+            match reloadable.mode {
+                ReloadMode::Patch => match self.bundles.get(&id) {
+                    Some(bundle) => bundle.apply(cmd, entity),
+                    None => self.prefab(id)?.apply(cmd, entity)?,
+                },
+                ReloadMode::Respawn => {
+                    cmd.entity(entity).despawn_descendants().retain::<(
+                        HotReloadable,
+                        Transform,
+                        GlobalTransform,
+                        Parent,
+                    )>();
+                    self.build_tree(
+                        cmd,
+                        id,
+                        entity,
+                        None,
+                        &mut SpawnState::new(SpawnSource::HotReload),
+                    )?;
+                }
+            }
+            updated += 1;
+        }
+        Ok(updated)
+    }
+}
+
 /// Bevy system for processing hot-reload events
+///
+/// Requires the [`Factory`] resource and the receiver returned by
+/// [`Factory::take_hot_reload_receiver`].
 #[cfg(feature = "hot-reload")]
 pub fn process_hot_reload_events(
     mut receiver: ResMut<HotReloadReceiver>,
-    // Add other system parameters as needed for prefab reloading
+    mut factory: ResMut<Factory>,
+    mut commands: Commands,
+    instances: Query<(Entity, &HotReloadable)>,
 ) {
     // Process all pending events
     while let Ok(event) = receiver.try_recv() {
-        match event {
-            HotReloadEvent::Created(path) => {
-                log::info!("Hot-reload: File created: {}", path.display());
-                // TODO: Load new prefab
-            }
-            HotReloadEvent::Modified(path) => {
-                log::info!("Hot-reload: File modified: {}", path.display());
-                // TODO: Reload existing prefab
-            }
-            HotReloadEvent::Deleted(path) => {
-                log::info!("Hot-reload: File deleted: {}", path.display());
-                // TODO: Remove prefab from registry
+        log::info!("Hot-reload: {:?}", event);
+        let id = match factory.handle_hot_reload_event(&event) {
+            Ok(Some(id)) => id,
+            Ok(None) => continue,
+            Err(e) => {
+                log::error!("Hot-reload of {} failed: {}", event.path().display(), e);
+                continue;
             }
+        };
+
+        match factory.reload_instances(&mut commands, id, instances.iter()) {
+            Ok(count) => log::info!("Hot-reload: updated {count} instance(s) of {id:?}"),
+            Err(e) => log::error!("Hot-reload: failed to update instances of {id:?}: {e}"),
         }
     }
 }
@@ -336,3 +483,105 @@ pub fn process_hot_reload_events(
 pub fn process_hot_reload_events() {
     // No-op when hot-reload is disabled
 }
+
+#[cfg(all(test, feature = "ron"))]
+mod tests {
+    use super::*;
+    use crate::register_default_components;
+    use bevy_core::Name;
+    use bevy_ecs::system::CommandQueue;
+    use bevy_ecs::world::World;
+    use tempfile::TempDir;
+
+    #[derive(Component)]
+    struct Damage;
+
+    fn write_prefab(path: &Path, name: &str) {
+        std::fs::write(
+            path,
+            format!(
+                r#"RonPrefab(components: [RonComponent(component_type: "Name", data: "{name}")])"#
+            ),
+        )
+        .unwrap();
+    }
+
+    fn with_commands<R>(world: &mut World, f: impl FnOnce(&mut Commands) -> R) -> R {
+        let mut queue = CommandQueue::default();
+        let result = {
+            let mut cmd = Commands::new(&mut queue, world);
+            f(&mut cmd)
+        };
+        queue.apply(world);
+        result
+    }
+
+    #[test]
+    fn test_modified_prefab_updates_instances() {
+        let _guard = crate::component_registry::registry_test_guard();
+        register_default_components();
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("sedan.ron");
+        write_prefab(&path, "sedan v1");
+
+        let mut factory = Factory::new();
+        let id = factory.reload_prefab_file(&path).unwrap();
+
+        let mut world = World::new();
+        let (patched, respawned) = with_commands(&mut world, |cmd| {
+            let patched = factory
+                .spawn_hot_reloadable(cmd, id, ReloadMode::Patch)
+                .unwrap();
+            let respawned = factory
+                .spawn_hot_reloadable(cmd, id, ReloadMode::Respawn)
+                .unwrap();
+            cmd.entity(patched).insert(Damage);
+            cmd.entity(respawned).insert(Damage);
+            (patched, respawned)
+        });
+
+        write_prefab(&path, "sedan v2");
+        let reloaded = factory
+            .handle_hot_reload_event(&HotReloadEvent::Modified(path.clone()))
+            .unwrap();
+        assert_eq!(reloaded, Some(id));
+
+        let instances: Vec<(Entity, HotReloadable)> = world
+            .query::<(Entity, &HotReloadable)>()
+            .iter(&world)
+            .map(|(entity, reloadable)| (entity, *reloadable))
+            .collect();
+        let updated = with_commands(&mut world, |cmd| {
+            factory
+                .reload_instances(cmd, id, instances.iter().map(|(e, r)| (*e, r)))
+                .unwrap()
+        });
+        assert_eq!(updated, 2);
+
+        for entity in [patched, respawned] {
+            assert_eq!(world.get::<Name>(entity).unwrap().as_str(), "sedan v2");
+            assert!(world.get::<HotReloadable>(entity).is_some());
+        }
+        assert!(world.get::<Damage>(patched).is_some());
+        assert!(world.get::<Damage>(respawned).is_none());
+    }
+
+    #[test]
+    fn test_deleted_prefab_is_unregistered() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("bus.ron");
+        std::fs::write(&path, "RonPrefab(components: [])").unwrap();
+
+        let mut factory = Factory::new();
+        let id = factory.reload_prefab_file(&path).unwrap();
+        assert!(factory.contains(id));
+
+        std::fs::remove_file(&path).unwrap();
+        let reloaded = factory
+            .handle_hot_reload_event(&HotReloadEvent::Deleted(path))
+            .unwrap();
+        assert_eq!(reloaded, None);
+        assert!(!factory.contains(id));
+        assert!(!crate::is_prefab_id_registered(id));
+    }
+}
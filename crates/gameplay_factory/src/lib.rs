@@ -3,7 +3,17 @@
 //! This crate provides a factory pattern for creating game entities from prefab definitions.
 //! It supports loading prefabs from various sources and spawning them into the ECS world.
 
-use bevy_ecs::system::Commands;
+use bevy_ecs::{
+    bundle::Bundle,
+    entity::Entity,
+    system::{Commands, Resource},
+    world::World,
+};
+use bevy_hierarchy::{BuildChildren, DespawnRecursiveExt};
+use bevy_transform::{
+    components::{GlobalTransform, Transform},
+    TransformBundle,
+};
 use dashmap::DashSet;
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
@@ -13,11 +23,15 @@ pub use amp_core::Error;
 // Re-export component registry functions
 pub use component_registry::{
     call_component_deserializer, register_component, register_default_components,
-    registered_components, ComponentDeserializer,
+    register_reflect_component, register_reflect_type, registered_components,
+    ComponentDeserializer,
 };
 
 mod component_registry;
 
+mod bundle;
+use bundle::{BundleTemplate, TypedBundle};
+
 mod prefab;
 pub use prefab::*;
 
@@ -29,6 +43,33 @@ pub use ron_loader::*;
 mod hot_reload;
 pub use hot_reload::*;
 
+mod pool;
+pub use pool::*;
+
+mod async_spawn;
+pub use async_spawn::*;
+
+mod asset_refs;
+pub use asset_refs::*;
+
+mod names;
+pub use names::*;
+
+mod preload;
+pub use preload::*;
+
+mod scene_assets;
+pub use scene_assets::*;
+
+#[cfg(feature = "gltf")]
+mod gltf_import;
+#[cfg(feature = "gltf")]
+pub use gltf_import::*;
+
+mod hooks;
+use hooks::PostSpawnHooks;
+pub use hooks::{PostSpawnHook, SpawnContext, SpawnSource};
+
 /// Unique identifier for prefab definitions
 ///
 /// This is a hardened type that prevents silent narrowing and uses a global
@@ -39,7 +80,7 @@ pub struct PrefabId(u64);
 
 impl PrefabId {
     /// Create a new PrefabId from a u64 value
-    pub fn new(id: u64) -> Self {
+    pub const fn new(id: u64) -> Self {
         Self(id)
     }
 
@@ -71,7 +112,7 @@ impl std::fmt::Display for PrefabId {
 ///
 /// This singleton tracks all registered PrefabIds across all Factory instances
 /// to prevent ID collisions even when using multiple factories.
-static GLOBAL_PREFAB_IDS: Lazy<DashSet<PrefabId>> = Lazy::new(|| DashSet::new());
+static GLOBAL_PREFAB_IDS: Lazy<DashSet<PrefabId>> = Lazy::new(DashSet::new);
 
 /// Check if a PrefabId has been registered globally
 pub fn is_prefab_id_registered(id: PrefabId) -> bool {
@@ -94,12 +135,34 @@ pub trait PrefabSource {
     fn load(&self) -> Result<Prefab, Error>;
 }
 
+/// Traversal state of one spawned prefab hierarchy
+pub(crate) struct SpawnState {
+    /// Prefabs on the path from the root, used to reject cycles
+    ancestors: Vec<PrefabId>,
+    /// How the hierarchy is being created, reported to post-spawn hooks
+    source: SpawnSource,
+}
+
+impl SpawnState {
+    pub(crate) fn new(source: SpawnSource) -> Self {
+        Self {
+            ancestors: Vec::new(),
+            source,
+        }
+    }
+}
+
 /// Factory for creating entities from prefab definitions
+#[derive(Resource)]
 pub struct Factory {
     registry: HashMap<PrefabId, Prefab>,
+    bundles: HashMap<PrefabId, Box<dyn BundleTemplate>>,
+    hooks: PostSpawnHooks,
     #[cfg(feature = "hot-reload")]
     hot_reload_sender: Option<HotReloadSender>,
     #[cfg(feature = "hot-reload")]
+    hot_reload_receiver: Option<HotReloadReceiver>,
+    #[cfg(feature = "hot-reload")]
     watcher_handle: Option<WatcherHandle>,
 }
 
@@ -108,9 +171,13 @@ impl Factory {
     pub fn new() -> Self {
         Self {
             registry: HashMap::new(),
+            bundles: HashMap::new(),
+            hooks: PostSpawnHooks::default(),
             #[cfg(feature = "hot-reload")]
             hot_reload_sender: None,
             #[cfg(feature = "hot-reload")]
+            hot_reload_receiver: None,
+            #[cfg(feature = "hot-reload")]
             watcher_handle: None,
         }
     }
@@ -123,7 +190,7 @@ impl Factory {
         }
 
         // Check for local collision detection
-        if self.registry.contains_key(&id) {
+        if self.contains(id) {
             log::warn!(
                 "Prefab ID {:?} already exists in local registry, replacing existing prefab",
                 id
@@ -132,49 +199,318 @@ impl Factory {
 
         // Register globally and locally
         GLOBAL_PREFAB_IDS.insert(id);
+        self.bundles.remove(&id);
         self.registry.insert(id, prefab);
         Ok(())
     }
 
+    /// Register a prefab defined by a complete component bundle
+    ///
+    /// Bundle prefabs are spawned with all components at once, which lets
+    /// [`Factory::spawn_batch`] hand whole batches to `World::spawn_batch`.
+    pub fn register_bundle<B: Bundle + Clone>(
+        &mut self,
+        id: PrefabId,
+        bundle: B,
+    ) -> Result<(), Error> {
+        if GLOBAL_PREFAB_IDS.contains(&id) {
+            return Err(Error::validation(format!("Duplicate PrefabId {id:?}")));
+        }
+
+        GLOBAL_PREFAB_IDS.insert(id);
+        self.registry.remove(&id);
+        self.bundles.insert(id, Box::new(TypedBundle(bundle)));
+        Ok(())
+    }
+
     /// Load and register a prefab from a source
     pub fn load_from_source(
         &mut self,
         id: PrefabId,
         source: &dyn PrefabSource,
     ) -> Result<(), Error> {
-        let prefab = Prefab::new();
-        source.load()?;
-        self.register(id, prefab)?;
-        Ok(())
+        let prefab = source.load()?;
+        self.register(id, prefab)
     }
 
     /// Spawn an entity from a registered prefab
+    ///
+    /// Child prefabs are spawned recursively and attached to their parent
+    /// with their relative transforms. Post-spawn hooks run for each entity
+    /// once its own children are built. If any entity of the hierarchy fails
+    /// to spawn, the whole hierarchy is despawned.
     pub fn spawn(
         &self,
         cmd: &mut Commands,
         id: PrefabId,
     ) -> Result<bevy_ecs::entity::Entity, Error> {
-        let prefab = Prefab::new();
-        self.registry.get(&id).ok_or_else(|| {
-            Error::resource_load(format!("Prefab {id:?}"), "not found in registry")
-        })?;
+        self.spawn_tree(cmd, id, None, &mut SpawnState::new(SpawnSource::Direct))
+    }
+
+    /// Spawn a prefab and its children, tracking ancestors to reject cycles
+    fn spawn_tree(
+        &self,
+        cmd: &mut Commands,
+        id: PrefabId,
+        parent: Option<Entity>,
+        state: &mut SpawnState,
+    ) -> Result<Entity, Error> {
+        let (entity, result) = match self.bundles.get(&id) {
+            Some(bundle) => {
+                let entity = bundle.spawn(cmd);
+                let context = SpawnContext {
+                    prefab: id,
+                    tags: &[],
+                    parent,
+                    source: state.source,
+                };
+                (entity, self.hooks.run(cmd, entity, &context))
+            }
+            None => {
+                self.prefab(id)?;
+                let entity = cmd.spawn_empty().id();
+                (entity, self.build_tree(cmd, id, entity, parent, state))
+            }
+        };
+
+        if let Err(e) = result {
+            cmd.entity(entity).despawn_recursive();
+            return Err(e);
+        }
+        Ok(entity)
+    }
+
+    /// Initialize a prefab's components and children on an existing entity,
+    /// then run its post-spawn hooks
+    ///
+    /// On error, children spawned so far are already attached to `entity`,
+    /// so the caller despawns it recursively.
+    fn build_tree(
+        &self,
+        cmd: &mut Commands,
+        id: PrefabId,
+        entity: Entity,
+        parent: Option<Entity>,
+        state: &mut SpawnState,
+    ) -> Result<(), Error> {
+        if let Some(bundle) = self.bundles.get(&id) {
+            bundle.apply(cmd, entity);
+            let context = SpawnContext {
+                prefab: id,
+                tags: &[],
+                parent,
+                source: state.source,
+            };
+            return self.hooks.run(cmd, entity, &context);
+        }
+
+        let prefab = self.prefab(id)?;
+        let ancestors = &mut state.ancestors;
+
+        if ancestors.contains(&id) {
+            let chain: Vec<String> = ancestors
+                .iter()
+                .chain(std::iter::once(&id))
+                .map(ToString::to_string)
+                .collect();
+            return Err(Error::validation(format!(
+                "Prefab cycle detected: {}",
+                chain.join(" -> ")
+            )));
+        }
+        if ancestors.len() >= MAX_PREFAB_DEPTH {
+            return Err(Error::validation(format!(
+                "Prefab {id} exceeds the maximum nesting depth of {MAX_PREFAB_DEPTH}"
+            )));
+        }
+
+        prefab.apply(cmd, entity)?;
+        if !prefab.children().is_empty() {
+            state.ancestors.push(id);
+            let result = self.spawn_children(cmd, prefab, entity, state);
+            state.ancestors.pop();
+            result?;
+        }
+
+        let context = SpawnContext {
+            prefab: id,
+            tags: prefab.tags(),
+            parent,
+            source: state.source,
+        };
+        self.hooks.run(cmd, entity, &context)
+    }
+
+    /// Look up a registered component prefab
+    fn prefab(&self, id: PrefabId) -> Result<&Prefab, Error> {
+        self.registry
+            .get(&id)
+            .ok_or_else(|| Error::resource_load(format!("Prefab {id:?}"), "not found in registry"))
+    }
+
+    /// Spawn `prefab`'s children and attach them to `parent`
+    ///
+    /// `parent` gets a default `TransformBundle` unless its prefab already
+    /// provides one, so transforms propagate down to the children. Each
+    /// child's own `Transform` is placed relative to the parent by its
+    /// [`PrefabChild`] transform.
+    fn spawn_children(
+        &self,
+        cmd: &mut Commands,
+        prefab: &Prefab,
+        parent: Entity,
+        state: &mut SpawnState,
+    ) -> Result<(), Error> {
+        cmd.add(ensure_transform(parent));
+        for child in prefab.children() {
+            let entity = self.spawn_tree(cmd, child.prefab, Some(parent), state)?;
+            cmd.add(place_child(entity, child.transform));
+            cmd.entity(parent).add_child(entity);
+        }
+        Ok(())
+    }
+
+    /// Spawn `count` entities from a registered prefab directly into the world
+    ///
+    /// Bundle prefabs are spawned with a single `World::spawn_batch` call.
+    /// Component prefabs, including prefabs loaded from RON, are not batched:
+    /// each entity is spawned through one shared command queue with an insert
+    /// per component, so it still moves through one archetype per component.
+    /// See "Batched Spawning" in `docs/architecture/gameplay_factory.md`.
+    ///
+    /// If any entity fails to initialize, every entity of the batch is
+    /// despawned and the error is returned.
+    pub fn spawn_batch(
+        &self,
+        world: &mut World,
+        id: PrefabId,
+        count: usize,
+    ) -> Result<Vec<bevy_ecs::entity::Entity>, Error> {
+        if let Some(bundle) = self.bundles.get(&id) {
+            let entities = bundle.spawn_batch(world, count);
+            if !self.hooks.matches(id, &[]) {
+                return Ok(entities);
+            }
+
+            let mut queue = bevy_ecs::system::CommandQueue::default();
+            let mut cmd = Commands::new(&mut queue, world);
+            let context = SpawnContext {
+                prefab: id,
+                tags: &[],
+                parent: None,
+                source: SpawnSource::Batch,
+            };
+            let result = entities
+                .iter()
+                .try_for_each(|&entity| self.hooks.run(&mut cmd, entity, &context));
+            if result.is_err() {
+                for &entity in &entities {
+                    cmd.entity(entity).despawn_recursive();
+                }
+            }
+            queue.apply(world);
+            return result.map(|()| entities);
+        }
+
+        if !self.registry.contains_key(&id) {
+            return Err(Error::resource_load(
+                format!("Prefab {id:?}"),
+                "not found in registry",
+            ));
+        }
+
+        let mut queue = bevy_ecs::system::CommandQueue::default();
+        let mut cmd = Commands::new(&mut queue, world);
+        let mut entities = Vec::with_capacity(count);
+        let mut result = Ok(());
+
+        for _ in 0..count {
+            match self.spawn_tree(&mut cmd, id, None, &mut SpawnState::new(SpawnSource::Batch)) {
+                Ok(entity) => entities.push(entity),
+                Err(e) => {
+                    for entity in entities.drain(..) {
+                        cmd.entity(entity).despawn_recursive();
+                    }
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+
+        queue.apply(world);
+        result.map(|()| entities)
+    }
+
+    /// Spawn an entity from a registered prefab, reusing a pooled entity if available
+    ///
+    /// Recycled entities are reset by re-applying the prefab's components and
+    /// made visible again. Components added after the original spawn are left
+    /// in place, and child entities spawned from nested prefabs stay attached
+    /// and are not re-spawned. Freshly spawned entities are tagged with [`Pooled`] so they can
+    /// later be returned with [`EntityPool::release`].
+    pub fn spawn_pooled(
+        &self,
+        cmd: &mut Commands,
+        pool: &mut EntityPool,
+        id: PrefabId,
+    ) -> Result<bevy_ecs::entity::Entity, Error> {
+        let active = Pooled {
+            prefab: id,
+            active: true,
+        };
+
+        if let Some(entity) = pool.take(id) {
+            cmd.entity(entity)
+                .insert((active, bevy_render::view::Visibility::Inherited));
+            if let Err(e) = self.reset_pooled(cmd, id, entity) {
+                cmd.entity(entity).despawn_recursive();
+                return Err(e);
+            }
+            return Ok(entity);
+        }
 
-        prefab.spawn(cmd)
+        let mut state = SpawnState::new(SpawnSource::Pooled { recycled: false });
+        let entity = self.spawn_tree(cmd, id, None, &mut state)?;
+        cmd.entity(entity).insert(active);
+        Ok(entity)
+    }
+
+    /// Re-apply a prefab's root components to a recycled entity and run its hooks
+    fn reset_pooled(&self, cmd: &mut Commands, id: PrefabId, entity: Entity) -> Result<(), Error> {
+        let tags = match self.bundles.get(&id) {
+            Some(bundle) => {
+                bundle.apply(cmd, entity);
+                &[][..]
+            }
+            None => {
+                let prefab = self.prefab(id)?;
+                prefab.apply(cmd, entity)?;
+                prefab.tags()
+            }
+        };
+
+        let context = SpawnContext {
+            prefab: id,
+            tags,
+            parent: None,
+            source: SpawnSource::Pooled { recycled: true },
+        };
+        self.hooks.run(cmd, entity, &context)
     }
 
     /// Check if a prefab is registered
     pub fn contains(&self, id: PrefabId) -> bool {
-        self.registry.contains_key(&id)
+        self.registry.contains_key(&id) || self.bundles.contains_key(&id)
     }
 
     /// Get the number of registered prefabs
     pub fn len(&self) -> usize {
-        self.registry.len()
+        self.registry.len() + self.bundles.len()
     }
 
     /// Check if the factory is empty
     pub fn is_empty(&self) -> bool {
-        self.registry.is_empty()
+        self.registry.is_empty() && self.bundles.is_empty()
     }
 
     /// Load prefabs from a directory based on factory settings
@@ -197,7 +533,7 @@ impl Factory {
         let paths = glob::glob(&expanded_path).map_err(|e| {
             Error::resource_load(
                 "glob pattern",
-                &format!("Invalid glob pattern '{}': {}", expanded_path, e),
+                format!("Invalid glob pattern '{}': {}", expanded_path, e),
             )
         })?;
 
@@ -261,7 +597,7 @@ impl Factory {
             if !parent_dir.exists() {
                 return Err(Error::resource_load(
                     "prefab directory",
-                    &format!("Directory {} does not exist", parent_dir.display()),
+                    format!("Directory {} does not exist", parent_dir.display()),
                 ));
             }
 
@@ -275,6 +611,23 @@ impl Factory {
     /// Generate a PrefabId from a file path
     #[cfg(feature = "ron")]
     pub fn generate_prefab_id_from_path(&self, path: &std::path::Path) -> Result<PrefabId, Error> {
+        let id = Self::path_prefab_id(path)?;
+
+        // Check for collision in global registry
+        if GLOBAL_PREFAB_IDS.contains(&id) {
+            return Err(Error::validation(format!(
+                "Hash collision detected for path {}: ID {:?} already exists globally",
+                path.display(),
+                id
+            )));
+        }
+
+        Ok(id)
+    }
+
+    /// Hash a file path into the PrefabId it is loaded under
+    #[cfg(feature = "ron")]
+    fn path_prefab_id(path: &std::path::Path) -> Result<PrefabId, Error> {
         use std::collections::hash_map::DefaultHasher;
         use std::hash::{Hash, Hasher};
 
@@ -286,18 +639,7 @@ impl Factory {
         // Create a full 64-bit hash of the path (no truncation)
         let mut hasher = DefaultHasher::new();
         full_path.hash(&mut hasher);
-        let hash = hasher.finish();
-
-        // Check for collision in global registry
-        let id = PrefabId(hash);
-        if GLOBAL_PREFAB_IDS.contains(&id) {
-            return Err(Error::validation(format!(
-                "Hash collision detected for path {}: ID {:?} already exists globally",
-                full_path, id
-            )));
-        }
-
-        Ok(id)
+        Ok(PrefabId(hasher.finish()))
     }
 
     /// Load a prefab from a RON file
@@ -305,8 +647,8 @@ impl Factory {
     fn load_prefab_file(&self, path: &std::path::Path) -> Result<Prefab, Error> {
         let content = std::fs::read_to_string(path).map_err(|e| {
             Error::resource_load(
-                &format!("prefab file {}", path.display()),
-                &format!("IO error: {}", e),
+                format!("prefab file {}", path.display()),
+                format!("IO error: {}", e),
             )
         })?;
 
@@ -319,15 +661,21 @@ impl Factory {
     fn setup_file_watcher(&mut self, _path: &str) -> Result<(), Error> {
         #[cfg(feature = "hot-reload")]
         {
+            // The watcher runs as a tokio task, so a runtime must be active
+            let runtime = tokio::runtime::Handle::try_current().map_err(|e| {
+                Error::resource_load("file watcher", format!("no tokio runtime: {e}"))
+            })?;
+
             // Create channel for hot-reload events
-            let (tx, _rx) = create_reload_channel();
+            let (tx, rx) = create_reload_channel();
 
-            // Store the sender for later use
+            // Store both ends; the receiver is handed out by take_hot_reload_receiver
             self.hot_reload_sender = Some(tx.clone());
+            self.hot_reload_receiver = Some(rx);
 
             // Start the watcher
             let pattern = _path.to_string();
-            let watcher_handle = tokio::task::spawn(async move {
+            let watcher_handle = runtime.spawn(async move {
                 if let Err(e) = watcher::run_watcher(&pattern, tx).await {
                     log::error!("Hot-reload watcher error: {}", e);
                 }
@@ -348,13 +696,7 @@ impl Factory {
     /// Get the hot-reload receiver if hot-reload is enabled
     #[cfg(feature = "hot-reload")]
     pub fn take_hot_reload_receiver(&mut self) -> Option<HotReloadReceiver> {
-        if let Some(_tx) = &self.hot_reload_sender {
-            let (new_tx, rx) = create_reload_channel();
-            self.hot_reload_sender = Some(new_tx);
-            Some(rx)
-        } else {
-            None
-        }
+        self.hot_reload_receiver.take()
     }
 
     /// Stub method when hot-reload is disabled
@@ -370,11 +712,213 @@ impl Default for Factory {
     }
 }
 
+/// Insert whichever of `Transform` and `GlobalTransform` `entity` lacks
+fn ensure_transform(entity: Entity) -> impl FnOnce(&mut World) + Send + 'static {
+    move |world: &mut World| {
+        let Some(mut entity) = world.get_entity_mut(entity) else {
+            return;
+        };
+        if !entity.contains::<Transform>() {
+            entity.insert(Transform::IDENTITY);
+        }
+        if !entity.contains::<GlobalTransform>() {
+            entity.insert(GlobalTransform::IDENTITY);
+        }
+    }
+}
+
+/// Combine a child's own `Transform` with its placement under the parent
+///
+/// Runs after the child's components are applied, so a `Transform` set by
+/// the child prefab is kept and offset by `relative` instead of replaced.
+fn place_child(entity: Entity, relative: Transform) -> impl FnOnce(&mut World) + Send + 'static {
+    move |world: &mut World| {
+        let Some(mut entity) = world.get_entity_mut(entity) else {
+            return;
+        };
+        let own = entity.get::<Transform>().copied().unwrap_or_default();
+        entity.insert(TransformBundle::from_transform(relative.mul_transform(own)));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bevy_ecs::component::Component;
+    use bevy_ecs::system::CommandQueue;
+    use bevy_hierarchy::{Children, Parent};
+    use bevy_math::{Quat, Vec3};
+    use std::any::Any;
     use std::collections::HashSet;
 
+    #[derive(Component, Clone, Debug, PartialEq)]
+    struct Tag(&'static str);
+
+    impl ComponentInit for Tag {
+        fn init(&self, cmd: &mut Commands, entity: Entity) -> Result<(), Error> {
+            cmd.entity(entity).insert(self.clone());
+            Ok(())
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    fn spawn_in(world: &mut World, factory: &Factory, id: PrefabId) -> Result<Entity, Error> {
+        let mut queue = CommandQueue::default();
+        let mut cmd = Commands::new(&mut queue, world);
+        let result = factory.spawn(&mut cmd, id);
+        queue.apply(world);
+        result
+    }
+
+    #[test]
+    fn test_nested_prefab_spawns_hierarchy() {
+        let car = PrefabId::new(0x9427_0001);
+        let officer = PrefabId::new(0x9427_0002);
+        let seat = Transform::from_xyz(-0.4, 0.5, 0.2);
+
+        let mut factory = Factory::new();
+        factory
+            .register(
+                officer,
+                Prefab::new().with_component(Box::new(Tag("officer"))),
+            )
+            .unwrap();
+        factory
+            .register(
+                car,
+                Prefab::new()
+                    .with_component(Box::new(Tag("police_car")))
+                    .with_child(officer, seat),
+            )
+            .unwrap();
+
+        let mut world = World::new();
+        let root = spawn_in(&mut world, &factory, car).unwrap();
+
+        let children = world.get::<Children>(root).unwrap();
+        assert_eq!(children.len(), 1);
+        let driver = children[0];
+        assert_eq!(world.get::<Tag>(driver), Some(&Tag("officer")));
+        assert_eq!(world.get::<Parent>(driver).unwrap().get(), root);
+        assert_eq!(
+            world.get::<Transform>(driver).unwrap().translation,
+            Vec3::new(-0.4, 0.5, 0.2)
+        );
+    }
+
+    struct Pose(Transform);
+
+    impl ComponentInit for Pose {
+        fn init(&self, cmd: &mut Commands, entity: Entity) -> Result<(), Error> {
+            cmd.entity(entity).insert(self.0);
+            Ok(())
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn test_child_transform_is_combined_with_placement() {
+        let car = PrefabId::new(0x9427_0031);
+        let wheel = PrefabId::new(0x9427_0032);
+        let mount = Transform::from_xyz(1.0, 0.0, 2.0)
+            .with_rotation(Quat::from_rotation_y(std::f32::consts::FRAC_PI_2));
+
+        let mut factory = Factory::new();
+        factory
+            .register(
+                wheel,
+                Prefab::new().with_component(Box::new(Pose(Transform::from_xyz(0.0, 0.3, 0.5)))),
+            )
+            .unwrap();
+        factory
+            .register(
+                car,
+                Prefab::new()
+                    .with_component(Box::new(Tag("car")))
+                    .with_child(wheel, mount),
+            )
+            .unwrap();
+
+        let mut world = World::new();
+        let root = spawn_in(&mut world, &factory, car).unwrap();
+
+        assert_eq!(world.get::<Transform>(root), Some(&Transform::IDENTITY));
+        assert!(world.get::<GlobalTransform>(root).is_some());
+
+        let wheel = world.get::<Children>(root).unwrap()[0];
+        let translation = world.get::<Transform>(wheel).unwrap().translation;
+        assert!(translation.abs_diff_eq(Vec3::new(1.5, 0.3, 2.0), 1e-5));
+        assert!(world.get::<GlobalTransform>(wheel).is_some());
+    }
+
+    #[test]
+    fn test_root_transform_is_kept() {
+        let car = PrefabId::new(0x9427_0041);
+        let wheel = PrefabId::new(0x9427_0042);
+        let parked = Transform::from_xyz(10.0, 0.0, -4.0);
+
+        let mut factory = Factory::new();
+        factory
+            .register(wheel, Prefab::new().with_component(Box::new(Tag("wheel"))))
+            .unwrap();
+        factory
+            .register(
+                car,
+                Prefab::new()
+                    .with_component(Box::new(Pose(parked)))
+                    .with_child(wheel, Transform::IDENTITY),
+            )
+            .unwrap();
+
+        let mut world = World::new();
+        let root = spawn_in(&mut world, &factory, car).unwrap();
+
+        assert_eq!(world.get::<Transform>(root), Some(&parked));
+    }
+
+    #[test]
+    fn test_nested_prefab_cycle_is_rejected() {
+        let a = PrefabId::new(0x9427_0011);
+        let b = PrefabId::new(0x9427_0012);
+
+        let mut factory = Factory::new();
+        factory
+            .register(a, Prefab::new().with_child(b, Transform::IDENTITY))
+            .unwrap();
+        factory
+            .register(b, Prefab::new().with_child(a, Transform::IDENTITY))
+            .unwrap();
+
+        let mut world = World::new();
+        let err = spawn_in(&mut world, &factory, a).unwrap_err();
+
+        assert!(err.to_string().contains("cycle"));
+        assert_eq!(world.entities().len(), 0);
+    }
+
+    #[test]
+    fn test_missing_child_prefab_despawns_parent() {
+        let parent = PrefabId::new(0x9427_0021);
+
+        let mut factory = Factory::new();
+        factory
+            .register(
+                parent,
+                Prefab::new().with_child(PrefabId::new(0x9427_0022), Transform::IDENTITY),
+            )
+            .unwrap();
+
+        let mut world = World::new();
+        assert!(spawn_in(&mut world, &factory, parent).is_err());
+        assert_eq!(world.entities().len(), 0);
+    }
+
     #[test]
     fn test_prefab_id_collision_detection() {
         // Clear global registry before test
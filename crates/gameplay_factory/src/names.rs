@@ -0,0 +1,409 @@
+//! String-named prefabs
+//!
+//! Prefabs are addressed by namespaced names such as `"vehicles/sedan_01"` or
+//! `"props/street_lamp"` instead of raw numbers. A name maps to a stable
+//! [`PrefabId`] through [`PrefabId::from_name`], which is a `const fn`, so core
+//! prefabs can be declared as constants with [`prefab_names!`]; a misspelled
+//! constant fails to compile, and a malformed name fails const evaluation.
+//!
+//! At startup, [`Factory::load_manifest`] loads each prefab listed in a
+//! manifest and records its name in [`PrefabNames`]:
+//!
+//! ```ron
+//! PrefabManifest(
+//!     namespace: "vehicles",
+//!     prefabs: {
+//!         "sedan_01": "sedan_01.ron",
+//!         "police_cruiser": "police/cruiser.ron",
+//!     },
+//! )
+//! ```
+
+use crate::{Factory, PrefabId};
+use amp_core::Error;
+use bevy_ecs::system::Resource;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Separator between a prefab name's namespace segments
+pub const PREFAB_NAMESPACE_SEPARATOR: char = '/';
+
+impl PrefabId {
+    /// Derive the id of a namespaced prefab name
+    ///
+    /// Uses 64-bit FNV-1a, so ids are stable across builds and platforms.
+    pub const fn from_name(name: &str) -> Self {
+        let bytes = name.as_bytes();
+        let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+        let mut i = 0;
+        while i < bytes.len() {
+            hash ^= bytes[i] as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+            i += 1;
+        }
+        Self(hash)
+    }
+}
+
+/// Check that a prefab name is `namespace/name`
+///
+/// Names have at least two non-empty segments separated by `/`, made of
+/// lowercase ASCII letters, digits and underscores.
+pub const fn is_valid_prefab_name(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    let mut segments = 1;
+    let mut segment_len = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' => {
+                if segment_len == 0 {
+                    return false;
+                }
+                segments += 1;
+                segment_len = 0;
+            }
+            b'a'..=b'z' | b'0'..=b'9' | b'_' => segment_len += 1,
+            _ => return false,
+        }
+        i += 1;
+    }
+    segments >= 2 && segment_len > 0
+}
+
+/// Declare prefab name constants
+///
+/// Each constant is the [`PrefabId`] of its name; `ALL` lists every
+/// `(name, id)` pair for [`PrefabNames::check_registered`]. Malformed names
+/// are rejected at compile time.
+///
+/// # Examples
+///
+/// ```
+/// use gameplay_factory::{prefab_names, PrefabId};
+///
+/// prefab_names! {
+///     /// Prefabs the game cannot start without
+///     pub mod core_prefabs {
+///         SEDAN_01 = "vehicles/sedan_01",
+///         STREET_LAMP = "props/street_lamp",
+///     }
+/// }
+///
+/// assert_eq!(core_prefabs::SEDAN_01, PrefabId::from_name("vehicles/sedan_01"));
+/// assert_eq!(core_prefabs::ALL.len(), 2);
+/// ```
+#[macro_export]
+macro_rules! prefab_names {
+    (
+        $(#[$meta:meta])*
+        $vis:vis mod $module:ident {
+            $($(#[$const_meta:meta])* $constant:ident = $name:literal),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis mod $module {
+            $(
+                $(#[$const_meta])*
+                #[doc = concat!("`", $name, "`")]
+                pub const $constant: $crate::PrefabId = {
+                    assert!(
+                        $crate::is_valid_prefab_name($name),
+                        concat!("invalid prefab name: ", $name)
+                    );
+                    $crate::PrefabId::from_name($name)
+                };
+            )*
+
+            /// Every declared `(name, id)` pair
+            pub const ALL: &[(&str, $crate::PrefabId)] = &[$(($name, $constant)),*];
+        }
+    };
+}
+
+/// Manifest listing the prefab files of one namespace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrefabManifest {
+    /// Namespace prepended to every prefab name, such as `vehicles`
+    pub namespace: String,
+    /// Prefab names within the namespace, mapped to RON files relative to
+    /// the manifest
+    pub prefabs: BTreeMap<String, String>,
+}
+
+impl PrefabManifest {
+    /// Get the fully qualified names of the listed prefabs
+    pub fn names(&self) -> impl Iterator<Item = String> + '_ {
+        self.prefabs
+            .keys()
+            .map(|name| format!("{}{PREFAB_NAMESPACE_SEPARATOR}{name}", self.namespace))
+    }
+}
+
+/// Bidirectional map between prefab names and ids
+#[derive(Resource, Debug, Default)]
+pub struct PrefabNames {
+    ids: HashMap<String, PrefabId>,
+    names: HashMap<PrefabId, String>,
+}
+
+impl PrefabNames {
+    /// Create an empty name registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a name and return its id
+    ///
+    /// Registering the same name twice returns the same id. Fails if the
+    /// name is malformed or its id collides with a different name.
+    pub fn register(&mut self, name: &str) -> Result<PrefabId, Error> {
+        if !is_valid_prefab_name(name) {
+            return Err(Error::validation(format!(
+                "Invalid prefab name '{name}': expected lowercase 'namespace/name'"
+            )));
+        }
+
+        let id = PrefabId::from_name(name);
+        if let Some(existing) = self.names.get(&id) {
+            if existing != name {
+                return Err(Error::validation(format!(
+                    "Prefab names '{existing}' and '{name}' hash to the same id {id:?}"
+                )));
+            }
+            return Ok(id);
+        }
+
+        self.ids.insert(name.to_string(), id);
+        self.names.insert(id, name.to_string());
+        Ok(id)
+    }
+
+    /// Get the id of a registered name
+    pub fn id(&self, name: &str) -> Option<PrefabId> {
+        self.ids.get(name).copied()
+    }
+
+    /// Get the name of a registered id
+    pub fn name(&self, id: PrefabId) -> Option<&str> {
+        self.names.get(&id).map(String::as_str)
+    }
+
+    /// Get the id of a registered name, or an error listing the names
+    /// registered in the same namespace
+    pub fn resolve(&self, name: &str) -> Result<PrefabId, Error> {
+        if let Some(id) = self.id(name) {
+            return Ok(id);
+        }
+
+        let namespace = name
+            .rsplit_once(PREFAB_NAMESPACE_SEPARATOR)
+            .map_or("", |(namespace, _)| namespace);
+        let mut siblings: Vec<&str> = self.in_namespace(namespace).collect();
+        siblings.sort_unstable();
+        Err(Error::resource_load(
+            format!("prefab '{name}'"),
+            format!(
+                "no prefab with this name; known in '{namespace}': [{}]",
+                siblings.join(", ")
+            ),
+        ))
+    }
+
+    /// Iterate over registered names directly inside `namespace`
+    pub fn in_namespace<'a>(&'a self, namespace: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.ids.keys().map(String::as_str).filter(move |name| {
+            name.rsplit_once(PREFAB_NAMESPACE_SEPARATOR)
+                .is_some_and(|(parent, _)| parent == namespace)
+        })
+    }
+
+    /// Check that every expected name is registered with the expected id
+    ///
+    /// Used at startup with the `ALL` list generated by [`prefab_names!`].
+    pub fn check_registered(&self, expected: &[(&str, PrefabId)]) -> Result<(), Error> {
+        let missing: Vec<&str> = expected
+            .iter()
+            .filter(|(name, id)| self.id(name) != Some(*id))
+            .map(|(name, _)| *name)
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::validation(format!(
+                "Missing prefabs: {}",
+                missing.join(", ")
+            )))
+        }
+    }
+
+    /// Iterate over all `(name, id)` pairs
+    pub fn iter(&self) -> impl Iterator<Item = (&str, PrefabId)> {
+        self.ids.iter().map(|(name, id)| (name.as_str(), *id))
+    }
+
+    /// Get the number of registered names
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Check if no names are registered
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+}
+
+impl Factory {
+    /// Load every prefab listed in a RON manifest and register it under its
+    /// name
+    ///
+    /// Returns the number of prefabs loaded. Stops at the first prefab that
+    /// fails to load or register.
+    #[cfg(feature = "ron")]
+    pub fn load_manifest(
+        &mut self,
+        names: &mut PrefabNames,
+        path: &std::path::Path,
+    ) -> Result<usize, Error> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            Error::resource_load(
+                format!("prefab manifest {}", path.display()),
+                format!("IO error: {}", e),
+            )
+        })?;
+        let manifest: PrefabManifest = ron::from_str(&content).map_err(|e| {
+            Error::resource_load(
+                format!("prefab manifest {}", path.display()),
+                format!("RON parse error: {}", e),
+            )
+        })?;
+
+        let base = path.parent().unwrap_or_else(|| std::path::Path::new("."));
+        for (name, file) in manifest.names().zip(manifest.prefabs.values()) {
+            let id = names.register(&name)?;
+            let prefab = self.load_prefab_file(&base.join(file))?;
+            self.register(id, prefab)?;
+            log::debug!("Loaded prefab '{name}' ({id:?}) from {file}");
+        }
+        Ok(manifest.prefabs.len())
+    }
+
+    /// Spawn a prefab by name
+    pub fn spawn_named(
+        &self,
+        cmd: &mut bevy_ecs::system::Commands,
+        names: &PrefabNames,
+        name: &str,
+    ) -> Result<bevy_ecs::entity::Entity, Error> {
+        self.spawn(cmd, names.resolve(name)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    prefab_names! {
+        mod test_prefabs {
+            SEDAN = "test_names/vehicles/sedan_01",
+            LAMP = "test_names/props/street_lamp",
+        }
+    }
+
+    #[test]
+    fn test_name_ids_are_stable_constants() {
+        const SEDAN: PrefabId = PrefabId::from_name("vehicles/sedan_01");
+        assert_eq!(SEDAN, PrefabId::from_name("vehicles/sedan_01"));
+        assert_ne!(SEDAN, PrefabId::from_name("vehicles/sedan_02"));
+        assert_eq!(PrefabId::from_name("").raw(), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(
+            test_prefabs::ALL,
+            &[
+                ("test_names/vehicles/sedan_01", test_prefabs::SEDAN),
+                ("test_names/props/street_lamp", test_prefabs::LAMP),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_name_validation() {
+        assert!(is_valid_prefab_name("vehicles/sedan_01"));
+        assert!(is_valid_prefab_name("props/city/street_lamp"));
+        for invalid in [
+            "sedan",
+            "vehicles/",
+            "/sedan",
+            "vehicles//sedan",
+            "Vehicles/sedan",
+            "vehicles/sedan-01",
+            "",
+        ] {
+            assert!(!is_valid_prefab_name(invalid), "{invalid}");
+        }
+
+        let mut names = PrefabNames::new();
+        assert!(names.register("Vehicles/Sedan").is_err());
+    }
+
+    #[test]
+    fn test_resolve_lists_namespace() {
+        let mut names = PrefabNames::new();
+        let sedan = names.register("vehicles/sedan_01").unwrap();
+        names.register("vehicles/coupe_01").unwrap();
+        names.register("props/street_lamp").unwrap();
+
+        assert_eq!(names.register("vehicles/sedan_01").unwrap(), sedan);
+        assert_eq!(names.resolve("vehicles/sedan_01").unwrap(), sedan);
+        assert_eq!(names.name(sedan), Some("vehicles/sedan_01"));
+
+        let err = names.resolve("vehicles/sedan_1").unwrap_err().to_string();
+        assert!(err.contains("known in 'vehicles': [vehicles/coupe_01, vehicles/sedan_01]"));
+    }
+
+    #[cfg(feature = "ron")]
+    #[test]
+    fn test_load_manifest_registers_names() {
+        use bevy_ecs::system::{CommandQueue, Commands};
+        use bevy_ecs::world::World;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(temp_dir.path().join("lamps")).unwrap();
+        std::fs::write(
+            temp_dir.path().join("lamps/street.ron"),
+            "RonPrefab(components: [])",
+        )
+        .unwrap();
+        std::fs::write(
+            temp_dir.path().join("bench.ron"),
+            "RonPrefab(components: [])",
+        )
+        .unwrap();
+        let manifest = temp_dir.path().join("props.ron");
+        std::fs::write(
+            &manifest,
+            r#"PrefabManifest(
+                namespace: "test_names/props",
+                prefabs: {"street_lamp": "lamps/street.ron", "bench": "bench.ron"},
+            )"#,
+        )
+        .unwrap();
+
+        let mut factory = Factory::new();
+        let mut names = PrefabNames::new();
+        assert_eq!(factory.load_manifest(&mut names, &manifest).unwrap(), 2);
+        assert!(factory.contains(test_prefabs::LAMP));
+
+        let err = names
+            .check_registered(test_prefabs::ALL)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Missing prefabs: test_names/vehicles/sedan_01"));
+
+        let world = World::new();
+        let mut queue = CommandQueue::default();
+        let mut cmd = Commands::new(&mut queue, &world);
+        assert!(factory
+            .spawn_named(&mut cmd, &names, "test_names/props/bench")
+            .is_ok());
+    }
+}
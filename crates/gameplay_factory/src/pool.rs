@@ -0,0 +1,294 @@
+//! Entity pooling for frequently spawned prefabs
+//!
+//! Streaming constantly spawns and despawns NPCs, vehicles and props. Instead of
+//! despawning, released entities are parked in an [`EntityPool`] and reset to
+//! their prefab state when spawned again, avoiding archetype churn and the
+//! allocations that come with fresh entities.
+
+use bevy_ecs::{component::Component, entity::Entity, system::Commands, system::Resource};
+use bevy_hierarchy::DespawnRecursiveExt;
+use bevy_render::view::Visibility;
+use std::collections::{HashMap, HashSet};
+
+use crate::PrefabId;
+
+/// Marks an entity as managed by an [`EntityPool`]
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pooled {
+    /// Prefab the entity was spawned from
+    pub prefab: PrefabId,
+    /// Whether the entity is currently in use
+    ///
+    /// Gameplay systems should skip entities that are parked in the pool.
+    pub active: bool,
+}
+
+/// Pool of released entities, keyed by prefab
+///
+/// Each prefab has its own free list capped at a per-prefab capacity. Entities
+/// released while the pool for their prefab is full are despawned.
+///
+/// Pooled entities must only be despawned through the pool; despawning them
+/// directly leaves stale handles in the free list.
+#[derive(Resource, Debug)]
+pub struct EntityPool {
+    free: HashMap<PrefabId, Vec<Entity>>,
+    parked: HashSet<Entity>,
+    capacities: HashMap<PrefabId, usize>,
+    default_capacity: usize,
+}
+
+impl EntityPool {
+    /// Default number of entities kept per prefab
+    pub const DEFAULT_CAPACITY: usize = 64;
+
+    /// Create a pool using [`EntityPool::DEFAULT_CAPACITY`] for every prefab
+    pub fn new() -> Self {
+        Self::with_default_capacity(Self::DEFAULT_CAPACITY)
+    }
+
+    /// Create a pool with the given capacity for prefabs without an explicit cap
+    pub fn with_default_capacity(default_capacity: usize) -> Self {
+        Self {
+            free: HashMap::new(),
+            parked: HashSet::new(),
+            capacities: HashMap::new(),
+            default_capacity,
+        }
+    }
+
+    /// Set the number of entities kept for a prefab
+    ///
+    /// Lowering the capacity does not despawn entities already pooled; the
+    /// free list shrinks as they are reused.
+    pub fn set_capacity(&mut self, id: PrefabId, capacity: usize) {
+        self.capacities.insert(id, capacity);
+    }
+
+    /// Get the number of entities kept for a prefab
+    pub fn capacity(&self, id: PrefabId) -> usize {
+        self.capacities
+            .get(&id)
+            .copied()
+            .unwrap_or(self.default_capacity)
+    }
+
+    /// Get the number of parked entities available for a prefab
+    pub fn available(&self, id: PrefabId) -> usize {
+        self.free.get(&id).map_or(0, Vec::len)
+    }
+
+    /// Take a parked entity for a prefab, if any
+    pub(crate) fn take(&mut self, id: PrefabId) -> Option<Entity> {
+        let entity = self.free.get_mut(&id)?.pop()?;
+        self.parked.remove(&entity);
+        Some(entity)
+    }
+
+    /// Release an entity spawned from `id` back to the pool
+    ///
+    /// The entity is hidden and marked inactive. If the pool for the prefab is
+    /// full it is despawned instead. Returns `true` if the entity was pooled.
+    ///
+    /// Releasing an entity that is already parked does nothing and returns
+    /// `true`, so it is never handed out twice.
+    pub fn release(&mut self, cmd: &mut Commands, entity: Entity, id: PrefabId) -> bool {
+        if self.parked.contains(&entity) {
+            return true;
+        }
+
+        let capacity = self.capacity(id);
+        let free = self.free.entry(id).or_default();
+
+        if free.len() >= capacity {
+            cmd.entity(entity).despawn_recursive();
+            return false;
+        }
+
+        cmd.entity(entity).insert((
+            Pooled {
+                prefab: id,
+                active: false,
+            },
+            Visibility::Hidden,
+        ));
+        free.push(entity);
+        self.parked.insert(entity);
+        true
+    }
+
+    /// Despawn every parked entity
+    pub fn clear(&mut self, cmd: &mut Commands) {
+        self.parked.clear();
+        for entity in self.free.drain().flat_map(|(_, entities)| entities) {
+            cmd.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+impl Default for EntityPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ComponentInit, Factory, Prefab};
+    use amp_core::Error;
+    use bevy_ecs::system::CommandQueue;
+    use bevy_ecs::world::World;
+    use std::any::Any;
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Health(u32);
+
+    struct HealthInit(u32);
+
+    impl ComponentInit for HealthInit {
+        fn init(&self, cmd: &mut Commands, entity: Entity) -> Result<(), Error> {
+            cmd.entity(entity).insert(Health(self.0));
+            Ok(())
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    fn factory_with(id: PrefabId) -> Factory {
+        let mut factory = Factory::new();
+        factory
+            .register(id, Prefab::new().with_component(Box::new(HealthInit(100))))
+            .unwrap();
+        factory
+    }
+
+    fn spawn(world: &mut World, factory: &Factory, pool: &mut EntityPool, id: PrefabId) -> Entity {
+        let mut queue = CommandQueue::default();
+        let mut cmd = Commands::new(&mut queue, world);
+        let entity = factory.spawn_pooled(&mut cmd, pool, id).unwrap();
+        queue.apply(world);
+        entity
+    }
+
+    fn release(world: &mut World, pool: &mut EntityPool, entity: Entity, id: PrefabId) -> bool {
+        let mut queue = CommandQueue::default();
+        let mut cmd = Commands::new(&mut queue, world);
+        let pooled = pool.release(&mut cmd, entity, id);
+        queue.apply(world);
+        pooled
+    }
+
+    #[test]
+    fn test_released_entity_is_reused_and_reset() {
+        let id = PrefabId::new(0x9007_0001);
+        let factory = factory_with(id);
+        let mut pool = EntityPool::new();
+        let mut world = World::new();
+
+        let entity = spawn(&mut world, &factory, &mut pool, id);
+        world.get_mut::<Health>(entity).unwrap().0 = 5;
+
+        assert!(release(&mut world, &mut pool, entity, id));
+        assert_eq!(pool.available(id), 1);
+        assert_eq!(
+            world.get::<Pooled>(entity),
+            Some(&Pooled {
+                prefab: id,
+                active: false
+            })
+        );
+        assert_eq!(world.get::<Visibility>(entity), Some(&Visibility::Hidden));
+
+        let reused = spawn(&mut world, &factory, &mut pool, id);
+        assert_eq!(reused, entity);
+        assert_eq!(pool.available(id), 0);
+        assert_eq!(world.get::<Health>(reused), Some(&Health(100)));
+        assert!(world.get::<Pooled>(reused).unwrap().active);
+        assert_eq!(
+            world.get::<Visibility>(reused),
+            Some(&Visibility::Inherited)
+        );
+    }
+
+    #[test]
+    fn test_release_beyond_capacity_despawns() {
+        let id = PrefabId::new(0x9007_0002);
+        let factory = factory_with(id);
+        let mut pool = EntityPool::with_default_capacity(8);
+        pool.set_capacity(id, 1);
+        let mut world = World::new();
+
+        let first = spawn(&mut world, &factory, &mut pool, id);
+        let second = spawn(&mut world, &factory, &mut pool, id);
+
+        assert!(release(&mut world, &mut pool, first, id));
+        assert!(!release(&mut world, &mut pool, second, id));
+        assert_eq!(pool.available(id), 1);
+        assert!(world.get_entity(first).is_some());
+        assert!(world.get_entity(second).is_none());
+    }
+
+    #[test]
+    fn test_double_release_is_ignored() {
+        let id = PrefabId::new(0x9007_0006);
+        let factory = factory_with(id);
+        let mut pool = EntityPool::with_default_capacity(1);
+        let mut world = World::new();
+
+        let entity = spawn(&mut world, &factory, &mut pool, id);
+        assert!(release(&mut world, &mut pool, entity, id));
+        assert!(release(&mut world, &mut pool, entity, id));
+        assert_eq!(pool.available(id), 1);
+        assert!(world.get_entity(entity).is_some());
+
+        let first = spawn(&mut world, &factory, &mut pool, id);
+        let second = spawn(&mut world, &factory, &mut pool, id);
+        assert_eq!(first, entity);
+        assert_ne!(second, entity);
+    }
+
+    #[test]
+    fn test_pools_are_per_prefab() {
+        let id = PrefabId::new(0x9007_0003);
+        let other = PrefabId::new(0x9007_0004);
+        let mut factory = factory_with(id);
+        factory
+            .register(
+                other,
+                Prefab::new().with_component(Box::new(HealthInit(50))),
+            )
+            .unwrap();
+        let mut pool = EntityPool::new();
+        let mut world = World::new();
+
+        let entity = spawn(&mut world, &factory, &mut pool, id);
+        release(&mut world, &mut pool, entity, id);
+
+        let spawned = spawn(&mut world, &factory, &mut pool, other);
+        assert_ne!(spawned, entity);
+        assert_eq!(world.get::<Health>(spawned), Some(&Health(50)));
+        assert_eq!(pool.available(id), 1);
+    }
+
+    #[test]
+    fn test_clear_despawns_parked_entities() {
+        let id = PrefabId::new(0x9007_0005);
+        let factory = factory_with(id);
+        let mut pool = EntityPool::new();
+        let mut world = World::new();
+
+        let entity = spawn(&mut world, &factory, &mut pool, id);
+        release(&mut world, &mut pool, entity, id);
+
+        let mut queue = CommandQueue::default();
+        let mut cmd = Commands::new(&mut queue, &world);
+        pool.clear(&mut cmd);
+        queue.apply(&mut world);
+
+        assert_eq!(pool.available(id), 0);
+        assert!(world.get_entity(entity).is_none());
+    }
+}
@@ -1,9 +1,17 @@
 //! Prefab definitions and component initialization
 
+use crate::PrefabId;
 use amp_core::Error;
 use bevy_ecs::{entity::Entity, system::Commands};
+use bevy_transform::components::Transform;
 use std::any::Any;
 
+/// Maximum nesting depth of child prefabs
+///
+/// Guards against runaway hierarchies; reference cycles are rejected
+/// separately when spawning.
+pub const MAX_PREFAB_DEPTH: usize = 16;
+
 /// Trait for initializing components on spawned entities
 pub trait ComponentInit: Send + Sync {
     /// Initialize the component on the given entity
@@ -13,10 +21,27 @@ pub trait ComponentInit: Send + Sync {
     fn as_any(&self) -> &dyn Any;
 }
 
+/// Reference to another prefab spawned as a child entity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrefabChild {
+    /// Prefab spawned for the child
+    pub prefab: PrefabId,
+    /// Transform of the child relative to its parent
+    ///
+    /// Applied on top of any `Transform` the child prefab sets itself.
+    pub transform: Transform,
+}
+
 /// A prefab definition containing component initializers
 pub struct Prefab {
     /// Component initializers for this prefab
     components: Vec<Box<dyn ComponentInit>>,
+    /// Child prefabs spawned under this prefab's entity
+    children: Vec<PrefabChild>,
+    /// Asset paths that must be resident before the prefab is spawned
+    dependencies: Vec<String>,
+    /// Free-form labels used to select post-spawn hooks
+    tags: Vec<String>,
 }
 
 impl Prefab {
@@ -24,6 +49,9 @@ impl Prefab {
     pub fn new() -> Self {
         Self {
             components: Vec::new(),
+            children: Vec::new(),
+            dependencies: Vec::new(),
+            tags: Vec::new(),
         }
     }
 
@@ -38,8 +66,67 @@ impl Prefab {
         self.components.push(component);
     }
 
+    /// Add a child prefab reference to this prefab
+    pub fn with_child(mut self, prefab: PrefabId, transform: Transform) -> Self {
+        self.add_child(prefab, transform);
+        self
+    }
+
+    /// Add a child prefab reference to this prefab (mutable)
+    pub fn add_child(&mut self, prefab: PrefabId, transform: Transform) {
+        self.children.push(PrefabChild { prefab, transform });
+    }
+
+    /// Get the child prefab references of this prefab
+    pub fn children(&self) -> &[PrefabChild] {
+        &self.children
+    }
+
+    /// Add an asset dependency, such as a mesh, material or sound path
+    pub fn with_dependency(mut self, path: impl Into<String>) -> Self {
+        self.add_dependency(path);
+        self
+    }
+
+    /// Add an asset dependency (mutable)
+    pub fn add_dependency(&mut self, path: impl Into<String>) {
+        self.dependencies.push(path.into());
+    }
+
+    /// Get the asset paths this prefab depends on, excluding its children's
+    pub fn dependencies(&self) -> &[String] {
+        &self.dependencies
+    }
+
+    /// Add a tag, such as `traffic` or `mission_pickup`
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.add_tag(tag);
+        self
+    }
+
+    /// Add a tag (mutable)
+    pub fn add_tag(&mut self, tag: impl Into<String>) {
+        let tag = tag.into();
+        if !self.has_tag(&tag) {
+            self.tags.push(tag);
+        }
+    }
+
+    /// Get the tags of this prefab
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Check if this prefab has a tag
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
     /// Spawn an entity from this prefab
     ///
+    /// Only this prefab's own components are initialized; child prefabs are
+    /// resolved and spawned by [`Factory::spawn`](crate::Factory::spawn).
+    ///
     /// Returns the spawned entity ID on success. If any component initialization
     /// fails, the entity is despawned to maintain transaction safety.
     pub fn spawn(&self, cmd: &mut Commands) -> Result<Entity, Error> {
@@ -48,16 +135,26 @@ impl Prefab {
 
         // Initialize all components for this entity
         // If any fail, despawn the entity to maintain transaction safety
-        for component in &self.components {
-            if let Err(e) = component.init(cmd, entity) {
-                cmd.entity(entity).despawn();
-                return Err(e);
-            }
+        if let Err(e) = self.apply(cmd, entity) {
+            cmd.entity(entity).despawn();
+            return Err(e);
         }
 
         Ok(entity)
     }
 
+    /// Initialize this prefab's components on an existing entity
+    ///
+    /// Components already present on the entity are overwritten with the
+    /// prefab's values, which resets recycled entities to their initial state.
+    /// Stops at the first component that fails to initialize.
+    pub fn apply(&self, cmd: &mut Commands, entity: Entity) -> Result<(), Error> {
+        for component in &self.components {
+            component.init(cmd, entity)?;
+        }
+        Ok(())
+    }
+
     /// Get the number of components in this prefab
     pub fn len(&self) -> usize {
         self.components.len()
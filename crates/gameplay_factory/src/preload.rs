@@ -0,0 +1,302 @@
+//! Asset preloading per game state
+//!
+//! Loading assets the first time a prefab is spawned causes hitches in the
+//! first seconds of play. A [`PreloadManifest`] lists, per game state, the
+//! assets that should be resident before that state is entered. While the
+//! game shows its loading screen, a [`Preloader`] requests them through a
+//! [`DependencyResolver`] and reports [`PreloadProgress`] for the loading bar;
+//! [`Preloader::ensure_critical_resident`] then confirms every critical asset
+//! loaded before gameplay starts.
+
+use crate::{DependencyResolver, DependencyState, Factory, PrefabId};
+use amp_core::Error;
+use bevy_ecs::{event::Event, system::Resource};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// Asset listed in a [`PreloadManifest`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreloadAsset {
+    /// Asset path, as passed to [`DependencyResolver::request`]
+    pub path: String,
+    /// Whether the state cannot be entered without this asset
+    #[serde(default)]
+    pub critical: bool,
+}
+
+/// Assets to preload before entering each game state
+///
+/// ```ron
+/// PreloadManifest(
+///     states: {
+///         "playing": [
+///             (path: "models/player.glb", critical: true),
+///             (path: "audio/city_ambience.ogg"),
+///         ],
+///     },
+/// )
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreloadManifest {
+    /// Assets per game state name
+    pub states: BTreeMap<String, Vec<PreloadAsset>>,
+}
+
+impl PreloadManifest {
+    /// Create an empty manifest
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an asset to a state, upgrading it to critical if already listed
+    pub fn add(&mut self, state: &str, path: impl Into<String>, critical: bool) {
+        let path = path.into();
+        let assets = self.states.entry(state.to_string()).or_default();
+        match assets.iter_mut().find(|asset| asset.path == path) {
+            Some(asset) => asset.critical |= critical,
+            None => assets.push(PreloadAsset { path, critical }),
+        }
+    }
+
+    /// Add every dependency of a prefab and its children to a state
+    pub fn add_prefab(
+        &mut self,
+        state: &str,
+        factory: &Factory,
+        id: PrefabId,
+        critical: bool,
+    ) -> Result<(), Error> {
+        for dependency in factory.dependencies(id)? {
+            self.add(state, dependency, critical);
+        }
+        Ok(())
+    }
+
+    /// Get the assets listed for a state
+    pub fn assets(&self, state: &str) -> &[PreloadAsset] {
+        self.states
+            .get(state)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+/// Sent while preloading so a loading screen can show progress
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct PreloadProgress {
+    /// Game state being preloaded
+    pub state: String,
+    /// Assets that finished loading
+    pub loaded: usize,
+    /// Assets that failed to load
+    pub failed: usize,
+    /// Assets listed for the state
+    pub total: usize,
+}
+
+impl PreloadProgress {
+    /// Fraction of assets that finished, successfully or not, from 0 to 1
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            (self.loaded + self.failed) as f32 / self.total as f32
+        }
+    }
+
+    /// Check if every asset finished loading or failed
+    pub fn is_finished(&self) -> bool {
+        self.loaded + self.failed == self.total
+    }
+}
+
+/// Tracks the assets being preloaded for one game state
+#[derive(Resource, Debug, Default)]
+pub struct Preloader {
+    state: String,
+    assets: Vec<PreloadAsset>,
+    last_progress: Option<PreloadProgress>,
+}
+
+impl Preloader {
+    /// Create an idle preloader
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start loading the assets listed for `state`
+    ///
+    /// Replaces any preload in progress.
+    pub fn start(
+        &mut self,
+        manifest: &PreloadManifest,
+        state: &str,
+        resolver: &dyn DependencyResolver,
+    ) {
+        self.state = state.to_string();
+        self.assets = manifest.assets(state).to_vec();
+        self.last_progress = None;
+        for asset in &self.assets {
+            resolver.request(&asset.path);
+        }
+    }
+
+    /// Get the state being preloaded
+    pub fn state(&self) -> &str {
+        &self.state
+    }
+
+    /// Compute the current progress
+    pub fn progress(&self, resolver: &dyn DependencyResolver) -> PreloadProgress {
+        let mut progress = PreloadProgress {
+            state: self.state.clone(),
+            loaded: 0,
+            failed: 0,
+            total: self.assets.len(),
+        };
+        for asset in &self.assets {
+            match resolver.state(&asset.path) {
+                DependencyState::Ready => progress.loaded += 1,
+                DependencyState::Failed(_) => progress.failed += 1,
+                DependencyState::Pending => {}
+            }
+        }
+        progress
+    }
+
+    /// Get the progress if it changed since the last poll
+    ///
+    /// The first poll after [`start`](Preloader::start) always reports, so a
+    /// loading screen receives at least one event per state.
+    pub fn poll(&mut self, resolver: &dyn DependencyResolver) -> Option<PreloadProgress> {
+        let progress = self.progress(resolver);
+        if self.last_progress.as_ref() == Some(&progress) {
+            return None;
+        }
+        self.last_progress = Some(progress.clone());
+        Some(progress)
+    }
+
+    /// Check that every critical asset is resident
+    ///
+    /// Call once [`PreloadProgress::is_finished`] before entering the state.
+    /// The error lists every critical asset that failed or is still pending.
+    pub fn ensure_critical_resident(&self, resolver: &dyn DependencyResolver) -> Result<(), Error> {
+        let mut seen = HashSet::new();
+        let missing: Vec<String> = self
+            .assets
+            .iter()
+            .filter(|asset| asset.critical && seen.insert(&asset.path))
+            .filter_map(|asset| match resolver.state(&asset.path) {
+                DependencyState::Ready => None,
+                DependencyState::Pending => Some(format!("{} (still loading)", asset.path)),
+                DependencyState::Failed(reason) => Some(format!("{} ({reason})", asset.path)),
+            })
+            .collect();
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::resource_load(
+                format!("preload for state '{}'", self.state),
+                format!("critical assets not resident: {}", missing.join(", ")),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct ManualResolver {
+        states: Mutex<HashMap<String, DependencyState>>,
+        requested: Mutex<Vec<String>>,
+    }
+
+    impl ManualResolver {
+        fn set(&self, path: &str, state: DependencyState) {
+            self.states.lock().unwrap().insert(path.to_string(), state);
+        }
+    }
+
+    impl DependencyResolver for ManualResolver {
+        fn request(&self, path: &str) {
+            self.requested.lock().unwrap().push(path.to_string());
+        }
+
+        fn state(&self, path: &str) -> DependencyState {
+            self.states
+                .lock()
+                .unwrap()
+                .get(path)
+                .cloned()
+                .unwrap_or(DependencyState::Pending)
+        }
+    }
+
+    fn manifest() -> PreloadManifest {
+        let mut manifest = PreloadManifest::new();
+        manifest.add("playing", "models/player.glb", true);
+        manifest.add("playing", "audio/ambience.ogg", false);
+        manifest.add("menu", "ui/title.png", true);
+        manifest
+    }
+
+    #[test]
+    fn test_progress_events_only_on_change() {
+        let resolver = ManualResolver::default();
+        let mut preloader = Preloader::new();
+        preloader.start(&manifest(), "playing", &resolver);
+        assert_eq!(
+            *resolver.requested.lock().unwrap(),
+            vec!["models/player.glb", "audio/ambience.ogg"]
+        );
+
+        let first = preloader.poll(&resolver).unwrap();
+        assert_eq!((first.loaded, first.total), (0, 2));
+        assert_eq!(preloader.poll(&resolver), None);
+
+        resolver.set("models/player.glb", DependencyState::Ready);
+        resolver.set("audio/ambience.ogg", DependencyState::Failed("gone".into()));
+        let done = preloader.poll(&resolver).unwrap();
+        assert!(done.is_finished());
+        assert_eq!((done.loaded, done.failed), (1, 1));
+        assert_eq!(done.fraction(), 1.0);
+
+        // Only the non-critical asset failed
+        assert!(preloader.ensure_critical_resident(&resolver).is_ok());
+    }
+
+    #[test]
+    fn test_missing_critical_asset_is_reported() {
+        let resolver = ManualResolver::default();
+        let mut preloader = Preloader::new();
+        preloader.start(&manifest(), "playing", &resolver);
+        resolver.set("audio/ambience.ogg", DependencyState::Ready);
+
+        let error = preloader
+            .ensure_critical_resident(&resolver)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("models/player.glb (still loading)"));
+        assert!(!error.contains("ambience"));
+    }
+
+    #[cfg(feature = "ron")]
+    #[test]
+    fn test_manifest_from_ron() {
+        let manifest: PreloadManifest = ron::from_str(
+            r#"PreloadManifest(states: {
+                "playing": [(path: "models/player.glb", critical: true), (path: "a.ogg")],
+            })"#,
+        )
+        .unwrap();
+        assert_eq!(manifest.assets("playing").len(), 2);
+        assert!(!manifest.assets("playing")[1].critical);
+        assert!(manifest.assets("menu").is_empty());
+    }
+}
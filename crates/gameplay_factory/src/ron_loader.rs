@@ -1,7 +1,8 @@
 //! RON (Rusty Object Notation) loader for prefab definitions
 
-use crate::{ComponentInit, Error, Prefab, PrefabSource};
+use crate::{ComponentInit, Error, Prefab, PrefabId, PrefabSource};
 use bevy_ecs::{entity::Entity, system::Commands};
+use bevy_transform::components::Transform;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
 
@@ -35,10 +36,7 @@ impl RonLoader {
 
 impl PrefabSource for RonLoader {
     fn load(&self) -> Result<Prefab, Error> {
-        let ron_prefab: RonPrefab = ron::from_str(&self.content)
-            .map_err(|e| Error::serialization(format!("Failed to parse RON: {e}")))?;
-
-        Ok(ron_prefab.into())
+        RonPrefab::parse(&self.content)?.try_into()
     }
 }
 
@@ -47,18 +45,63 @@ impl PrefabSource for RonLoader {
 pub struct RonPrefab {
     /// Component definitions
     pub components: Vec<RonComponent>,
+    /// Child prefabs spawned under this prefab's entity
+    #[serde(default)]
+    pub children: Vec<RonPrefabChild>,
+    /// Asset paths resolved before the prefab is spawned asynchronously
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    /// Tags used to select post-spawn hooks
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl RonPrefab {
+    /// Parse a prefab definition, accepting `ron::Value`-style data tags
+    ///
+    /// Component data may be written plainly, as in `data: (hp: 100)`, or
+    /// tagged with the `ron::Value` variant, as in `data: Number(100.0)`.
+    pub fn parse(content: &str) -> Result<Self, Error> {
+        ron::from_str(&strip_value_tags(content))
+            .map_err(|e| Error::serialization(format!("Failed to parse RON: {e}")))
+    }
 }
 
-impl From<RonPrefab> for Prefab {
-    fn from(ron_prefab: RonPrefab) -> Self {
+impl TryFrom<RonPrefab> for Prefab {
+    type Error = Error;
+
+    fn try_from(ron_prefab: RonPrefab) -> Result<Self, Error> {
         let mut prefab = Prefab::new();
         for component in ron_prefab.components {
             prefab.add_component(Box::new(component));
         }
-        prefab
+        for child in ron_prefab.children {
+            let transform = match &child.transform {
+                Some(value) => crate::component_registry::deserialize_transform(value)?,
+                None => Transform::IDENTITY,
+            };
+            prefab.add_child(PrefabId::new(child.prefab), transform);
+        }
+        for dependency in ron_prefab.dependencies {
+            prefab.add_dependency(dependency);
+        }
+        for tag in ron_prefab.tags {
+            prefab.add_tag(tag);
+        }
+        Ok(prefab)
     }
 }
 
+/// RON-serializable child prefab reference
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RonPrefabChild {
+    /// Raw id of the child prefab
+    pub prefab: u64,
+    /// Transform relative to the parent, in the `Transform` component format
+    #[serde(default, deserialize_with = "deserialize_optional_data")]
+    pub transform: Option<ron::Value>,
+}
+
 /// RON-serializable component definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RonComponent {
@@ -68,22 +111,126 @@ pub struct RonComponent {
     pub data: ron::Value,
 }
 
+fn deserialize_optional_data<'de, D>(deserializer: D) -> Result<Option<ron::Value>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    ron::Value::deserialize(deserializer).map(|value| match value {
+        ron::Value::Option(inner) => inner.map(|inner| *inner),
+        value => Some(value),
+    })
+}
+
+/// `ron::Value` variant names that prefab files use to tag data
+const VALUE_TAGS: &[&str] = &["Bool", "Char", "Map", "Number", "Seq", "String"];
+
+/// Remove `ron::Value`-style tags such as `Number(100.0)` or `Map({...})`
+///
+/// RON parses a tag as a one-element tuple when targeting `ron::Value`,
+/// which can't be told apart from a real one-element list afterwards, so
+/// tags are removed from the source text instead. Strings and comments are
+/// copied unchanged.
+fn strip_value_tags(source: &str) -> String {
+    let bytes = source.as_bytes();
+    let mut output = String::with_capacity(source.len());
+    // For every open parenthesis, whether its closing one is dropped
+    let mut parens: Vec<bool> = Vec::new();
+    let mut copied = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let start = i;
+        match bytes[i] {
+            b'"' => i = skip_string(bytes, i + 1, 0),
+            b'\'' => i = skip_char(bytes, i + 1),
+            b'r' if matches!(bytes.get(i + 1), Some(b'"' | b'#')) => {
+                let hashes = bytes[i + 1..].iter().take_while(|&&b| b == b'#').count();
+                i = skip_string(bytes, i + 1 + hashes + 1, hashes);
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i = source[i..].find('\n').map_or(bytes.len(), |end| i + end);
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = source[i + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |end| i + 2 + end + 2);
+            }
+            b'(' => {
+                parens.push(false);
+                i += 1;
+            }
+            b')' => {
+                i += 1;
+                if parens.pop() == Some(true) {
+                    output.push_str(&source[copied..start]);
+                    copied = i;
+                }
+            }
+            b if b.is_ascii_alphabetic() || b == b'_' => {
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+                let open = i + source[i..].len() - source[i..].trim_start().len();
+                if VALUE_TAGS.contains(&&source[start..i]) && bytes.get(open) == Some(&b'(') {
+                    output.push_str(&source[copied..start]);
+                    parens.push(true);
+                    i = open + 1;
+                    copied = i;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    output.push_str(&source[copied..]);
+    output
+}
+
+/// Get the index after a string literal whose opening quote ends at `i`.
+fn skip_string(bytes: &[u8], mut i: usize, hashes: usize) -> usize {
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if hashes == 0 => i += 2,
+            b'"' if bytes[i + 1..]
+                .iter()
+                .take(hashes)
+                .filter(|&&b| b == b'#')
+                .count()
+                == hashes =>
+            {
+                return i + 1 + hashes;
+            }
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Get the index after a character literal whose opening quote ends at `i`.
+fn skip_char(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'\'' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
 impl ComponentInit for RonComponent {
     fn init(&self, cmd: &mut Commands, entity: Entity) -> Result<(), Error> {
-        // Use the component registry to deserialize and insert the component
-        match crate::call_component_deserializer(&self.component_type, &self.data, cmd, entity) {
-            Ok(()) => Ok(()),
-            Err(e) => {
-                // If the component is not found, provide a more helpful error message
-                let available_types = crate::registered_components();
-                Err(Error::validation(format!(
-                    "Component type '{}' not found in registry. Available types: {}. Original error: {}",
-                    self.component_type,
-                    available_types.join(", "),
-                    e
-                )))
-            }
+        let mut available_types = crate::registered_components();
+        if !available_types.contains(&self.component_type.as_str()) {
+            available_types.sort_unstable();
+            return Err(Error::validation(format!(
+                "Component type '{}' not found in registry. Available types: {}",
+                self.component_type,
+                available_types.join(", ")
+            )));
         }
+
+        // Use the component registry to deserialize and insert the component
+        crate::call_component_deserializer(&self.component_type, &self.data, cmd, entity)
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -120,6 +267,35 @@ mod tests {
         assert!(!prefab.is_empty());
     }
 
+    #[rstest]
+    fn test_ron_loader_children() {
+        let ron_content = r#"
+        RonPrefab(
+            components: [],
+            children: [
+                RonPrefabChild(
+                    prefab: 7001,
+                    transform: Map({
+                        "translation": Map({"x": Number(0.4), "y": Number(0.5), "z": Number(0.2)})
+                    })
+                ),
+                RonPrefabChild(prefab: 7002)
+            ]
+        )
+        "#;
+
+        let prefab = RonLoader::new(ron_content.to_string()).load().unwrap();
+        let children = prefab.children();
+
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0].prefab, PrefabId::new(7001));
+        assert_eq!(
+            children[0].transform.translation,
+            bevy_math::Vec3::new(0.4, 0.5, 0.2)
+        );
+        assert_eq!(children[1].transform, Transform::IDENTITY);
+    }
+
     #[rstest]
     fn test_ron_loader_empty() {
         let ron_content = r#"
@@ -209,7 +385,9 @@ mod tests {
         // Make file unreadable (Unix-specific test)
         if let Ok(mut perms) = fs::metadata(temp_path).map(|m| m.permissions()) {
             perms.set_mode(0o000);
-            if fs::set_permissions(temp_path, perms).is_ok() {
+            // Privileged users can still read the file, so only assert when
+            // the permission change actually took effect
+            if fs::set_permissions(temp_path, perms).is_ok() && fs::File::open(temp_path).is_err() {
                 let result = RonLoader::from_file(temp_path);
 
                 assert!(result.is_err());
@@ -232,6 +410,7 @@ mod tests {
 
     #[rstest]
     fn test_ron_component_init() {
+        let _guard = crate::component_registry::registry_test_guard();
         // Register a test component
         let _ = crate::register_component(
             "TestComponent",
@@ -267,4 +446,45 @@ mod tests {
         assert!(downcasted.is_some());
         assert_eq!(downcasted.unwrap().component_type, "TestComponent");
     }
+
+    #[rstest]
+    fn test_one_element_lists_stay_lists() {
+        let prefab = RonPrefab {
+            components: vec![RonComponent {
+                component_type: "Tags".to_string(),
+                data: ron::Value::Seq(vec![ron::Value::String("car".to_string())]),
+            }],
+            children: Vec::new(),
+            dependencies: Vec::new(),
+            tags: vec!["vehicle".to_string()],
+        };
+        let text = ron::to_string(&prefab).unwrap();
+        let parsed = RonPrefab::parse(&text).unwrap();
+        assert_eq!(parsed.components[0].data, prefab.components[0].data);
+
+        let parsed = RonPrefab::parse(
+            r#"RonPrefab(components: [RonComponent(
+                component_type: "Vehicle",
+                data: (wheels: [(radius: 0.4)], tags: ["car"], name: String("Map(1)"))
+            )])"#,
+        )
+        .unwrap();
+        let expected: ron::Value =
+            ron::from_str(r#"{"wheels": [{"radius": 0.4}], "tags": ["car"], "name": "Map(1)"}"#)
+                .unwrap();
+        assert_eq!(parsed.components[0].data, expected);
+    }
+
+    #[rstest]
+    fn test_value_tags_are_stripped() {
+        assert_eq!(
+            strip_value_tags(r#"Map({"hp": Number(100.0), "ids": Seq([1])}) // Number(1)"#),
+            r#"{"hp": 100.0, "ids": [1]} // Number(1)"#
+        );
+        assert_eq!(
+            strip_value_tags(r##"(a: r#"String("x")"#, b: 'c')"##),
+            r##"(a: r#"String("x")"#, b: 'c')"##
+        );
+        assert_eq!(strip_value_tags("Mapping(1)"), "Mapping(1)");
+    }
 }
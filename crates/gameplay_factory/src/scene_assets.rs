@@ -0,0 +1,61 @@
+//! Components referencing scene assets
+//!
+//! Prefabs converted from glTF scenes refer to their meshes and materials by
+//! asset label, such as `models/sedan.glb#Mesh0/Primitive0`, and describe
+//! collision shapes with plain collider components. Rendering and physics
+//! resolve these into their own resources; the factory only carries them.
+
+use bevy_ecs::component::Component;
+use bevy_reflect::Reflect;
+use serde::Serialize;
+
+/// Mesh drawn for an entity
+#[derive(Component, Reflect, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct MeshAsset {
+    /// Asset label of the mesh primitive
+    pub path: String,
+}
+
+/// Material used to draw an entity's [`MeshAsset`]
+#[derive(Component, Reflect, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct MaterialAsset {
+    /// Asset label of the material
+    pub path: String,
+}
+
+/// Box collider aligned with the entity's local axes
+#[derive(Component, Reflect, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct BoxCollider {
+    /// Box center in local space
+    pub center: [f32; 3],
+    /// Half the box size along each local axis
+    pub half_extents: [f32; 3],
+}
+
+/// Sphere collider
+#[derive(Component, Reflect, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct SphereCollider {
+    /// Sphere center in local space
+    pub center: [f32; 3],
+    /// Sphere radius
+    pub radius: f32,
+}
+
+/// Convex hull collider built from a mesh's vertices
+#[derive(Component, Reflect, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ConvexCollider {
+    /// Asset label of the hull mesh primitive
+    pub mesh: String,
+}
+
+/// Register the scene asset components with the component registry
+pub(crate) fn register_scene_asset_components() {
+    crate::register_reflect_type::<f32>();
+    crate::register_reflect_type::<[f32; 3]>();
+    crate::register_reflect_type::<String>();
+    let _ = crate::register_reflect_component::<MeshAsset>();
+    let _ = crate::register_reflect_component::<MaterialAsset>();
+    let _ = crate::register_reflect_component::<BoxCollider>();
+    let _ = crate::register_reflect_component::<SphereCollider>();
+    let _ = crate::register_reflect_component::<ConvexCollider>();
+}
@@ -4,7 +4,11 @@
 //! that the hot-reload system works correctly.
 
 use std::fs;
+#[cfg(feature = "hot-reload")]
+use std::time::Duration;
 use tempfile::TempDir;
+#[cfg(feature = "hot-reload")]
+use tokio::time::sleep;
 
 use gameplay_factory::*;
 
@@ -23,9 +27,7 @@ async fn test_file_creation_triggers_reload() {
     let (tx, mut rx) = create_reload_channel();
 
     // Start the watcher
-    let _handle = watcher::run_watcher(&pattern, tx)
-        .await
-        .expect("Failed to start watcher");
+    let _handle = tokio::spawn(async move { watcher::run_watcher(&pattern, tx).await });
 
     // Wait a bit for the watcher to initialize
     sleep(Duration::from_millis(100)).await;
@@ -84,9 +86,7 @@ async fn test_file_modification_triggers_reload() {
     let (tx, mut rx) = create_reload_channel();
 
     // Start the watcher
-    let _handle = watcher::run_watcher(&pattern, tx)
-        .await
-        .expect("Failed to start watcher");
+    let _handle = tokio::spawn(async move { watcher::run_watcher(&pattern, tx).await });
 
     // Wait a bit for the watcher to initialize
     sleep(Duration::from_millis(100)).await;
@@ -144,9 +144,7 @@ async fn test_file_deletion_triggers_reload() {
     let (tx, mut rx) = create_reload_channel();
 
     // Start the watcher
-    let _handle = watcher::run_watcher(&pattern, tx)
-        .await
-        .expect("Failed to start watcher");
+    let _handle = tokio::spawn(async move { watcher::run_watcher(&pattern, tx).await });
 
     // Wait a bit for the watcher to initialize
     sleep(Duration::from_millis(100)).await;
@@ -180,9 +178,7 @@ async fn test_non_matching_files_ignored() {
     let (tx, mut rx) = create_reload_channel();
 
     // Start the watcher
-    let _handle = watcher::run_watcher(&pattern, tx)
-        .await
-        .expect("Failed to start watcher");
+    let _handle = tokio::spawn(async move { watcher::run_watcher(&pattern, tx).await });
 
     // Wait a bit for the watcher to initialize
     sleep(Duration::from_millis(100)).await;
@@ -212,9 +208,12 @@ async fn test_factory_hot_reload_integration() {
     fs::write(
         &test_file,
         r#"
-        (
+        RonPrefab(
             components: [
-                ("TransformComponent", "{ translation: [0.0, 0.0, 0.0] }"),
+                RonComponent(
+                    component_type: "Transform",
+                    data: Map({"translation": Map({"x": 0.0, "y": 0.0, "z": 0.0})})
+                ),
             ]
         )
     "#,
@@ -226,7 +225,6 @@ async fn test_factory_hot_reload_integration() {
     let settings = config_core::FactorySettings {
         prefab_path: pattern,
         hot_reload: true,
-        ..Default::default()
     };
 
     // Create factory and load directory
@@ -263,9 +261,12 @@ async fn test_hot_reload_disabled_gracefully() {
     fs::write(
         &test_file,
         r#"
-        (
+        RonPrefab(
             components: [
-                ("TransformComponent", "{ translation: [0.0, 0.0, 0.0] }"),
+                RonComponent(
+                    component_type: "Transform",
+                    data: Map({"translation": Map({"x": 0.0, "y": 0.0, "z": 0.0})})
+                ),
             ]
         )
     "#,
@@ -277,7 +278,6 @@ async fn test_hot_reload_disabled_gracefully() {
     let settings = config_core::FactorySettings {
         prefab_path: pattern,
         hot_reload: true,
-        ..Default::default()
     };
 
     // Create factory and load directory
@@ -313,9 +313,7 @@ async fn test_debouncing_rapid_changes() {
     let (tx, mut rx) = create_reload_channel();
 
     // Start the watcher
-    let _handle = watcher::run_watcher(&pattern, tx)
-        .await
-        .expect("Failed to start watcher");
+    let _handle = tokio::spawn(async move { watcher::run_watcher(&pattern, tx).await });
 
     // Wait a bit for the watcher to initialize
     sleep(Duration::from_millis(100)).await;
@@ -87,7 +87,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut hash_map: HashMap<u64, Vec<PrefabInfo>> = HashMap::new();
     for info in prefab_infos {
         let hash = info.id.raw();
-        hash_map.entry(hash).or_insert_with(Vec::new).push(info);
+        hash_map.entry(hash).or_default().push(info);
     }
 
     // Report results
@@ -0,0 +1,74 @@
+//! Fails when systems gain conflicting access without an explicit order
+//!
+//! Builds the frame schedule the way the game does, from each crate's own
+//! `systems()` registration with its system sets chained in frame order
+//! (input, settings menu, camera, HUD, saving, then world upkeep), and
+//! compares Bevy's ambiguity report against the known pairs below. When this
+//! test fails, order the new pair with `.before`/`.after` or, if the order
+//! genuinely does not matter, add it here with a reason.
+
+use amp_camera::CameraSystems;
+use amp_hud::HudSystems;
+use amp_input::InputSystems;
+use amp_save::{CaptureSave, SaveGameState, SaveMetadata, SaveSystems};
+use amp_settings::SettingsSystems;
+use amp_world::{AmbiguityReport, IntoSystemSetConfigs, Schedule, World, WorldSystems};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// System pairs whose relative order is known not to matter
+const KNOWN_AMBIGUITIES: &[(&str, &str)] = &[];
+
+/// Stand-in for the game state captured by autosaves
+#[derive(Serialize, Deserialize)]
+struct GameState;
+
+impl SaveGameState for GameState {}
+
+impl CaptureSave for GameState {
+    fn capture(_world: &mut World) -> (SaveMetadata, Self) {
+        (SaveMetadata::new("", 0, Duration::ZERO), GameState)
+    }
+}
+
+fn frame_schedule() -> Schedule {
+    let mut schedule = Schedule::default();
+    schedule.configure_sets(
+        (
+            InputSystems,
+            SettingsSystems,
+            CameraSystems,
+            HudSystems,
+            SaveSystems,
+            WorldSystems,
+        )
+            .chain(),
+    );
+    schedule.add_systems((
+        amp_input::systems(),
+        amp_settings::systems(),
+        amp_camera::systems(),
+        amp_hud::systems(),
+        amp_save::systems::<GameState>(),
+        amp_world::systems(),
+    ));
+    schedule
+}
+
+#[test]
+fn test_no_new_system_ambiguities() {
+    let mut schedule = frame_schedule();
+    let report = AmbiguityReport::from_schedule(&mut schedule, &mut World::new())
+        .expect("frame schedule should build");
+
+    let unexpected: Vec<_> = report.unexpected(KNOWN_AMBIGUITIES).collect();
+    assert!(
+        unexpected.is_empty(),
+        "new system ordering ambiguities:\n{}\nfull report:\n{report}",
+        unexpected
+            .iter()
+            .map(|ambiguity| format!("  {ambiguity}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+}
@@ -2,8 +2,12 @@
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use config_core::{Config, ConfigFormat, GameConfig};
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+mod validate_assets;
+
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
@@ -29,6 +33,49 @@ enum Commands {
     Check,
     /// Run coverage analysis
     Coverage,
+    /// Run performance benchmarks with machine-readable output
+    Perf,
+    /// Upgrade config files to the current schema version in place
+    MigrateConfig {
+        /// Config files to migrate
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// Report outdated files without rewriting them
+        #[arg(long)]
+        check: bool,
+    },
+    /// Check prefabs, prefab manifests, configs and referenced asset files
+    ValidateAssets {
+        /// Asset directories to validate
+        #[arg(default_value = "assets")]
+        paths: Vec<PathBuf>,
+    },
+    /// Convert a glTF scene into prefab RON files and a prefab manifest
+    GltfToPrefab {
+        /// glTF or GLB file below the assets directory
+        input: PathBuf,
+        /// Assets directory the game loads files from
+        #[arg(long, default_value = "assets")]
+        assets: PathBuf,
+        /// Directory for the manifest; defaults to `<assets>/prefabs`
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// Prefab namespace; defaults to the file name
+        #[arg(long)]
+        namespace: Option<String>,
+    },
+    /// Build a pack file from every file in the assets directory
+    PackAssets {
+        /// Assets directory to pack
+        #[arg(default_value = "assets")]
+        assets: PathBuf,
+        /// Pack file to write
+        #[arg(long, default_value = "assets.pack")]
+        out: PathBuf,
+        /// Compress entries with zstd when that makes them smaller
+        #[arg(long)]
+        compress: bool,
+    },
     /// Bump version
     BumpVersion {
         /// Version type to bump
@@ -56,6 +103,20 @@ fn main() -> Result<()> {
         Commands::DocValidate => run_doc_validate(),
         Commands::Check => run_check(),
         Commands::Coverage => run_coverage(),
+        Commands::Perf => run_perf(),
+        Commands::MigrateConfig { paths, check } => migrate_config(&paths, check),
+        Commands::ValidateAssets { paths } => validate_assets::validate_assets(&paths),
+        Commands::GltfToPrefab {
+            input,
+            assets,
+            out,
+            namespace,
+        } => gltf_to_prefab(&input, &assets, out, namespace),
+        Commands::PackAssets {
+            assets,
+            out,
+            compress,
+        } => pack_assets(&assets, &out, compress),
         Commands::BumpVersion { version_type } => bump_version(version_type),
     }
 }
@@ -232,6 +293,154 @@ fn run_coverage() -> Result<()> {
     Ok(())
 }
 
+fn run_perf() -> Result<()> {
+    println!("Running performance benchmarks...");
+
+    // Bencher format prints one parseable `test <name> ... bench: <ns> ns/iter` line per benchmark
+    let status = Command::new("cargo")
+        .args([
+            "bench",
+            "--workspace",
+            "--benches",
+            "--",
+            "--output-format",
+            "bencher",
+        ])
+        .status()?;
+
+    if !status.success() {
+        anyhow::bail!("Benchmarks failed");
+    }
+
+    println!("✅ Benchmarks completed");
+    Ok(())
+}
+
+fn migrate_config(paths: &[PathBuf], check: bool) -> Result<()> {
+    let mut outdated = 0;
+
+    for path in paths {
+        // Configs may be authored in any supported format, so match on the stem
+        let version = if path.file_stem() == GameConfig::default_path().file_stem() {
+            config_version::<GameConfig>(path)?
+        } else {
+            anyhow::bail!("No config type is registered for {}", path.display());
+        };
+
+        match version {
+            Some((from, to)) if check => {
+                println!(
+                    "❌ {} is at schema version {from}, current is {to}",
+                    path.display()
+                );
+                outdated += 1;
+            }
+            Some((from, to)) => {
+                config_core::migrate_file::<GameConfig>(path)?;
+                println!("✅ Migrated {} from version {from} to {to}", path.display());
+            }
+            None => println!("✅ {} is up to date", path.display()),
+        }
+    }
+
+    if outdated > 0 {
+        anyhow::bail!("{outdated} config file(s) need migration");
+    }
+    Ok(())
+}
+
+/// Returns `(file version, current version)` if the file is outdated
+fn config_version<T: Config>(path: &Path) -> Result<Option<(u32, u32)>> {
+    let data = std::fs::read_to_string(path)?;
+    let doc: ron::Value = ConfigFormat::from_path(path)
+        .unwrap_or_default()
+        .parse(&data)?;
+    let version = config_core::document_version(&doc)?;
+    Ok((version != T::SCHEMA_VERSION).then_some((version, T::SCHEMA_VERSION)))
+}
+
+fn gltf_to_prefab(
+    input: &Path,
+    assets: &Path,
+    out: Option<PathBuf>,
+    namespace: Option<String>,
+) -> Result<()> {
+    // Mesh labels must use the path the game loads the scene from
+    let asset_path = input
+        .strip_prefix(assets)
+        .map_err(|_| anyhow::anyhow!("{} is not below {}", input.display(), assets.display()))?
+        .to_str()
+        .ok_or_else(|| anyhow::anyhow!("Non-UTF8 path {}", input.display()))?
+        .replace('\\', "/");
+    let namespace = namespace.unwrap_or_else(|| gameplay_factory::gltf_namespace(input));
+    let converted =
+        gameplay_factory::convert_gltf(&std::fs::read(input)?, &asset_path, &namespace)?;
+
+    let out = out.unwrap_or_else(|| assets.join("prefabs"));
+    let pretty = ron::ser::PrettyConfig::default().struct_names(true);
+    std::fs::create_dir_all(out.join(&converted.namespace))?;
+    for (name, prefab) in &converted.prefabs {
+        let path = out.join(converted.file_name(name));
+        std::fs::write(&path, ron::ser::to_string_pretty(prefab, pretty.clone())?)?;
+    }
+    let manifest_path = out.join(format!("{}.ron", converted.namespace));
+    std::fs::write(
+        &manifest_path,
+        ron::ser::to_string_pretty(&converted.manifest(), pretty)?,
+    )?;
+
+    println!(
+        "✅ Converted {} into {} prefab(s); manifest at {}",
+        input.display(),
+        converted.prefabs.len(),
+        manifest_path.display()
+    );
+    Ok(())
+}
+
+fn pack_assets(assets: &Path, out: &Path, compress: bool) -> Result<()> {
+    fn collect(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                collect(&path, files)?;
+            } else {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    collect(assets, &mut files)?;
+    files.sort();
+
+    let mut writer = amp_pack::PackWriter::new().with_compression(compress);
+    let mut size = 0;
+    for file in &files {
+        // Don't pack a previous pack written inside the assets directory
+        if out.exists() && std::fs::canonicalize(file)? == std::fs::canonicalize(out)? {
+            continue;
+        }
+        let bytes = std::fs::read(file)?;
+        size += bytes.len();
+        let relative = file.strip_prefix(assets)?;
+        let relative = relative
+            .to_str()
+            .ok_or_else(|| anyhow::anyhow!("Non-UTF8 path {}", file.display()))?;
+        writer.add(relative, bytes)?;
+    }
+
+    writer.write_to(std::io::BufWriter::new(std::fs::File::create(out)?))?;
+    println!(
+        "✅ Packed {} file(s), {size} bytes, into {} ({} bytes)",
+        writer.len(),
+        out.display(),
+        std::fs::metadata(out)?.len()
+    );
+    Ok(())
+}
+
 fn bump_version(version_type: VersionType) -> Result<()> {
     let version_arg = match version_type {
         VersionType::Patch => "patch",
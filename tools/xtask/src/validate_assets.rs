@@ -0,0 +1,213 @@
+//! `cargo xtask validate-assets`
+//!
+//! Loads every prefab, prefab manifest and config below the asset roots the
+//! way the game would, without a window or renderer, and reports content
+//! errors before they surface at runtime:
+//!
+//! - prefab or manifest RON that fails to parse
+//! - referenced GLB/glTF and audio files, and prefab dependencies, that do not exist
+//! - unknown component types and invalid component data, found by spawning
+//!   each prefab into a headless ECS world
+//! - configs that fail to parse, migrate or validate
+
+use anyhow::Result;
+use bevy_ecs::system::{CommandQueue, Commands};
+use bevy_ecs::world::World;
+use config_core::{Config, ConfigFormat, ConfigLayer, ConfigLoader, GameConfig};
+use gameplay_factory::{
+    register_default_components, Factory, Prefab, PrefabManifest, PrefabNames, RonPrefab,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Extensions of referenced files whose existence is checked
+const REFERENCED_EXTENSIONS: &[&str] = &["glb", "gltf", "ogg", "wav", "mp3", "flac"];
+
+/// Problems found while validating, per file
+#[derive(Default)]
+struct Report {
+    problems: Vec<(PathBuf, String)>,
+    checked: usize,
+}
+
+impl Report {
+    fn problem(&mut self, path: &Path, message: impl Into<String>) {
+        self.problems.push((path.to_path_buf(), message.into()));
+    }
+}
+
+pub fn validate_assets(roots: &[PathBuf]) -> Result<()> {
+    register_default_components();
+
+    let mut report = Report::default();
+    for root in roots {
+        if !root.is_dir() {
+            anyhow::bail!("Asset root {} is not a directory", root.display());
+        }
+        println!("Validating assets in {}...", root.display());
+        validate_root(root, &mut report)?;
+    }
+
+    for (path, message) in &report.problems {
+        println!("❌ {}: {message}", path.display());
+    }
+    if !report.problems.is_empty() {
+        anyhow::bail!(
+            "{} problem(s) found in {} file(s)",
+            report.problems.len(),
+            report.checked
+        );
+    }
+
+    println!("✅ {} asset file(s) valid", report.checked);
+    Ok(())
+}
+
+fn validate_root(root: &Path, report: &mut Report) -> Result<()> {
+    let mut files = Vec::new();
+    collect_files(root, &mut files)?;
+    files.sort();
+
+    let mut prefabs = Vec::new();
+    let mut manifests = Vec::new();
+    for path in &files {
+        if is_config(path) {
+            report.checked += 1;
+            let loader = ConfigLoader::new();
+            if let Err(e) =
+                loader.load_layers::<GameConfig>(&[ConfigLayer::new("asset", path.as_path())])
+            {
+                report.problem(path, e.to_string());
+            }
+            continue;
+        }
+        if path.extension().and_then(|e| e.to_str()) != Some("ron") {
+            continue;
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        let document = content.trim_start();
+        if document.starts_with("RonPrefab") {
+            report.checked += 1;
+            match RonPrefab::parse(&content) {
+                Ok(prefab) => prefabs.push((path.clone(), prefab)),
+                Err(e) => report.problem(path, format!("invalid prefab: {e}")),
+            }
+        } else if document.starts_with("PrefabManifest") {
+            report.checked += 1;
+            match ron::from_str::<PrefabManifest>(&content) {
+                Ok(manifest) => manifests.push((path.clone(), manifest)),
+                Err(e) => report.problem(path, format!("invalid prefab manifest: {e}")),
+            }
+        }
+    }
+
+    // Prefabs listed in a manifest are registered under their name's id
+    let mut names = PrefabNames::new();
+    let mut named_ids = HashMap::new();
+    for (path, manifest) in &manifests {
+        let base = path.parent().unwrap_or(root);
+        for (name, file) in manifest.names().zip(manifest.prefabs.values()) {
+            let prefab_path = base.join(file);
+            if !prefab_path.is_file() {
+                report.problem(path, format!("'{name}' refers to missing file {file}"));
+            }
+            match names.register(&name) {
+                Ok(id) => {
+                    named_ids.insert(prefab_path, id);
+                }
+                Err(e) => report.problem(path, e.to_string()),
+            }
+        }
+    }
+
+    let mut factory = Factory::new();
+    let mut registered = Vec::new();
+    for (path, ron_prefab) in prefabs {
+        for reference in referenced_paths(&ron_prefab) {
+            if !root.join(&reference).is_file() {
+                report.problem(&path, format!("missing referenced file {reference}"));
+            }
+        }
+
+        let id = match named_ids.get(&path) {
+            Some(id) => *id,
+            None => match factory.generate_prefab_id_from_path(&path) {
+                Ok(id) => id,
+                Err(e) => {
+                    report.problem(&path, e.to_string());
+                    continue;
+                }
+            },
+        };
+        match Prefab::try_from(ron_prefab).and_then(|prefab| factory.register(id, prefab)) {
+            Ok(()) => registered.push((path, id)),
+            Err(e) => report.problem(&path, e.to_string()),
+        }
+    }
+
+    // Spawning runs every component deserializer and resolves child prefabs
+    let mut world = World::new();
+    for (path, id) in registered {
+        let mut queue = CommandQueue::default();
+        let result = factory.spawn(&mut Commands::new(&mut queue, &world), id);
+        queue.apply(&mut world);
+        if let Err(e) = result {
+            report.problem(&path, e.to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Check if a file is a config document, including platform variants such as
+/// `game.linux.ron`
+fn is_config(path: &Path) -> bool {
+    let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let config_stem = GameConfig::FILE_NAME.split('.').next().unwrap_or_default();
+    ConfigFormat::from_path(path).is_some() && file_name.split('.').next() == Some(config_stem)
+}
+
+/// Collect prefab dependencies and asset paths referenced by component data
+fn referenced_paths(prefab: &RonPrefab) -> Vec<String> {
+    fn visit(value: &ron::Value, paths: &mut Vec<String>) {
+        match value {
+            ron::Value::String(text) => {
+                let referenced = Path::new(text)
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| {
+                        REFERENCED_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str())
+                    });
+                if referenced {
+                    paths.push(text.clone());
+                }
+            }
+            ron::Value::Seq(values) => values.iter().for_each(|v| visit(v, paths)),
+            ron::Value::Map(map) => map.iter().for_each(|(_, v)| visit(v, paths)),
+            ron::Value::Option(Some(inner)) => visit(inner, paths),
+            _ => {}
+        }
+    }
+
+    let mut paths = prefab.dependencies.clone();
+    for component in &prefab.components {
+        visit(&component.data, &mut paths);
+    }
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
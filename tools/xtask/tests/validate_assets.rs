@@ -0,0 +1,65 @@
+//! Runs `xtask validate-assets` on fixture asset trees and checks its exit status
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn validate(fixture: &str) -> Output {
+    let root: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "fixtures", fixture]
+        .iter()
+        .collect();
+    validate_root(root)
+}
+
+fn validate_root(root: PathBuf) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_xtask"))
+        .arg("validate-assets")
+        .arg(root)
+        .output()
+        .expect("failed to run xtask")
+}
+
+#[test]
+fn test_clean_assets_pass() {
+    let output = validate("clean");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("1 asset file(s) valid"), "{stdout}");
+}
+
+#[test]
+fn test_missing_reference_fails() {
+    let output = validate("broken");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "{stdout}");
+    assert!(
+        stdout.contains("missing referenced file models/missing.glb"),
+        "{stdout}"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_unhashable_path_does_not_stop_validation() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let root = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("non_utf8_assets");
+    let prefabs = root.join("prefabs");
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&prefabs).unwrap();
+    let fixture: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "fixtures", "broken"]
+        .iter()
+        .collect();
+    let broken = std::fs::read_to_string(fixture.join("prefabs/crate.ron")).unwrap();
+    std::fs::write(prefabs.join("crate.ron"), &broken).unwrap();
+    std::fs::write(prefabs.join(OsStr::from_bytes(b"crate-\xff.ron")), &broken).unwrap();
+
+    let output = validate_root(root);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "{stdout}");
+    assert!(stdout.contains("Non-UTF8 path"), "{stdout}");
+    assert!(
+        stdout.contains("missing referenced file models/missing.glb"),
+        "{stdout}"
+    );
+}